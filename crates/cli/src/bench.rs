@@ -0,0 +1,470 @@
+//! `roc bench`, which times the benchmarks in a module, like
+//! `expect Inspect.bench("sort", List.sort_asc(list))`, and compares them with a saved baseline.
+use crate::{
+    FLAG_BASELINE, FLAG_JSON, FLAG_MAIN, FLAG_MAX_THREADS, FLAG_SAMPLES, FLAG_WARMUP_MS, ROC_FILE,
+};
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_build::program::{handle_error_module, handle_loading_problem};
+use roc_error_macros::user_error;
+use roc_gen_llvm::llvm::build::LlvmBackendMode;
+use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError, Threading};
+use roc_mono::ir::OptLevel;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_repl_expect::bench::{measure_bench, BenchOptions, BenchSamples};
+use roc_repl_expect::property::PropertyOptions;
+use roc_repl_expect::run::{ExpectMemory, ExpectOptions, ToplevelExpect};
+use roc_reporting::report::RenderTarget;
+use roc_target::Target;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// The statistics of the times a benchmark took, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Stats {
+    mean: f64,
+    median: f64,
+    stddev: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Stats {
+    fn new(times: &[Duration]) -> Self {
+        let mut nanos: Vec<f64> = times.iter().map(|time| time.as_nanos() as f64).collect();
+        nanos.sort_by(f64::total_cmp);
+
+        let count = nanos.len().max(1) as f64;
+        let mean = nanos.iter().sum::<f64>() / count;
+
+        let median = match nanos.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (nanos[len / 2 - 1] + nanos[len / 2]) / 2.0,
+            len => nanos[len / 2],
+        };
+
+        // the standard deviation of a sample, rather than of everything it was drawn from
+        let variance = match nanos.len() {
+            0 | 1 => 0.0,
+            len => nanos.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / (len - 1) as f64,
+        };
+
+        Self {
+            mean,
+            median,
+            stddev: variance.sqrt(),
+            min: nanos.first().copied().unwrap_or_default(),
+            max: nanos.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// A benchmark which was timed.
+#[derive(Debug)]
+struct BenchResult {
+    module: String,
+    name: String,
+    samples: usize,
+    iterations: u64,
+    stats: Stats,
+}
+
+impl BenchResult {
+    fn new(module: &str, name: &str, samples: BenchSamples) -> Self {
+        Self {
+            module: module.to_string(),
+            name: name.to_string(),
+            samples: samples.times.len(),
+            iterations: samples.iterations,
+            stats: Stats::new(&samples.times),
+        }
+    }
+}
+
+pub fn bench(matches: &ArgMatches, target: Target) -> io::Result<i32> {
+    let start_time = Instant::now();
+    let arena = Bump::new();
+
+    let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(*n),
+    };
+
+    let path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+    if !path.exists() {
+        eprintln!(
+            "\nThis file was not found: {}\n\nYou can run `roc help` for more information on how to provide a .roc file.\n",
+            path.display()
+        );
+
+        return Ok(1);
+    }
+
+    let options = BenchOptions {
+        warmup: Duration::from_millis(*matches.get_one::<u64>(FLAG_WARMUP_MS).unwrap()),
+        samples: *matches.get_one::<usize>(FLAG_SAMPLES).unwrap(),
+    };
+
+    let baseline = match matches.get_one::<PathBuf>(FLAG_BASELINE) {
+        Some(baseline_path) => match read_baseline(baseline_path) {
+            Ok(baseline) => Some(baseline),
+            Err(message) => {
+                eprintln!(
+                    "\nThe baseline at {} could not be read: {message}\n",
+                    baseline_path.display()
+                );
+                return Ok(1);
+            }
+        },
+        None => None,
+    };
+
+    let load_config = LoadConfig {
+        target,
+        function_kind: FunctionKind::from_env(),
+        render: RenderTarget::ColorTerminal,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Test,
+    };
+    let load_result = roc_load::load_and_monomorphize(
+        &arena,
+        path.to_path_buf(),
+        matches.get_one::<PathBuf>(FLAG_MAIN).cloned(),
+        RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+        load_config,
+    );
+
+    let mut loaded = match load_result {
+        Ok(loaded) => loaded,
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return handle_loading_problem(problem);
+        }
+        Err(LoadMonomorphizedError::ErrorModule(module)) => {
+            return handle_error_module(module, start_time.elapsed());
+        }
+    };
+    let problems = roc_build::program::report_problems_monomorphized(&mut loaded);
+
+    // only the benchmarks are run, and not the other expects
+    for expects in loaded.toplevel_expects.values_mut() {
+        let benches = &expects.benches;
+        expects.pure = std::mem::take(&mut expects.pure)
+            .into_iter()
+            .filter(|(symbol, _)| benches.contains_key(symbol))
+            .collect();
+    }
+    loaded
+        .toplevel_expects
+        .retain(|_, expects| !expects.pure.is_empty());
+
+    if loaded.toplevel_expects.is_empty() {
+        println!("No benchmarks were found.");
+
+        // Like `roc test` without expectations, this is an error, so that a CI script which
+        // stopped benchmarking anything doesn't go unnoticed
+        return Ok(1);
+    }
+
+    if problems.warnings > 0 {
+        problems.print_error_warning_count(start_time.elapsed());
+        println!(
+            ".\n\nRunning benchmarks…\n\n\x1B[36m{}\x1B[39m",
+            "─".repeat(80)
+        );
+    }
+
+    let mut expectations = std::mem::take(&mut loaded.expectations);
+    let interns = loaded.interns.clone();
+
+    let (dyn_lib, expects_by_module, layout_interner) =
+        roc_repl_expect::run::expect_mono_module_to_dylib(
+            &arena,
+            target,
+            loaded,
+            // benchmarks are only worth timing when they're built like a release would be
+            OptLevel::Optimize,
+            LlvmBackendMode::CliTest,
+        )
+        .unwrap();
+
+    let arena = &Bump::new();
+    let interns = arena.alloc(interns);
+    let global_layout_interner = layout_interner.into_global();
+
+    // Sorted so the benchmarks always run in the same order
+    let mut expects_by_module: Vec<_> = expects_by_module.into_iter().collect();
+    expects_by_module
+        .sort_by(|(a, _), (b, _)| interns.module_name(*a).cmp(interns.module_name(*b)));
+
+    let benches: Vec<(&str, ToplevelExpect)> = expects_by_module
+        .iter()
+        .flat_map(|(module_id, functions)| {
+            let module_name = interns.module_name(*module_id).as_str();
+
+            functions
+                .pure
+                .iter()
+                .map(move |expect| (module_name, *expect))
+        })
+        .collect();
+
+    // Every benchmark runs once first, like `roc test` would run it, so that one which fails
+    // isn't timed
+    let mut writer = io::stdout();
+    let results = roc_repl_expect::run::run_toplevel_expects(
+        &mut writer,
+        RenderTarget::ColorTerminal,
+        arena,
+        interns,
+        &global_layout_interner,
+        &dyn_lib,
+        &mut expectations,
+        expects_by_module,
+        ExpectOptions {
+            workers: 1,
+            timeout: None,
+            property: PropertyOptions::default(),
+            update_snapshots: false,
+        },
+    )?;
+
+    let failed: Vec<_> = results
+        .iter()
+        .flat_map(|(_, results)| results.iter())
+        .filter(|result| !result.passed())
+        .map(|result| result.symbol)
+        .collect();
+
+    let mut memory = ExpectMemory::shared_with_children();
+    let mut timed = Vec::with_capacity(benches.len());
+    let mut crashed_count = 0;
+
+    println!("{}", table_header(baseline.is_some()));
+
+    for (module_name, expect) in benches {
+        if failed.contains(&expect.symbol) {
+            continue;
+        }
+
+        let name = expect.bench.unwrap_or_default();
+
+        match measure_bench(&dyn_lib, &mut memory, expect, options)? {
+            Ok(samples) => {
+                let result = BenchResult::new(module_name, name, samples);
+                println!("{}", table_row(&result, baseline.as_ref()));
+                timed.push(result);
+            }
+            Err(message) => {
+                println!("{name:<32} crashed: {message}");
+                crashed_count += 1;
+            }
+        }
+    }
+
+    if let Some(json_path) = matches.get_one::<PathBuf>(FLAG_JSON) {
+        write_json(json_path, &timed)?;
+        println!("\nWrote the results to {}", json_path.display());
+    }
+
+    Ok((!failed.is_empty() || crashed_count > 0) as i32)
+}
+
+fn table_header(with_baseline: bool) -> String {
+    let mut header = format!(
+        "{:<32} {:>12} {:>12} {:>12}",
+        "benchmark", "mean", "median", "stddev"
+    );
+
+    if with_baseline {
+        let _ = write!(header, " {:>10}", "change");
+    }
+
+    header
+}
+
+fn table_row(result: &BenchResult, baseline: Option<&Baseline>) -> String {
+    let mut row = format!(
+        "{:<32} {:>12} {:>12} {:>12}",
+        result.name,
+        format_nanos(result.stats.mean),
+        format_nanos(result.stats.median),
+        format_nanos(result.stats.stddev),
+    );
+
+    if let Some(baseline) = baseline {
+        let change = match baseline.get(&(result.module.clone(), result.name.clone())) {
+            Some(base_mean) => format_change(result.stats.mean, *base_mean),
+            None => "new".to_string(),
+        };
+
+        let _ = write!(row, " {change:>10}");
+    }
+
+    row
+}
+
+/// A time in the unit which fits it best, like `1.25 ms`.
+fn format_nanos(nanos: f64) -> String {
+    let (value, unit) = if nanos < 1e3 {
+        (nanos, "ns")
+    } else if nanos < 1e6 {
+        (nanos / 1e3, "µs")
+    } else if nanos < 1e9 {
+        (nanos / 1e6, "ms")
+    } else {
+        (nanos / 1e9, "s")
+    };
+
+    format!("{value:.2} {unit}")
+}
+
+/// How much slower (+) or faster (-) `mean` is than the mean of the baseline.
+fn format_change(mean: f64, base_mean: f64) -> String {
+    if base_mean <= 0.0 {
+        return "n/a".to_string();
+    }
+
+    format!("{:+.1}%", (mean - base_mean) / base_mean * 100.0)
+}
+
+fn json_results(results: &[BenchResult]) -> String {
+    let benchmarks: Vec<_> = results
+        .iter()
+        .map(|result| {
+            serde_json::json!({
+                "module": result.module,
+                "name": result.name,
+                "samples": result.samples,
+                "iterations": result.iterations,
+                "mean_ns": result.stats.mean,
+                "median_ns": result.stats.median,
+                "stddev_ns": result.stats.stddev,
+                "min_ns": result.stats.min,
+                "max_ns": result.stats.max,
+            })
+        })
+        .collect();
+
+    let mut json =
+        serde_json::to_string_pretty(&serde_json::json!({ "benchmarks": benchmarks })).unwrap();
+    json.push('\n');
+
+    json
+}
+
+fn write_json(path: &Path, results: &[BenchResult]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        if !dir.as_os_str().is_empty() {
+            std::fs::create_dir_all(dir)?;
+        }
+    }
+
+    std::fs::write(path, json_results(results))
+}
+
+/// The mean time of every benchmark of a previous run, by its module and name.
+type Baseline = HashMap<(String, String), f64>;
+
+/// Reads the results a previous run wrote with `--json`.
+fn read_baseline(path: &Path) -> Result<Baseline, String> {
+    let json = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+
+    parse_baseline(&json)
+}
+
+fn parse_baseline(json: &str) -> Result<Baseline, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|error| error.to_string())?;
+
+    let Some(benchmarks) = value.get("benchmarks").and_then(|b| b.as_array()) else {
+        return Err("it has no list of benchmarks".to_string());
+    };
+
+    let mut baseline = Baseline::new();
+
+    for benchmark in benchmarks {
+        let module = benchmark.get("module").and_then(|m| m.as_str());
+        let name = benchmark.get("name").and_then(|n| n.as_str());
+        let mean = benchmark.get("mean_ns").and_then(|m| m.as_f64());
+
+        match (module, name, mean) {
+            (Some(module), Some(name), Some(mean)) => {
+                baseline.insert((module.to_string(), name.to_string()), mean);
+            }
+            _ => return Err("a benchmark lacks its module, name or mean_ns".to_string()),
+        }
+    }
+
+    Ok(baseline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, mean: f64) -> BenchResult {
+        BenchResult {
+            module: "Sort".to_string(),
+            name: name.to_string(),
+            samples: 3,
+            iterations: 100,
+            stats: Stats {
+                mean,
+                median: mean,
+                stddev: 0.0,
+                min: mean,
+                max: mean,
+            },
+        }
+    }
+
+    #[test]
+    fn stats_of_samples() {
+        let times: Vec<_> = [4, 1, 3, 2].into_iter().map(Duration::from_nanos).collect();
+        let stats = Stats::new(&times);
+
+        assert_eq!(stats.mean, 2.5);
+        assert_eq!(stats.median, 2.5);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 4.0);
+        assert!((stats.stddev - (5.0f64 / 3.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn nanos_in_best_unit() {
+        assert_eq!(format_nanos(512.0), "512.00 ns");
+        assert_eq!(format_nanos(1_250_000.0), "1.25 ms");
+        assert_eq!(format_nanos(3.5e9), "3.50 s");
+    }
+
+    #[test]
+    fn change_from_baseline() {
+        assert_eq!(format_change(110.0, 100.0), "+10.0%");
+        assert_eq!(format_change(75.0, 100.0), "-25.0%");
+    }
+
+    #[test]
+    fn json_is_read_back_as_baseline() {
+        let json = json_results(&[result("quick", 120.0), result("merge", 80.0)]);
+        let baseline = parse_baseline(&json).unwrap();
+
+        assert_eq!(baseline.len(), 2);
+        assert_eq!(baseline[&("Sort".to_string(), "quick".to_string())], 120.0);
+
+        let row = table_row(&result("merge", 100.0), Some(&baseline));
+        assert!(row.ends_with("+25.0%"));
+
+        let row = table_row(&result("heap", 100.0), Some(&baseline));
+        assert!(row.ends_with("new"));
+    }
+
+    #[test]
+    fn baseline_without_benchmarks() {
+        assert!(parse_baseline("{\"expects\": []}").is_err());
+    }
+}
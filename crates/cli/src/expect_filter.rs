@@ -0,0 +1,202 @@
+//! Choosing which top-level `expect`s `roc test` runs.
+use clap::ArgMatches;
+use roc_collections::MutMap;
+use roc_load::ToplevelExpects;
+use roc_module::symbol::{Interns, ModuleId};
+use roc_region::all::{LineInfo, Region};
+use std::path::{Path, PathBuf};
+
+use crate::{FLAG_FILTER, FLAG_MODULE};
+
+/// The expects to run, as given by `--filter` and `--module`. Every expect is run by default.
+#[derive(Debug, Default)]
+pub struct ExpectFilter {
+    pattern: Option<String>,
+    module: Option<String>,
+}
+
+impl ExpectFilter {
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        Self {
+            pattern: matches.get_one::<String>(FLAG_FILTER).cloned(),
+            module: matches.get_one::<String>(FLAG_MODULE).cloned(),
+        }
+    }
+
+    /// Whether the expect at `region` of the module named `module_name` should run.
+    ///
+    /// A pattern like `Foo.roc:12` matches the expects spanning line 12 of a file whose path ends
+    /// in `Foo.roc`. Any other pattern matches the expects whose module name or source contains it.
+    fn matches(
+        &self,
+        module_name: &str,
+        path: &Path,
+        source: &str,
+        line_info: &LineInfo,
+        region: Region,
+    ) -> bool {
+        if let Some(module) = &self.module {
+            if module != module_name {
+                return false;
+            }
+        }
+
+        let Some(pattern) = &self.pattern else {
+            return true;
+        };
+
+        if let Some((file, line)) = parse_location(pattern) {
+            let start = line_info.convert_pos(region.start()).line + 1;
+            let end = line_info.convert_pos(region.end()).line + 1;
+
+            return path.ends_with(file) && (start..=end).contains(&line);
+        }
+
+        let expect_source = source
+            .get(region.start().offset as usize..region.end().offset as usize)
+            .unwrap_or_default();
+
+        module_name.contains(pattern.as_str()) || expect_source.contains(pattern.as_str())
+    }
+}
+
+/// Splits a pattern like `Foo.roc:12` into the file and the (1-based) line.
+fn parse_location(pattern: &str) -> Option<(&str, u32)> {
+    let (file, line) = pattern.rsplit_once(':')?;
+    let line = line.parse().ok()?;
+
+    file.ends_with(".roc").then_some((file, line))
+}
+
+/// Drops the expects which `filter` doesn't match.
+pub fn filter_expects(
+    filter: &ExpectFilter,
+    toplevel_expects: &mut MutMap<ModuleId, ToplevelExpects>,
+    interns: &Interns,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+) {
+    for (module_id, expects) in toplevel_expects.iter_mut() {
+        let module_name = interns.module_name(*module_id);
+        let Some((path, source)) = sources.get(module_id) else {
+            continue;
+        };
+        let line_info = LineInfo::new(source);
+
+        expects.pure = std::mem::take(&mut expects.pure)
+            .into_iter()
+            .filter(|(_, region)| filter.matches(module_name, path, source, &line_info, *region))
+            .collect();
+    }
+
+    toplevel_expects.retain(|_, expects| !expects.pure.is_empty());
+}
+
+/// Prints the location of every expect along with its first line, sorted by file and line.
+pub fn list_expects(
+    toplevel_expects: &MutMap<ModuleId, ToplevelExpects>,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+) -> usize {
+    let mut listed = vec![];
+
+    for (module_id, expects) in toplevel_expects.iter() {
+        let Some((path, source)) = sources.get(module_id) else {
+            continue;
+        };
+        let line_info = LineInfo::new(source);
+
+        for region in expects.pure.values() {
+            let start = line_info.convert_pos(region.start()).line + 1;
+            let expect_source = &source[region.start().offset as usize..];
+
+            // The region starts at the comment above the expect, if there is one
+            let Some((offset, first_line)) = expect_source
+                .lines()
+                .map(str::trim)
+                .enumerate()
+                .find(|(_, line)| !line.starts_with('#'))
+            else {
+                continue;
+            };
+
+            listed.push((
+                path.display().to_string(),
+                start + offset as u32,
+                first_line.to_string(),
+            ));
+        }
+    }
+
+    listed.sort();
+
+    for (path, line, first_line) in listed.iter() {
+        println!("{path}:{line}  {first_line}");
+    }
+
+    listed.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roc_region::all::Position;
+
+    const SOURCE: &str = "module []\n\nexpect 1 == 1\n\nexpect\n    x = 2\n    x == 2\n";
+
+    fn region_of(expect_source: &str) -> Region {
+        let start = SOURCE.find(expect_source).unwrap();
+        let end = start + expect_source.len();
+
+        Region::new(Position::new(start as u32), Position::new(end as u32))
+    }
+
+    fn matches(pattern: Option<&str>, module: Option<&str>, expect_source: &str) -> bool {
+        let filter = ExpectFilter {
+            pattern: pattern.map(String::from),
+            module: module.map(String::from),
+        };
+
+        filter.matches(
+            "Foo",
+            Path::new("/project/src/Foo.roc"),
+            SOURCE,
+            &LineInfo::new(SOURCE),
+            region_of(expect_source),
+        )
+    }
+
+    #[test]
+    fn no_filter_matches_everything() {
+        assert!(matches(None, None, "expect 1 == 1"));
+    }
+
+    #[test]
+    fn pattern_in_module_name() {
+        assert!(matches(Some("Fo"), None, "expect 1 == 1"));
+    }
+
+    #[test]
+    fn pattern_in_source() {
+        assert!(matches(
+            Some("x == 2"),
+            None,
+            "expect\n    x = 2\n    x == 2"
+        ));
+        assert!(!matches(Some("x == 2"), None, "expect 1 == 1"));
+    }
+
+    #[test]
+    fn pattern_is_location() {
+        let multiline = "expect\n    x = 2\n    x == 2";
+
+        assert!(matches(Some("Foo.roc:3"), None, "expect 1 == 1"));
+        assert!(matches(Some("src/Foo.roc:6"), None, multiline));
+        assert!(!matches(Some("Foo.roc:6"), None, "expect 1 == 1"));
+        assert!(!matches(Some("Bar.roc:3"), None, "expect 1 == 1"));
+    }
+
+    #[test]
+    fn module_must_match_exactly() {
+        assert!(matches(None, Some("Foo"), "expect 1 == 1"));
+        assert!(!matches(None, Some("Fo"), "expect 1 == 1"));
+    }
+}
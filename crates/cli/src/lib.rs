@@ -41,11 +41,23 @@ use strum::IntoEnumIterator;
 #[cfg(not(target_os = "linux"))]
 use tempfile::TempDir;
 
+#[cfg(not(windows))]
+mod bench;
+#[cfg(not(windows))]
+mod expect_filter;
 mod format;
+#[cfg(not(windows))]
+mod test_coverage;
+#[cfg(not(windows))]
+mod test_report;
+mod watch;
+#[cfg(not(windows))]
+pub use bench::bench;
 pub use format::{
     annotate_file, annotation_edit, annotation_edits, format_files, format_src, AnnotationProblem,
     FormatMode,
 };
+pub use watch::check_watch;
 
 pub const CMD_BUILD: &str = "build";
 pub const CMD_RUN: &str = "run";
@@ -57,7 +69,9 @@ pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_FORMAT_ANNOTATE: &str = "annotate";
 pub const CMD_TEST: &str = "test";
+pub const CMD_BENCH: &str = "bench";
 pub const CMD_GLUE: &str = "glue";
+pub const CMD_EXPLAIN: &str = "explain";
 pub const CMD_PREPROCESS_HOST: &str = "preprocess-host";
 pub const CMD_LICENSES: &str = "licenses";
 
@@ -90,11 +104,26 @@ pub const GLUE_DIR: &str = "GLUE_DIR";
 pub const GLUE_SPEC: &str = "GLUE_SPEC";
 pub const DIRECTORY_OR_FILES: &str = "DIRECTORY_OR_FILES";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
+pub const ERROR_CODE: &str = "ERROR_CODE";
 pub const FLAG_PP_HOST: &str = "host";
 pub const FLAG_PP_PLATFORM: &str = "platform";
 pub const FLAG_PP_DYLIB: &str = "lib";
 pub const FLAG_MIGRATE: &str = "migrate";
 pub const FLAG_DOCS_ROOT: &str = "root-dir";
+pub const FLAG_WATCH: &str = "watch";
+pub const FLAG_FILTER: &str = "filter";
+pub const FLAG_MODULE: &str = "module";
+pub const FLAG_LIST: &str = "list";
+pub const FLAG_REPORT: &str = "report";
+pub const FLAG_TIMEOUT_MS: &str = "timeout-ms";
+pub const FLAG_PROPERTY_RUNS: &str = "property-runs";
+pub const FLAG_SEED: &str = "seed";
+pub const FLAG_COVERAGE: &str = "coverage";
+pub const FLAG_UPDATE_SNAPSHOTS: &str = "update-snapshots";
+pub const FLAG_WARMUP_MS: &str = "warmup-ms";
+pub const FLAG_SAMPLES: &str = "samples";
+pub const FLAG_JSON: &str = "json";
+pub const FLAG_BASELINE: &str = "baseline";
 
 pub const VERSION: &str = env!("ROC_VERSION");
 const DEFAULT_GENERATED_DOCS_DIR: &str = "generated-docs";
@@ -108,7 +137,7 @@ pub fn build_app() -> Command {
 
     let flag_max_threads = Arg::new(FLAG_MAX_THREADS)
         .long(FLAG_MAX_THREADS)
-        .help("Limit the number of threads (and hence cores) used during compilation")
+        .help("Limit the number of threads (and hence cores) used during compilation, and by `roc test` to run `expect`s")
         .value_parser(value_parser!(usize))
         .required(false);
 
@@ -172,6 +201,12 @@ pub fn build_app() -> Command {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_watch = Arg::new(FLAG_WATCH)
+        .long(FLAG_WATCH)
+        .help("Build again whenever a file the program is built from changes, and restart the program if it's running\n(This uses the dev backend unless --optimize or --opt-size is given. Inline expects are not reported while watching.)")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let flag_main = Arg::new(FLAG_MAIN)
         .long(FLAG_MAIN)
         .help("The .roc file of the main app/package module to resolve dependencies from")
@@ -222,6 +257,7 @@ pub fn build_app() -> Command {
             .arg(flag_build_host.clone())
             .arg(flag_suppress_build_host_warning.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_watch.clone())
             .arg(flag_wasm_stack_size_kb)
             .arg(
                 Arg::new(FLAG_TARGET)
@@ -289,6 +325,74 @@ pub fn build_app() -> Command {
                     .action(ArgAction::SetTrue)
                     .required(false)
             )
+            .arg(
+                Arg::new(FLAG_FILTER)
+                    .long(FLAG_FILTER)
+                    .help("Only run the `expect`s whose module name or source contains this pattern, or the ones at a location like `Foo.roc:12`")
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_MODULE)
+                    .long(FLAG_MODULE)
+                    .help("Only run the `expect`s in the module with this name")
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_LIST)
+                    .long(FLAG_LIST)
+                    .help("List the `expect`s that would run, with their locations, without running them")
+                    .action(ArgAction::SetTrue)
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_REPORT)
+                    .long(FLAG_REPORT)
+                    .help("Also write the results of every `expect` to a file, as junit=<path> (JUnit XML) or json=<path>. Can be given more than once.")
+                    .value_name("FORMAT=PATH")
+                    .value_parser(report_request_parser)
+                    .action(ArgAction::Append)
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_TIMEOUT_MS)
+                    .long(FLAG_TIMEOUT_MS)
+                    .help("Stop any `expect` that runs for longer than this many milliseconds, and count it as failed")
+                    .value_parser(value_parser!(u64).range(1..))
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_PROPERTY_RUNS)
+                    .long(FLAG_PROPERTY_RUNS)
+                    .help("How many generated inputs to check every property with, like `expect |list| ...`")
+                    .value_parser(value_parser!(u32).range(1..))
+                    .default_value("100")
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_SEED)
+                    .long(FLAG_SEED)
+                    .help("Generate the inputs of properties from this seed, to get the same inputs as a previous run\n(By default, a new seed is picked every time.)")
+                    .value_parser(value_parser!(u64))
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_COVERAGE)
+                    .long(FLAG_COVERAGE)
+                    .help("Report which top-level defs and `when`/`if` branches the `expect`s ran, in the terminal and as an lcov file at this path\n(The path is lcov.info if none is given.)")
+                    .value_name("LCOV_PATH")
+                    .value_parser(value_parser!(PathBuf))
+                    .num_args(0..=1)
+                    .require_equals(true)
+                    .default_missing_value("lcov.info")
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_UPDATE_SNAPSHOTS)
+                    .long(FLAG_UPDATE_SNAPSHOTS)
+                    .help("Store the values of snapshots, like `expect Inspect.snapshot(\"page\", render(page))`, which are missing or have changed, instead of failing them")
+                    .action(ArgAction::SetTrue)
+                    .required(false)
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file to test")
@@ -299,6 +403,50 @@ pub fn build_app() -> Command {
             )
             .arg(args_for_app.clone().last(true))
         )
+        .subcommand(Command::new(CMD_BENCH)
+            .about("Time the benchmarks in a module, which are top-level `expect`s like `expect Inspect.bench(\"sort\", List.sort_asc(list))`\n(They are built with --optimize.)")
+            .arg(flag_main.clone())
+            .arg(flag_max_threads.clone())
+            .arg(
+                Arg::new(FLAG_WARMUP_MS)
+                    .long(FLAG_WARMUP_MS)
+                    .help("How many milliseconds to run every benchmark for before timing it")
+                    .value_parser(value_parser!(u64))
+                    .default_value("500")
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_SAMPLES)
+                    .long(FLAG_SAMPLES)
+                    .help("How many times to time every benchmark")
+                    .value_parser(value_parser!(usize).range(1..))
+                    .default_value("50")
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_JSON)
+                    .long(FLAG_JSON)
+                    .help("Also write the results to a JSON file at this path, which a later run can compare itself with using --baseline")
+                    .value_name("PATH")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_BASELINE)
+                    .long(FLAG_BASELINE)
+                    .help("Show how much the mean time of every benchmark changed since the results at this path, which an earlier run wrote with --json")
+                    .value_name("PATH")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file with the benchmarks")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME)
+            )
+        )
         .subcommand(Command::new(CMD_REPL)
             .about("Launch the interactive Read Eval Print Loop (REPL)")
             .arg(
@@ -329,6 +477,7 @@ pub fn build_app() -> Command {
             .arg(flag_build_host.clone())
             .arg(flag_suppress_build_host_warning.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_watch.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -345,6 +494,7 @@ pub fn build_app() -> Command {
             .arg(flag_build_host.clone())
             .arg(flag_suppress_build_host_warning.clone())
             .arg(flag_fuzz.clone())
+            .arg(flag_watch.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -396,6 +546,14 @@ pub fn build_app() -> Command {
                 )
             )
         )
+        .subcommand(Command::new(CMD_EXPLAIN)
+            .about("Explain an error code, like the E0301 in `── TYPE MISMATCH [E0301] ──`")
+            .arg(
+                Arg::new(ERROR_CODE)
+                    .help("The code to explain")
+                    .required(true)
+            )
+        )
         .subcommand(Command::new(CMD_VERSION)
             .about(concatcp!("Print the Roc compiler’s version, which is currently ", VERSION)))
         .subcommand(Command::new(CMD_LICENSES)
@@ -405,6 +563,13 @@ pub fn build_app() -> Command {
             .arg(flag_main.clone())
             .arg(flag_time.clone())
             .arg(flag_max_threads.clone())
+            .arg(
+                Arg::new(FLAG_WATCH)
+                    .long(FLAG_WATCH)
+                    .help("Check again whenever a module changes\n(Modules which didn’t change are not checked again.)")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file to check")
@@ -502,6 +667,7 @@ pub fn build_app() -> Command {
         .arg(flag_build_host)
         .arg(flag_suppress_build_host_warning)
         .arg(flag_fuzz)
+        .arg(flag_watch)
         .arg(roc_file_to_run)
         .arg(args_for_app.trailing_var_arg(true))
 }
@@ -527,11 +693,28 @@ fn opt_level_from_flags(matches: &ArgMatches) -> OptLevel {
     }
 }
 
+#[cfg(not(windows))]
+fn report_request_parser(value: &str) -> Result<test_report::ReportRequest, String> {
+    test_report::parse_report_request(value)
+}
+
+#[cfg(windows)]
+fn report_request_parser(value: &str) -> Result<String, String> {
+    Ok(value.to_string())
+}
+
 #[cfg(windows)]
 pub fn test(_matches: &ArgMatches, _target: Target) -> io::Result<i32> {
     todo!("running tests does not work on windows right now")
 }
 
+#[cfg(windows)]
+pub fn bench(_matches: &ArgMatches, _target: Target) -> io::Result<i32> {
+    eprintln!("Running benchmarks does not work on Windows right now.");
+
+    Ok(1)
+}
+
 #[cfg(not(windows))]
 struct ModuleTestResults {
     module_id: ModuleId,
@@ -542,9 +725,15 @@ struct ModuleTestResults {
 
 #[cfg(not(windows))]
 pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
+    use expect_filter::{filter_expects, list_expects, ExpectFilter};
     use roc_build::program::report_problems_monomorphized;
     use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError};
     use roc_packaging::cache;
+    use roc_repl_expect::coverage::CoverageCounters;
+    use roc_repl_expect::property::PropertyOptions;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use test_coverage::{coverage_summary, write_lcov, ModuleCoverage};
+    use test_report::{write_reports, ReportRequest, ReportedExpect};
 
     let start_time = Instant::now();
     let arena = Bump::new();
@@ -589,8 +778,41 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
         flatten_paths
     };
 
+    let filter = ExpectFilter::from_matches(matches);
+    let list = matches.get_flag(FLAG_LIST);
+    let report_requests: Vec<ReportRequest> = matches
+        .get_many::<ReportRequest>(FLAG_REPORT)
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+    let mut reported_expects = Vec::new();
+    let timeout = matches
+        .get_one::<u64>(FLAG_TIMEOUT_MS)
+        .map(|ms| Duration::from_millis(*ms));
+    let property = PropertyOptions {
+        runs: *matches.get_one::<u32>(FLAG_PROPERTY_RUNS).unwrap(),
+        seed: matches
+            .get_one::<u64>(FLAG_SEED)
+            .copied()
+            .unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+            }),
+    };
+    let update_snapshots = matches.get_flag(FLAG_UPDATE_SNAPSHOTS);
+    let coverage_path = matches.get_one::<PathBuf>(FLAG_COVERAGE);
+    let mut covered_modules: Vec<ModuleCoverage> = Vec::new();
+    let workers = match threading {
+        Threading::Single => 1,
+        Threading::AtMost(n) => n,
+        Threading::AllAvailable => std::thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+
     let mut all_files_total_failed_count = 0;
     let mut all_files_total_passed_count = 0;
+    let mut all_files_listed_count = 0;
 
     for path in paths.iter() {
         let arena = &arena;
@@ -606,7 +828,11 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             render: roc_reporting::report::RenderTarget::ColorTerminal,
             palette: roc_reporting::report::DEFAULT_PALETTE,
             threading,
-            exec_mode: ExecutionMode::Test,
+            exec_mode: if coverage_path.is_some() {
+                ExecutionMode::TestWithCoverage
+            } else {
+                ExecutionMode::Test
+            },
         };
         let load_result = roc_load::load_and_monomorphize(
             arena,
@@ -627,7 +853,20 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
         };
         let problems = report_problems_monomorphized(&mut loaded);
 
+        filter_expects(
+            &filter,
+            &mut loaded.toplevel_expects,
+            &loaded.interns,
+            &loaded.sources,
+        );
+
+        if list {
+            all_files_listed_count += list_expects(&loaded.toplevel_expects, &loaded.sources);
+            continue;
+        }
+
         let mut expectations = std::mem::take(&mut loaded.expectations);
+        let coverage_probes = std::mem::take(&mut loaded.coverage);
 
         let interns = loaded.interns.clone();
         let sources = loaded.sources.clone();
@@ -654,6 +893,13 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             }
         }
 
+        // The counters are shared with the processes the expects run in, so they must exist first
+        let coverage_counters = coverage_path.map(|_| {
+            let counters = CoverageCounters::shared_with_children(&coverage_probes);
+            counters.set_in(&dyn_lib);
+            counters
+        });
+
         // Run the tests.
         let arena = &bumpalo::Bump::new();
         let interns = arena.alloc(interns);
@@ -668,22 +914,47 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
 
         let compilation_duration = start_time.elapsed();
 
-        for (module_id, expects) in expects_by_module.into_iter() {
-            let test_start_time = Instant::now();
+        // Sorted so the output is the same however the expects are scheduled
+        let mut expects_by_module: Vec<_> = expects_by_module.into_iter().collect();
+        expects_by_module
+            .sort_by(|(a, _), (b, _)| interns.module_name(*a).cmp(interns.module_name(*b)));
 
-            let (failed_count, passed_count) = roc_repl_expect::run::run_toplevel_expects(
-                &mut writer,
-                roc_reporting::report::RenderTarget::ColorTerminal,
-                arena,
-                interns,
-                &global_layout_interner,
-                &dyn_lib,
-                &mut expectations,
-                expects,
-            )
-            .unwrap();
+        let options = roc_repl_expect::run::ExpectOptions {
+            workers,
+            timeout,
+            property,
+            update_snapshots,
+        };
+        let results = roc_repl_expect::run::run_toplevel_expects(
+            &mut writer,
+            roc_reporting::report::RenderTarget::ColorTerminal,
+            arena,
+            interns,
+            &global_layout_interner,
+            &dyn_lib,
+            &mut expectations,
+            expects_by_module,
+            options,
+        )
+        .unwrap();
+
+        for (module_id, results) in results {
+            // The expects may have run in parallel, so this is the time spent running them
+            let tests_duration = results.iter().map(|result| result.duration).sum();
 
-            let tests_duration = test_start_time.elapsed();
+            let passed_count = results.iter().filter(|result| result.passed()).count();
+            let failed_count = results.len() - passed_count;
+
+            if !report_requests.is_empty() {
+                let module_name = interns.module_name(module_id);
+                let (module_path, source) = &sources[&module_id];
+
+                reported_expects.extend(
+                    results.into_iter().map(|result| {
+                        ReportedExpect::new(module_name, module_path, source, result)
+                    }),
+                );
+            }
 
             results_by_module.push(ModuleTestResults {
                 module_id,
@@ -696,6 +967,28 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             total_passed_count += passed_count;
         }
 
+        if let Some(counters) = &coverage_counters {
+            for (module_id, probes) in coverage_probes.iter() {
+                let (module_path, source) = &sources[module_id];
+                let covered = ModuleCoverage::new(
+                    module_path,
+                    source,
+                    interns,
+                    *probes,
+                    counters.hits(*module_id),
+                );
+
+                // a module imported by several of the tested files is covered by all of them
+                match covered_modules
+                    .iter_mut()
+                    .find(|other| other.path() == module_path.as_path())
+                {
+                    Some(other) => other.merge(covered),
+                    None => covered_modules.push(covered),
+                }
+            }
+        }
+
         let total_duration = start_time.elapsed();
         all_files_total_failed_count += total_failed_count;
         all_files_total_passed_count += total_passed_count;
@@ -713,7 +1006,19 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
             println!("{test_summary_str}");
         }
     }
-    if all_files_total_failed_count == 0 && all_files_total_passed_count == 0 {
+    write_reports(&report_requests, reported_expects)?;
+
+    if let Some(coverage_path) = coverage_path {
+        covered_modules.sort_by(|a, b| a.path().cmp(b.path()));
+
+        write_lcov(coverage_path, &covered_modules)?;
+        print!("{}", coverage_summary(&covered_modules));
+        println!("\nWrote the coverage to {}", coverage_path.display());
+    }
+
+    if list && all_files_listed_count > 0 {
+        Ok(0)
+    } else if all_files_total_failed_count == 0 && all_files_total_passed_count == 0 {
         // TODO print this in a more nicely formatted way!
         println!("No expectations were found.");
 
@@ -789,6 +1094,32 @@ fn test_summary(failed_count: usize, passed_count: usize, tests_duration: Durati
     )
 }
 
+/// Prints the explanation of an error code, like the `E0301` of a TYPE MISMATCH.
+pub fn explain(matches: &ArgMatches) -> io::Result<i32> {
+    use roc_reporting::error::codes::lookup_code;
+    use roc_reporting::report::pretty_header;
+
+    let code = matches.get_one::<String>(ERROR_CODE).unwrap();
+
+    match lookup_code(code) {
+        Some(error_code) => {
+            let title = format!("{} [{}]", error_code.title, error_code.code);
+
+            println!("{}\n", pretty_header(&title));
+            print!("{}", error_code.explanation);
+
+            Ok(0)
+        }
+        None => {
+            eprintln!(
+                "There is no error code `{code}`. Codes look like E0301, and are printed in the header of every report."
+            );
+
+            Ok(1)
+        }
+    }
+}
+
 /// Find the element of `options` with the smallest edit distance to
 /// `reference`. Returns a tuple containing the element and the distance, or
 /// `None` if the `options` `Vec` is empty.
@@ -929,14 +1260,29 @@ pub fn build(
     // so we don't want to spend time freeing these values
     let arena = ManuallyDrop::new(Bump::new());
 
-    let opt_level = opt_level_from_flags(matches);
+    let watch = matches
+        .try_get_one::<bool>(FLAG_WATCH)
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false);
+
+    // When watching, building again quickly matters more than how fast the program runs, so
+    // the dev backend is used unless an opt level is asked for
+    let opt_level = match opt_level_from_flags(matches) {
+        OptLevel::Normal if watch => OptLevel::Development,
+        opt_level => opt_level,
+    };
 
     let should_run_expects = matches!(opt_level, OptLevel::Development | OptLevel::Normal) &&
         // TODO: once expect is decoupled from roc launching the executable, remove this part of the conditional.
         matches!(
             config,
             BuildConfig::BuildAndRun | BuildConfig::BuildAndRunIfNoErrors
-        );
+        ) &&
+        // A watched program is restarted rather than replacing this process, so the failures of
+        // its expects have nowhere to go
+        !watch;
 
     let code_gen_backend = if matches!(opt_level, OptLevel::Development) {
         if matches!(target.architecture(), Architecture::Wasm32) {
@@ -1007,6 +1353,36 @@ pub fn build(
         fuzz,
     };
 
+    if watch {
+        // `roc build` only builds, so it has no arguments for the program
+        let args: Option<Vec<OsString>> = (config != BuildOnly).then(|| {
+            matches
+                .get_many::<OsString>(ARGS_FOR_APP)
+                .unwrap_or_default()
+                .cloned()
+                .collect()
+        });
+
+        return watch::build_watch(path, args.as_deref(), build_host, |arena| {
+            roc_build::program::build_file(
+                arena,
+                target,
+                path.to_owned(),
+                code_gen_options,
+                emit_timings,
+                link_type,
+                linking_strategy,
+                build_host,
+                suppress_build_host_warning,
+                wasm_dev_stack_bytes,
+                roc_cache_dir,
+                standard_load_config(target, build_ordering, threading),
+                out_path,
+                verbose,
+            )
+        });
+    }
+
     let load_config = standard_load_config(target, build_ordering, threading);
 
     let res_binary_path = roc_build::program::build_file(
@@ -1032,6 +1408,7 @@ pub fn build(
             problems,
             total_time,
             expect_metadata,
+            ..
         }) => {
             match config {
                 BuildOnly => {
@@ -1,15 +1,16 @@
 //! The `roc` binary that brings together all functionality in the Roc toolset.
 use bumpalo::Bump;
 use roc_build::link::LinkType;
-use roc_build::program::{check_file, CodeGenBackend};
+use roc_build::program::{check_file, handle_loading_problem, CodeGenBackend};
 use roc_cli::{
-    annotate_file, build_app, default_linking_strategy, format_files, format_src, test,
-    AnnotationProblem, BuildConfig, FormatMode, CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_DOCS,
-    CMD_FORMAT, CMD_FORMAT_ANNOTATE, CMD_GLUE, CMD_LICENSES, CMD_PREPROCESS_HOST, CMD_REPL,
-    CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_DOCS_ROOT,
-    FLAG_LIB, FLAG_MAIN, FLAG_MIGRATE, FLAG_NO_COLOR, FLAG_NO_HEADER, FLAG_NO_LINK, FLAG_OUTPUT,
-    FLAG_PP_DYLIB, FLAG_PP_HOST, FLAG_PP_PLATFORM, FLAG_STDIN, FLAG_STDOUT, FLAG_TARGET, FLAG_TIME,
-    FLAG_VERBOSE, GLUE_DIR, GLUE_SPEC, ROC_FILE, VERSION,
+    annotate_file, bench, build_app, check_watch, default_linking_strategy, explain, format_files,
+    format_src, test, AnnotationProblem, BuildConfig, FormatMode, CMD_BENCH, CMD_BUILD, CMD_CHECK,
+    CMD_DEV, CMD_DOCS, CMD_EXPLAIN, CMD_FORMAT, CMD_FORMAT_ANNOTATE, CMD_GLUE, CMD_LICENSES,
+    CMD_PREPROCESS_HOST, CMD_REPL, CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK,
+    FLAG_DEV, FLAG_DOCS_ROOT, FLAG_LIB, FLAG_MAIN, FLAG_MIGRATE, FLAG_NO_COLOR, FLAG_NO_HEADER,
+    FLAG_NO_LINK, FLAG_OUTPUT, FLAG_PP_DYLIB, FLAG_PP_HOST, FLAG_PP_PLATFORM, FLAG_STDIN,
+    FLAG_STDOUT, FLAG_TARGET, FLAG_TIME, FLAG_VERBOSE, FLAG_WATCH, GLUE_DIR, GLUE_SPEC, ROC_FILE,
+    VERSION,
 };
 use roc_docs::generate_docs_html;
 use roc_error_macros::{internal_error, user_error};
@@ -89,6 +90,7 @@ fn main() -> io::Result<()> {
                 Ok(1)
             }
         }
+        Some((CMD_BENCH, matches)) => bench(matches, Triple::host().into()),
         Some((CMD_DEV, matches)) => {
             if matches.contains_id(ROC_FILE) {
                 build(
@@ -226,8 +228,12 @@ fn main() -> io::Result<()> {
             };
 
             let opt_main_path = matches.get_one::<PathBuf>(FLAG_MAIN);
+            let watch = matches.get_flag(FLAG_WATCH);
 
             match roc_file_path.extension().and_then(OsStr::to_str) {
+                Some("md") if watch => {
+                    user_error!("--{FLAG_WATCH} is not supported for .md files")
+                }
                 Some("md") => {
                     // Extract the blocks of roc code
                     let file = fs::File::open(roc_file_path.as_path())?;
@@ -272,13 +278,8 @@ fn main() -> io::Result<()> {
                                 exit_code = problems.exit_code();
                             }
 
-                            Err(LoadingProblem::FormattedReport(report, _)) => {
-                                print!("{report}");
-
-                                exit_code = 1;
-                            }
-                            Err(other) => {
-                                panic!("build_file failed with error:\n{other:?}");
+                            Err(problem) => {
+                                exit_code = handle_loading_problem(problem)?;
                             }
                         }
 
@@ -289,6 +290,12 @@ fn main() -> io::Result<()> {
 
                     Ok(exit_code)
                 }
+                _ if watch => check_watch(
+                    roc_file_path,
+                    opt_main_path.map(PathBuf::as_path),
+                    emit_timings,
+                    threading,
+                ),
                 _ => {
                     match check_file(
                         &arena,
@@ -304,14 +311,7 @@ fn main() -> io::Result<()> {
                             Ok(problems.exit_code())
                         }
 
-                        Err(LoadingProblem::FormattedReport(report, _)) => {
-                            print!("{report}");
-
-                            Ok(1)
-                        }
-                        Err(other) => {
-                            panic!("build_file failed with error:\n{other:?}");
-                        }
+                        Err(problem) => handle_loading_problem(problem),
                     }
                 }
             }
@@ -495,6 +495,7 @@ fn main() -> io::Result<()> {
             println!("roc {}", VERSION);
             Ok(0)
         }
+        Some((CMD_EXPLAIN, matches)) => explain(matches),
         Some((CMD_LICENSES, _)) => {
             println!("{LEGAL_DETAILS_TEXT}");
             Ok(0)
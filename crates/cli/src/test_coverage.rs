@@ -0,0 +1,330 @@
+//! Reports of which top-level defs and branches the expects of `roc test --coverage` ran.
+use roc_module::symbol::Interns;
+use roc_mono::coverage::{CoverageProbes, ProbeKind};
+use roc_region::all::{LineInfo, Region};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// How often the probed code of a module ran. Lines are 1-based here, as they are shown to people.
+#[derive(Debug)]
+pub struct ModuleCoverage {
+    path: PathBuf,
+    defs: Vec<CoveredDef>,
+    /// The `when` and `if` expressions, in the order they appear in.
+    decisions: Vec<CoveredDecision>,
+}
+
+#[derive(Debug)]
+struct CoveredDef {
+    name: String,
+    line: u32,
+    source: String,
+    hits: u64,
+}
+
+#[derive(Debug)]
+struct CoveredDecision {
+    line: u32,
+    branches: Vec<CoveredBranch>,
+}
+
+#[derive(Debug)]
+struct CoveredBranch {
+    line: u32,
+    source: String,
+    hits: u64,
+}
+
+impl ModuleCoverage {
+    /// `hits` has how often each of the `probes` ran, in the same order.
+    pub fn new(
+        path: &Path,
+        source: &str,
+        interns: &Interns,
+        probes: CoverageProbes,
+        hits: &[u64],
+    ) -> Self {
+        let line_info = LineInfo::new(source);
+        let line_of = |region: Region| line_info.convert_pos(region.start()).line + 1;
+        let source_at = |region: Region| {
+            source[region.start().offset as usize..]
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+
+        let mut defs = Vec::new();
+        let mut decisions: Vec<(Region, CoveredDecision)> = Vec::new();
+
+        for (probe, hits) in probes.probes.iter().zip(hits.iter().copied()) {
+            let decision = match probe.kind {
+                ProbeKind::Def(symbol) => {
+                    defs.push(CoveredDef {
+                        name: symbol.as_str(interns).to_string(),
+                        line: line_of(probe.region),
+                        source: source_at(probe.region),
+                        hits,
+                    });
+                    continue;
+                }
+                ProbeKind::WhenBranch(decision) | ProbeKind::IfBranch(decision) => decision,
+            };
+
+            let branch = CoveredBranch {
+                line: line_of(probe.region),
+                source: source_at(probe.region),
+                hits,
+            };
+
+            match decisions.iter_mut().find(|(region, _)| *region == decision) {
+                Some((_, covered)) => covered.branches.push(branch),
+                None => decisions.push((
+                    decision,
+                    CoveredDecision {
+                        line: line_of(decision),
+                        branches: vec![branch],
+                    },
+                )),
+            }
+        }
+
+        decisions.sort_by_key(|(region, _)| *region);
+
+        Self {
+            path: path.to_path_buf(),
+            defs,
+            decisions: decisions
+                .into_iter()
+                .map(|(_, decision)| decision)
+                .collect(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Adds the hits of `other`, which has the same probes because it's the same module.
+    pub fn merge(&mut self, other: Self) {
+        for (def, other) in self.defs.iter_mut().zip(other.defs) {
+            def.hits += other.hits;
+        }
+
+        let branches = self
+            .decisions
+            .iter_mut()
+            .flat_map(|d| d.branches.iter_mut());
+        let other_branches = other.decisions.into_iter().flat_map(|d| d.branches);
+
+        for (branch, other) in branches.zip(other_branches) {
+            branch.hits += other.hits;
+        }
+    }
+
+    fn branches(&self) -> impl Iterator<Item = &CoveredBranch> {
+        self.decisions
+            .iter()
+            .flat_map(|decision| decision.branches.iter())
+    }
+}
+
+/// Writes the coverage of every module as an lcov tracefile, which most coverage tools read.
+pub fn write_lcov(path: &Path, modules: &[ModuleCoverage]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        if !dir.as_os_str().is_empty() {
+            std::fs::create_dir_all(dir)?;
+        }
+    }
+
+    std::fs::write(path, lcov(modules))
+}
+
+fn lcov(modules: &[ModuleCoverage]) -> String {
+    let mut lcov = String::new();
+
+    for module in modules {
+        let _ = writeln!(lcov, "TN:\nSF:{}", module.path.display());
+
+        for def in module.defs.iter() {
+            let _ = writeln!(lcov, "FN:{},{}", def.line, def.name);
+        }
+
+        for def in module.defs.iter() {
+            let _ = writeln!(lcov, "FNDA:{},{}", def.hits, def.name);
+        }
+
+        let defs_hit = module.defs.iter().filter(|def| def.hits > 0).count();
+        let _ = writeln!(lcov, "FNF:{}\nFNH:{defs_hit}", module.defs.len());
+
+        for (block, decision) in module.decisions.iter().enumerate() {
+            // a decision which was never reached has no counts, rather than counts of 0
+            let reached = decision.branches.iter().any(|branch| branch.hits > 0);
+
+            for (index, branch) in decision.branches.iter().enumerate() {
+                let taken = if reached {
+                    branch.hits.to_string()
+                } else {
+                    "-".to_string()
+                };
+
+                let _ = writeln!(lcov, "BRDA:{},{block},{index},{taken}", decision.line);
+            }
+        }
+
+        let branch_count = module.branches().count();
+        let branches_hit = module.branches().filter(|branch| branch.hits > 0).count();
+        let _ = writeln!(lcov, "BRF:{branch_count}\nBRH:{branches_hit}");
+
+        // a line ran if anything probed on it ran
+        let mut lines: BTreeMap<u32, u64> = BTreeMap::new();
+        let probed = (module.defs.iter().map(|def| (def.line, def.hits)))
+            .chain(module.branches().map(|branch| (branch.line, branch.hits)));
+
+        for (line, hits) in probed {
+            let entry = lines.entry(line).or_default();
+            *entry = (*entry).max(hits);
+        }
+
+        for (line, hits) in lines.iter() {
+            let _ = writeln!(lcov, "DA:{line},{hits}");
+        }
+
+        let lines_hit = lines.values().filter(|hits| **hits > 0).count();
+        let _ = writeln!(lcov, "LF:{}\nLH:{lines_hit}", lines.len());
+
+        lcov.push_str("end_of_record\n");
+    }
+
+    lcov
+}
+
+/// A summary of the coverage of every module, listing the defs and branches which never ran.
+pub fn coverage_summary(modules: &[ModuleCoverage]) -> String {
+    let mut summary = String::from("\nCoverage:\n");
+
+    for module in modules {
+        let file = module
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        let defs_hit = module.defs.iter().filter(|def| def.hits > 0).count();
+        let branches_hit = module.branches().filter(|branch| branch.hits > 0).count();
+
+        let _ = writeln!(
+            summary,
+            "\n{file}: {defs_hit} of {} defs and {branches_hit} of {} branches ran.",
+            module.defs.len(),
+            module.branches().count(),
+        );
+
+        let mut missed: Vec<(u32, &str, &str)> = module
+            .defs
+            .iter()
+            .filter(|def| def.hits == 0)
+            .map(|def| (def.line, "def never ran", def.source.as_str()))
+            .chain(
+                module
+                    .branches()
+                    .filter(|branch| branch.hits == 0)
+                    .map(|branch| (branch.line, "branch never taken", branch.source.as_str())),
+            )
+            .collect();
+
+        missed.sort();
+
+        for (line, what, source) in missed {
+            let location = format!("line {line}, {what}:");
+            let _ = writeln!(summary, "    {location:<32} {source}");
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn covered() -> ModuleCoverage {
+        ModuleCoverage {
+            path: PathBuf::from("src/Color.roc"),
+            defs: vec![
+                CoveredDef {
+                    name: "to_str".to_string(),
+                    line: 3,
+                    source: "to_str = |color|".to_string(),
+                    hits: 2,
+                },
+                CoveredDef {
+                    name: "mix".to_string(),
+                    line: 9,
+                    source: "mix = |a, b|".to_string(),
+                    hits: 0,
+                },
+            ],
+            decisions: vec![
+                CoveredDecision {
+                    line: 4,
+                    branches: vec![
+                        CoveredBranch {
+                            line: 5,
+                            source: "Red -> \"red\"".to_string(),
+                            hits: 2,
+                        },
+                        CoveredBranch {
+                            line: 6,
+                            source: "Green -> \"green\"".to_string(),
+                            hits: 0,
+                        },
+                    ],
+                },
+                CoveredDecision {
+                    line: 10,
+                    branches: vec![
+                        CoveredBranch {
+                            line: 11,
+                            source: "a".to_string(),
+                            hits: 0,
+                        },
+                        CoveredBranch {
+                            line: 13,
+                            source: "b".to_string(),
+                            hits: 0,
+                        },
+                    ],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn lcov_records() {
+        let lcov = lcov(&[covered()]);
+
+        assert!(lcov.starts_with("TN:\nSF:src/Color.roc\n"));
+        assert!(lcov.contains("FN:3,to_str\nFN:9,mix\nFNDA:2,to_str\nFNDA:0,mix\nFNF:2\nFNH:1\n"));
+        assert!(lcov.contains("BRDA:4,0,0,2\nBRDA:4,0,1,0\nBRDA:10,1,0,-\nBRDA:10,1,1,-\n"));
+        assert!(lcov.contains("BRF:4\nBRH:1\n"));
+        assert!(lcov.contains("DA:3,2\nDA:5,2\nDA:6,0\nDA:9,0\nDA:11,0\nDA:13,0\nLF:6\nLH:2\n"));
+        assert!(lcov.ends_with("end_of_record\n"));
+    }
+
+    #[test]
+    fn summary_lists_what_never_ran() {
+        let summary = coverage_summary(&[covered()]);
+
+        assert!(summary.contains("Color.roc: 1 of 2 defs and 1 of 4 branches ran."));
+        assert!(summary.contains("line 6, branch never taken:"));
+        assert!(summary.contains("line 9, def never ran:"));
+        assert!(!summary.contains("line 5,"));
+
+        let line_6 = summary.find("line 6,").unwrap();
+        let line_11 = summary.find("line 11,").unwrap();
+        assert!(line_6 < line_11);
+    }
+}
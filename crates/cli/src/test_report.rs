@@ -0,0 +1,305 @@
+//! Machine-readable reports of the expects `roc test` ran, for CI.
+use roc_region::all::{LineColumn, LineColumnRegion, LineInfo};
+use roc_repl_expect::run::ExpectResult;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Junit,
+    Json,
+}
+
+/// A report to write, as given by `--report <format>=<path>`.
+#[derive(Debug, Clone)]
+pub struct ReportRequest {
+    pub format: ReportFormat,
+    pub path: PathBuf,
+}
+
+/// Parses the value of `--report`, e.g. `junit=target/roc-tests.xml`.
+pub fn parse_report_request(value: &str) -> Result<ReportRequest, String> {
+    let Some((format, path)) = value.split_once('=') else {
+        return Err(format!(
+            "expected <format>=<path>, like junit=report.xml, but got `{value}`"
+        ));
+    };
+
+    let format = match format {
+        "junit" => ReportFormat::Junit,
+        "json" => ReportFormat::Json,
+        other => {
+            return Err(format!(
+                "unknown report format `{other}`, the supported ones are junit and json"
+            ))
+        }
+    };
+
+    if path.is_empty() {
+        return Err(format!("no path was given for the {value} report"));
+    }
+
+    Ok(ReportRequest {
+        format,
+        path: PathBuf::from(path),
+    })
+}
+
+/// An expect which ran, along with where it is.
+#[derive(Debug)]
+pub struct ReportedExpect {
+    module: String,
+    path: PathBuf,
+    region: LineColumnRegion,
+    duration: Duration,
+    /// The report of the failure, without any terminal colors.
+    failure: Option<String>,
+}
+
+impl ReportedExpect {
+    pub fn new(module: &str, path: &Path, source: &str, result: ExpectResult) -> Self {
+        let failure = result.failure.map(|failure| {
+            let stripped = strip_ansi_escapes::strip(failure.as_bytes())
+                .unwrap_or_else(|_| failure.clone().into_bytes());
+
+            String::from_utf8_lossy(&stripped).trim().to_string()
+        });
+
+        Self {
+            module: module.to_string(),
+            path: path.to_path_buf(),
+            region: LineInfo::new(source).convert_region(result.region),
+            duration: result.duration,
+            failure,
+        }
+    }
+
+    /// Lines are 1-based here, as they are shown to people.
+    fn line(&self) -> u32 {
+        self.region.start.line + 1
+    }
+
+    fn name(&self) -> String {
+        let file = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        format!("expect at {file}:{}", self.line())
+    }
+}
+
+/// Writes the reports of all the expects that ran, ordered by module and line.
+pub fn write_reports(
+    requests: &[ReportRequest],
+    mut expects: Vec<ReportedExpect>,
+) -> std::io::Result<()> {
+    expects.sort_by(|a, b| {
+        (&a.module, &a.path, a.region.start).cmp(&(&b.module, &b.path, b.region.start))
+    });
+
+    for request in requests {
+        let contents = match request.format {
+            ReportFormat::Junit => junit_report(&expects),
+            ReportFormat::Json => json_report(&expects),
+        };
+
+        if let Some(dir) = request.path.parent() {
+            if !dir.as_os_str().is_empty() {
+                std::fs::create_dir_all(dir)?;
+            }
+        }
+
+        std::fs::write(&request.path, contents)?;
+    }
+
+    Ok(())
+}
+
+fn junit_report(expects: &[ReportedExpect]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    let total_failures = expects.iter().filter(|e| e.failure.is_some()).count();
+    let total_time: Duration = expects.iter().map(|e| e.duration).sum();
+
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"roc test\" tests=\"{}\" failures=\"{total_failures}\" time=\"{}\">",
+        expects.len(),
+        total_time.as_secs_f64()
+    );
+
+    // Every module is a test suite, and the expects are sorted by module
+    for suite in expects.chunk_by(|a, b| a.module == b.module) {
+        let module = xml_escape(&suite[0].module);
+        let failures = suite.iter().filter(|e| e.failure.is_some()).count();
+        let time: Duration = suite.iter().map(|e| e.duration).sum();
+
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{module}\" tests=\"{}\" failures=\"{failures}\" time=\"{}\">",
+            suite.len(),
+            time.as_secs_f64()
+        );
+
+        for expect in suite {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{module}\" file=\"{}\" line=\"{}\" time=\"{}\"",
+                xml_escape(&expect.name()),
+                xml_escape(&expect.path.to_string_lossy()),
+                expect.line(),
+                expect.duration.as_secs_f64()
+            );
+
+            match &expect.failure {
+                None => xml.push_str("/>\n"),
+                Some(failure) => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n      <failure message=\"expect failed\">{}</failure>\n    </testcase>",
+                        xml_escape(failure)
+                    );
+                }
+            }
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+
+    xml
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Other control characters aren't allowed in XML 1.0
+            '\n' | '\r' | '\t' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn json_report(expects: &[ReportedExpect]) -> String {
+    let entries: Vec<_> = expects
+        .iter()
+        .map(|expect| {
+            serde_json::json!({
+                "module": expect.module,
+                "path": expect.path.to_string_lossy(),
+                "region": {
+                    "start": position(expect.region.start),
+                    "end": position(expect.region.end),
+                },
+                "duration_ms": expect.duration.as_secs_f64() * 1000.0,
+                "passed": expect.failure.is_none(),
+                "failure": expect.failure,
+            })
+        })
+        .collect();
+
+    let failed = expects.iter().filter(|e| e.failure.is_some()).count();
+
+    let report = serde_json::json!({
+        "passed": expects.len() - failed,
+        "failed": failed,
+        "expects": entries,
+    });
+
+    let mut json = serde_json::to_string_pretty(&report).unwrap();
+    json.push('\n');
+
+    json
+}
+
+/// Lines and columns are 1-based here, as they are shown to people.
+fn position(pos: LineColumn) -> serde_json::Value {
+    serde_json::json!({
+        "line": pos.line + 1,
+        "column": pos.column + 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reported(module: &str, line: u32, failure: Option<&str>) -> ReportedExpect {
+        ReportedExpect {
+            module: module.to_string(),
+            path: PathBuf::from(format!("src/{module}.roc")),
+            region: LineColumnRegion::new(
+                LineColumn { line, column: 0 },
+                LineColumn { line, column: 13 },
+            ),
+            duration: Duration::from_millis(2),
+            failure: failure.map(String::from),
+        }
+    }
+
+    #[test]
+    fn parse_requests() {
+        let request = parse_report_request("junit=out/report.xml").unwrap();
+        assert_eq!(request.format, ReportFormat::Junit);
+        assert_eq!(request.path, PathBuf::from("out/report.xml"));
+
+        let request = parse_report_request("json=report.json").unwrap();
+        assert_eq!(request.format, ReportFormat::Json);
+
+        assert!(parse_report_request("report.xml").is_err());
+        assert!(parse_report_request("tap=report.tap").is_err());
+        assert!(parse_report_request("json=").is_err());
+    }
+
+    #[test]
+    fn junit() {
+        let expects = [
+            reported(
+                "Bar",
+                4,
+                Some("── EXPECT FAILED ──\n\nx : Num *\nx = 1 & <2>"),
+            ),
+            reported("Bar", 9, None),
+            reported("Foo", 2, None),
+        ];
+
+        let xml = junit_report(&expects);
+
+        assert!(xml.contains("<testsuites name=\"roc test\" tests=\"3\" failures=\"1\""));
+        assert!(xml.contains("<testsuite name=\"Bar\" tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("<testsuite name=\"Foo\" tests=\"1\" failures=\"0\""));
+        assert!(xml.contains(
+            "<testcase name=\"expect at Bar.roc:10\" classname=\"Bar\" file=\"src/Bar.roc\" line=\"10\""
+        ));
+        assert!(xml.contains("x = 1 &amp; &lt;2&gt;</failure>"));
+    }
+
+    #[test]
+    fn json() {
+        let expects = [reported("Foo", 2, Some("failed")), reported("Foo", 6, None)];
+
+        let report: serde_json::Value = serde_json::from_str(&json_report(&expects)).unwrap();
+
+        assert_eq!(report["passed"], 1);
+        assert_eq!(report["failed"], 1);
+        assert_eq!(report["expects"][0]["module"], "Foo");
+        assert_eq!(report["expects"][0]["region"]["start"]["line"], 3);
+        assert_eq!(report["expects"][0]["failure"], "failed");
+        assert_eq!(report["expects"][1]["passed"], true);
+        assert!(report["expects"][1]["failure"].is_null());
+    }
+}
@@ -0,0 +1,290 @@
+//! Re-running commands whenever the files they depend on change.
+use bumpalo::Bump;
+use roc_build::link::host_source_paths;
+use roc_build::program::{
+    check_file_incremental, handle_error_module, handle_loading_problem, BuildFileError, BuiltFile,
+    CheckedFile,
+};
+use roc_collections::{MutMap, MutSet};
+use roc_load::{SharedIncrementalCache, Threading};
+use roc_packaging::cache::{self, RocCacheDir};
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the watched files are looked at.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How long to wait for the rest of a change once one is noticed, since editors and formatters
+/// may write a file in several steps, or several files at once.
+const SETTLE_TIME: Duration = Duration::from_millis(50);
+
+/// How long a program gets to shut down by itself before it's killed, so that e.g. a server can
+/// close its connections.
+#[cfg(unix)]
+const STOP_GRACE_TIME: Duration = Duration::from_secs(2);
+
+/// Watches files for changes by polling their modification times.
+///
+/// Other `.roc` files in the directories of the watched ones are watched too, so that adding or
+/// removing a module counts as a change. That covers modules an import couldn't find yet.
+pub(crate) struct FileWatcher {
+    paths: Vec<PathBuf>,
+    snapshot: MutMap<PathBuf, Option<SystemTime>>,
+}
+
+impl FileWatcher {
+    pub(crate) fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let paths: Vec<_> = paths.into_iter().collect();
+        let snapshot = take_snapshot(&paths);
+
+        Self { paths, snapshot }
+    }
+
+    /// Blocks until one of the watched files changes, then watches the same files again.
+    pub(crate) fn wait_for_change(&mut self) {
+        while !self.poll_for_change() {}
+    }
+
+    /// Waits for a while, then tells whether one of the watched files changed in the meantime.
+    pub(crate) fn poll_for_change(&mut self) -> bool {
+        thread::sleep(POLL_INTERVAL);
+
+        if take_snapshot(&self.paths) == self.snapshot {
+            return false;
+        }
+
+        thread::sleep(SETTLE_TIME);
+        self.snapshot = take_snapshot(&self.paths);
+
+        true
+    }
+}
+
+fn take_snapshot(paths: &[PathBuf]) -> MutMap<PathBuf, Option<SystemTime>> {
+    let mut snapshot = MutMap::default();
+
+    for path in paths {
+        snapshot
+            .entry(path.clone())
+            .or_insert_with(|| modified(path));
+    }
+
+    // Many watched modules usually share a directory, which only needs to be read once
+    let dirs: MutSet<&Path> = paths.iter().filter_map(|path| path.parent()).collect();
+
+    for dir in dirs {
+        let siblings = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|sibling| sibling.extension().is_some_and(|ext| ext == "roc"));
+
+        for sibling in siblings {
+            if !snapshot.contains_key(&sibling) {
+                let modified = modified(&sibling);
+                snapshot.insert(sibling, modified);
+            }
+        }
+    }
+
+    snapshot
+}
+
+/// A file which doesn't exist (anymore) has no modification time.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn clear_screen() {
+    // Clear the screen and move the cursor to its top left
+    print!("\x1B[2J\x1B[1;1H");
+    let _ = io::stdout().flush();
+}
+
+/// Checks the file like `roc check` does, then checks it again whenever one of the modules it
+/// loaded changes. Modules which didn't change since the previous check are not solved again.
+///
+/// This only returns if something goes wrong.
+pub fn check_watch(
+    roc_file_path: &Path,
+    opt_main_path: Option<&Path>,
+    emit_timings: bool,
+    threading: Threading,
+) -> io::Result<i32> {
+    let cache = SharedIncrementalCache::default();
+    // The root modules are watched even if loading them fails
+    let roots: Vec<_> = std::iter::once(roc_file_path)
+        .chain(opt_main_path)
+        .map(Path::to_path_buf)
+        .collect();
+    let mut module_paths = roots.clone();
+
+    loop {
+        clear_screen();
+
+        let arena = Bump::new();
+        let result = check_file_incremental(
+            &arena,
+            roc_file_path.to_path_buf(),
+            opt_main_path.map(Path::to_path_buf),
+            emit_timings,
+            RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
+            threading,
+            cache.clone(),
+        );
+
+        match result {
+            Ok(CheckedFile {
+                problems,
+                total_time,
+                module_paths: loaded,
+            }) => {
+                problems.print_error_warning_count(total_time);
+                println!(".\n");
+
+                module_paths = roots.iter().cloned().chain(loaded).collect();
+            }
+            // A module which doesn't parse keeps the modules loaded before it from being known,
+            // so the ones from the last successful check stay watched.
+            Err(problem) => {
+                handle_loading_problem(problem)?;
+            }
+        }
+
+        // Files changing from now on are noticed, even before the message is seen
+        let mut watcher = FileWatcher::new(module_paths.iter().cloned());
+
+        println!("Watching for changes…");
+        io::stdout().flush()?;
+
+        watcher.wait_for_change();
+    }
+}
+
+/// Builds the app, then builds it again whenever one of the files it was built from changes:
+/// its modules, those of the local packages and platform it uses, and the sources of the
+/// platform's host if `watch_host` is set. Packages downloaded to the cache never change, so
+/// they aren't watched.
+///
+/// With `run_args`, the program is run after every build, and stopped before the next one starts.
+///
+/// This only returns if something goes wrong.
+pub(crate) fn build_watch<F>(
+    roc_file_path: &Path,
+    run_args: Option<&[OsString]>,
+    watch_host: bool,
+    mut build: F,
+) -> io::Result<i32>
+where
+    F: for<'a> FnMut(&'a Bump) -> Result<BuiltFile<'a>, BuildFileError<'a>>,
+{
+    let packages_dir = cache::roc_cache_packages_dir();
+    let is_local = |path: &PathBuf| !path.starts_with(&packages_dir);
+
+    // The app is watched even if loading it fails
+    let mut watched_paths = vec![roc_file_path.to_path_buf()];
+
+    loop {
+        clear_screen();
+
+        let arena = Bump::new();
+        let mut running = None;
+
+        match build(&arena) {
+            Ok(BuiltFile {
+                binary_path,
+                problems,
+                total_time,
+                module_paths,
+                platform_main_roc,
+                ..
+            }) => {
+                watched_paths = std::iter::once(roc_file_path.to_path_buf())
+                    .chain(module_paths.into_iter().filter(is_local))
+                    .collect();
+
+                if watch_host {
+                    watched_paths.extend(host_source_paths(&platform_main_roc));
+                }
+
+                problems.print_error_warning_count(total_time);
+
+                match run_args {
+                    None => println!(" while building:\n\n    {}\n", binary_path.display()),
+                    Some(_) if problems.fatally_errored => {
+                        println!(".\n\nCannot run program due to fatal error…\n");
+                    }
+                    Some(args) => {
+                        println!(
+                            ".\n\nRunning program, until a file changes…\n\n\x1B[36m{}\x1B[39m",
+                            "─".repeat(80)
+                        );
+                        io::stdout().flush()?;
+
+                        running = Some(Command::new(&binary_path).args(args).spawn()?);
+                    }
+                }
+            }
+            Err(BuildFileError::ErrorModule { module, total_time }) => {
+                watched_paths = std::iter::once(roc_file_path.to_path_buf())
+                    .chain(module.sources.values().map(|(path, _)| path.clone()))
+                    .filter(is_local)
+                    .collect();
+
+                handle_error_module(module, total_time)?;
+                println!(".\n");
+            }
+            // A module which doesn't parse keeps the modules loaded before it from being known,
+            // so the ones from the last successful build stay watched.
+            Err(BuildFileError::LoadingProblem(problem)) => {
+                handle_loading_problem(problem)?;
+            }
+        }
+
+        let mut watcher = FileWatcher::new(watched_paths.iter().cloned());
+
+        if running.is_none() {
+            println!("Watching for changes…");
+        }
+        io::stdout().flush()?;
+
+        while !watcher.poll_for_change() {
+            if let Some(status) = running.as_mut().map(Child::try_wait).transpose()?.flatten() {
+                println!("\nThe program exited ({status}). Watching for changes…");
+                running = None;
+            }
+        }
+
+        if let Some(child) = running {
+            stop(child)?;
+        }
+    }
+}
+
+/// Asks the program to stop, and kills it if it doesn't in time.
+fn stop(mut child: Child) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGTERM) };
+
+        let asked_at = std::time::Instant::now();
+        while asked_at.elapsed() < STOP_GRACE_TIME {
+            if child.try_wait()?.is_some() {
+                return Ok(());
+            }
+
+            thread::sleep(SETTLE_TIME);
+        }
+    }
+
+    child.kill()?;
+    child.wait()?;
+
+    Ok(())
+}
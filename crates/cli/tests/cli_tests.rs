@@ -11,7 +11,7 @@ mod cli_tests {
     use cli_test_utils::exec_cli::ExecCli;
     use cli_test_utils::helpers::{dir_from_root, file_from_root};
     use const_format::concatcp;
-    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_FORMAT, CMD_TEST};
+    use roc_cli::{CMD_BENCH, CMD_BUILD, CMD_CHECK, CMD_EXPLAIN, CMD_FORMAT, CMD_TEST};
 
     #[cfg(all(unix, not(target_os = "macos")))]
     const ALLOW_VALGRIND: bool = true;
@@ -235,6 +235,355 @@ mod cli_tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_timeout() {
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root("crates/cli/tests/test-projects/expects", "Timeout.roc"),
+        )
+        .arg("--timeout-ms=500");
+
+        let cli_test_out = cli_test.run();
+        cli_test_out.assert_nonzero_exit();
+
+        let output = cli_test_out.normalize_stdout_and_stderr();
+        assert!(output.contains("EXPECT TIMED OUT"), "{output}");
+        cli_test_out.assert_stdout_and_stderr_ends_with(
+            "1 failed and 1 passed in <ignored for test> ms.\n",
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_crash() {
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root("crates/cli/tests/test-projects/expects", "Crash.roc"),
+        );
+
+        let cli_test_out = cli_test.run();
+        cli_test_out.assert_nonzero_exit();
+
+        // the stack overflow only takes down the process of its own expect
+        let output = cli_test_out.normalize_stdout_and_stderr();
+        assert!(output.contains("EXPECT PANICKED"), "{output}");
+        assert!(output.contains("The process was stopped by"), "{output}");
+        cli_test_out.assert_stdout_and_stderr_ends_with(
+            "1 failed and 2 passed in <ignored for test> ms.\n",
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_parallel() {
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root("crates/cli/tests/test-projects/expects", "Parallel.roc"),
+        )
+        .add_args(["--timeout-ms=1000", "--max-threads=4", "--verbose"]);
+
+        let started = std::time::Instant::now();
+        let cli_test_out = cli_test.run();
+        let total_time = started.elapsed();
+        cli_test_out.assert_nonzero_exit();
+
+        // every failure is reported with the values of its own expect, in the order of the module
+        let output = cli_test_out.normalize_stdout_and_stderr();
+        assert_eq!(output.matches("EXPECT TIMED OUT").count(), 4, "{output}");
+        let first = output.find("first = 1").expect(&output);
+        let second = output.find("second = 2").expect(&output);
+        assert!(first < second, "{output}");
+        assert!(!output.contains("first = 2"), "{output}");
+        assert!(
+            output.contains("6 failed and 1 passed in <ignored for test> ms."),
+            "{output}"
+        );
+
+        // the expects which never finish time out together, rather than one after the other
+        let compile_ms: u64 = cli_test_out
+            .stdout
+            .split("Compiled in ")
+            .nth(1)
+            .and_then(|rest| rest.split(' ').next())
+            .and_then(|ms| ms.parse().ok())
+            .expect(&output);
+        let run_time = total_time.saturating_sub(std::time::Duration::from_millis(compile_ms));
+        assert!(
+            run_time < std::time::Duration::from_secs(3),
+            "running the expects took {run_time:?}"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_property() {
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root("crates/cli/tests/test-projects/expects", "Property.roc"),
+        )
+        .arg("--seed=1");
+
+        let cli_test_out = cli_test.run();
+        cli_test_out.assert_nonzero_exit();
+
+        let output = cli_test_out.normalize_stdout_and_stderr();
+        assert!(output.contains("PROPERTY FALSIFIED"), "{output}");
+        assert!(output.contains("n = 1000"), "{output}");
+        cli_test_out.assert_stdout_and_stderr_ends_with(
+            "1 failed and 2 passed in <ignored for test> ms.\n",
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_coverage() {
+        let dir = tempfile::tempdir().unwrap();
+        let lcov_path = dir.path().join("coverage.info");
+
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root("crates/cli/tests/test-projects/expects", "Coverage.roc"),
+        )
+        .arg(format!("--coverage={}", lcov_path.display()));
+
+        let cli_test_out = cli_test.run();
+        cli_test_out.assert_clean_success();
+
+        let output = cli_test_out.normalize_stdout_and_stderr();
+        assert!(
+            output.contains("Coverage.roc: 1 of 2 defs and 2 of 5 branches ran."),
+            "{output}"
+        );
+        assert!(output.contains("line 7, branch never taken:"), "{output}");
+        assert!(output.contains("line 9, def never ran:"), "{output}");
+
+        let lcov = std::fs::read_to_string(&lcov_path).unwrap();
+        assert!(lcov.contains("FNDA:2,describe\nFNDA:0,sign\n"), "{lcov}");
+        assert!(
+            lcov.contains("BRDA:4,0,0,1\nBRDA:4,0,1,1\nBRDA:4,0,2,0\n"),
+            "{lcov}"
+        );
+        assert!(lcov.contains("BRDA:10,1,0,-\nBRDA:10,1,1,-\n"), "{lcov}");
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_snapshot() {
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root("crates/cli/tests/test-projects/expects", "Snapshot.roc"),
+        );
+
+        let cli_test_out = cli_test.run();
+        cli_test_out.assert_nonzero_exit();
+
+        let output = cli_test_out.normalize_stdout_and_stderr();
+        assert!(output.contains("SNAPSHOT CHANGED"), "{output}");
+        assert!(output.contains("- [2, 4, 7]"), "{output}");
+        assert!(output.contains("+ [2, 4, 6]"), "{output}");
+        cli_test_out.assert_stdout_and_stderr_ends_with(
+            "1 failed and 1 passed in <ignored for test> ms.\n",
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_snapshot_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let module_path = dir.path().join("Snapshot.roc");
+        std::fs::copy(
+            file_from_root("crates/cli/tests/test-projects/expects", "Snapshot.roc"),
+            &module_path,
+        )
+        .unwrap();
+
+        let missing_out = ExecCli::new(CMD_TEST, module_path.clone()).run();
+        missing_out.assert_nonzero_exit();
+
+        let output = missing_out.normalize_stdout_and_stderr();
+        assert!(output.contains("SNAPSHOT MISSING"), "{output}");
+
+        let update_out = ExecCli::new(CMD_TEST, module_path.clone())
+            .arg("--update-snapshots")
+            .run();
+        update_out.assert_clean_success();
+
+        let snapshots = dir.path().join("snapshots");
+        assert_eq!(
+            std::fs::read_to_string(snapshots.join("Snapshot__greeting.snap")).unwrap(),
+            "\"Hello, World\"\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(snapshots.join("Snapshot__doubled.snap")).unwrap(),
+            "[2, 4, 6]\n"
+        );
+
+        let stored_out = ExecCli::new(CMD_TEST, module_path).run();
+        stored_out.assert_clean_success();
+        stored_out.assert_stdout_and_stderr_ends_with(
+            "0 failed and 2 passed in <ignored for test> ms.\n",
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_snapshot_name_clash() {
+        let cli_test = ExecCli::new(
+            CMD_TEST,
+            file_from_root(
+                "crates/cli/tests/test-projects/expects",
+                "SnapshotClash.roc",
+            ),
+        );
+
+        let cli_test_out = cli_test.run();
+        cli_test_out.assert_nonzero_exit();
+
+        let output = cli_test_out.normalize_stdout_and_stderr();
+        assert_eq!(output.matches("SNAPSHOT NAME CLASH").count(), 2, "{output}");
+        assert!(output.contains("SnapshotClash__home_page.snap"), "{output}");
+        cli_test_out.assert_stdout_and_stderr_ends_with(
+            "2 failed and 1 passed in <ignored for test> ms.\n",
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn bench_with_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let json_path = dir.path().join("bench.json");
+
+        let bench = |extra_args: &[&std::ffi::OsStr]| {
+            ExecCli::new(
+                CMD_BENCH,
+                file_from_root("crates/cli/tests/test-projects/expects", "Bench.roc"),
+            )
+            .add_args(["--warmup-ms", "1", "--samples", "3"])
+            .add_args(extra_args)
+            .run()
+        };
+
+        let first_out = bench(&["--json".as_ref(), json_path.as_os_str()]);
+        first_out.assert_clean_success();
+
+        let output = first_out.normalize_stdout_and_stderr();
+        assert!(output.contains("sum of squares"), "{output}");
+        assert!(output.contains("join"), "{output}");
+
+        let json = std::fs::read_to_string(&json_path).unwrap();
+        assert!(json.contains("\"name\": \"sum of squares\""), "{json}");
+        assert!(json.contains("\"mean_ns\""), "{json}");
+
+        let second_out = bench(&["--baseline".as_ref(), json_path.as_os_str()]);
+        second_out.assert_clean_success();
+
+        let output = second_out.normalize_stdout_and_stderr();
+        assert!(output.contains("change"), "{output}");
+        assert!(output.contains('%'), "{output}");
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn bench_with_crash() {
+        let bench_out = ExecCli::new(
+            CMD_BENCH,
+            file_from_root("crates/cli/tests/test-projects/expects", "BenchCrash.roc"),
+        )
+        .add_args(["--warmup-ms", "1", "--samples", "3"])
+        .run();
+        bench_out.assert_nonzero_exit();
+
+        // the benchmark after the one which crashed is still timed
+        let output = bench_out.normalize_stdout_and_stderr();
+        assert!(output.contains("EXPECT PANICKED"), "{output}");
+        assert!(
+            output.lines().any(|line| line.starts_with("sum ")),
+            "{output}"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn bench_without_benchmarks() {
+        let bench_out = ExecCli::new(
+            CMD_BENCH,
+            file_from_root("crates/cli/tests/test-projects/expects", "Crash.roc"),
+        )
+        .run();
+
+        assert_eq!(bench_out.status.code(), Some(1), "{bench_out}");
+        assert!(
+            bench_out.stdout.contains("No benchmarks were found."),
+            "{bench_out}"
+        );
+    }
+
+    #[test]
+    fn explain_error_code() {
+        // codes are looked up regardless of case
+        let explain_out = ExecCli::new(CMD_EXPLAIN, "e0301".into()).run();
+        explain_out.assert_clean_success();
+
+        let output = explain_out.normalize_stdout_and_stderr();
+        assert!(output.contains("── TYPE MISMATCH [E0301] ──"), "{output}");
+        assert!(output.contains("total = 1 + \"2\""), "{output}");
+
+        let unknown_out = ExecCli::new(CMD_EXPLAIN, "E9999".into()).run();
+        assert!(!unknown_out.status.success(), "{unknown_out}");
+        assert!(unknown_out.stderr.contains("E9999"), "{unknown_out}");
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn check_watch_rechecks_after_edit() {
+        let dir = tempfile::tempdir().unwrap();
+        let module_path = dir.path().join("Greeting.roc");
+        let write_greeting = |body: &str| {
+            let source = format!("module [greeting]\n\ngreeting : Str\ngreeting = {body}\n");
+            std::fs::write(&module_path, source).unwrap();
+        };
+
+        write_greeting("1");
+
+        let mut watched = ExecCli::new(CMD_CHECK, module_path.clone()).watch();
+
+        let output = watched.wait_for("Watching for changes");
+        assert!(output.contains("TYPE MISMATCH"), "{output}");
+        assert!(output.contains("1 error and 0 warnings found"), "{output}");
+
+        write_greeting("\"hello\"");
+
+        let output = watched.wait_for("Watching for changes");
+        assert!(!output.contains("TYPE MISMATCH"), "{output}");
+        assert!(output.contains("0 errors and 0 warnings found"), "{output}");
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn build_watch_uses_dev_backend_by_default() {
+        // LLVM IR can only be emitted by the LLVM backend, so this is refused before anything
+        // is built or watched
+        let build_out = ExecCli::new(
+            CMD_BUILD,
+            file_from_root(
+                "crates/cli/tests/test-projects/test-platform-simple-zig",
+                "app.roc",
+            ),
+        )
+        .arg("--watch")
+        .arg("--emit-llvm-ir")
+        .run();
+
+        build_out.assert_nonzero_exit();
+        assert!(
+            build_out
+                .stderr
+                .contains("Cannot emit llvm ir while using a dev backend."),
+            "{build_out}"
+        );
+    }
+
     #[test]
     #[cfg_attr(windows, ignore)]
     fn transitive_expects_verbose() {
@@ -252,6 +601,29 @@ mod cli_tests {
         insta::assert_snapshot!(cli_test_out.normalize_stdout_and_stderr());
     }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn transitive_expects_parallel() {
+        let run_with_threads = |threads: &str| {
+            let cli_test = ExecCli::new(
+                CMD_TEST,
+                file_from_root(
+                    "crates/cli/tests/test-projects/expects_transitive",
+                    "main.roc",
+                ),
+            )
+            .arg("--verbose")
+            .arg(format!("--max-threads={threads}"));
+
+            let cli_test_out = cli_test.run();
+            cli_test_out.assert_clean_success();
+            cli_test_out.normalize_stdout_and_stderr()
+        };
+
+        // However the expects are scheduled, the output is the same
+        assert_eq!(run_with_threads("1"), run_with_threads("4"));
+    }
+
     #[test]
     #[cfg_attr(windows, ignore)]
     fn multiple_exposed() {
@@ -527,6 +899,42 @@ mod cli_tests {
             );
         }
 
+        #[test]
+        #[cfg_attr(windows, ignore)]
+        fn dev_watch_rebuilds_and_restarts_after_edit() {
+            build_platform_host();
+
+            // The app has to be next to the platform, which it refers to by a relative path
+            let dir = tempfile::Builder::new()
+                .prefix("watch")
+                .tempdir_in(dir_from_root(
+                    "crates/cli/tests/test-projects/test-platform-simple-zig",
+                ))
+                .unwrap();
+            let app_path = dir.path().join("app.roc");
+            let write_app = |main: &str| {
+                let source = format!(
+                    "app [main] {{ pf: platform \"../main.roc\" }}\n\nmain : Str\nmain = \"{main}\"\n"
+                );
+                std::fs::write(&app_path, source).unwrap();
+            };
+
+            write_app("first build");
+
+            let mut watched = ExecCli::new(CMD_DEV, app_path.clone()).watch();
+
+            let output = watched.wait_for("The program exited");
+            assert!(output.contains("Running program"), "{output}");
+            assert!(output.contains("first build"), "{output}");
+
+            write_app("second build");
+
+            let output = watched.wait_for("The program exited");
+            assert!(output.contains("Running program"), "{output}");
+            assert!(output.contains("second build"), "{output}");
+            assert!(!output.contains("first build"), "{output}");
+        }
+
         #[test]
         #[cfg_attr(windows, ignore)]
         fn run_multi_dep_thunk() {
@@ -0,0 +1,12 @@
+module [sum_squares]
+
+sum_squares = |n| List.walk(List.range({ start: At 1, end: At n }), 0, |sum, i| sum + i * i)
+
+expect sum_squares(3) == 14
+
+expect Inspect.bench("sum of squares", sum_squares(1000))
+
+expect
+    words = ["pear", "apple", "fig", "banana"]
+
+    Inspect.bench("join", Str.join_with(words, ", "))
@@ -0,0 +1,9 @@
+module [overflow]
+
+overflow : U64 -> List U64
+overflow = |n|
+    List.prepend(overflow(n + 1), n)
+
+expect Inspect.bench("overflow", List.len(overflow(0)))
+
+expect Inspect.bench("sum", List.sum([1, 2, 3]))
@@ -0,0 +1,17 @@
+module [describe, sign]
+
+describe = |color|
+    when color is
+        Red -> "warm"
+        Blue -> "cool"
+        Green -> "natural"
+
+sign = |n|
+    if n < 0 then
+        "negative"
+    else
+        "positive"
+
+expect describe(Red) == "warm"
+
+expect describe(Blue) == "cool"
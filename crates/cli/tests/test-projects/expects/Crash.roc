@@ -0,0 +1,13 @@
+module [
+    overflow,
+]
+
+overflow : U64 -> List U64
+overflow = \n ->
+    List.prepend(overflow(n + 1), n)
+
+expect 1 + 1 == 2
+
+expect List.len(overflow(0)) == 0
+
+expect 2 + 2 == 4
@@ -0,0 +1,28 @@
+module [
+    spin,
+]
+
+spin : U8 -> U8
+spin = \n ->
+    if n == 0 then
+        spin(1)
+    else
+        spin(0)
+
+expect spin(0) == 0
+
+expect
+    first = 1
+    first == 2
+
+expect spin(0) == 0
+
+expect 1 + 1 == 2
+
+expect spin(0) == 0
+
+expect
+    second = 2
+    second == 3
+
+expect spin(0) == 0
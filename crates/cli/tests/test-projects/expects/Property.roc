@@ -0,0 +1,7 @@
+module []
+
+expect |list| List.len(List.sort_asc(list)) == List.len(list)
+
+expect |str| Str.concat(str, "") == str
+
+expect |n| n < 1000
@@ -0,0 +1,10 @@
+module [greet]
+
+greet = |name| Str.concat("Hello, ", name)
+
+expect Inspect.snapshot("greeting", greet("World"))
+
+expect
+    doubled = List.map([1, 2, 3], |n| n * 2)
+
+    Inspect.snapshot("doubled", doubled)
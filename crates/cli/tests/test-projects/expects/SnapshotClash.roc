@@ -0,0 +1,9 @@
+module [render]
+
+render = |page| Str.concat("<h1>", page)
+
+expect Inspect.snapshot("home page", render("Home"))
+
+expect Inspect.snapshot("home_page", render("Home, again"))
+
+expect render("About") == "<h1>About"
@@ -0,0 +1,14 @@
+module [
+    spin,
+]
+
+spin : U8 -> U8
+spin = \n ->
+    if n == 0 then
+        spin(1)
+    else
+        spin(0)
+
+expect spin(0) == 0
+
+expect 1 + 1 == 2
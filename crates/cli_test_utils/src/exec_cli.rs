@@ -1,12 +1,20 @@
 use std::ffi::{OsStr, OsString};
+use std::io::Read;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::command::{run_command, CmdOut};
 use crate::helpers::path_to_roc_binary;
 use const_format::concatcp;
 
 const LINKER_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINKER, "=", "legacy");
+const WATCH_FLAG: &str = concatcp!("--", roc_cli::FLAG_WATCH);
+
+/// How long a watched command gets to print what a test waits for, which may take a build.
+const WATCH_TIMEOUT: Duration = Duration::from_secs(300);
 
 /// A builder for running the Roc CLI.
 ///
@@ -57,6 +65,48 @@ impl ExecCli {
         run_command(roc_cli_command, app_stdin_opt)
     }
 
+    /// Starts the command with `--watch`, leaving it running until the result is dropped.
+    pub fn watch(&self) -> WatchedCli {
+        let mut roc_cli_command = Command::new(path_to_roc_binary());
+
+        roc_cli_command.arg(self.sub_command);
+        roc_cli_command.arg(self.roc_file_path.clone());
+        roc_cli_command.args(&self.args);
+        roc_cli_command.arg(WATCH_FLAG);
+
+        let mut child = roc_cli_command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap_or_else(|err| panic!("Failed to start {roc_cli_command:?}: {err}"));
+
+        let (output_tx, output_rx) = mpsc::channel();
+        let stdout = child.stdout.take().unwrap();
+        let stderr = child.stderr.take().unwrap();
+
+        for mut pipe in [Box::new(stdout) as Box<dyn Read + Send>, Box::new(stderr)] {
+            let output_tx = output_tx.clone();
+
+            thread::spawn(move || {
+                let mut buf = [0; 4096];
+
+                while let Ok(read @ 1..) = pipe.read(&mut buf) {
+                    if output_tx.send(buf[..read].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        WatchedCli {
+            child,
+            output_rx,
+            output: String::new(),
+            partial: Vec::new(),
+        }
+    }
+
     pub fn full_check_build_and_run(
         mut self,
         expected_output: &'static str,
@@ -147,3 +197,59 @@ impl ExecCli {
         }
     }
 }
+
+/// A Roc CLI command running with `--watch`, which is killed when this is dropped.
+pub struct WatchedCli {
+    child: Child,
+    output_rx: Receiver<Vec<u8>>,
+    /// What was printed since the last [WatchedCli::wait_for] returned, without colors.
+    output: String,
+    /// The start of a character which was split between reads.
+    partial: Vec<u8>,
+}
+
+impl WatchedCli {
+    /// Waits until the command prints `expected`, and returns everything it printed up to then,
+    /// from stdout and stderr, without colors.
+    pub fn wait_for(&mut self, expected: &str) -> String {
+        let deadline = Instant::now() + WATCH_TIMEOUT;
+
+        loop {
+            let printed = roc_reporting::report::strip_colors(&self.output);
+
+            if let Some(index) = printed.find(expected) {
+                let end = index + expected.len();
+                self.output = printed[end..].to_string();
+
+                return printed[..end].to_string();
+            }
+
+            let timeout = deadline.saturating_duration_since(Instant::now());
+
+            match self.output_rx.recv_timeout(timeout) {
+                Ok(bytes) => {
+                    self.partial.extend(bytes);
+
+                    let valid = match std::str::from_utf8(&self.partial) {
+                        Ok(_) => self.partial.len(),
+                        Err(err) => err.valid_up_to(),
+                    };
+                    let text = String::from_utf8_lossy(&self.partial[..valid]).into_owned();
+
+                    self.output.push_str(&text);
+                    self.partial.drain(..valid);
+                }
+                Err(_) => panic!(
+                    "The watched command didn't print {expected:?} in time, or exited. It printed:\n{printed}"
+                ),
+            }
+        }
+    }
+}
+
+impl Drop for WatchedCli {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -271,7 +271,7 @@ where
                     m.add_func(entry_point_name, entry_point_function)?;
                 }
             }
-            EntryPoint::Expects { symbols } => {
+            EntryPoint::Expects { expects } => {
                 // construct a big pattern match picking one of the expects at random
                 let layout: ProcLayout<'a> = ProcLayout {
                     arguments: &[],
@@ -279,12 +279,17 @@ where
                     niche: Niche::NONE,
                 };
 
-                let host_exposed: Vec<_> = symbols
+                let host_exposed: Vec<_> = expects
                     .iter()
-                    .map(|symbol| {
+                    .map(|(symbol, expect_layout)| {
                         (
-                            func_name_bytes_help(*symbol, [], Niche::NONE, layout.result),
-                            [].as_slice(),
+                            func_name_bytes_help(
+                                *symbol,
+                                expect_layout.arguments.iter().copied(),
+                                expect_layout.niche,
+                                expect_layout.result,
+                            ),
+                            expect_layout.arguments,
                         )
                     })
                     .collect();
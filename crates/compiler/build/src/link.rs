@@ -383,6 +383,38 @@ pub fn build_swift_host_native(
     command
 }
 
+/// The files [rebuild_host] builds the host of the platform from, which exist. A Rust host is
+/// built from its Cargo package, so everything in the `src` directory next to the platform counts.
+pub fn host_source_paths(platform_main_roc: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = ["host.c", "host.zig", "host.rs", "host.swift", "host.h"]
+        .into_iter()
+        .map(|file_name| platform_main_roc.with_file_name(file_name))
+        .filter(|path| path.exists())
+        .collect();
+
+    let cargo_host_src = platform_main_roc.with_file_name("Cargo.toml");
+
+    if cargo_host_src.exists() {
+        let mut dirs = vec![platform_main_roc.with_file_name("src")];
+
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+                let path = entry.path();
+
+                if path.is_dir() {
+                    dirs.push(path);
+                } else {
+                    paths.push(path);
+                }
+            }
+        }
+
+        paths.push(cargo_host_src);
+    }
+
+    paths
+}
+
 pub fn rebuild_host(
     opt_level: OptLevel,
     target: Target,
@@ -8,13 +8,14 @@ use roc_gen_llvm::llvm::build::{module_from_builtins, LlvmBackendMode};
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
 use roc_load::{
     EntryPoint, ExecutionMode, ExpectMetadata, FunctionKind, LoadConfig, LoadMonomorphizedError,
-    LoadedModule, LoadingProblem, MonomorphizedModule, Threading,
+    LoadedModule, LoadingProblem, MonomorphizedModule, SharedIncrementalCache, Threading,
 };
 use roc_mono::ir::{OptLevel, SingleEntryPoint};
-use roc_packaging::cache::RocCacheDir;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::parser::FileError;
 use roc_reporting::{
     cli::{report_problems, Problems},
-    report::{RenderTarget, DEFAULT_PALETTE},
+    report::{to_file_problem_report_string, RenderTarget, DEFAULT_PALETTE},
 };
 use roc_target::{Architecture, Target};
 use std::ops::Deref;
@@ -244,7 +245,7 @@ fn gen_from_mono_module_llvm<'a>(
 
             roc_mono::ir::EntryPoint::Program(entry_points.into_bump_slice())
         }
-        EntryPoint::Test => roc_mono::ir::EntryPoint::Expects { symbols: &[] },
+        EntryPoint::Test => roc_mono::ir::EntryPoint::Expects { expects: &[] },
     };
 
     roc_gen_llvm::llvm::build::build_procedures(
@@ -614,8 +615,12 @@ pub struct BuiltFile<'a> {
     pub problems: Problems,
     pub total_time: Duration,
     pub expect_metadata: ExpectMetadata<'a>,
+    /// The paths of every module that was loaded, including those of packages and the platform.
+    pub module_paths: Vec<PathBuf>,
+    pub platform_main_roc: PathBuf,
 }
 
+#[derive(Debug, Clone, Copy)]
 pub enum BuildOrdering {
     /// Run up through typechecking first; continue building iff that is successful.
     BuildIfChecks,
@@ -662,15 +667,51 @@ pub fn handle_error_module(
 
 pub fn handle_loading_problem(problem: LoadingProblem) -> std::io::Result<i32> {
     match problem {
-        LoadingProblem::FormattedReport(report, _) => {
-            print!("{report}");
-            Ok(1)
+        LoadingProblem::FormattedReport(report, _) => print!("{report}"),
+        other => eprint!("{}", loading_problem_report(other)),
+    }
+
+    Ok(1)
+}
+
+/// Load turns most problems into a report before returning them, this renders the others.
+fn loading_problem_report(problem: LoadingProblem) -> String {
+    match problem {
+        LoadingProblem::FormattedReport(report, _) => report,
+        LoadingProblem::FileProblem { filename, error } => {
+            to_file_problem_report_string(filename, error, true)
         }
-        _ => {
-            // TODO: tighten up the types here, we should always end up with a
-            // formatted report from load.
-            println!("Failed with error: {problem:?}");
-            Ok(1)
+        LoadingProblem::ParsingFailed(FileError { filename, .. })
+        | LoadingProblem::IncorrectModuleName(FileError { filename, .. }) => {
+            format!("Could not load {}.\n", filename.display())
+        }
+        LoadingProblem::MultiplePlatformPackages { filename, .. } => format!(
+            "{} specifies more than one platform, but an app must specify exactly one.\n",
+            filename.display()
+        ),
+        LoadingProblem::NoPlatformPackage { filename, .. } => format!(
+            "{} specifies no platform, but an app must specify exactly one.\n",
+            filename.display()
+        ),
+        LoadingProblem::UnrecognizedPackageShorthand {
+            filename,
+            shorthand,
+            ..
+        } => format!(
+            "{} uses the package shorthand `{shorthand}`, which is not defined.\n",
+            filename.display()
+        ),
+        LoadingProblem::ImportCycle(filename, _) => {
+            format!("{} is part of an import cycle.\n", filename.display())
+        }
+        LoadingProblem::UnexpectedHeader(message) => format!("Unexpected header: {message}\n"),
+        LoadingProblem::TriedToImportAppModule => "An app module cannot be imported.\n".into(),
+        LoadingProblem::CouldNotFindCacheDir => format!(
+            "Could not find the Roc cache directory {}.\n",
+            cache::roc_cache_packages_dir().display()
+        ),
+        LoadingProblem::ErrJoiningWorkerThreads | LoadingProblem::ChannelProblem(_) => {
+            "The compiler's worker threads stopped unexpectedly.\n".into()
         }
     }
 }
@@ -887,6 +928,12 @@ fn build_loaded_file<'a>(
         }
     }
 
+    let module_paths = loaded
+        .sources
+        .values()
+        .map(|(path, _)| path.clone())
+        .collect();
+
     // This only needs to be mutable for report_problems. This can't be done
     // inside a nested scope without causing a borrow error!
     let mut loaded = loaded;
@@ -1037,6 +1084,8 @@ fn build_loaded_file<'a>(
         problems,
         total_time,
         expect_metadata,
+        module_paths,
+        platform_main_roc: platform_main_roc_path,
     })
 }
 
@@ -1336,6 +1385,58 @@ pub fn check_file<'a>(
     roc_cache_dir: RocCacheDir<'_>,
     threading: Threading,
 ) -> Result<(Problems, Duration), LoadingProblem<'a>> {
+    let checked = check_file_help(
+        arena,
+        roc_file_path,
+        opt_main_path,
+        emit_timings,
+        roc_cache_dir,
+        threading,
+        None,
+    )?;
+
+    Ok((checked.problems, checked.total_time))
+}
+
+/// The outcome of checking a file with [check_file_incremental].
+pub struct CheckedFile {
+    pub problems: Problems,
+    pub total_time: Duration,
+    /// The paths of every module that was loaded, including those of packages.
+    pub module_paths: Vec<PathBuf>,
+}
+
+/// Like [check_file], but modules which did not change since an earlier check with the same
+/// `cache` are not solved again.
+pub fn check_file_incremental<'a>(
+    arena: &'a Bump,
+    roc_file_path: PathBuf,
+    opt_main_path: Option<PathBuf>,
+    emit_timings: bool,
+    roc_cache_dir: RocCacheDir<'_>,
+    threading: Threading,
+    cache: SharedIncrementalCache,
+) -> Result<CheckedFile, LoadingProblem<'a>> {
+    check_file_help(
+        arena,
+        roc_file_path,
+        opt_main_path,
+        emit_timings,
+        roc_cache_dir,
+        threading,
+        Some(cache),
+    )
+}
+
+fn check_file_help<'a>(
+    arena: &'a Bump,
+    roc_file_path: PathBuf,
+    opt_main_path: Option<PathBuf>,
+    emit_timings: bool,
+    roc_cache_dir: RocCacheDir<'_>,
+    threading: Threading,
+    incremental: Option<SharedIncrementalCache>,
+) -> Result<CheckedFile, LoadingProblem<'a>> {
     let compilation_start = Instant::now();
 
     // only used for generating errors. We don't do code generation, so hardcoding should be fine
@@ -1353,13 +1454,23 @@ pub fn check_file<'a>(
         threading,
         exec_mode: ExecutionMode::Check,
     };
-    let mut loaded = roc_load::load_and_typecheck(
-        arena,
-        roc_file_path,
-        opt_main_path,
-        roc_cache_dir,
-        load_config,
-    )?;
+    let mut loaded = match incremental {
+        Some(cache) => roc_load::load_and_typecheck_incremental(
+            arena,
+            roc_file_path,
+            opt_main_path,
+            roc_cache_dir,
+            load_config,
+            cache,
+        )?,
+        None => roc_load::load_and_typecheck(
+            arena,
+            roc_file_path,
+            opt_main_path,
+            roc_cache_dir,
+            load_config,
+        )?,
+    };
 
     let buf = &mut String::with_capacity(1024);
 
@@ -1403,7 +1514,17 @@ pub fn check_file<'a>(
         println!("Finished checking in {} ms\n", compilation_end.as_millis(),);
     }
 
-    Ok((report_problems_typechecked(&mut loaded), compilation_end))
+    let module_paths = loaded
+        .sources
+        .values()
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    Ok(CheckedFile {
+        problems: report_problems_typechecked(&mut loaded),
+        total_time: compilation_end,
+        module_paths,
+    })
 }
 
 pub fn build_str_test<'a>(
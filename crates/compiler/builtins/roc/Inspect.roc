@@ -33,6 +33,8 @@ module [
     apply,
     to_inspector,
     to_str,
+    snapshot,
+    bench,
 ]
 
 import Bool exposing [Bool]
@@ -100,6 +102,25 @@ to_str = |val|
     |> inspect
     |> to_dbg_str
 
+## A snapshot, when a top-level `expect` ends with it:
+## ```roc
+## expect Inspect.snapshot("greeting", greet("World"))
+## ```
+## `roc test` renders the value like the REPL does, and compares it with the one stored for the
+## name in the `snapshots` directory next to the module. The name has to be a plain string.
+## Anywhere else, this is just `Bool.true`.
+snapshot : Str, val -> Bool
+snapshot = |_name, _value| Bool.true
+
+## A benchmark, when a top-level `expect` ends with it:
+## ```roc
+## expect Inspect.bench("sort", List.sort_asc(list))
+## ```
+## `roc bench` times how long computing the value takes, and `roc test` computes it once. The name
+## has to be a plain string. Anywhere else, this is just `Bool.true`.
+bench : Str, val -> Bool
+bench = |_name, _value| Bool.true
+
 # The current default formatter for inspect.
 # This just returns a simple string for debugging.
 # More powerful formatters will likely be wanted in the future.
@@ -383,6 +383,7 @@ pub(crate) fn make_apply_symbol(
                 module_params: _,
             }) => {
                 references.insert_type_lookup(symbol, QualifiedReference::Unqualified);
+                env.add_type_lookup(symbol, region, module_name, ident);
                 Ok(symbol)
             }
             Err(problem) => {
@@ -398,6 +399,7 @@ pub(crate) fn make_apply_symbol(
                 module_params: _,
             }) => {
                 references.insert_type_lookup(symbol, QualifiedReference::Qualified);
+                env.add_type_lookup(symbol, region, module_name, ident);
                 Ok(symbol)
             }
             Err(problem) => {
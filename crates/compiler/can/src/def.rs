@@ -24,6 +24,7 @@ use crate::procedure::References;
 use crate::scope::create_alias;
 use crate::scope::SymbolLookup;
 use crate::scope::{PendingAbilitiesInScope, Scope};
+use crate::traverse::ReferenceKind;
 use roc_collections::ReferenceMatrix;
 use roc_collections::VecMap;
 use roc_collections::VecSet;
@@ -1317,6 +1318,13 @@ fn canonicalize_type_defs<'a>(
             symbols_introduced.insert(symbol, region);
         }
 
+        if let PendingTypeDef::Alias { name, .. }
+        | PendingTypeDef::Opaque { name, .. }
+        | PendingTypeDef::Ability { name, .. } = &pending_def
+        {
+            env.add_type_reference(name.value, name.region, ReferenceKind::Declaration);
+        }
+
         match pending_def {
             PendingTypeDef::Alias { name, vars, ann } => {
                 let referenced_symbols = find_type_def_symbols(scope, &ann.value);
@@ -1702,8 +1710,11 @@ pub(crate) fn sort_top_level_can_defs(
     for ((condition, region), preceding_comment) in it {
         // an `expect` does not have a user-defined name, but we'll need a name to call the expectation
         let name = scope.gen_unique_symbol();
+        let loc_condition =
+            crate::expr::bind_snapshot_value(env, Loc::at(region, condition), scope, var_store);
+        crate::expr::check_bench_name(env, &loc_condition);
 
-        declarations.push_expect(preceding_comment, name, Loc::at(region, condition));
+        declarations.push_expect(preceding_comment, name, loc_condition);
     }
 
     for (symbol, alias) in aliases.into_iter() {
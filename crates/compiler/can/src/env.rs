@@ -2,12 +2,13 @@ use std::path::Path;
 
 use crate::procedure::References;
 use crate::scope::{ModuleLookup, Scope, SymbolLookup};
+use crate::traverse::ReferenceKind;
 use bumpalo::Bump;
 use roc_collections::{MutMap, VecSet};
 use roc_module::ident::{Ident, ModuleName};
 use roc_module::symbol::{IdentIdsByModule, ModuleId, PQModuleName, PackageModuleIds, Symbol};
 use roc_problem::can::{Problem, RuntimeError};
-use roc_region::all::{LineInfo, Loc, Region};
+use roc_region::all::{LineInfo, Loc, Position, Region};
 use roc_types::subs::Variable;
 
 /// The canonicalization environment for a particular module.
@@ -39,6 +40,10 @@ pub struct Env<'a> {
 
     pub home_params_record: Option<(Symbol, Variable)>,
 
+    /// Every place a type, alias, opaque type or ability is declared or named. Types are not part
+    /// of the canonical expressions, so this is where tools look to find their references.
+    pub type_references: Vec<(Loc<Symbol>, ReferenceKind)>,
+
     pub arena: &'a Bump,
 
     pub opt_shorthand: Option<&'a str>,
@@ -77,6 +82,7 @@ impl<'a> Env<'a> {
             tailcallable_symbol: None,
             top_level_symbols: Default::default(),
             home_params_record: None,
+            type_references: Vec::new(),
             opt_shorthand,
             line_info,
         }
@@ -105,6 +111,7 @@ impl<'a> Env<'a> {
             tailcallable_symbol: None,
             top_level_symbols: VecSet::default(),
             home_params_record: None,
+            type_references: Vec::new(),
             opt_shorthand,
             line_info: arena.alloc(None),
         }
@@ -254,6 +261,49 @@ impl<'a> Env<'a> {
         self.problems.push(problem)
     }
 
+    /// Records that the type `symbol` is named at `region`.
+    pub fn add_type_reference(&mut self, symbol: Symbol, region: Region, kind: ReferenceKind) {
+        self.type_references.push((Loc::at(region, symbol), kind));
+    }
+
+    /// Records a lookup of the type `symbol`, named as `module_name.ident` somewhere in `region`.
+    /// `region` may cover more than the name, e.g. the arguments of an applied type.
+    pub fn add_type_lookup(
+        &mut self,
+        symbol: Symbol,
+        region: Region,
+        module_name: &str,
+        ident: &str,
+    ) {
+        let name = if module_name.is_empty() {
+            ident.to_string()
+        } else {
+            format!("{module_name}.{ident}")
+        };
+
+        // Parentheses may come before the name, so look for where it actually starts
+        let start = region.start().offset as usize;
+        let end = region.end().offset as usize;
+        let name_start = self
+            .src
+            .get(start..end)
+            .and_then(|text| text.find(&name))
+            .map_or(start, |offset| start + offset);
+
+        let name_region = Region::new(
+            Position::new(name_start as u32),
+            Position::new((name_start + name.len()) as u32),
+        );
+        self.add_type_reference(symbol, name_region, ReferenceKind::Lookup);
+    }
+
+    /// Records a use of the opaque type `symbol` as `@Name`, where `region` covers the `@Name`.
+    pub fn add_opaque_lookup(&mut self, symbol: Symbol, region: Region) {
+        let name_start = Position::new(region.start().offset + 1);
+        let name_region = Region::new(name_start.min(region.end()), region.end());
+        self.add_type_reference(symbol, name_region, ReferenceKind::Lookup);
+    }
+
     pub fn line_info(&mut self) -> &LineInfo {
         if self.line_info.is_none() {
             *self.line_info = Some(LineInfo::new(self.src));
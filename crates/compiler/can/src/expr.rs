@@ -10,7 +10,7 @@ use crate::params_in_abilities_unimplemented;
 use crate::pattern::{canonicalize_pattern, BindingsFromPattern, Pattern, PermitShadows};
 use crate::procedure::{QualifiedReference, References};
 use crate::scope::{Scope, SymbolLookup};
-use crate::traverse::{walk_expr, Visitor};
+use crate::traverse::{walk_expr, ReferenceKind, Visitor};
 use bumpalo::collections::Vec as BumpVec;
 use roc_collections::soa::index_push_new;
 use roc_collections::{SendMap, VecMap, VecSet};
@@ -25,7 +25,7 @@ use roc_parse::pattern::PatternType::*;
 use roc_problem::can::{PrecedenceProblem, Problem, RuntimeError};
 use roc_region::all::{Loc, Region};
 use roc_types::num::SingleQuoteBound;
-use roc_types::subs::{ExhaustiveMark, IllegalCycleMark, RedundantMark, VarStore, Variable};
+use roc_types::subs::{ExhaustiveMark, IllegalCycleMark, RedundantMark, Subs, VarStore, Variable};
 use roc_types::types::{
     Alias, Category, EarlyReturnKind, IndexOrField, LambdaSet, OptAbleVar, Type,
 };
@@ -798,6 +798,7 @@ fn canonicalize_expr_apply<'a>(
                     output
                         .references
                         .insert_type_lookup(name, QualifiedReference::Unqualified);
+                    env.add_opaque_lookup(name, fn_region);
 
                     let (type_arguments, lambda_set_variables, specialized_def_type) =
                         freshen_opaque_def(var_store, opaque_def);
@@ -1327,6 +1328,7 @@ pub fn canonicalize_expr<'a>(
                     output
                         .references
                         .insert_type_lookup(name, QualifiedReference::Unqualified);
+                    env.add_opaque_lookup(name, region);
 
                     let (type_arguments, lambda_set_variables, specialized_def_type) =
                         freshen_opaque_def(var_store, opaque_def);
@@ -2614,6 +2616,10 @@ pub struct Declarations {
     pub function_bodies: Vec<Loc<FunctionDef>>,
     pub expressions: Vec<Loc<Expr>>,
     pub destructs: Vec<DestructureDef>,
+
+    /// Where types, aliases, opaque types and abilities are declared and named in the module.
+    /// These don't appear in the expressions, so they are collected during canonicalization.
+    pub type_references: Vec<(Loc<Symbol>, ReferenceKind)>,
 }
 
 impl Default for Declarations {
@@ -2639,6 +2645,7 @@ impl Declarations {
             specializes: VecMap::default(), // number of specializations is probably low
             destructs: Vec::new(),          // number of destructs is probably low
             arity_by_name: VecMap::with_capacity(capacity),
+            type_references: Vec::new(),
         }
     }
 
@@ -3023,12 +3030,32 @@ impl Declarations {
                 MutualRecursion { .. } => {
                     // the self of this group will be treaded individually by later iterations
                 }
-                Expectation => {
-                    let loc_expr =
-                        toplevel_expect_to_inline_expect_pure(self.expressions[index].clone());
+                Expectation => match &self.expressions[index].value {
+                    Expr::Closure(closure_data) => {
+                        let closure_data = toplevel_property_to_inline_expect(closure_data.clone());
+                        let loc_body = &closure_data.loc_body;
 
-                    collector.visit_expr(&loc_expr.value, loc_expr.region, var);
-                }
+                        collector.visit_expr(&loc_body.value, loc_body.region, var);
+                    }
+                    _ if toplevel_bench(&self.expressions[index]).is_some() => {
+                        // a benchmark isn't turned into an inline expect, but may contain some
+                        let loc_expr = &self.expressions[index];
+
+                        collector.visit_expr(&loc_expr.value, loc_expr.region, var);
+                    }
+                    _ if toplevel_snapshot(&self.expressions[index]).is_some() => {
+                        let loc_expr =
+                            toplevel_snapshot_to_inline_expect(self.expressions[index].clone());
+
+                        collector.visit_expr(&loc_expr.value, loc_expr.region, var);
+                    }
+                    _ => {
+                        let loc_expr =
+                            toplevel_expect_to_inline_expect_pure(self.expressions[index].clone());
+
+                        collector.visit_expr(&loc_expr.value, loc_expr.region, var);
+                    }
+                },
             }
         }
 
@@ -3267,14 +3294,308 @@ pub(crate) fn get_lookup_symbols(expr: &Expr) -> Vec<ExpectLookup> {
 /// This is supposed to happen just before monomorphization:
 /// all type errors and such are generated from the user source,
 /// but this transformation means that we don't need special codegen for toplevel expects
-pub fn toplevel_expect_to_inline_expect_pure(mut loc_expr: Loc<Expr>) -> Loc<Expr> {
+pub fn toplevel_expect_to_inline_expect_pure(loc_expr: Loc<Expr>) -> Loc<Expr> {
+    toplevel_expect_to_inline_expect_help(loc_expr, vec![], Expr::EmptyRecord)
+}
+
+/// Turns a property, a top-level `expect` of a function like `expect \list -> ...`, into that
+/// function with an inline expect of the condition as its body. The named arguments are looked up
+/// in the condition, so that the inputs which falsified it are shown.
+pub fn toplevel_property_to_inline_expect(mut closure_data: ClosureData) -> ClosureData {
+    let lookups_in_cond = closure_data
+        .arguments
+        .iter()
+        .filter_map(|(var, _, loc_pattern)| match loc_pattern.value {
+            Pattern::Identifier(symbol) => Some(ExpectLookup {
+                symbol,
+                var: *var,
+                ability_info: None,
+            }),
+            _ => None,
+        })
+        .collect();
+
+    // the function still has to return a Bool once the expect has run
+    let continuation = Expr::Var(Symbol::BOOL_TRUE, closure_data.return_type);
+
+    let loc_body = toplevel_expect_to_inline_expect_help(
+        *closure_data.loc_body,
+        lookups_in_cond,
+        continuation,
+    );
+    closure_data.loc_body = Box::new(loc_body);
+
+    closure_data
+}
+
+/// The expression a top-level `expect` ends with, after its defs.
+fn toplevel_expect_result(mut loc_expr: &Loc<Expr>) -> &Loc<Expr> {
+    loop {
+        match &loc_expr.value {
+            Expr::LetNonRec(_, remainder) | Expr::LetRec(_, remainder, _) => {
+                loc_expr = remainder;
+            }
+            _ => return loc_expr,
+        }
+    }
+}
+
+/// The name and value passed to `builtin`, if a top-level `expect` ends with a call to it, like
+/// `expect Inspect.snapshot("name", value)`.
+fn toplevel_builtin_call(
+    loc_expr: &Loc<Expr>,
+    builtin: Symbol,
+) -> Option<(&Loc<Expr>, &Loc<Expr>)> {
+    let Expr::Call(boxed, arguments, _) = &toplevel_expect_result(loc_expr).value else {
+        return None;
+    };
+
+    match (&boxed.1.value, arguments.as_slice()) {
+        (Expr::Var(symbol, _), [(_, loc_name), (_, loc_value)]) if *symbol == builtin => {
+            Some((loc_name, loc_value))
+        }
+        _ => None,
+    }
+}
+
+/// The name of the snapshot, if this top-level `expect` is one, like
+/// `expect Inspect.snapshot("page", render(page))`. `roc test` compares the rendered value with
+/// the one it stored for the snapshot's name.
+pub fn toplevel_snapshot(loc_expr: &Loc<Expr>) -> Option<&str> {
+    let (loc_name, _) = toplevel_builtin_call(loc_expr, Symbol::INSPECT_SNAPSHOT)?;
+
+    match &loc_name.value {
+        Expr::Str(name) => Some(name),
+        _ => None,
+    }
+}
+
+/// The name of the benchmark, if this top-level `expect` is one, like
+/// `expect Inspect.bench("sort", List.sort_asc(list))`. `roc bench` times how long computing the
+/// value takes, and `roc test` computes it once.
+pub fn toplevel_bench(loc_expr: &Loc<Expr>) -> Option<&str> {
+    let (loc_name, _) = toplevel_builtin_call(loc_expr, Symbol::INSPECT_BENCH)?;
+
+    match &loc_name.value {
+        Expr::Str(name) => Some(name),
+        _ => None,
+    }
+}
+
+/// Reports a benchmark whose name isn't a plain string, since benchmarks are told apart by their
+/// names when they're compared with a baseline.
+pub(crate) fn check_bench_name(env: &mut Env<'_>, loc_expr: &Loc<Expr>) {
+    if let Some((loc_name, _)) = toplevel_builtin_call(loc_expr, Symbol::INSPECT_BENCH) {
+        if !matches!(loc_name.value, Expr::Str(_)) {
+            env.problem(Problem::InvalidBenchName(loc_name.region));
+        }
+    }
+}
+
+/// Turns a benchmark into the computation of its value, which is then passed to the foreign
+/// function `black_box`. That function does nothing with it, but the optimizer can't tell, so the
+/// computation is kept.
+///
+/// The inputs of the computation, the values the benchmark defines and the arguments of the
+/// function it calls, are passed through `black_box` first, which returns them as they are. The
+/// optimizer can't tell what they are then, so it can't compute the value ahead of time, like it
+/// could from `sum_squares(1000)`. Functions are left alone, so that calls to them stay direct.
+pub fn toplevel_bench_to_black_box(
+    loc_expr: Loc<Expr>,
+    black_box: ForeignSymbol,
+    subs: &Subs,
+) -> Loc<Expr> {
+    let Loc { region, value } = loc_expr;
+
+    let value = match value {
+        Expr::LetNonRec(mut def, remainder) => {
+            def.loc_expr = black_box_input(def.loc_expr, def.expr_var, &black_box, subs);
+
+            Expr::LetNonRec(
+                def,
+                Box::new(toplevel_bench_to_black_box(*remainder, black_box, subs)),
+            )
+        }
+        Expr::LetRec(defs, remainder, mark) => Expr::LetRec(
+            defs,
+            Box::new(toplevel_bench_to_black_box(*remainder, black_box, subs)),
+            mark,
+        ),
+        Expr::Call(_, mut arguments, _) => {
+            let Some((value_var, loc_value)) = arguments.pop() else {
+                internal_error!("a benchmark has a name and a value")
+            };
+
+            let value = match loc_value.value {
+                Expr::Call(boxed, args, called_via) => {
+                    let args = args
+                        .into_iter()
+                        .map(|(var, loc_arg)| {
+                            (var, black_box_input(loc_arg, var, &black_box, subs))
+                        })
+                        .collect();
+
+                    Expr::Call(boxed, args, called_via)
+                }
+                value => value,
+            };
+
+            Expr::ForeignCall {
+                foreign_symbol: black_box,
+                args: vec![(value_var, value)],
+                ret_var: Variable::EMPTY_RECORD,
+            }
+        }
+        _ => internal_error!("a benchmark ends with its call"),
+    };
+
+    Loc::at(region, value)
+}
+
+/// Passes an input of a benchmark through `black_box`, unless it's a function.
+fn black_box_input(
+    loc_input: Loc<Expr>,
+    var: Variable,
+    black_box: &ForeignSymbol,
+    subs: &Subs,
+) -> Loc<Expr> {
+    if subs.is_function(var) {
+        return loc_input;
+    }
+
+    let Loc { region, value } = loc_input;
+
+    let value = Expr::ForeignCall {
+        foreign_symbol: black_box.clone(),
+        args: vec![(var, value)],
+        ret_var: var,
+    };
+
+    Loc::at(region, value)
+}
+
+/// Binds the value of a snapshot to a symbol of its own, so that it can be looked up once the
+/// snapshot is turned into an inline expect. Other top-level `expect`s are returned as they are.
+///
+/// The name of a snapshot has to be a plain string, because it names the file its value is
+/// stored in before the snapshot runs.
+pub(crate) fn bind_snapshot_value(
+    env: &mut Env<'_>,
+    loc_expr: Loc<Expr>,
+    scope: &mut Scope,
+    var_store: &mut VarStore,
+) -> Loc<Expr> {
+    match toplevel_builtin_call(&loc_expr, Symbol::INSPECT_SNAPSHOT) {
+        Some((loc_name, _)) if !matches!(loc_name.value, Expr::Str(_)) => {
+            env.problem(Problem::InvalidSnapshotName(loc_name.region));
+
+            loc_expr
+        }
+        Some(_) => bind_snapshot_value_help(loc_expr, scope, var_store),
+        None => loc_expr,
+    }
+}
+
+fn bind_snapshot_value_help(
+    loc_expr: Loc<Expr>,
+    scope: &mut Scope,
+    var_store: &mut VarStore,
+) -> Loc<Expr> {
+    let Loc { region, value } = loc_expr;
+
+    let value = match value {
+        Expr::LetNonRec(def, remainder) => Expr::LetNonRec(
+            def,
+            Box::new(bind_snapshot_value_help(*remainder, scope, var_store)),
+        ),
+        Expr::LetRec(defs, remainder, mark) => Expr::LetRec(
+            defs,
+            Box::new(bind_snapshot_value_help(*remainder, scope, var_store)),
+            mark,
+        ),
+        Expr::Call(boxed, mut arguments, called_via) => {
+            let Some((value_var, loc_value)) = arguments.pop() else {
+                internal_error!("a snapshot has a name and a value")
+            };
+
+            let symbol = scope.gen_unique_symbol();
+            let expr_var = var_store.fresh();
+            let lookup = Expr::Var(symbol, var_store.fresh());
+            arguments.push((value_var, Loc::at(loc_value.region, lookup)));
+
+            let mut pattern_vars = SendMap::default();
+            pattern_vars.insert(symbol, expr_var);
+
+            let def = Def {
+                loc_pattern: Loc::at(loc_value.region, Pattern::Identifier(symbol)),
+                loc_expr: loc_value,
+                expr_var,
+                pattern_vars,
+                annotation: None,
+                kind: crate::def::DefKind::Let,
+            };
+
+            let call = Expr::Call(boxed, arguments, called_via);
+
+            Expr::LetNonRec(Box::new(def), Box::new(Loc::at(region, call)))
+        }
+        _ => internal_error!("a snapshot ends with its call"),
+    };
+
+    Loc::at(region, value)
+}
+
+/// Turns a snapshot into an inline expect which always fails, looking up the value of the
+/// snapshot so that `roc test` is sent the value to compare.
+pub fn toplevel_snapshot_to_inline_expect(loc_expr: Loc<Expr>) -> Loc<Expr> {
+    let Loc { region, value } = loc_expr;
+
+    let value = match value {
+        Expr::LetNonRec(def, remainder) => Expr::LetNonRec(
+            def,
+            Box::new(toplevel_snapshot_to_inline_expect(*remainder)),
+        ),
+        Expr::LetRec(defs, remainder, mark) => Expr::LetRec(
+            defs,
+            Box::new(toplevel_snapshot_to_inline_expect(*remainder)),
+            mark,
+        ),
+        Expr::Call(_, mut arguments, _) => {
+            let lookups_in_cond = match arguments.pop().map(|(_, loc_value)| loc_value.value) {
+                Some(Expr::Var(symbol, var)) => vec![ExpectLookup {
+                    symbol,
+                    var,
+                    ability_info: None,
+                }],
+                _ => internal_error!("the value of a snapshot is bound to a symbol of its own"),
+            };
+
+            Expr::Expect {
+                loc_condition: Box::new(Loc::at(
+                    region,
+                    Expr::Var(Symbol::BOOL_FALSE, Variable::BOOL),
+                )),
+                loc_continuation: Box::new(Loc::at_zero(Expr::EmptyRecord)),
+                lookups_in_cond,
+            }
+        }
+        _ => internal_error!("a snapshot ends with its call"),
+    };
+
+    Loc::at(region, value)
+}
+
+fn toplevel_expect_to_inline_expect_help(
+    mut loc_expr: Loc<Expr>,
+    mut lookups_in_cond: Vec<ExpectLookup>,
+    continuation: Expr,
+) -> Loc<Expr> {
     enum StoredDef {
         NonRecursive(Region, Box<Def>),
         Recursive(Region, Vec<Def>, IllegalCycleMark),
     }
 
     let mut stack = vec![];
-    let mut lookups_in_cond = vec![];
 
     loop {
         match loc_expr.value {
@@ -3307,7 +3628,7 @@ pub fn toplevel_expect_to_inline_expect_pure(mut loc_expr: Loc<Expr>) -> Loc<Exp
     let expect_region = loc_expr.region;
     let expect = Expr::Expect {
         loc_condition: Box::new(loc_expr),
-        loc_continuation: Box::new(Loc::at_zero(Expr::EmptyRecord)),
+        loc_continuation: Box::new(Loc::at_zero(continuation)),
         lookups_in_cond,
     };
 
@@ -710,6 +710,8 @@ pub fn canonicalize_module_defs<'a>(
 
     let collected = declarations.expects();
 
+    declarations.type_references = std::mem::take(&mut env.type_references);
+
     ModuleOutput {
         scope,
         aliases,
@@ -1176,7 +1178,7 @@ fn fix_values_captured_in_closure_expr(
 }
 
 /// Type state for a single module.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TypeState {
     pub subs: Subs,
     pub exposed_vars_by_symbol: Vec<(Symbol, Variable)>,
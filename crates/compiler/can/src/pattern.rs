@@ -409,6 +409,7 @@ pub fn canonicalize_apply_tag<'a>(
                         opaque,
                         crate::procedure::QualifiedReference::Unqualified,
                     );
+                    env.add_opaque_lookup(opaque, tag.region);
 
                     Pattern::UnwrappedOpaque {
                         whole_var: var_store.fresh(),
@@ -1,8 +1,14 @@
 //! Traversals over the can ast.
 
-use roc_module::{ident::Lowercase, symbol::Symbol};
+use roc_module::{
+    ident::{Lowercase, TagName},
+    symbol::Symbol,
+};
 use roc_region::all::{Loc, Position, Region};
-use roc_types::{subs::Variable, types::MemberImpl};
+use roc_types::{
+    subs::Variable,
+    types::{IndexOrField, MemberImpl},
+};
 
 use crate::{
     abilities::AbilitiesStore,
@@ -926,6 +932,18 @@ impl<'a> FoundDeclaration<'a> {
             FoundDeclaration::Def(def) => def.expr_var,
         }
     }
+
+    pub fn annotation(&self) -> Option<&Annotation> {
+        match self {
+            FoundDeclaration::Decl(
+                DeclarationInfo::Value { annotation, .. }
+                | DeclarationInfo::Function { annotation, .. }
+                | DeclarationInfo::Destructure { annotation, .. },
+            ) => *annotation,
+            FoundDeclaration::Decl(DeclarationInfo::Expectation { .. }) => None,
+            FoundDeclaration::Def(def) => def.annotation.as_ref(),
+        }
+    }
 }
 
 /// Finds the declaration of `symbol`.
@@ -978,3 +996,247 @@ pub fn find_declaration(symbol: Symbol, decls: &'_ Declarations) -> Option<Found
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// The place where the symbol is introduced, e.g. `foo` in `foo = ...`.
+    Declaration,
+    /// A lookup of the symbol, e.g. `foo` or `Module.foo` in an expression.
+    Lookup,
+    /// A punned record field that introduces the symbol, e.g. `foo` in `{ foo } = ...`.
+    RecordField,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoundReference {
+    pub region: Region,
+    pub kind: ReferenceKind,
+}
+
+/// Finds every place in `decls` that declares or looks up `symbol`. That can be a value, or a
+/// type, alias, opaque type or ability named in annotations and `@Opaque` wrappers.
+///
+/// Usages of an ability member are included both when they refer to the member itself and when
+/// they are resolved to `symbol` as a specialization.
+pub fn find_references(
+    symbol: Symbol,
+    decls: &Declarations,
+    abilities_store: &AbilitiesStore,
+) -> Vec<FoundReference> {
+    let mut visitor = Finder {
+        symbol,
+        abilities_store,
+        found: Vec::new(),
+    };
+    visitor.visit_decls(decls);
+
+    let type_references = decls.type_references.iter();
+    visitor.found.extend(
+        type_references
+            .filter(|(loc_symbol, _)| loc_symbol.value == symbol)
+            .map(|(loc_symbol, kind)| FoundReference {
+                region: loc_symbol.region,
+                kind: *kind,
+            }),
+    );
+
+    return visitor.found;
+
+    struct Finder<'a> {
+        symbol: Symbol,
+        abilities_store: &'a AbilitiesStore,
+        found: Vec<FoundReference>,
+    }
+
+    impl Finder<'_> {
+        fn push(&mut self, region: Region, kind: ReferenceKind) {
+            self.found.push(FoundReference { region, kind });
+        }
+    }
+
+    impl Visitor for Finder<'_> {
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            match pattern {
+                Pattern::Identifier(symbol)
+                | Pattern::AbilityMemberSpecialization { ident: symbol, .. }
+                    if *symbol == self.symbol =>
+                {
+                    self.push(region, ReferenceKind::Declaration);
+                }
+                Pattern::Shadowed(_, loc_ident, symbol) if *symbol == self.symbol => {
+                    self.push(loc_ident.region, ReferenceKind::Declaration);
+                }
+                _ => walk_pattern(self, pattern),
+            }
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            match &destruct.typ {
+                DestructType::Required if destruct.symbol == self.symbol => {
+                    self.push(region, ReferenceKind::RecordField);
+                }
+                _ => walk_record_destruct(self, destruct),
+            }
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            match expr {
+                Expr::Var(symbol, _) | Expr::ParamsVar { symbol, .. } if *symbol == self.symbol => {
+                    self.push(region, ReferenceKind::Lookup);
+                }
+                &Expr::AbilityMember(member_symbol, specialization_id, _) => {
+                    let resolved =
+                        specialization_id.and_then(|id| self.abilities_store.get_resolved(id));
+
+                    if member_symbol == self.symbol || resolved == Some(self.symbol) {
+                        self.push(region, ReferenceKind::Lookup);
+                    }
+                }
+                _ => walk_expr(self, expr, var),
+            }
+        }
+    }
+}
+
+/// Finds every place in `decls` that names a record field: record literals and updates, accesses
+/// like `rec.foo`, accessor functions like `.foo`, and destructures like `{ foo } = rec`.
+///
+/// Records are structural, so a field is only identified by its name.
+pub fn find_record_fields(decls: &Declarations) -> Vec<(Lowercase, FoundReference)> {
+    let mut visitor = Finder { found: Vec::new() };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        found: Vec<(Lowercase, FoundReference)>,
+    }
+
+    impl Finder {
+        fn push_at_start(&mut self, field: &Lowercase, region: Region, kind: ReferenceKind) {
+            let start = region.start();
+            let end = Position::new(start.offset + field.as_str().len() as u32);
+            let region = Region::new(start, end.min(region.end()));
+            self.found
+                .push((field.clone(), FoundReference { region, kind }));
+        }
+
+        fn push_at_end(&mut self, field: &Lowercase, region: Region) {
+            let end = region.end();
+            let start = Position::new(end.offset.saturating_sub(field.as_str().len() as u32));
+            let region = Region::new(start.max(region.start()), end);
+            let kind = ReferenceKind::Lookup;
+            self.found
+                .push((field.clone(), FoundReference { region, kind }));
+        }
+    }
+
+    impl Visitor for Finder {
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            self.push_at_start(&destruct.label, region, ReferenceKind::Lookup);
+            walk_record_destruct(self, destruct);
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            match expr {
+                Expr::Record { fields, .. } => {
+                    for (name, field) in fields.iter() {
+                        self.push_at_start(name, field.region, ReferenceKind::Declaration);
+                    }
+                }
+                Expr::RecordUpdate { updates, .. } => {
+                    for (name, field) in updates.iter() {
+                        self.push_at_start(name, field.region, ReferenceKind::Declaration);
+                    }
+                }
+                Expr::RecordAccess { field, .. } => self.push_at_end(field, region),
+                Expr::RecordAccessor(StructAccessorData {
+                    field: IndexOrField::Field(field),
+                    ..
+                }) => self.push_at_end(field, region),
+                _ => {}
+            }
+
+            walk_expr(self, expr, var);
+        }
+    }
+}
+
+/// A tag named in an expression or pattern.
+#[derive(Debug, Clone)]
+pub struct FoundTag {
+    pub name: TagName,
+    /// The region of just the tag's name, e.g. `Ok` in `Ok 1`.
+    pub region: Region,
+    /// The type of the tag union this tag is part of.
+    pub var: Variable,
+    /// The opaque type directly wrapping this tag, like `Id` in `@Id (Id 1)`.
+    pub opaque: Option<Symbol>,
+}
+
+/// Finds every tag applied or matched on in `decls`.
+///
+/// Tags are structural, so which union a tag belongs to has to be worked out from its `var`.
+pub fn find_tags(decls: &Declarations) -> Vec<FoundTag> {
+    let mut visitor = Finder {
+        found: Vec::new(),
+        opaque: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        found: Vec<FoundTag>,
+        /// Set while visiting the argument of an opaque wrapper.
+        opaque: Option<Symbol>,
+    }
+
+    impl Finder {
+        fn push(&mut self, name: &TagName, region: Region, var: Variable, opaque: Option<Symbol>) {
+            let start = region.start();
+            let end = Position::new(start.offset + name.0.as_str().len() as u32);
+            self.found.push(FoundTag {
+                name: name.clone(),
+                region: Region::new(start, end.min(region.end())),
+                var,
+                opaque,
+            });
+        }
+    }
+
+    impl Visitor for Finder {
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            let opaque = self.opaque.take();
+
+            match expr {
+                Expr::Tag {
+                    tag_union_var,
+                    name,
+                    ..
+                } => self.push(name, region, *tag_union_var, opaque),
+                Expr::ZeroArgumentTag {
+                    variant_var, name, ..
+                } => self.push(name, region, *variant_var, opaque),
+                Expr::OpaqueRef { name, .. } => self.opaque = Some(*name),
+                _ => {}
+            }
+
+            walk_expr(self, expr, var);
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            let opaque = self.opaque.take();
+
+            match pattern {
+                Pattern::AppliedTag {
+                    whole_var,
+                    tag_name,
+                    ..
+                } => self.push(tag_name, region, *whole_var, opaque),
+                Pattern::UnwrappedOpaque { opaque, .. } => self.opaque = Some(*opaque),
+                _ => {}
+            }
+
+            walk_pattern(self, pattern);
+        }
+    }
+}
@@ -2826,20 +2826,52 @@ pub fn constrain_decls(
                 let loc_expr = &declarations.expressions[index];
 
                 let bool_type = constraints.push_variable(Variable::BOOL);
-                let expected = constraints.push_expected_type(Expected::ForReason(
-                    Reason::ExpectCondition,
-                    bool_type,
-                    loc_expr.region,
-                ));
 
-                let expect_constraint = constrain_expr(
-                    types,
-                    constraints,
-                    &mut env,
-                    loc_expr.region,
-                    &loc_expr.value,
-                    expected,
-                );
+                let expect_constraint = match &loc_expr.value {
+                    // A property, like `expect \list -> ...`, is a function returning the condition
+                    Closure(closure_data) => {
+                        let expected_bool = constraints.push_expected_type(Expected::ForReason(
+                            Reason::ExpectCondition,
+                            bool_type,
+                            closure_data.loc_body.region,
+                        ));
+                        let return_constraint = constraints.equal_types_var(
+                            closure_data.return_type,
+                            expected_bool,
+                            Category::Storage(std::file!(), std::line!()),
+                            closure_data.loc_body.region,
+                        );
+
+                        let fn_type = constraints.push_variable(closure_data.function_type);
+                        let expected = constraints.push_expected_type(NoExpectation(fn_type));
+                        let closure_constraint = constrain_expr(
+                            types,
+                            constraints,
+                            &mut env,
+                            loc_expr.region,
+                            &loc_expr.value,
+                            expected,
+                        );
+
+                        constraints.and_constraint([closure_constraint, return_constraint])
+                    }
+                    _ => {
+                        let expected = constraints.push_expected_type(Expected::ForReason(
+                            Reason::ExpectCondition,
+                            bool_type,
+                            loc_expr.region,
+                        ));
+
+                        constrain_expr(
+                            types,
+                            constraints,
+                            &mut env,
+                            loc_expr.region,
+                            &loc_expr.value,
+                            expected,
+                        )
+                    }
+                };
 
                 constraint = constraints.let_constraint(
                     [],
@@ -34,7 +34,7 @@ use inkwell::values::{
     PointerMathValue, PointerValue, StructValue,
 };
 use inkwell::FloatPredicate;
-use inkwell::{AddressSpace, IntPredicate};
+use inkwell::{AddressSpace, AtomicOrdering, AtomicRMWBinOp, IntPredicate};
 use morphic_lib::{
     CalleeSpecVar, FuncName, FuncSpec, FuncSpecSolutions, ModSolutions, UpdateMode, UpdateModeVar,
 };
@@ -45,9 +45,11 @@ use roc_debug_flags::dbg_do;
 use roc_debug_flags::ROC_PRINT_LLVM_FN_VERIFICATION;
 use roc_error_macros::{internal_error, todo_lambda_erasure};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::coverage::COVERAGE_HIT;
 use roc_mono::ir::{
     BranchInfo, CallType, CrashTag, EntryPoint, GlueLayouts, HostExposedLambdaSet,
     HostExposedLambdaSets, ListLiteralElement, ModifyRc, OptLevel, ProcLayout, SingleEntryPoint,
+    BENCH_BLACK_BOX,
 };
 use roc_mono::layout::{
     Builtin, InLayout, LambdaName, LambdaSet, Layout, LayoutIds, LayoutInterner, LayoutRepr, Niche,
@@ -296,6 +298,14 @@ pub(crate) trait BuilderExt<'ctx> {
     fn new_build_unreachable(&self) -> InstructionValue<'ctx>;
 
     fn new_build_free(&self, ptr: PointerValue<'ctx>) -> InstructionValue<'ctx>;
+
+    fn new_build_atomicrmw(
+        &self,
+        op: AtomicRMWBinOp,
+        ptr: PointerValue<'ctx>,
+        value: IntValue<'ctx>,
+        ordering: AtomicOrdering,
+    ) -> IntValue<'ctx>;
 }
 
 impl<'ctx> BuilderExt<'ctx> for Builder<'ctx> {
@@ -647,6 +657,16 @@ impl<'ctx> BuilderExt<'ctx> for Builder<'ctx> {
     fn new_build_free(&self, ptr: PointerValue<'ctx>) -> InstructionValue<'ctx> {
         self.build_free(ptr).unwrap()
     }
+
+    fn new_build_atomicrmw(
+        &self,
+        op: AtomicRMWBinOp,
+        ptr: PointerValue<'ctx>,
+        value: IntValue<'ctx>,
+        ordering: AtomicOrdering,
+    ) -> IntValue<'ctx> {
+        self.build_atomicrmw(op, ptr, value, ordering).unwrap()
+    }
 }
 
 #[inline(always)]
@@ -1504,6 +1524,18 @@ pub(crate) fn build_exp_call<'a, 'ctx>(
             )
         }
 
+        CallType::Foreign {
+            foreign_symbol,
+            ret_layout,
+        } if foreign_symbol.as_str().starts_with(BENCH_BLACK_BOX) => build_bench_black_box(
+            env,
+            layout_interner,
+            layout_ids,
+            scope,
+            arguments,
+            *ret_layout,
+        ),
+
         CallType::Foreign {
             foreign_symbol,
             ret_layout,
@@ -5466,19 +5498,62 @@ pub fn build_procedures_return_main<'a, 'ctx>(
     )
 }
 
+/// The global through which the host passes the arguments of a property, an expect like
+/// `expect \list -> ...`. It points to an array with a pointer to each argument.
+pub const PROPERTY_ARGUMENTS_GLOBAL: &str = "roc_property_arguments";
+
+/// The global through which the host passes the counters of the probes `roc test --coverage`
+/// inserts. It points to a `u64` for every (zero-indexed) module id, which is the index at which
+/// the counters of that module's probes start in the same memory.
+pub const COVERAGE_COUNTERS_GLOBAL: &str = "roc_coverage_counters";
+
+/// A top-level expect, exposed to the host as a function without arguments.
+#[derive(Debug, Clone, Copy)]
+pub struct ExposedExpect<'a> {
+    pub name: &'a str,
+    /// The layouts of the arguments of a property, which the host passes through
+    /// [PROPERTY_ARGUMENTS_GLOBAL]. Other expects have none.
+    pub arguments: &'a [InLayout<'a>],
+}
+
 pub fn build_procedures_expose_expects<'a>(
     env: &Env<'a, '_, '_>,
     layout_interner: &STLayoutInterner<'a>,
     opt_level: OptLevel,
     expects_by_module: MutMap<ModuleId, Vec<'a, Symbol>>,
     procedures: MutMap<(Symbol, ProcLayout<'a>), roc_mono::ir::Proc<'a>>,
-) -> MutMap<ModuleId, Vec<'a, &'a str>> {
-    // converts Vec<Vec<Symbol>> into Vec<Symbol>
-    let flattened_symbols: Vec<Symbol> =
-        Vec::from_iter_in(expects_by_module.values().flatten().copied(), env.arena);
+) -> MutMap<ModuleId, Vec<'a, ExposedExpect<'a>>> {
+    let captures_niche = Niche::NONE;
+
+    let top_level = ProcLayout {
+        arguments: &[],
+        result: Layout::UNIT,
+        niche: captures_niche,
+    };
+
+    // properties take arguments, the other expects are thunks
+    let layout_of = |symbol: Symbol| {
+        procedures
+            .keys()
+            .find(|(proc_symbol, _)| *proc_symbol == symbol)
+            .map_or(top_level, |(_, layout)| *layout)
+    };
+
+    let layouts_by_module: MutMap<ModuleId, Vec<'a, (Symbol, ProcLayout<'a>)>> = expects_by_module
+        .into_iter()
+        .map(|(module_id, expects)| {
+            let it = expects.iter().map(|symbol| (*symbol, layout_of(*symbol)));
+
+            (module_id, Vec::from_iter_in(it, env.arena))
+        })
+        .collect();
+
+    // converts Vec<Vec<(Symbol, ProcLayout)>> into Vec<(Symbol, ProcLayout)>
+    let flattened_expects: Vec<(Symbol, ProcLayout<'a>)> =
+        Vec::from_iter_in(layouts_by_module.values().flatten().copied(), env.arena);
 
     let entry_point = EntryPoint::Expects {
-        symbols: &flattened_symbols,
+        expects: &flattened_expects,
     };
 
     let mod_solutions = build_procedures_help(
@@ -5491,27 +5566,21 @@ pub fn build_procedures_expose_expects<'a>(
         Some(&std::env::temp_dir().join("test.ll")),
     );
 
-    let captures_niche = Niche::NONE;
-
-    let top_level = ProcLayout {
-        arguments: &[],
-        result: Layout::UNIT,
-        niche: captures_niche,
-    };
+    build_coverage_hit(env);
 
     let mut expect_names_by_module = MutMap::default();
 
-    for (module_id, expects) in expects_by_module {
+    for (module_id, expects) in layouts_by_module {
         let mut expect_names = Vec::with_capacity_in(expects.len(), env.arena);
 
-        for symbol in expects.iter().copied() {
-            let args_iter = top_level.arguments.iter().copied();
+        for (symbol, layout) in expects.iter().copied() {
+            let args_iter = layout.arguments.iter().copied();
 
             let func_name_bytes = roc_alias_analysis::func_name_bytes_help(
                 symbol,
                 args_iter,
-                captures_niche,
-                top_level.result,
+                layout.niche,
+                layout.result,
             );
 
             let func_name = FuncName(&func_name_bytes);
@@ -5537,7 +5606,16 @@ pub fn build_procedures_expose_expects<'a>(
 
             let expect_name = &format!("Expect_{name}");
             let expect_name_str = env.arena.alloc_str(expect_name);
-            expect_names.push(&*expect_name_str);
+            expect_names.push(ExposedExpect {
+                name: expect_name_str,
+                arguments: layout.arguments,
+            });
+
+            let roc_main_fn = if layout.arguments.is_empty() {
+                roc_main_fn
+            } else {
+                build_property_thunk(env, layout_interner, roc_main_fn, name, layout)
+            };
 
             // Add main to the module.
             let _ = expose_function_to_host_help_c_abi(
@@ -5556,6 +5634,219 @@ pub fn build_procedures_expose_expects<'a>(
     expect_names_by_module
 }
 
+/// Wraps a property in a thunk, which calls it with the arguments the host put behind
+/// [PROPERTY_ARGUMENTS_GLOBAL]. That way it is exposed like any other expect.
+fn build_property_thunk<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    roc_function: FunctionValue<'ctx>,
+    name: &str,
+    layout: ProcLayout<'a>,
+) -> FunctionValue<'ctx> {
+    let builder = env.builder;
+    let ptr_type = env.context.ptr_type(AddressSpace::default());
+
+    let arguments_global = env
+        .module
+        .get_global(PROPERTY_ARGUMENTS_GLOBAL)
+        .unwrap_or_else(|| {
+            let global = env
+                .module
+                .add_global(ptr_type, None, PROPERTY_ARGUMENTS_GLOBAL);
+            global.set_initializer(&ptr_type.const_null());
+
+            global
+        });
+
+    let unit_repr = layout_interner.get_repr(Layout::UNIT);
+    let unit_type = basic_type_from_layout(env, layout_interner, unit_repr);
+    let roc_return = RocReturn::from_layout(layout_interner, unit_repr);
+    let fn_spec = FunctionSpec::fastcc(env, roc_return, unit_type, Vec::new_in(env.arena));
+
+    let thunk_name = format!("{name}_property_thunk");
+    let thunk = add_func(
+        env.context,
+        env.module,
+        &thunk_name,
+        fn_spec,
+        Linkage::Internal,
+    );
+
+    let subprogram = env.new_subprogram(&thunk_name);
+    thunk.set_subprogram(subprogram);
+
+    debug_info_init!(env, thunk);
+
+    let entry = env.context.append_basic_block(thunk, "entry");
+    builder.position_at_end(entry);
+
+    let arguments_ptr = builder
+        .new_build_load(
+            ptr_type,
+            arguments_global.as_pointer_value(),
+            "load_arguments",
+        )
+        .into_pointer_value();
+
+    let mut arguments = Vec::with_capacity_in(layout.arguments.len(), env.arena);
+
+    for (index, argument_layout) in layout.arguments.iter().enumerate() {
+        let index = env.ptr_int().const_int(index as u64, false);
+        let argument_ptr_ptr = unsafe {
+            builder.new_build_in_bounds_gep(ptr_type, arguments_ptr, &[index], "argument_ptr_ptr")
+        };
+        let argument_ptr = builder
+            .new_build_load(ptr_type, argument_ptr_ptr, "argument_ptr")
+            .into_pointer_value();
+
+        arguments.push(load_roc_value(
+            env,
+            layout_interner,
+            layout_interner.get_repr(*argument_layout),
+            argument_ptr,
+            "argument",
+        ));
+    }
+
+    // a property returns its condition, but failures are reported through the expect inside it
+    let _ = call_direct_roc_function(
+        env,
+        layout_interner,
+        roc_function,
+        layout_interner.get_repr(layout.result),
+        &arguments,
+    );
+
+    builder.new_build_return(Some(&unit_type.into_struct_type().const_zero()));
+
+    thunk
+}
+
+/// Defines the function which the coverage probes call, if there are any. It counts a probe by
+/// adding 1 to its counter behind [COVERAGE_COUNTERS_GLOBAL], atomically because the expects may
+/// run in parallel. Nothing is counted while the host hasn't set that global.
+fn build_coverage_hit(env: &Env<'_, '_, '_>) {
+    let Some(function) = env.module.get_function(COVERAGE_HIT) else {
+        return;
+    };
+
+    let builder = env.builder;
+    let ptr_type = env.context.ptr_type(AddressSpace::default());
+    let i64_type = env.context.i64_type();
+
+    let counters_global = env
+        .module
+        .add_global(ptr_type, None, COVERAGE_COUNTERS_GLOBAL);
+    counters_global.set_initializer(&ptr_type.const_null());
+
+    let subprogram = env.new_subprogram(COVERAGE_HIT);
+    function.set_subprogram(subprogram);
+
+    debug_info_init!(env, function);
+
+    let entry = env.context.append_basic_block(function, "entry");
+    let count = env.context.append_basic_block(function, "count");
+    let done = env.context.append_basic_block(function, "done");
+
+    builder.position_at_end(entry);
+
+    let counters = builder
+        .new_build_load(ptr_type, counters_global.as_pointer_value(), "counters")
+        .into_pointer_value();
+    let is_null = builder.new_build_is_null(counters, "is_null");
+    builder.new_build_conditional_branch(is_null, done, count);
+
+    builder.position_at_end(count);
+
+    // the probe passes the id of its module, and its index among the probes of that module
+    let module_id = function.get_nth_param(0).unwrap().into_int_value();
+    let probe_index = function.get_nth_param(1).unwrap().into_int_value();
+    let module_id = builder.new_build_int_z_extend(module_id, i64_type, "module_id");
+    let probe_index = builder.new_build_int_z_extend(probe_index, i64_type, "probe_index");
+
+    let start_ptr =
+        unsafe { builder.new_build_in_bounds_gep(i64_type, counters, &[module_id], "start_ptr") };
+    let start = builder
+        .new_build_load(i64_type, start_ptr, "start")
+        .into_int_value();
+    let counter_index = builder.new_build_int_add(start, probe_index, "counter_index");
+    let counter_ptr = unsafe {
+        builder.new_build_in_bounds_gep(i64_type, counters, &[counter_index], "counter_ptr")
+    };
+
+    builder.new_build_atomicrmw(
+        AtomicRMWBinOp::Add,
+        counter_ptr,
+        i64_type.const_int(1, false),
+        AtomicOrdering::Monotonic,
+    );
+    builder.new_build_unconditional_branch(done);
+
+    builder.position_at_end(done);
+    builder.new_build_return(None);
+}
+
+/// Calls to foreign functions named after [BENCH_BLACK_BOX], which benchmarks pass their inputs
+/// and their value to. Nothing is called: a pointer to each argument is passed to an empty piece
+/// of inline assembly which may read and write any memory, so the optimizer has to compute the
+/// arguments, and can't tell what they are afterwards.
+///
+/// The first argument is returned, as it is after the assembly, if it has the layout of the
+/// result. The arguments are borrowed, like those of any foreign function, so the result gets a
+/// reference of its own.
+fn build_bench_black_box<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    layout_ids: &mut LayoutIds<'a>,
+    scope: &Scope<'a, 'ctx>,
+    arguments: &[Symbol],
+    ret_layout: InLayout<'a>,
+) -> BasicValueEnum<'ctx> {
+    let builder = env.builder;
+    let ptr_type = env.context.ptr_type(AddressSpace::default());
+
+    let asm_type = env.context.void_type().fn_type(&[ptr_type.into()], false);
+    let asm = env.context.create_inline_asm(
+        asm_type,
+        String::new(),
+        "r,~{memory}".to_string(),
+        true,
+        false,
+        None,
+        false,
+    );
+
+    let mut first = None;
+
+    for symbol in arguments {
+        let (value, layout) = scope.load_symbol_and_layout(symbol);
+
+        let value = match value {
+            BasicValueEnum::PointerValue(ptr) => {
+                builder.new_build_indirect_call(asm_type, asm, &[ptr.into()], "black_box");
+                value
+            }
+            value => {
+                let ptr = create_entry_block_alloca(env, value.get_type(), "black_box");
+                builder.new_build_store(ptr, value);
+                builder.new_build_indirect_call(asm_type, asm, &[ptr.into()], "black_box");
+                builder.new_build_load(value.get_type(), ptr, "black_box")
+            }
+        };
+
+        first.get_or_insert((value, layout));
+    }
+
+    match first {
+        Some((value, layout)) if layout == ret_layout => {
+            increment_refcount_layout(env, layout_interner, layout_ids, 1, value, layout);
+            value
+        }
+        _ => basic_type_from_layout(env, layout_interner, layout_interner.get_repr(ret_layout))
+            .const_zero(),
+    }
+}
+
 fn build_procedures_help<'a>(
     env: &Env<'a, '_, '_>,
     layout_interner: &STLayoutInterner<'a>,
@@ -758,6 +758,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                         self.code_builder.f64_const(f64::from_bits(*value));
                         self.code_builder.f64_eq();
                     }
+                    ValueType::V128 => internal_error!("Cannot switch on a V128 value"),
                 }
             }
 
@@ -114,6 +114,7 @@ impl WasmLayout {
             Self::Primitive(I64, _) => &[I64],
             Self::Primitive(F32, _) => &[F32],
             Self::Primitive(F64, _) => &[F64],
+            Self::Primitive(V128, _) => &[V128],
 
             // 1 Roc argument => 0-2 Wasm arguments (depending on size and calling convention)
             Self::StackMemory { size, format, .. } => stack_memory_arg_types(*size, *format),
@@ -74,6 +74,7 @@ impl From<ValueType> for CodeGenNumType {
             ValueType::I64 => CodeGenNumType::I64,
             ValueType::F32 => CodeGenNumType::F32,
             ValueType::F64 => CodeGenNumType::F64,
+            ValueType::V128 => internal_error!("V128 is not a Roc number type"),
         }
     }
 }
@@ -2245,6 +2246,7 @@ impl<'a> LowLevelCall<'a> {
                     ValueType::I64 => backend.code_builder.i64_const(0),
                     ValueType::F32 => backend.code_builder.f32_const(0.0),
                     ValueType::F64 => backend.code_builder.f64_const(0.0),
+                    ValueType::V128 => internal_error!("Roc values are never stored as V128"),
                 },
                 StoredValue::StackMemory { .. } => { /* do nothing */ }
             },
@@ -2365,12 +2367,14 @@ impl<'a> LowLevelCall<'a> {
                         ValueType::I64 => backend.code_builder.i64_eq(),
                         ValueType::F32 => backend.code_builder.f32_eq(),
                         ValueType::F64 => backend.code_builder.f64_eq(),
+                        ValueType::V128 => internal_error!("V128 is not a Roc number type"),
                     },
                     LowLevel::NotEq => match value_type {
                         ValueType::I32 => backend.code_builder.i32_ne(),
                         ValueType::I64 => backend.code_builder.i64_ne(),
                         ValueType::F32 => backend.code_builder.f32_ne(),
                         ValueType::F64 => backend.code_builder.f64_ne(),
+                        ValueType::V128 => internal_error!("V128 is not a Roc number type"),
                     },
                     _ => internal_error!("{:?} ended up in Equality code", self.lowlevel),
                 }
@@ -2508,6 +2512,7 @@ fn num_is_nan(backend: &mut WasmBackend<'_, '_>, argument: Symbol) {
                     backend.code_builder.i64_ne(); // Mantissa is non-zero
                     backend.code_builder.i32_and();
                 }
+                ValueType::V128 => internal_error!("V128 is not a Roc number type"),
             }
         }
         StackMemory { format, .. } => {
@@ -2551,6 +2556,7 @@ fn num_is_infinite(backend: &mut WasmBackend<'_, '_>, argument: Symbol) {
                     backend.code_builder.i64_const(0x7ff0_0000_0000_0000);
                     backend.code_builder.i64_eq();
                 }
+                ValueType::V128 => internal_error!("V128 is not a Roc number type"),
             }
         }
         StackMemory { format, .. } => {
@@ -2594,6 +2600,7 @@ fn num_is_finite(backend: &mut WasmBackend<'_, '_>, argument: Symbol) {
                     backend.code_builder.i64_const(0x7ff0_0000_0000_0000);
                     backend.code_builder.i64_ne();
                 }
+                ValueType::V128 => internal_error!("V128 is not a Roc number type"),
             }
         }
         StackMemory { format, .. } => {
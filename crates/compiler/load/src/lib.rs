@@ -21,8 +21,12 @@ pub use roc_load_internal::file::{
     ExecutionMode, ExpectMetadata, LoadConfig, LoadResult, LoadStart, LoadingProblem, Phase,
     Threading,
 };
+pub use roc_load_internal::incremental::{
+    IncrementalCache, IncrementalStats, SharedIncrementalCache, DEFAULT_INCREMENTAL_CAPACITY,
+};
 pub use roc_load_internal::module::{
     CheckedModule, EntryPoint, Expectations, ExposedToHost, LoadedModule, MonomorphizedModule,
+    ToplevelExpects,
 };
 pub use roc_solve::FunctionKind;
 
@@ -172,6 +176,36 @@ pub fn load_and_typecheck<'a>(
     }
 }
 
+/// Like [load_and_typecheck], but modules which did not change since an earlier load with the same
+/// `cache` take their types from it instead of being solved again.
+pub fn load_and_typecheck_incremental<'a>(
+    arena: &'a Bump,
+    filename: PathBuf,
+    opt_main_path: Option<PathBuf>,
+    roc_cache_dir: RocCacheDir<'_>,
+    load_config: LoadConfig,
+    cache: SharedIncrementalCache,
+) -> Result<LoadedModule, LoadingProblem<'a>> {
+    use LoadResult::*;
+
+    let load_start = LoadStart::from_path_incremental(
+        arena,
+        filename,
+        opt_main_path,
+        load_config.render,
+        roc_cache_dir,
+        load_config.palette,
+        cache,
+    )?;
+
+    let exposed_types = ExposedByModule::default();
+
+    match load(arena, load_start, exposed_types, roc_cache_dir, load_config)? {
+        Monomorphized(_) => unreachable!(""),
+        TypeChecked(module) => Ok(module),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn load_and_typecheck_str<'a>(
     arena: &'a Bump,
@@ -214,6 +248,49 @@ pub fn load_and_typecheck_str<'a>(
     }
 }
 
+/// Like [load_and_typecheck_str], but modules which did not change since an earlier load with the
+/// same `cache` take their types from it instead of being solved again.
+#[allow(clippy::too_many_arguments)]
+pub fn load_and_typecheck_str_incremental<'a>(
+    arena: &'a Bump,
+    filename: PathBuf,
+    source: &'a str,
+    src_dir: PathBuf,
+    opt_main_path: Option<PathBuf>,
+    target: Target,
+    function_kind: FunctionKind,
+    render: RenderTarget,
+    roc_cache_dir: RocCacheDir<'_>,
+    palette: Palette,
+    cache: SharedIncrementalCache,
+) -> Result<LoadedModule, LoadingProblem<'a>> {
+    use LoadResult::*;
+
+    let load_start = LoadStart::from_str_incremental(
+        arena,
+        filename,
+        opt_main_path,
+        source,
+        roc_cache_dir,
+        src_dir,
+        cache,
+    )?;
+
+    match load_single_threaded(
+        arena,
+        load_start,
+        target,
+        function_kind,
+        render,
+        palette,
+        roc_cache_dir,
+        ExecutionMode::Check,
+    )? {
+        Monomorphized(_) => unreachable!(""),
+        TypeChecked(module) => Ok(module),
+    }
+}
+
 macro_rules! include_bytes_align_as {
     ($align_ty:ty, $path:expr) => {{
         // const block expression to encapsulate the static
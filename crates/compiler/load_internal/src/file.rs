@@ -1,6 +1,7 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::docs::ModuleDocumentation;
+use crate::incremental::{IncrementalLoad, SharedIncrementalCache};
 use crate::module::{
     CheckedModule, ConstrainedModule, EntryPoint, Expectations, ExposedToHost,
     FoundSpecializationsModule, LateSpecializationsModule, LoadedModule, ModuleHeader,
@@ -41,9 +42,10 @@ use roc_module::symbol::{
     IdentIds, IdentIdsByModule, Interns, ModuleId, ModuleIds, PQModuleName, PackageModuleIds,
     PackageQualified, Symbol,
 };
+use roc_mono::coverage::CoverageProbes;
 use roc_mono::ir::{
     CapturedSymbols, ExternalSpecializations, GlueLayouts, HostExposedLambdaSets, PartialProc,
-    Proc, ProcLayout, Procs, ProcsBase, UpdateModeIds, UsageTrackingMap,
+    Proc, ProcLayout, Procs, ProcsBase, UpdateModeIds, UsageTrackingMap, BENCH_BLACK_BOX,
 };
 use roc_mono::layout::{
     GlobalLayoutInterner, LambdaName, Layout, LayoutCache, LayoutProblem, Niche, STLayoutInterner,
@@ -60,6 +62,7 @@ use roc_parse::header::{
 use roc_parse::parser::{FileError, SourceError, SyntaxError};
 use roc_problem::Severity;
 use roc_region::all::{LineInfo, Loc, Region};
+use roc_reporting::error::codes;
 use roc_reporting::error::r#type::suggest;
 #[cfg(not(target_family = "wasm"))]
 use roc_reporting::report::to_https_problem_report_string;
@@ -127,6 +130,9 @@ pub enum ExecutionMode {
     /// Test is like [`ExecutionMode::ExecutableIfCheck`], but rather than producing a proper
     /// executable, run tests.
     Test,
+    /// Like [`ExecutionMode::Test`], but the defs and branches of every module are probed, to
+    /// count which of them the tests run.
+    TestWithCoverage,
 }
 
 impl ExecutionMode {
@@ -135,12 +141,19 @@ impl ExecutionMode {
 
         match self {
             Executable => Phase::MakeSpecializations,
-            Check | ExecutableIfCheck | Test => Phase::SolveTypes,
+            Check | ExecutableIfCheck | Test | TestWithCoverage => Phase::SolveTypes,
         }
     }
 
     fn build_if_checks(&self) -> bool {
-        matches!(self, Self::ExecutableIfCheck | Self::Test)
+        matches!(
+            self,
+            Self::ExecutableIfCheck | Self::Test | Self::TestWithCoverage
+        )
+    }
+
+    fn is_test(&self) -> bool {
+        matches!(self, Self::Test | Self::TestWithCoverage)
     }
 }
 
@@ -315,6 +328,25 @@ fn start_phase<'a>(
                     }
                 }
 
+                if let Some(incremental) = state.incremental.as_mut() {
+                    let module_name = &state.module_cache.module_names[&module_id];
+                    let (_, source) = state.module_cache.sources[&module_id];
+                    let imports = state.module_cache.imports.get(&module_id);
+
+                    let opt_type_state = incremental.reuse(
+                        module_id,
+                        module_name,
+                        source,
+                        state.ident_ids_by_module.lock().get(&module_id),
+                        imports.into_iter().flatten(),
+                    );
+
+                    // Cached types make us skip generating constraints, just like for builtins.
+                    if let Some(type_state) = opt_type_state {
+                        state.cached_types.lock().insert(module_id, type_state);
+                    }
+                }
+
                 let skip_constraint_gen = {
                     // Give this its own scope to make sure that the Guard from the lock() is dropped
                     // immediately after contains_key returns
@@ -431,8 +463,10 @@ fn start_phase<'a>(
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
 
-                let build_expects =
-                    matches!(state.exec_mode, ExecutionMode::Test) && expectations.is_some();
+                let build_expects = state.exec_mode.is_test() && expectations.is_some();
+                // only the code being tested is probed, not the builtins
+                let coverage = matches!(state.exec_mode, ExecutionMode::TestWithCoverage)
+                    && !module_id.is_builtin();
 
                 BuildTask::BuildPendingSpecializations {
                     layout_cache,
@@ -449,6 +483,7 @@ fn start_phase<'a>(
                     derived_module,
                     expectations,
                     build_expects,
+                    coverage,
                 }
             }
             Phase::MakeSpecializations => {
@@ -741,6 +776,7 @@ struct State<'a> {
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
     pub host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
     pub toplevel_expects: MutMap<ModuleId, ToplevelExpects>,
+    pub coverage: MutMap<ModuleId, CoverageProbes<'a>>,
     pub exposed_to_host: ExposedToHost,
 
     /// This is the "final" list of IdentIds, after canonicalization and constraint gen
@@ -779,11 +815,30 @@ struct State<'a> {
     // cached types (used for builtin modules, could include packages in the future too)
     cached_types: CachedTypeState,
 
+    /// Set when types solved by earlier loads may be reused for modules which did not change.
+    incremental: Option<IncrementalLoad>,
+
     layout_interner: GlobalLayoutInterner<'a>,
 }
 
 type CachedTypeState = Arc<Mutex<MutMap<ModuleId, TypeState>>>;
 
+fn incremental_load(
+    cache: Option<SharedIncrementalCache>,
+    exec_mode: ExecutionMode,
+    root_id: ModuleId,
+) -> Option<IncrementalLoad> {
+    // Modules taking their types from the cache are not solved, so the derived implementations
+    // and lowered params which later phases need would be missing.
+    match exec_mode {
+        ExecutionMode::Check => cache.map(|cache| IncrementalLoad::new(cache, root_id)),
+        ExecutionMode::Executable
+        | ExecutionMode::ExecutableIfCheck
+        | ExecutionMode::Test
+        | ExecutionMode::TestWithCoverage => None,
+    }
+}
+
 impl<'a> State<'a> {
     fn goal_phase(&self) -> Phase {
         self.exec_mode.goal_phase()
@@ -825,6 +880,7 @@ impl<'a> State<'a> {
             procedures: MutMap::default(),
             host_exposed_lambda_sets: std::vec::Vec::new(),
             toplevel_expects: MutMap::default(),
+            coverage: MutMap::default(),
             exposed_to_host: ExposedToHost::default(),
             exposed_modules: &[],
             exposed_types,
@@ -838,6 +894,7 @@ impl<'a> State<'a> {
             timings: MutMap::default(),
             layout_caches: std::vec::Vec::with_capacity(number_of_workers),
             cached_types: Arc::new(Mutex::new(cached_types)),
+            incremental: None,
             render,
             palette,
             exec_mode,
@@ -960,6 +1017,7 @@ enum BuildTask<'a> {
         derived_module: SharedDerivedModule,
         expectations: Option<Expectations>,
         build_expects: bool,
+        coverage: bool,
     },
     MakeSpecializations {
         module_id: ModuleId,
@@ -1175,6 +1233,7 @@ pub struct LoadStart<'a> {
     root_type: RootType,
     opt_platform_shorthand: Option<&'a str>,
     src_dir: PathBuf,
+    incremental: Option<SharedIncrementalCache>,
 }
 
 #[derive(Debug, Clone)]
@@ -1192,7 +1251,54 @@ impl<'a> LoadStart<'a> {
         roc_cache_dir: RocCacheDir<'_>,
         palette: Palette,
     ) -> Result<Self, LoadingProblem<'a>> {
-        let arc_modules = Arc::new(Mutex::new(PackageModuleIds::default()));
+        Self::from_path_help(
+            arena,
+            filename,
+            opt_main_path,
+            render,
+            roc_cache_dir,
+            palette,
+            None,
+        )
+    }
+
+    /// Like [LoadStart::from_path], but modules which did not change since they were last solved
+    /// with this `cache` take their types from it rather than being solved again.
+    pub fn from_path_incremental(
+        arena: &'a Bump,
+        filename: PathBuf,
+        opt_main_path: Option<PathBuf>,
+        render: RenderTarget,
+        roc_cache_dir: RocCacheDir<'_>,
+        palette: Palette,
+        cache: SharedIncrementalCache,
+    ) -> Result<Self, LoadingProblem<'a>> {
+        Self::from_path_help(
+            arena,
+            filename,
+            opt_main_path,
+            render,
+            roc_cache_dir,
+            palette,
+            Some(cache),
+        )
+    }
+
+    fn from_path_help(
+        arena: &'a Bump,
+        filename: PathBuf,
+        opt_main_path: Option<PathBuf>,
+        render: RenderTarget,
+        roc_cache_dir: RocCacheDir<'_>,
+        palette: Palette,
+        incremental: Option<SharedIncrementalCache>,
+    ) -> Result<Self, LoadingProblem<'a>> {
+        // Reusing solved types requires the modules to get the same ids as in earlier loads.
+        let module_ids = match &incremental {
+            Some(cache) => cache.lock().module_ids(arena),
+            None => PackageModuleIds::default(),
+        };
+        let arc_modules = Arc::new(Mutex::new(module_ids));
         let arc_shorthands = Arc::new(Mutex::new(MutMap::default()));
         let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
         let ident_ids_by_module = Arc::new(Mutex::new(root_exposed_ident_ids));
@@ -1255,18 +1361,68 @@ impl<'a> LoadStart<'a> {
             root_msg: header_output.msg,
             root_type,
             opt_platform_shorthand: header_output.opt_platform_shorthand,
+            incremental,
         })
     }
 
     pub fn from_str(
+        arena: &'a Bump,
+        filename: PathBuf,
+        opt_main_path: Option<PathBuf>,
+        src: &'a str,
+        roc_cache_dir: RocCacheDir<'_>,
+        src_dir: PathBuf,
+    ) -> Result<Self, LoadingProblem<'a>> {
+        Self::from_str_help(
+            arena,
+            filename,
+            opt_main_path,
+            src,
+            roc_cache_dir,
+            src_dir,
+            None,
+        )
+    }
+
+    /// Like [LoadStart::from_str], but modules which did not change since they were last solved
+    /// with this `cache` take their types from it rather than being solved again.
+    /// Types are only reused when type-checking, and the types solved by this load are added to
+    /// the cache.
+    pub fn from_str_incremental(
+        arena: &'a Bump,
+        filename: PathBuf,
+        opt_main_path: Option<PathBuf>,
+        src: &'a str,
+        roc_cache_dir: RocCacheDir<'_>,
+        src_dir: PathBuf,
+        cache: SharedIncrementalCache,
+    ) -> Result<Self, LoadingProblem<'a>> {
+        Self::from_str_help(
+            arena,
+            filename,
+            opt_main_path,
+            src,
+            roc_cache_dir,
+            src_dir,
+            Some(cache),
+        )
+    }
+
+    fn from_str_help(
         arena: &'a Bump,
         filename: PathBuf,
         opt_main_path: Option<PathBuf>,
         src: &'a str,
         roc_cache_dir: RocCacheDir<'_>,
         mut src_dir: PathBuf,
+        incremental: Option<SharedIncrementalCache>,
     ) -> Result<Self, LoadingProblem<'a>> {
-        let arc_modules = Arc::new(Mutex::new(PackageModuleIds::default()));
+        // Reusing solved types requires the modules to get the same ids as in earlier loads.
+        let module_ids = match &incremental {
+            Some(cache) => cache.lock().module_ids(arena),
+            None => PackageModuleIds::default(),
+        };
+        let arc_modules = Arc::new(Mutex::new(module_ids));
         let arc_shorthands = Arc::new(Mutex::new(MutMap::default()));
         let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
         let ident_ids_by_module = Arc::new(Mutex::new(root_exposed_ident_ids));
@@ -1311,6 +1467,7 @@ impl<'a> LoadStart<'a> {
             root_msg,
             root_type,
             opt_platform_shorthand: opt_platform_id,
+            incremental,
         })
     }
 }
@@ -1604,7 +1761,7 @@ pub fn load_single_threaded<'a>(
         root_type,
         src_dir,
         opt_platform_shorthand,
-        ..
+        incremental,
     } = load_start;
 
     let (msg_tx, msg_rx) = bounded(1024);
@@ -1631,6 +1788,7 @@ pub fn load_single_threaded<'a>(
         number_of_workers,
         exec_mode,
     );
+    state.incremental = incremental_load(incremental, exec_mode, root_id);
 
     // We'll add tasks to this, and then worker threads will take tasks from it.
     let injector = Injector::new();
@@ -1985,7 +2143,7 @@ fn load_multi_threaded<'a>(
         root_type,
         src_dir,
         opt_platform_shorthand,
-        ..
+        incremental,
     } = load_start;
 
     let (msg_tx, msg_rx) = bounded(1024);
@@ -2027,6 +2185,7 @@ fn load_multi_threaded<'a>(
         num_workers,
         exec_mode,
     );
+    state.incremental = incremental_load(incremental, exec_mode, root_id);
 
     // an arena for every worker, stored in an arena-allocated bumpalo vec to make the lifetimes work
     let arenas = std::iter::repeat_with(Bump::new).take(num_workers);
@@ -2521,10 +2680,18 @@ fn update<'a>(
             log!("solved types for {:?}", module_id);
             module_timing.end_time = Instant::now();
 
-            state
-                .module_cache
-                .type_problems
-                .insert(module_id, solved_module.problems);
+            let mut problems = solved_module.problems;
+
+            if let Some(incremental) = state.incremental.as_mut() {
+                incremental.solved(module_id, &mut problems, || TypeState {
+                    subs: solved_subs.inner().clone(),
+                    exposed_vars_by_symbol: solved_module.exposed_vars_by_symbol.clone(),
+                    abilities: abilities_store.clone(),
+                    solved_implementations: solved_module.solved_implementations.clone(),
+                });
+            }
+
+            state.module_cache.type_problems.insert(module_id, problems);
             state
                 .module_cache
                 .exposes
@@ -2561,7 +2728,7 @@ fn update<'a>(
 
             let add_to_host_exposed = is_host_exposed &&
                 // During testing, we don't need to expose anything to the host.
-                !matches!(state.exec_mode, ExecutionMode::Test);
+                !state.exec_mode.is_test();
 
             if add_to_host_exposed {
                 state.exposed_to_host.top_level_values.extend(
@@ -2728,6 +2895,10 @@ fn update<'a>(
                 state.toplevel_expects.insert(module_id, toplevel_expects);
             }
 
+            if let Some(probes) = procs_base.coverage {
+                state.coverage.insert(module_id, probes);
+            }
+
             state
                 .module_cache
                 .top_level_thunks
@@ -3219,7 +3390,7 @@ fn finish_specialization<'a>(
     let entry_point = {
         let interns: &mut Interns = &mut interns;
         match state.exec_mode {
-            ExecutionMode::Test => Ok(EntryPoint::Test),
+            ExecutionMode::Test | ExecutionMode::TestWithCoverage => Ok(EntryPoint::Test),
             ExecutionMode::Executable | ExecutionMode::ExecutableIfCheck => {
                 use PlatformPath::*;
 
@@ -3285,6 +3456,7 @@ fn finish_specialization<'a>(
 
     let State {
         toplevel_expects,
+        coverage,
         procedures,
         host_exposed_lambda_sets,
         module_cache,
@@ -3327,6 +3499,7 @@ fn finish_specialization<'a>(
         sources,
         timings: state.timings,
         toplevel_expects,
+        coverage,
         glue_layouts: GlueLayouts { getters: vec![] },
         needs_prebuilt_host,
     })
@@ -3364,6 +3537,10 @@ fn finish(
     //
     #[cfg(debug_assertions)] checkmate: Option<roc_checkmate::Collector>,
 ) -> LoadedModule {
+    if let Some(incremental) = &state.incremental {
+        incremental.remember_module_ids(&state.arc_modules.lock());
+    }
+
     let module_ids = Arc::try_unwrap(state.arc_modules)
         .unwrap_or_else(|_| panic!("There were still outstanding Arc references to module_ids"))
         .into_inner()
@@ -4904,42 +5081,12 @@ fn run_solve<'a>(
     let module = module;
 
     let solve_result = {
-        if module_id.is_builtin() {
-            match cached_types.lock().remove(&module_id) {
-                None => run_solve_solve(
-                    exposed_for_module,
-                    types,
-                    constraints,
-                    constraint,
-                    function_kind,
-                    pending_derives,
-                    var_store,
-                    module,
-                    derived_module,
-                    is_host_exposed,
-                    //
-                    #[cfg(debug_assertions)]
-                    checkmate,
-                ),
-                Some(TypeState {
-                    subs,
-                    exposed_vars_by_symbol,
-                    abilities,
-                    solved_implementations,
-                }) => SolveResult {
-                    solved: Solved(subs),
-                    solved_implementations,
-                    exposed_vars_by_symbol,
-                    problems: vec![],
-                    abilities_store: abilities,
-                    imported_modules_with_params: vec![],
+        // Builtins are usually cached, other modules only when they were solved by an earlier
+        // incremental load and have not changed since.
+        let opt_cached = cached_types.lock().remove(&module_id);
 
-                    #[cfg(debug_assertions)]
-                    checkmate: None,
-                },
-            }
-        } else {
-            run_solve_solve(
+        match opt_cached {
+            None => run_solve_solve(
                 exposed_for_module,
                 types,
                 constraints,
@@ -4953,7 +5100,23 @@ fn run_solve<'a>(
                 //
                 #[cfg(debug_assertions)]
                 checkmate,
-            )
+            ),
+            Some(TypeState {
+                subs,
+                exposed_vars_by_symbol,
+                abilities,
+                solved_implementations,
+            }) => SolveResult {
+                solved: Solved(subs),
+                solved_implementations,
+                exposed_vars_by_symbol,
+                problems: vec![],
+                abilities_store: abilities,
+                imported_modules_with_params: vec![],
+
+                #[cfg(debug_assertions)]
+                checkmate: None,
+            },
         }
     };
 
@@ -4982,7 +5145,10 @@ fn run_solve<'a>(
         ExecutionMode::Check => {
             // Params are not lowered in check mode
         }
-        ExecutionMode::Executable | ExecutionMode::ExecutableIfCheck | ExecutionMode::Test => {
+        ExecutionMode::Executable
+        | ExecutionMode::ExecutableIfCheck
+        | ExecutionMode::Test
+        | ExecutionMode::TestWithCoverage => {
             roc_lower_params::type_error::remove_module_param_arguments(
                 &mut problems,
                 home_has_params,
@@ -5287,7 +5453,10 @@ fn canonicalize_and_constrain<'a>(
             // No need to lower params for `roc check` and lang server
             // If we did, we'd have to update the language server to exclude the extra arguments
         }
-        ExecutionMode::Executable | ExecutionMode::ExecutableIfCheck | ExecutionMode::Test => {
+        ExecutionMode::Executable
+        | ExecutionMode::ExecutableIfCheck
+        | ExecutionMode::Test
+        | ExecutionMode::TestWithCoverage => {
             // We need to lower params only if the current module has any or imports at least one with params
             if module_output.module_params.is_some() || !imported_module_params.is_empty() {
                 roc_lower_params::lower::lower(
@@ -5745,6 +5914,7 @@ fn make_specializations<'a>(
     procs.module_thunks = procs_base.module_thunks;
     procs.runtime_errors = procs_base.runtime_errors;
     procs.imported_module_thunks = procs_base.imported_module_thunks;
+    procs.coverage = procs_base.coverage;
 
     // TODO: for now this final specialization pass is sequential,
     // with no parallelization at all. We should try to parallelize
@@ -5800,6 +5970,7 @@ fn build_pending_specializations<'a>(
     derived_module: SharedDerivedModule,
     mut expectations: Option<Expectations>,
     build_expects: bool,
+    coverage: bool,
 ) -> Msg<'a> {
     let find_specializations_start = Instant::now();
 
@@ -5812,6 +5983,7 @@ fn build_pending_specializations<'a>(
         host_specializations: roc_mono::ir::HostSpecializations::new(),
         runtime_errors: BumpMap::default(),
         imported_module_thunks,
+        coverage: coverage.then(|| CoverageProbes::from_declarations(arena, &declarations)),
     };
 
     let mut update_mode_ids = UpdateModeIds::new();
@@ -6073,6 +6245,58 @@ fn build_pending_specializations<'a>(
                     continue;
                 }
 
+                // extend the region of the expect expression with the region of the preceding
+                // comment, so it is shown in failure/panic messages
+                let name_region = declarations.symbols[index].region;
+                let expr_region = declarations.expressions[index].region;
+                let region = Region::span_across(&name_region, &expr_region);
+
+                if let roc_can::expr::Expr::Closure(closure_data) = body.value {
+                    // A property is a function of its inputs, which the host generates. It is
+                    // named by its lambda, so that it matches its lambda set.
+                    let closure_data =
+                        roc_can::expr::toplevel_property_to_inline_expect(closure_data);
+                    let symbol = closure_data.name;
+                    let expr_var = closure_data.function_type;
+
+                    let layout_result =
+                        layout_cache.raw_from_var(mono_env.arena, expr_var, mono_env.subs);
+
+                    if let Err(e) = layout_result {
+                        let message = match e {
+                            LayoutProblem::Erroneous => "property has erroneous type".to_string(),
+                            LayoutProblem::UnresolvedTypeVar(v) => {
+                                format!("property has unresolved type variable {v:?}")
+                            }
+                        };
+                        procs_base
+                            .runtime_errors
+                            .insert(symbol, mono_env.arena.alloc(message));
+                        continue;
+                    }
+
+                    procs_base.host_specializations.insert_host_exposed(
+                        mono_env.subs,
+                        LambdaName::no_niche(symbol),
+                        None,
+                        expr_var,
+                    );
+
+                    register_toplevel_function_into_procs_base(
+                        &mut mono_env,
+                        &mut procs_base,
+                        symbol,
+                        expr_var,
+                        closure_data.arguments,
+                        closure_data.return_type,
+                        *closure_data.loc_body,
+                        false,
+                    );
+
+                    toplevel_expects.pure.insert(symbol, region);
+                    continue;
+                }
+
                 // mark this symbol as a top-level thunk before any other work on the procs
                 module_thunks.push(symbol);
 
@@ -6116,7 +6340,27 @@ fn build_pending_specializations<'a>(
                     );
                 }
 
-                let body = roc_can::expr::toplevel_expect_to_inline_expect_pure(body);
+                let body = if let Some(name) = roc_can::expr::toplevel_snapshot(&body) {
+                    toplevel_expects.snapshots.insert(symbol, name.to_string());
+
+                    roc_can::expr::toplevel_snapshot_to_inline_expect(body)
+                } else if let Some(name) = roc_can::expr::toplevel_bench(&body) {
+                    toplevel_expects.benches.insert(symbol, name.to_string());
+
+                    let black_box = format!(
+                        "{BENCH_BLACK_BOX}_{}_{}",
+                        home.to_zero_indexed(),
+                        symbol.ident_id().index()
+                    );
+
+                    roc_can::expr::toplevel_bench_to_black_box(
+                        body,
+                        black_box.into(),
+                        mono_env.subs,
+                    )
+                } else {
+                    roc_can::expr::toplevel_expect_to_inline_expect_pure(body)
+                };
 
                 let proc = PartialProc {
                     annotation: expr_var,
@@ -6130,12 +6374,6 @@ fn build_pending_specializations<'a>(
                     is_self_recursive: false,
                 };
 
-                // extend the region of the expect expression with the region of the preceding
-                // comment, so it is shown in failure/panic messages
-                let name_region = declarations.symbols[index].region;
-                let expr_region = declarations.expressions[index].region;
-                let region = Region::span_across(&name_region, &expr_region);
-
                 toplevel_expects.pure.insert(symbol, region);
                 procs_base.partial_procs.insert(symbol, proc);
             }
@@ -6430,6 +6668,7 @@ fn run_task<'a>(
             derived_module,
             expectations,
             build_expects,
+            coverage,
         } => Ok(build_pending_specializations(
             arena,
             solved_subs,
@@ -6446,6 +6685,7 @@ fn run_task<'a>(
             derived_module,
             expectations,
             build_expects,
+            coverage,
         )),
         MakeSpecializations {
             module_id,
@@ -6557,6 +6797,7 @@ fn to_import_cycle_report(
         filename,
         doc,
         title: "IMPORT CYCLE".to_string(),
+        code: Some(codes::E0418),
         severity: Severity::RuntimeError,
     };
 
@@ -6608,6 +6849,7 @@ fn to_incorrect_module_name_report<'a>(
         filename,
         doc,
         title: "INCORRECT MODULE NAME".to_string(),
+        code: Some(codes::E0419),
         severity,
     };
 
@@ -6656,6 +6898,7 @@ fn to_no_platform_package_report(
         filename,
         doc,
         title: "UNSPECIFIED PLATFORM".to_string(),
+        code: Some(codes::E0420),
         severity,
     };
 
@@ -6700,6 +6943,7 @@ fn to_multiple_platform_packages_report(
         filename,
         doc,
         title: "MULTIPLE PLATFORMS".to_string(),
+        code: Some(codes::E0421),
         severity,
     };
 
@@ -6807,6 +7051,7 @@ fn to_unrecognized_package_shorthand_report(
         filename,
         doc,
         title: "UNRECOGNIZED PACKAGE".to_string(),
+        code: Some(codes::E0422),
         severity,
     };
 
@@ -6890,6 +7135,7 @@ fn report_cannot_run(
                     filename,
                     doc,
                     title: "NO PLATFORM".to_string(),
+                    code: Some(codes::E0423),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -6905,6 +7151,7 @@ fn report_cannot_run(
                     filename,
                     doc,
                     title: "NO PLATFORM".to_string(),
+                    code: Some(codes::E0423),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -6920,6 +7167,7 @@ fn report_cannot_run(
                     filename,
                     doc,
                     title: "NO PLATFORM".to_string(),
+                    code: Some(codes::E0423),
                     severity: Severity::RuntimeError,
                 }
             }
@@ -6935,6 +7183,7 @@ fn report_cannot_run(
                     filename,
                     doc,
                     title: "NO PLATFORM".to_string(),
+                    code: Some(codes::E0423),
                     severity: Severity::RuntimeError,
                 }
             }
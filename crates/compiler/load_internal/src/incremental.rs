@@ -0,0 +1,301 @@
+//! Reusing the solved types of unchanged modules across loads of the same project.
+//!
+//! Long-running tools like the language server load the same project over and over, usually
+//! after a change to just one module. Solving every other module again is wasted work, so an
+//! [IncrementalCache] remembers the solved types of every module together with a fingerprint of
+//! everything solving that module depended on. A later load which arrives at the same fingerprint
+//! takes the types from the cache, and neither generates constraints for the module nor solves it.
+//!
+//! Modules are still parsed and canonicalized on every load: the modules importing them need the
+//! aliases and abilities they declare, and tools need their canonical declarations.
+use std::sync::Arc;
+
+use bumpalo::Bump;
+use parking_lot::Mutex;
+use roc_can::module::TypeState;
+use roc_collections::MutMap;
+use roc_module::ident::ModuleName;
+use roc_module::symbol::{IdentIds, ModuleId, PQModuleName, PackageModuleIds};
+use roc_solve_problem::TypeError;
+
+pub type SharedIncrementalCache = Arc<Mutex<IncrementalCache>>;
+
+/// The number of modules whose types an [IncrementalCache] keeps by default.
+pub const DEFAULT_INCREMENTAL_CAPACITY: usize = 256;
+
+/// Solved types kept around between loads.
+///
+/// Solved types refer to modules by their [ModuleId], so the cache also remembers which module
+/// got which id, and loads using the cache hand out the same ids again.
+///
+/// At most `capacity` modules are kept. Beyond that, the modules which were used least recently
+/// are evicted.
+#[derive(Debug)]
+pub struct IncrementalCache {
+    /// Package shorthand and name of every module, indexed by [ModuleId].
+    module_names: Vec<(Option<String>, ModuleName)>,
+    modules: MutMap<ModuleId, CachedModule>,
+    capacity: usize,
+    /// Counts the loads using this cache, to tell which modules were used least recently.
+    generation: u64,
+    stats: IncrementalStats,
+}
+
+/// How often loads using an [IncrementalCache] could reuse the types of a module.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IncrementalStats {
+    /// Modules whose types were taken from the cache.
+    pub reused: usize,
+    /// Modules which had to be solved, not counting builtins.
+    pub solved: usize,
+}
+
+#[derive(Debug)]
+struct CachedModule {
+    /// Covers the module's name, source and idents, and transitively those of its imports.
+    fingerprint: String,
+    /// The fingerprints of the imports at the time this module was solved.
+    imports: MutMap<ModuleId, String>,
+    type_state: TypeState,
+    type_problems: Vec<TypeError>,
+    /// The generation of the load which last used this module.
+    last_used: u64,
+}
+
+impl Default for IncrementalCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_INCREMENTAL_CAPACITY)
+    }
+}
+
+impl IncrementalCache {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            module_names: Vec::new(),
+            modules: MutMap::default(),
+            capacity,
+            generation: 0,
+            stats: IncrementalStats::default(),
+        }
+    }
+
+    /// The number of modules whose solved types are cached.
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    pub fn stats(&self) -> IncrementalStats {
+        self.stats
+    }
+
+    fn insert(&mut self, module_id: ModuleId, cached: CachedModule) {
+        self.modules.insert(module_id, cached);
+
+        while self.modules.len() > self.capacity {
+            let least_recently_used = self
+                .modules
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(module_id, _)| *module_id);
+
+            match least_recently_used {
+                Some(module_id) => self.modules.remove(&module_id),
+                None => break,
+            };
+        }
+    }
+
+    /// Module ids which agree with the ones used by earlier loads.
+    pub(crate) fn module_ids<'a>(&self, arena: &'a Bump) -> PackageModuleIds<'a> {
+        let mut module_ids = PackageModuleIds::default();
+
+        for (shorthand, name) in self.module_names.iter() {
+            let pq_name = match shorthand {
+                Some(shorthand) => {
+                    PQModuleName::Qualified(arena.alloc_str(shorthand), name.clone())
+                }
+                None => PQModuleName::Unqualified(name.clone()),
+            };
+
+            module_ids.get_or_insert(&pq_name);
+        }
+
+        module_ids
+    }
+
+    pub(crate) fn remember_module_ids(&mut self, module_ids: &PackageModuleIds) {
+        self.module_names = module_ids
+            .available_modules()
+            .map(|pq_name| {
+                (
+                    pq_name.package_shorthand().map(String::from),
+                    pq_name.as_inner().clone(),
+                )
+            })
+            .collect();
+    }
+}
+
+/// The bookkeeping of a single load which uses an [IncrementalCache].
+#[derive(Debug)]
+pub(crate) struct IncrementalLoad {
+    cache: SharedIncrementalCache,
+    generation: u64,
+    /// The module being loaded. It is the one most likely to be edited before the next load, so
+    /// its types are not worth copying into the cache.
+    root_id: ModuleId,
+    fingerprints: MutMap<ModuleId, String>,
+    imports: MutMap<ModuleId, MutMap<ModuleId, String>>,
+    /// Modules whose types came from the cache, with the problems found when they were solved.
+    reused: MutMap<ModuleId, Vec<TypeError>>,
+}
+
+impl IncrementalLoad {
+    pub(crate) fn new(cache: SharedIncrementalCache, root_id: ModuleId) -> Self {
+        let generation = {
+            let mut cache = cache.lock();
+            cache.generation += 1;
+            cache.generation
+        };
+
+        Self {
+            cache,
+            generation,
+            root_id,
+            fingerprints: MutMap::default(),
+            imports: MutMap::default(),
+            reused: MutMap::default(),
+        }
+    }
+
+    pub(crate) fn remember_module_ids(&self, module_ids: &PackageModuleIds) {
+        self.cache.lock().remember_module_ids(module_ids);
+    }
+
+    /// Called right before `module_id` gets canonicalized, at which point all of its imports are
+    /// canonicalized. Returns the types from an earlier load if nothing solving the module depends
+    /// on has changed since, in which case there is no need to generate its constraints.
+    ///
+    /// `ident_ids` are the module's idents before canonicalization. Given those and the same
+    /// source and imports, canonicalization creates the same symbols and variables as before.
+    pub(crate) fn reuse<'a>(
+        &mut self,
+        module_id: ModuleId,
+        module_name: &PQModuleName,
+        source: &str,
+        ident_ids: Option<&IdentIds>,
+        imports: impl IntoIterator<Item = &'a ModuleId>,
+    ) -> Option<TypeState> {
+        let imports = imports
+            .into_iter()
+            .map(|import| Some((*import, self.fingerprints.get(import)?.clone())))
+            .collect::<Option<MutMap<_, _>>>()?;
+
+        let mut hasher = blake3::Hasher::new();
+
+        if let Some(shorthand) = module_name.package_shorthand() {
+            hasher.update(shorthand.as_bytes());
+            hasher.update(b".");
+        }
+        hasher.update(module_name.as_inner().as_str().as_bytes());
+        hasher.update(b"\0");
+        hasher.update(source.as_bytes());
+
+        for (_, ident) in ident_ids.into_iter().flat_map(IdentIds::ident_strs) {
+            hasher.update(b"\0");
+            hasher.update(ident.as_bytes());
+        }
+
+        let mut import_fingerprints: Vec<_> = imports.values().collect();
+        import_fingerprints.sort();
+
+        for fingerprint in import_fingerprints {
+            hasher.update(b"\0");
+            hasher.update(fingerprint.as_bytes());
+        }
+
+        let fingerprint = base64_url::encode(hasher.finalize().as_bytes());
+
+        // Builtins already have their types cached, independently of any incremental cache.
+        let reused = if module_id.is_builtin() {
+            None
+        } else {
+            let mut cache = self.cache.lock();
+            let reused = cache
+                .modules
+                .get_mut(&module_id)
+                .filter(|cached| cached.fingerprint == fingerprint && cached.imports == imports)
+                .map(|cached| {
+                    cached.last_used = self.generation;
+                    (cached.type_state.clone(), cached.type_problems.clone())
+                });
+
+            if reused.is_some() {
+                cache.stats.reused += 1;
+            }
+
+            reused
+        };
+
+        self.fingerprints.insert(module_id, fingerprint);
+
+        match reused {
+            Some((type_state, type_problems)) => {
+                self.reused.insert(module_id, type_problems);
+
+                Some(type_state)
+            }
+            None => {
+                self.imports.insert(module_id, imports);
+
+                None
+            }
+        }
+    }
+
+    /// Called once `module_id` is solved. Restores the problems of modules whose types came from
+    /// the cache, and caches the types of modules other than the root which were solved from
+    /// scratch.
+    pub(crate) fn solved(
+        &mut self,
+        module_id: ModuleId,
+        problems: &mut Vec<TypeError>,
+        type_state: impl FnOnce() -> TypeState,
+    ) {
+        if let Some(type_problems) = self.reused.remove(&module_id) {
+            *problems = type_problems;
+            return;
+        }
+
+        if module_id.is_builtin() {
+            return;
+        }
+
+        self.cache.lock().stats.solved += 1;
+
+        if module_id == self.root_id {
+            return;
+        }
+
+        let (Some(fingerprint), Some(imports)) = (
+            self.fingerprints.get(&module_id),
+            self.imports.remove(&module_id),
+        ) else {
+            return;
+        };
+
+        let cached = CachedModule {
+            fingerprint: fingerprint.clone(),
+            imports,
+            type_state: type_state(),
+            type_problems: problems.clone(),
+            last_used: self.generation,
+        };
+
+        self.cache.lock().insert(module_id, cached);
+    }
+}
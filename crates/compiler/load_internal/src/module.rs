@@ -11,6 +11,7 @@ use roc_module::ident::Ident;
 use roc_module::symbol::{
     IdentIds, IdentIdsByModule, Interns, ModuleId, PQModuleName, PackageQualified, Symbol,
 };
+use roc_mono::coverage::CoverageProbes;
 use roc_mono::ir::{GlueLayouts, HostExposedLambdaSets, LambdaSetId, Proc, ProcLayout, ProcsBase};
 use roc_mono::layout::{LayoutCache, STLayoutInterner};
 use roc_parse::ast::{CommentOrNewline, Defs, TypeAnnotation};
@@ -167,6 +168,12 @@ pub(crate) struct LateSpecializationsModule<'a> {
 #[derive(Debug, Default)]
 pub struct ToplevelExpects {
     pub pure: VecMap<Symbol, Region>,
+    /// The names of the expects which are snapshots, like
+    /// `expect Inspect.snapshot("page", render(page))`.
+    pub snapshots: VecMap<Symbol, String>,
+    /// The names of the expects which are benchmarks, like
+    /// `expect Inspect.bench("sort", List.sort_asc(list))`.
+    pub benches: VecMap<Symbol, String>,
 }
 
 #[derive(Debug)]
@@ -180,6 +187,8 @@ pub struct MonomorphizedModule<'a> {
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
     pub host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
     pub toplevel_expects: MutMap<ModuleId, ToplevelExpects>,
+    /// The probes of every module, if the expects run with `--coverage`
+    pub coverage: MutMap<ModuleId, CoverageProbes<'a>>,
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
@@ -21,6 +21,7 @@ use roc_load_internal::docs::DocDef;
 use roc_load_internal::file::{
     ExecutionMode, LoadConfig, LoadResult, LoadStart, LoadingProblem, Threading,
 };
+use roc_load_internal::incremental::{IncrementalCache, IncrementalStats, SharedIncrementalCache};
 use roc_load_internal::module::LoadedModule;
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{Interns, ModuleId};
@@ -78,6 +79,40 @@ fn load_and_typecheck(
     }
 }
 
+/// Type-checks `filename`, reusing the types in `cache` where possible.
+fn load_incremental(filename: PathBuf, cache: SharedIncrementalCache) {
+    let arena = Bump::new();
+
+    let load_start = LoadStart::from_path_incremental(
+        &arena,
+        filename,
+        None,
+        RenderTarget::Generic,
+        RocCacheDir::Disallowed,
+        DEFAULT_PALETTE,
+        cache,
+    )
+    .unwrap();
+    let load_config = LoadConfig {
+        target: TARGET,
+        function_kind: FunctionKind::LambdaSet,
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Check,
+    };
+
+    roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(),
+        RocCacheDir::Disallowed,
+        load_config,
+    )
+    .unwrap();
+}
+
 const TARGET: Target = Target::LinuxX64;
 
 // HELPERS
@@ -754,7 +789,7 @@ fn parse_problem() {
             report,
             indoc!(
                 "
-                    ── UNFINISHED LIST in tmp/parse_problem/Main.roc ───────────────────────────────
+                    ── UNFINISHED LIST [E0130] in tmp/parse_problem/Main.roc ───────────────────────
 
                     I am partway through started parsing a list, but I got stuck here:
 
@@ -919,7 +954,7 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
         err,
         indoc!(
             r"
-                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE in ...d_outside_defining_module/Main.roc ─
+                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE [E0227] in ...e_defining_module/Main.roc ─
 
                 The unwrapped opaque type Age referenced here:
 
@@ -933,7 +968,7 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
 
                 Note: Opaque types can only be wrapped and unwrapped in the module they are defined in!
 
-                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE in ...d_outside_defining_module/Main.roc ─
+                ── OPAQUE TYPE DECLARED OUTSIDE SCOPE [E0227] in ...e_defining_module/Main.roc ─
 
                 The unwrapped opaque type Age referenced here:
 
@@ -947,7 +982,7 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
 
                 Note: Opaque types can only be wrapped and unwrapped in the module they are defined in!
 
-                ── UNUSED IMPORT in ...aque_wrapped_unwrapped_outside_defining_module/Main.roc ─
+                ── UNUSED IMPORT [E0205] in ...pped_unwrapped_outside_defining_module/Main.roc ─
 
                 Age is imported but not used.
 
@@ -1034,7 +1069,7 @@ fn unused_imports() {
         err,
         indoc!(
             r"
-            ── UNUSED IMPORT in tmp/unused_imports/Main.roc ────────────────────────────────
+            ── UNUSED IMPORT [E0205] in tmp/unused_imports/Main.roc ────────────────────────
 
             Dep2 is imported but not used.
 
@@ -1043,7 +1078,7 @@ fn unused_imports() {
 
             Since Dep2 isn't used, you don't need to import it.
 
-            ── UNUSED IMPORT in tmp/unused_imports/Main.roc ────────────────────────────────
+            ── UNUSED IMPORT [E0205] in tmp/unused_imports/Main.roc ────────────────────────
 
             Dep2 is imported but not used.
 
@@ -1052,7 +1087,7 @@ fn unused_imports() {
 
             Since Dep2 isn't used, you don't need to import it.
 
-            ── UNUSED IMPORT in tmp/unused_imports/Main.roc ────────────────────────────────
+            ── UNUSED IMPORT [E0205] in tmp/unused_imports/Main.roc ────────────────────────
 
             Dep2 is imported but not used.
 
@@ -1061,7 +1096,7 @@ fn unused_imports() {
 
             Since Dep2 isn't used, you don't need to import it.
 
-            ── UNUSED IMPORT in tmp/unused_imports/Main.roc ────────────────────────────────
+            ── UNUSED IMPORT [E0205] in tmp/unused_imports/Main.roc ────────────────────────
 
             Dep1 is imported but not used.
 
@@ -1070,7 +1105,7 @@ fn unused_imports() {
 
             Since Dep1 isn't used, you don't need to import it.
 
-            ── UNUSED IMPORT in tmp/unused_imports/Main.roc ────────────────────────────────
+            ── UNUSED IMPORT [E0205] in tmp/unused_imports/Main.roc ────────────────────────
 
             `Dep3.Three` is not used in this module.
 
@@ -1136,7 +1171,7 @@ fn explicit_builtin_import() {
         err,
         indoc!(
             r"
-            ── EXPLICIT BUILTIN IMPORT in tmp/explicit_builtin_import/Main.roc ─────────────
+            ── EXPLICIT BUILTIN IMPORT [E0207] in tmp/explicit_builtin_import/Main.roc ─────
 
             The builtin Bool was imported here:
 
@@ -1171,7 +1206,7 @@ fn explicit_builtin_import_empty_exposing() {
         err,
         indoc!(
             r"
-            ── EXPLICIT BUILTIN IMPORT in tmp/empty_exposing_builtin_import/Main.roc ───────
+            ── EXPLICIT BUILTIN IMPORT [E0207] in ...mpty_exposing_builtin_import/Main.roc ─
 
             The builtin Bool was imported here:
 
@@ -1210,7 +1245,7 @@ fn explicit_builtin_type_import() {
         err,
         indoc!(
             r"
-            ── EXPLICIT BUILTIN IMPORT in tmp/explicit_builtin_type_import/Main.roc ────────
+            ── EXPLICIT BUILTIN IMPORT [E0207] in ...explicit_builtin_type_import/Main.roc ─
 
             `Dict.Dict` was imported here:
 
@@ -1260,7 +1295,7 @@ fn import_shadows_symbol() {
         err,
         indoc!(
             r"
-            ── DUPLICATE NAME in tmp/import_shadows_symbol/Main.roc ────────────────────────
+            ── DUPLICATE NAME [E0218] in tmp/import_shadows_symbol/Main.roc ────────────────
 
             This import exposes `One.one`:
 
@@ -1274,7 +1309,7 @@ fn import_shadows_symbol() {
 
             You can rename it, or use the qualified name: `One.one`
 
-            ── UNUSED IMPORT in tmp/import_shadows_symbol/Main.roc ─────────────────────────
+            ── UNUSED IMPORT [E0205] in tmp/import_shadows_symbol/Main.roc ─────────────────
 
             One is imported but not used.
 
@@ -1308,7 +1343,7 @@ fn ingested_file_import_shadows_symbol() {
         err,
         indoc!(
             r#"
-            ── DUPLICATE NAME in tmp/ingested_import_shadows_symbol/Main.roc ───────────────
+            ── DUPLICATE NAME [E0218] in tmp/ingested_import_shadows_symbol/Main.roc ───────
 
             The `name` name is first defined here:
 
@@ -1401,7 +1436,7 @@ fn duplicate_alias() {
         err,
         indoc!(
             r"
-            ── IMPORT NAME CONFLICT in tmp/duplicate_alias/Main.roc ────────────────────────
+            ── IMPORT NAME CONFLICT [E0206] in tmp/duplicate_alias/Main.roc ────────────────
 
             Two was imported as D:
 
@@ -1466,7 +1501,7 @@ fn alias_using_module_name() {
         err,
         indoc!(
             r"
-            ── IMPORT NAME CONFLICT in tmp/alias_using_module_name/Main.roc ────────────────
+            ── IMPORT NAME CONFLICT [E0206] in tmp/alias_using_module_name/Main.roc ────────
 
             Two was imported as One:
 
@@ -1521,7 +1556,7 @@ fn alias_using_builtin_name() {
         err,
         indoc!(
             r"
-            ── IMPORT NAME CONFLICT in tmp/alias_using_builtin_name/Main.roc ───────────────
+            ── IMPORT NAME CONFLICT [E0206] in tmp/alias_using_builtin_name/Main.roc ───────
 
             BoolExtra was imported as Bool:
 
@@ -1663,7 +1698,7 @@ fn module_params_typecheck_fail() {
         result,
         indoc!(
             r#"
-            ── MODULE PARAMS MISMATCH in tmp/module_params_typecheck_fail/Main.roc ─────────
+            ── MODULE PARAMS MISMATCH [E0308] in tmp/module_params_typecheck_fail/Main.roc ─
 
             Something is off with the params provided by this import:
 
@@ -1714,7 +1749,7 @@ fn module_params_missing_fields() {
         result,
         indoc!(
             r#"
-            ── MODULE PARAMS MISMATCH in tmp/module_params_missing_fields/Main.roc ─────────
+            ── MODULE PARAMS MISMATCH [E0308] in tmp/module_params_missing_fields/Main.roc ─
 
             Something is off with the params provided by this import:
 
@@ -1767,7 +1802,7 @@ fn module_params_extra_fields() {
         result,
         indoc!(
             r#"
-            ── MODULE PARAMS MISMATCH in tmp/module_params_extra_fields/Main.roc ───────────
+            ── MODULE PARAMS MISMATCH [E0308] in tmp/module_params_extra_fields/Main.roc ───
 
             Something is off with the params provided by this import:
 
@@ -1820,7 +1855,7 @@ fn module_params_unexpected() {
         err,
         indoc!(
             r#"
-            ── UNEXPECTED MODULE PARAMS in tmp/module_params_unexpected/Main.roc ───────────
+            ── UNEXPECTED MODULE PARAMS [E0306] in tmp/module_params_unexpected/Main.roc ───
 
             This import specifies module params:
 
@@ -1866,7 +1901,7 @@ fn module_params_missing() {
         err,
         indoc!(
             r#"
-            ── MISSING MODULE PARAMS in tmp/module_params_missing/Main.roc ─────────────────
+            ── MISSING MODULE PARAMS [E0307] in tmp/module_params_missing/Main.roc ─────────
 
             This import specifies no module params:
 
@@ -1928,7 +1963,7 @@ fn issue_2863_module_type_does_not_exist() {
                 report,
                 indoc!(
                     "
-                        ── UNRECOGNIZED NAME in tmp/issue_2863_module_type_does_not_exist/main.roc ─────
+                        ── UNRECOGNIZED NAME [E0203] in ...ue_2863_module_type_does_not_exist/main.roc ─
 
                         Nothing is named `DoesNotExist` in this scope.
 
@@ -2006,7 +2041,7 @@ fn module_cyclic_import_itself() {
         err,
         indoc!(
             r"
-            ── IMPORT CYCLE in tmp/module_cyclic_import_itself/Age.roc ─────────────────────
+            ── IMPORT CYCLE [E0418] in tmp/module_cyclic_import_itself/Age.roc ─────────────
 
             I can't compile Age because it depends on itself through the following
             chain of module imports:
@@ -2055,7 +2090,7 @@ fn module_cyclic_import_transitive() {
         err,
         indoc!(
             r"
-            ── IMPORT CYCLE in tmp/module_cyclic_import_transitive/Age.roc ─────────────────
+            ── IMPORT CYCLE [E0418] in tmp/module_cyclic_import_transitive/Age.roc ─────────
 
             I can't compile Age because it depends on itself through the following
             chain of module imports:
@@ -2090,7 +2125,7 @@ fn non_roc_file_extension() {
 
     let expected = indoc!(
         r"
-        ── NOT A ROC FILE in tmp/non_roc_file_extension/main.md ────────────────────────
+        ── NOT A ROC FILE [E0416] in tmp/non_roc_file_extension/main.md ────────────────
 
         I expected a file with extension `.roc` or without extension.
         Instead I received a file with extension `.md`."
@@ -2120,7 +2155,7 @@ fn roc_file_no_extension() {
 
     let expected = indoc!(
         r"
-        ── NOT A ROC FILE in tmp/roc_file_no_extension/main ────────────────────────────
+        ── NOT A ROC FILE [E0416] in tmp/roc_file_no_extension/main ────────────────────
 
         I expected a file with either:
         - extension `.roc`
@@ -2183,3 +2218,88 @@ fn roc_package_depends_on_other_package() {
 
     assert!(result.is_ok());
 }
+
+#[test]
+fn incremental_load_reuses_unchanged_modules() {
+    let dir = TmpDir::new("tmp/incremental_load_reuses_unchanged_modules");
+    let write = |name: &str, source: &str| std::fs::write(dir.path().join(name), source).unwrap();
+
+    write("Dep.roc", "module [answer]\n\nanswer = 42\n");
+    write(
+        "Main.roc",
+        "module [main]\n\nimport Dep\n\nmain = Dep.answer + 1\n",
+    );
+
+    let main_path = dir.path().join("Main.roc");
+    let cache = SharedIncrementalCache::default();
+    let stats = || cache.lock().stats();
+
+    load_incremental(main_path.clone(), cache.clone());
+    assert_eq!(
+        stats(),
+        IncrementalStats {
+            reused: 0,
+            solved: 2
+        }
+    );
+
+    // Nothing changed, so only the root module is solved again.
+    load_incremental(main_path.clone(), cache.clone());
+    assert_eq!(
+        stats(),
+        IncrementalStats {
+            reused: 1,
+            solved: 3
+        }
+    );
+
+    // Changing the import invalidates it.
+    write("Dep.roc", "module [answer]\n\nanswer = 43\n");
+    load_incremental(main_path.clone(), cache.clone());
+    assert_eq!(
+        stats(),
+        IncrementalStats {
+            reused: 1,
+            solved: 5
+        }
+    );
+
+    load_incremental(main_path, cache.clone());
+    assert_eq!(
+        stats(),
+        IncrementalStats {
+            reused: 2,
+            solved: 6
+        }
+    );
+}
+
+#[test]
+fn incremental_cache_evicts_least_recently_used() {
+    let dir = TmpDir::new("tmp/incremental_cache_evicts_least_recently_used");
+    let write = |name: &str, source: &str| std::fs::write(dir.path().join(name), source).unwrap();
+
+    write("A.roc", "module [a]\n\na = 1\n");
+    write("B.roc", "module [b]\n\nb = 2\n");
+    write(
+        "Main.roc",
+        "module [main]\n\nimport A\nimport B\n\nmain = A.a + B.b\n",
+    );
+
+    let main_path = dir.path().join("Main.roc");
+    let cache: SharedIncrementalCache = Default::default();
+    *cache.lock() = IncrementalCache::with_capacity(1);
+
+    load_incremental(main_path.clone(), cache.clone());
+    assert_eq!(cache.lock().len(), 1);
+
+    load_incremental(main_path, cache.clone());
+    assert_eq!(cache.lock().len(), 1);
+    assert_eq!(
+        cache.lock().stats(),
+        IncrementalStats {
+            reused: 1,
+            solved: 5
+        }
+    );
+}
@@ -381,7 +381,7 @@ impl ModuleId {
         ModuleId(unsafe { NonZeroU32::new_unchecked(id as u32) })
     }
 
-    const fn to_zero_indexed(self) -> usize {
+    pub const fn to_zero_indexed(self) -> usize {
         (self.0.get() - 1) as usize
     }
 
@@ -1734,6 +1734,8 @@ define_builtins! {
         31 INSPECT_APPLY: "apply"
         32 INSPECT_TO_INSPECTOR: "to_inspector"
         33 INSPECT_TO_STR: "to_str"
+        34 INSPECT_SNAPSHOT: "snapshot"
+        35 INSPECT_BENCH: "bench"
     }
 
     num_modules: 15 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
@@ -0,0 +1,197 @@
+//! Probes which count how often the top-level defs and the branches of a module run, for
+//! `roc test --coverage`.
+//!
+//! A probe is a call to the foreign function [COVERAGE_HIT] with the module and the index of the
+//! probe. The backend defines that function, so nothing has to be linked in for it.
+use bumpalo::Bump;
+use roc_can::expr::{Declarations, Expr};
+use roc_can::traverse::{walk_decl, walk_expr, DeclarationInfo, Visitor};
+use roc_module::ident::ForeignSymbol;
+use roc_module::symbol::Symbol;
+use roc_region::all::Region;
+use roc_types::subs::Variable;
+
+use crate::ir::{Call, CallType, Env, Literal, Procs, Stmt};
+use crate::layout::Layout;
+
+/// The foreign function a probe calls, with the (zero-indexed) id of its module and its index
+/// within [CoverageProbes], both as a `U32`.
+pub const COVERAGE_HIT: &str = "roc_coverage_hit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeKind {
+    /// Evaluating the top-level def with this name.
+    Def(Symbol),
+    /// Taking a branch of the `when` expression at this region.
+    WhenBranch(Region),
+    /// Taking a branch of the `if` expression at this region, including its `else`.
+    IfBranch(Region),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Probe {
+    pub kind: ProbeKind,
+    /// The name of a def, or the body of a branch.
+    pub region: Region,
+}
+
+/// The probes of a module, sorted by their regions. A probe is identified by its index.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CoverageProbes<'a> {
+    pub probes: &'a [Probe],
+}
+
+impl<'a> CoverageProbes<'a> {
+    /// Finds the probes of the defs and branches of a module. Expects are not probed, since they
+    /// are what does the covering.
+    pub fn from_declarations(arena: &'a Bump, declarations: &Declarations) -> Self {
+        let mut collector = ProbeCollector { probes: Vec::new() };
+        collector.visit_decls(declarations);
+
+        let mut probes = collector.probes;
+        probes.sort_by_key(|probe| probe.region);
+
+        Self {
+            probes: arena.alloc_slice_copy(&probes),
+        }
+    }
+
+    fn def_probe(&self, symbol: Symbol) -> Option<u32> {
+        let index = self
+            .probes
+            .iter()
+            .position(|probe| probe.kind == ProbeKind::Def(symbol))?;
+
+        Some(index as u32)
+    }
+
+    fn branch_probe(&self, region: Region) -> Option<u32> {
+        let start = self.probes.partition_point(|probe| probe.region < region);
+
+        let offset = self.probes[start..]
+            .iter()
+            .take_while(|probe| probe.region == region)
+            .position(|probe| !matches!(probe.kind, ProbeKind::Def(_)))?;
+
+        Some((start + offset) as u32)
+    }
+}
+
+struct ProbeCollector {
+    probes: Vec<Probe>,
+}
+
+impl ProbeCollector {
+    fn push(&mut self, kind: ProbeKind, region: Region) {
+        self.probes.push(Probe { kind, region });
+    }
+}
+
+impl Visitor for ProbeCollector {
+    fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+        match &decl {
+            DeclarationInfo::Expectation { .. } => return,
+            DeclarationInfo::Value { loc_symbol, .. }
+            | DeclarationInfo::Function { loc_symbol, .. } => {
+                self.push(ProbeKind::Def(loc_symbol.value), loc_symbol.region);
+            }
+            DeclarationInfo::Destructure { .. } => {
+                // these are evaluated under a name of their own, so there is no def to probe
+            }
+        }
+
+        walk_decl(self, decl);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        match expr {
+            Expr::When { branches, .. } => {
+                for branch in branches {
+                    self.push(ProbeKind::WhenBranch(region), branch.value.region);
+                }
+            }
+            Expr::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                for (_, loc_then) in branches {
+                    self.push(ProbeKind::IfBranch(region), loc_then.region);
+                }
+
+                self.push(ProbeKind::IfBranch(region), final_else.region);
+            }
+            _ => {}
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
+
+/// Counts every time the top-level def `symbol` is evaluated, if it's probed.
+pub(crate) fn probe_def<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &Procs<'a>,
+    symbol: Symbol,
+    body: Stmt<'a>,
+) -> Stmt<'a> {
+    let probe_index = procs.coverage.and_then(|probes| probes.def_probe(symbol));
+
+    match probe_index {
+        Some(index) => probe(env, index, body),
+        None => body,
+    }
+}
+
+/// Counts every time the branch whose body is at `region` is taken, if it's probed.
+pub(crate) fn probe_branch<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &Procs<'a>,
+    region: Region,
+    body: Stmt<'a>,
+) -> Stmt<'a> {
+    let probe_index = procs
+        .coverage
+        .and_then(|probes| probes.branch_probe(region));
+
+    match probe_index {
+        Some(index) => probe(env, index, body),
+        None => body,
+    }
+}
+
+fn probe<'a>(env: &mut Env<'a, '_>, index: u32, body: Stmt<'a>) -> Stmt<'a> {
+    let module_symbol = env.unique_symbol();
+    let index_symbol = env.unique_symbol();
+    let unit_symbol = env.unique_symbol();
+
+    let call = Call {
+        call_type: CallType::Foreign {
+            foreign_symbol: ForeignSymbol::from(COVERAGE_HIT),
+            ret_layout: Layout::UNIT,
+        },
+        arguments: env.arena.alloc([module_symbol, index_symbol]),
+    };
+
+    let module_id = env.home.to_zero_indexed() as i128;
+
+    let stmt = Stmt::Let(
+        unit_symbol,
+        crate::ir::Expr::Call(call),
+        Layout::UNIT,
+        env.arena.alloc(body),
+    );
+    let stmt = Stmt::Let(
+        index_symbol,
+        crate::ir::Expr::Literal(Literal::Int((index as i128).to_ne_bytes())),
+        Layout::U32,
+        env.arena.alloc(stmt),
+    );
+
+    Stmt::Let(
+        module_symbol,
+        crate::ir::Expr::Literal(Literal::Int(module_id.to_ne_bytes())),
+        Layout::U32,
+        env.arena.alloc(stmt),
+    )
+}
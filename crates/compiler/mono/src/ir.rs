@@ -1,5 +1,6 @@
 #![allow(clippy::manual_map)]
 
+use crate::coverage::{probe_branch, probe_def, CoverageProbes};
 use crate::ir::erased::{build_erased_function, ResolvedErasedLambda};
 use crate::ir::literal::{make_num_literal, IntOrFloatValue};
 use crate::layout::{
@@ -66,6 +67,11 @@ pub fn pretty_print_ir_symbols() -> bool {
     false
 }
 
+/// The start of the names of the foreign functions which benchmarks pass their value to, so that
+/// it isn't optimized away. Each benchmark gets its own, because the functions differ in the
+/// layout of their argument.
+pub const BENCH_BLACK_BOX: &str = "roc_bench_black_box";
+
 // if your changes cause this number to go down, great!
 // please change it to the lower number.
 // if it went up, maybe check that the change is really required
@@ -140,7 +146,11 @@ pub struct SingleEntryPoint<'a> {
 #[derive(Debug, Clone, Copy)]
 pub enum EntryPoint<'a> {
     Program(&'a [SingleEntryPoint<'a>]),
-    Expects { symbols: &'a [Symbol] },
+    /// The top-level expects, along with their layouts. Properties have arguments, the others
+    /// don't.
+    Expects {
+        expects: &'a [(Symbol, ProcLayout<'a>)],
+    },
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -880,6 +890,8 @@ pub struct ProcsBase<'a> {
     pub host_specializations: HostSpecializations<'a>,
     pub runtime_errors: BumpMap<Symbol, &'a str>,
     pub imported_module_thunks: &'a [Symbol],
+    /// The probes to count the defs and branches that run with, if any
+    pub coverage: Option<CoverageProbes<'a>>,
 }
 
 impl<'a> ProcsBase<'a> {
@@ -924,6 +936,7 @@ pub struct Procs<'a> {
     pub imported_module_thunks: &'a [Symbol],
     pub module_thunks: &'a [Symbol],
     pub host_exposed_symbols: &'a [Symbol],
+    pub coverage: Option<CoverageProbes<'a>>,
 }
 
 impl<'a> Procs<'a> {
@@ -942,6 +955,7 @@ impl<'a> Procs<'a> {
             imported_module_thunks: &[],
             module_thunks: &[],
             host_exposed_symbols: &[],
+            coverage: None,
         }
     }
 
@@ -1021,6 +1035,7 @@ impl<'a> Procs<'a> {
             host_specializations: HostSpecializations::default(),
             runtime_errors: self.runtime_errors,
             imported_module_thunks: self.imported_module_thunks,
+            coverage: self.coverage,
         };
 
         (
@@ -3554,7 +3569,8 @@ fn specialize_proc_help<'a>(
     let body = partial_proc.body.clone();
     let body_var = partial_proc.body_var;
 
-    let mut specialized_body = from_can(env, body_var, body, procs, layout_cache);
+    let specialized_body = from_can(env, body_var, body, procs, layout_cache);
+    let mut specialized_body = probe_def(env, procs, lambda_name.name(), specialized_body);
 
     let specialized_proc = match specialized {
         SpecializedLayout::FunctionPointerBody {
@@ -4671,6 +4687,7 @@ pub fn with_hole<'a>(
                     if is_terminated {
                         let terminator = hole;
 
+                        let else_region = final_else.region;
                         let mut stmt = with_hole(
                             env,
                             final_else.value,
@@ -4680,6 +4697,7 @@ pub fn with_hole<'a>(
                             assigned,
                             terminator,
                         );
+                        stmt = probe_branch(env, procs, else_region, stmt);
 
                         for (loc_cond, loc_then) in branches.into_iter().rev() {
                             let branching_symbol = env.unique_symbol();
@@ -4693,6 +4711,7 @@ pub fn with_hole<'a>(
                                 assigned,
                                 terminator,
                             );
+                            let then = probe_branch(env, procs, loc_then.region, then);
 
                             stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -4716,6 +4735,7 @@ pub fn with_hole<'a>(
                             .arena
                             .alloc(Stmt::Jump(id, env.arena.alloc([assigned_in_jump])));
 
+                        let else_region = final_else.region;
                         let mut stmt = with_hole(
                             env,
                             final_else.value,
@@ -4725,6 +4745,7 @@ pub fn with_hole<'a>(
                             assigned_in_jump,
                             terminator,
                         );
+                        stmt = probe_branch(env, procs, else_region, stmt);
 
                         for (loc_cond, loc_then) in branches.into_iter().rev() {
                             let branching_symbol = possible_reuse_symbol_or_specialize(
@@ -4744,6 +4765,7 @@ pub fn with_hole<'a>(
                                 assigned_in_jump,
                                 terminator,
                             );
+                            let then = probe_branch(env, procs, loc_then.region, then);
 
                             stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -7076,7 +7098,9 @@ pub fn from_can<'a>(
                 "invalid condition type in if expression"
             );
 
+            let else_region = final_else.region;
             let mut stmt = from_can(env, branch_var, final_else.value, procs, layout_cache);
+            stmt = probe_branch(env, procs, else_region, stmt);
 
             for (loc_cond, loc_then) in branches.into_iter().rev() {
                 let branching_symbol = possible_reuse_symbol_or_specialize(
@@ -7087,6 +7111,7 @@ pub fn from_can<'a>(
                     cond_var,
                 );
                 let then = from_can(env, branch_var, loc_then.value, procs, layout_cache);
+                let then = probe_branch(env, procs, loc_then.region, then);
 
                 stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -7255,7 +7280,7 @@ fn to_opt_branches<'a>(
 ) -> std::vec::Vec<(
     Pattern<'a>,
     Option<Loc<roc_can::expr::Expr>>,
-    roc_can::expr::Expr,
+    Loc<roc_can::expr::Expr>,
 )> {
     debug_assert!(!branches.is_empty());
 
@@ -7300,15 +7325,18 @@ fn to_opt_branches<'a>(
                         ))
                     };
 
+                    // The body keeps its own region, which is where the branch is probed
+                    let loc_expr = Loc::at(when_branch.value.region, loc_expr.value);
+
                     // TODO remove clone?
-                    opt_branches.push((mono_pattern, when_branch.guard.clone(), loc_expr.value));
+                    opt_branches.push((mono_pattern, when_branch.guard.clone(), loc_expr));
                 }
                 Err(runtime_error) => {
                     // TODO remove clone?
                     opt_branches.push((
                         Pattern::Underscore,
                         when_branch.guard.clone(),
-                        roc_can::expr::Expr::RuntimeError(runtime_error),
+                        Loc::at_zero(roc_can::expr::Expr::RuntimeError(runtime_error)),
                     ));
                 }
             }
@@ -7321,7 +7349,9 @@ fn to_opt_branches<'a>(
         opt_branches.push((
             Pattern::Underscore,
             None,
-            roc_can::expr::Expr::RuntimeError(roc_problem::can::RuntimeError::NonExhaustivePattern),
+            Loc::at_zero(roc_can::expr::Expr::RuntimeError(
+                roc_problem::can::RuntimeError::NonExhaustivePattern,
+            )),
         ));
     }
 
@@ -7362,7 +7392,7 @@ fn from_can_when<'a>(
     let arena = env.arena;
     let it = opt_branches
         .into_iter()
-        .filter_map(|(pattern, opt_guard, can_expr)| {
+        .filter_map(|(pattern, opt_guard, loc_can_expr)| {
             // If the pattern has a void layout we can drop it; however, we must still perform the
             // work of building the body, because that may contain specializations we must
             // discover for use elsewhere. See
@@ -7379,6 +7409,7 @@ fn from_can_when<'a>(
                 None
             };
 
+            let can_expr = loc_can_expr.value;
             let branch_stmt = match join_point {
                 None => from_can(env, expr_var, can_expr, procs, layout_cache),
                 Some(id) => {
@@ -7389,6 +7420,7 @@ fn from_can_when<'a>(
                     with_hole(env, can_expr, expr_var, procs, layout_cache, symbol, jump)
                 }
             };
+            let branch_stmt = probe_branch(env, procs, loc_can_expr.region, branch_stmt);
 
             use decision_tree::Guard;
             let result = if let Some(loc_expr) = opt_guard {
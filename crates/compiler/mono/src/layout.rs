@@ -2430,6 +2430,19 @@ impl<'a> Layout<'a> {
         }
     }
 
+    pub fn semantic(&self) -> SemanticRepr<'a> {
+        self.semantic
+    }
+
+    /// The layout this one is represented like, e.g. that of the field of a record with a single
+    /// field.
+    pub fn newtype_of(&self) -> Option<InLayout<'a>> {
+        match self.repr {
+            LayoutWrapper::Direct(_) => None,
+            LayoutWrapper::Newtype(real) => Some(real),
+        }
+    }
+
     pub(crate) fn repr<I>(&self, interner: &I) -> LayoutRepr<'a>
     where
         I: LayoutInterner<'a>,
@@ -62,6 +62,22 @@ impl<'a> SemanticRepr<'a> {
     pub(super) fn lambdas(lambdas: &'a [Symbol]) -> Self {
         Self(Inner::Lambdas(SemaLambdas { lambdas }))
     }
+
+    /// The names of the fields of a record, in the order they're laid out in.
+    pub fn record_fields(&self) -> Option<&'a [&'a str]> {
+        match self.0 {
+            Inner::Record(SemaRecord { fields }) => Some(fields),
+            _ => None,
+        }
+    }
+
+    /// The names of the tags of a tag union, in the order of their ids.
+    pub fn tag_names(&self) -> Option<&'a [&'a str]> {
+        match self.0 {
+            Inner::TagUnion(SemaTagUnion { tags }) => Some(tags),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
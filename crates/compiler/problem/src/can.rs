@@ -264,6 +264,8 @@ pub enum Problem {
         record_region: Region,
     },
     InterpolatedStringNotAllowed(Region),
+    InvalidSnapshotName(Region),
+    InvalidBenchName(Region),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -282,6 +284,8 @@ impl Problem {
             Problem::UnusedImport(_, _) => Warning,
             Problem::UnusedModuleImport(_, _) => Warning,
             Problem::InterpolatedStringNotAllowed(_) => RuntimeError,
+            Problem::InvalidSnapshotName(_) => RuntimeError,
+            Problem::InvalidBenchName(_) => RuntimeError,
             Problem::ImportNameConflict { .. } => RuntimeError,
             Problem::ExplicitBuiltinImport(_, _) => Warning,
             Problem::ExplicitBuiltinTypeImport(_, _) => Warning,
@@ -379,6 +383,8 @@ impl Problem {
             }
             | Problem::ExplicitBuiltinImport(_, region)
             | Problem::InterpolatedStringNotAllowed(region)
+            | Problem::InvalidSnapshotName(region)
+            | Problem::InvalidBenchName(region)
             | Problem::ExplicitBuiltinTypeImport(_, region)
             | Problem::ImportShadowsSymbol { region, .. }
             | Problem::UnusedArgument(_, _, _, region)
@@ -9,7 +9,7 @@ use roc_packaging::cache::RocCacheDir;
 use roc_reporting::report::DEFAULT_PALETTE_HTML;
 use roc_solve::FunctionKind;
 use roc_std::RocStr;
-use roc_wasm_interp::{wasi, ImportDispatcher, Instance, WasiDispatcher};
+use roc_wasm_interp::{wasi, ImportDispatcher, Instance, Limits, WasiDispatcher};
 use roc_wasm_module::{Export, ExportType, Value, WasmModule};
 use std::marker::PhantomData;
 use std::path::PathBuf;
@@ -246,7 +246,13 @@ where
         wasi: wasi::WasiDispatcher::default(),
     };
     let is_debug_mode = roc_debug_flags::dbg_set!(roc_debug_flags::ROC_LOG_WASM_INTERP);
-    let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode)?;
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        dispatcher,
+        is_debug_mode,
+        Limits::default(),
+    )?;
     let opt_value = inst.call_export(test_wrapper_name, [])?;
     let addr_value = opt_value.ok_or("No return address from Wasm test")?;
     let addr = addr_value.expect_i32().map_err(|e| format!("{:?}", e))?;
@@ -275,7 +281,13 @@ where
         wasi: wasi::WasiDispatcher::default(),
     };
     let is_debug_mode = roc_debug_flags::dbg_set!(roc_debug_flags::ROC_LOG_WASM_INTERP);
-    let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode)?;
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        dispatcher,
+        is_debug_mode,
+        Limits::default(),
+    )?;
 
     // Allocate a vector in the test host that refcounts will be copied into
     let num_refcounts = refcount_locs.len();
@@ -17,7 +17,7 @@ use roc_mono::ir::{
     Call, CallType, Expr, Literal, Proc, ProcLayout, SelfRecursive, Stmt, UpdateModeId,
 };
 use roc_mono::layout::{LambdaName, Layout, Niche, STLayoutInterner};
-use roc_wasm_interp::{wasi, ImportDispatcher, Instance, WasiDispatcher};
+use roc_wasm_interp::{wasi, ImportDispatcher, Instance, Limits, WasiDispatcher};
 use roc_wasm_module::{Value, WasmModule};
 
 const LINKING_TEST_HOST_WASM: &str = "build/wasm_linking_test_host.wasm";
@@ -224,7 +224,13 @@ fn execute_wasm_module<'a>(arena: &'a Bump, orig_module: WasmModule<'a>) -> Resu
         wasi: wasi::WasiDispatcher::default(),
     };
     let is_debug_mode = false;
-    let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode)?;
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        dispatcher,
+        is_debug_mode,
+        Limits::default(),
+    )?;
 
     // In Zig, main can only return u8 or void, but our result is too wide for that.
     // But I want to use main so that I can test that _start is created for it!
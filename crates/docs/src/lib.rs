@@ -1454,6 +1454,7 @@ fn report_markdown_link_problem(
             filename,
             doc,
             title: "INVALID DOCS LINK".to_string(),
+            code: None,
             severity: Severity::Warning,
         }
     };
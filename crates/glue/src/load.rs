@@ -106,6 +106,7 @@ pub fn generate(
                     problems,
                     total_time,
                     expect_metadata: _,
+                    ..
                 }) => {
                     // TODO: Should binary_path be update to deal with extensions?
                     use roc_target::OperatingSystem;
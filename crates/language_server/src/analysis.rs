@@ -9,27 +9,40 @@ use bumpalo::Bump;
 use parking_lot::Mutex;
 use roc_can::{abilities::AbilitiesStore, expr::Declarations};
 use roc_collections::{MutMap, MutSet, VecMap};
-use roc_load::{docs::ModuleDocumentation, CheckedModule, LoadedModule};
+use roc_load::{docs::ModuleDocumentation, CheckedModule, LoadedModule, SharedIncrementalCache};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_packaging::cache::{self, RocCacheDir};
-use roc_region::all::LineInfo;
+use roc_problem::can::{Problem, RuntimeError};
+use roc_region::all::{LineInfo, Region};
 use roc_reporting::report::RocDocAllocator;
 use roc_solve_problem::TypeError;
-use roc_types::subs::{Subs, Variable};
+use roc_types::{
+    subs::{Subs, Variable},
+    types::ErrorType,
+};
 
 use tower_lsp::lsp_types::{Diagnostic, SemanticTokenType, Url};
 
 mod analysed_doc;
 mod annotation_visitor;
+mod auto_import;
 mod completion;
+mod folding;
+mod inlay_hints;
 mod parse_ast;
+mod rename;
 mod semantic_tokens;
+mod signature_help;
+mod symbols;
 mod tokens;
 mod utils;
+mod when_branches;
 
 use crate::convert::diag::{IntoLspDiagnostic, ProblemFmt};
 
 pub(crate) use self::analysed_doc::{AnalyzedDocument, DocInfo};
+pub(crate) use self::auto_import::ExposedValue;
+pub(crate) use self::rename::is_valid_new_name;
 use self::{analysed_doc::ModuleIdToUrl, tokens::Token};
 
 pub const HIGHLIGHT_TOKENS_LEGEND: &[SemanticTokenType] = Token::LEGEND;
@@ -91,6 +104,13 @@ pub(super) struct AnalyzedModule {
     abilities: AbilitiesStore,
     declarations: Declarations,
     modules_info: Arc<ModulesInfo>,
+    /// Non-exhaustive and redundant patterns, which code actions can fix.
+    exhaustive_problems: Vec<roc_exhaustive::Error>,
+    /// The types expected where expressions and patterns failed to type-check. Their variables
+    /// are errors in `subs`, so completion looks here instead.
+    mismatches: Vec<(Region, ErrorType)>,
+    /// Names and modules which weren't found, and which an import might provide.
+    unresolved: Vec<RuntimeError>,
     // ModuleIds are not stable between compilations, so a ModuleId visible to
     // one module may not be true global to the language server.
     module_id_to_url: ModuleIdToUrl,
@@ -101,12 +121,17 @@ pub struct AnalysisResult {
     diagnostics: Vec<Diagnostic>,
 }
 
-pub(crate) fn global_analysis(doc_info: DocInfo) -> Vec<AnalyzedDocument> {
+/// Type-checks the document and every module it imports. Modules which have not changed since an
+/// earlier analysis with the same `incremental_cache` are not solved again.
+pub(crate) fn global_analysis(
+    doc_info: DocInfo,
+    incremental_cache: SharedIncrementalCache,
+) -> Vec<AnalyzedDocument> {
     let fi = doc_info.url.to_file_path().unwrap();
     let src_dir = find_src_dir(&fi).to_path_buf();
 
     let arena = Bump::new();
-    let loaded = roc_load::load_and_typecheck_str(
+    let loaded = roc_load::load_and_typecheck_str_incremental(
         &arena,
         fi,
         &doc_info.source,
@@ -117,6 +142,7 @@ pub(crate) fn global_analysis(doc_info: DocInfo) -> Vec<AnalyzedDocument> {
         roc_reporting::report::RenderTarget::LanguageServer,
         RocCacheDir::Persistent(cache::roc_cache_packages_dir().as_path()),
         roc_reporting::report::DEFAULT_PALETTE,
+        incremental_cache,
     );
 
     let module = match loaded {
@@ -133,6 +159,7 @@ pub(crate) fn global_analysis(doc_info: DocInfo) -> Vec<AnalyzedDocument> {
                     module: None,
                     diagnostics: all_problems,
                 },
+                exposed_values: Default::default(),
             };
 
             return vec![analyzed_document];
@@ -263,7 +290,7 @@ struct RootModule {
 struct AnalyzedDocumentBuilder<'a> {
     interns: &'a Interns,
     module_id_to_url: ModuleIdToUrl,
-    can_problems: &'a mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    can_problems: &'a mut MutMap<ModuleId, Vec<Problem>>,
     type_problems: &'a mut MutMap<ModuleId, Vec<TypeError>>,
     declarations_by_id: &'a mut MutMap<ModuleId, Declarations>,
     typechecked: &'a mut MutMap<ModuleId, CheckedModule>,
@@ -302,6 +329,39 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
             declarations = self.declarations_by_id.remove(&module_id).unwrap();
         }
 
+        let can_problems = self.can_problems.remove(&module_id).unwrap_or_default();
+        let unresolved = can_problems
+            .iter()
+            .filter_map(|problem| match problem {
+                Problem::RuntimeError(
+                    error @ (RuntimeError::LookupNotInScope { .. }
+                    | RuntimeError::ModuleNotImported { .. }),
+                ) => Some(error.clone()),
+                _ => None,
+            })
+            .collect();
+
+        let type_problems = self.type_problems.remove(&module_id).unwrap_or_default();
+        let exhaustive_problems = type_problems
+            .iter()
+            .filter_map(|problem| match problem {
+                TypeError::Exhaustive(error) => Some(error.clone()),
+                _ => None,
+            })
+            .collect();
+        let mismatches = type_problems
+            .iter()
+            .filter_map(|problem| match problem {
+                TypeError::BadExpr(region, _, _, expected) => {
+                    Some((*region, expected.get_type_ref().clone()))
+                }
+                TypeError::BadPattern(region, _, _, expected) => {
+                    Some((*region, expected.get_type_ref().clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
         let analyzed_module = AnalyzedModule {
             exposed_imports,
             imports_by_module: imports,
@@ -310,12 +370,22 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
             declarations,
             module_id,
             modules_info: self.modules_info.clone(),
+            exhaustive_problems,
+            mismatches,
+            unresolved,
             interns: self.interns.clone(),
             module_id_to_url: self.module_id_to_url.clone(),
         };
 
         let line_info = LineInfo::new(&source);
-        let diagnostics = self.build_diagnostics(&path, &source, &line_info, module_id);
+        let diagnostics = self.build_diagnostics(
+            &path,
+            &source,
+            &line_info,
+            module_id,
+            can_problems,
+            type_problems,
+        );
 
         AnalyzedDocument {
             doc_info: DocInfo {
@@ -328,6 +398,7 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
                 module: Some(analyzed_module),
                 diagnostics,
             },
+            exposed_values: Default::default(),
         }
     }
 
@@ -357,6 +428,8 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
         source: &str,
         line_info: &LineInfo,
         module_id: ModuleId,
+        can_problems: Vec<Problem>,
+        type_problems: Vec<TypeError>,
     ) -> Vec<Diagnostic> {
         let lines: Vec<_> = source.lines().collect();
 
@@ -369,10 +442,6 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
             path: source_path,
         };
 
-        let can_problems = self.can_problems.remove(&module_id).unwrap_or_default();
-
-        let type_problems = self.type_problems.remove(&module_id).unwrap_or_default();
-
         for can_problem in can_problems {
             if let Some(diag) = can_problem.into_lsp_diagnostic(&fmt) {
                 all_problems.push(diag);
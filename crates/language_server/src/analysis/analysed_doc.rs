@@ -2,42 +2,79 @@ use log::{debug, info};
 
 use roc_cli::{annotation_edit, annotation_edits};
 use roc_fmt::MigrationFlags;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::OnceLock};
 
 use bumpalo::Bump;
 
-use roc_module::symbol::{ModuleId, Symbol};
-
+use roc_can::traverse::{find_record_fields, FoundReference, ReferenceKind};
+use roc_exhaustive::Context;
+use roc_module::{
+    ident::{Lowercase, ModuleName},
+    symbol::{ModuleId, Symbol},
+};
+use roc_parse::ast::TypeDef;
+use roc_problem::can::RuntimeError;
 use roc_region::all::{LineInfo, Position as RocPosition, Region};
 
 use tower_lsp::lsp_types::{
-    CodeAction, CodeActionKind, CompletionItem, Diagnostic, GotoDefinitionResponse, Hover,
-    HoverContents, LanguageString, Location, MarkedString, Position, Range, SemanticTokens,
-    SemanticTokensResult, TextEdit, Url, WorkspaceEdit,
+    CodeAction, CodeActionKind, CompletionItem, CompletionItemKind, CompletionItemLabelDetails,
+    Diagnostic, DocumentSymbolResponse, Documentation, FoldingRange, GotoDefinitionResponse, Hover,
+    HoverContents, InlayHint, InlayHintKind, InlayHintLabel, LanguageString, Location,
+    MarkedString, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, Position, Range,
+    SemanticTokens, SemanticTokensResult, SignatureHelp, SignatureInformation, SymbolInformation,
+    SymbolKind, TextEdit, Url, WorkspaceEdit,
 };
 
 use crate::{
     analysis::completion::{
-        field_completion, get_completion_items, get_module_completion_items,
-        get_tag_completion_items,
+        expected_field_completion, expected_tag_completion, field_completion, get_completion_items,
+        get_module_completion_items, get_tag_completion_items, in_type_annotation,
+        type_name_completion_items,
     },
     convert::{ToRange, ToRegion, ToRocPosition},
 };
 
 use super::{
     annotation_visitor::{find_declaration_at, FoundDeclaration, NotFound},
+    auto_import::{
+        import_edit, import_name, modules_exposing, package_shorthand, qualify_edits, ExposedValue,
+    },
+    find_src_dir,
+    folding::foldable_regions,
+    inlay_hints::find_inferred_types,
     parse_ast::Ast,
+    rename::{self, RenameTarget},
     semantic_tokens::arrange_semantic_tokens,
+    signature_help::{find_call_at, parameter_ranges},
+    symbols::{document_symbols, fuzzy_matches, type_def_kind},
     utils::{format_var_type, is_roc_identifier_char},
+    when_branches::{missing_branches_edit, redundant_branch_region},
     AnalysisResult, AnalyzedModule,
 };
 
 pub(super) type ModuleIdToUrl = HashMap<ModuleId, Url>;
 
+/// A [Symbol] identified by the names of its module and ident.
+/// ModuleIds are not stable between compilations, so this is how symbols are matched up across
+/// documents that may come from different analyses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct QualifiedSymbol {
+    module_name: ModuleName,
+    ident: String,
+}
+
+impl QualifiedSymbol {
+    pub(crate) fn ident(&self) -> &str {
+        &self.ident
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AnalyzedDocument {
     pub doc_info: DocInfo,
     pub analysis_result: AnalysisResult,
+    /// The values the module exposes, worked out the first time another module asks for them.
+    pub(crate) exposed_values: OnceLock<Vec<ExposedValue>>,
 }
 
 #[derive(Debug, Clone)]
@@ -117,6 +154,16 @@ impl DocInfo {
         }
     }
 
+    pub fn document_symbols(&self) -> Option<DocumentSymbolResponse> {
+        let source = &self.source;
+        let arena = &Bump::new();
+
+        let ast = Ast::parse(arena, source).ok()?;
+        let symbols = document_symbols(ast.defs(), source, &self.line_info);
+
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+
     pub fn semantic_tokens(&self) -> Option<SemanticTokensResult> {
         let source = &self.source;
         let arena = &Bump::new();
@@ -157,6 +204,15 @@ impl AnalyzedDocument {
         self.analysis_result.module.is_some()
     }
 
+    /// Whether the module at `url` was part of this document's analysis, i.e. whether this
+    /// document imports it, directly or not.
+    pub fn depends_on(&self, url: &Url) -> bool {
+        self.url() != url
+            && self
+                .module()
+                .is_some_and(|module| module.module_id_to_url.values().any(|other| other == url))
+    }
+
     pub fn diagnostics(&self) -> Vec<Diagnostic> {
         self.analysis_result.diagnostics.clone()
     }
@@ -221,6 +277,219 @@ impl AnalyzedDocument {
         })
     }
 
+    pub fn signature_help(&self, position: Position) -> Option<SignatureHelp> {
+        let AnalyzedModule {
+            subs,
+            declarations,
+            module_id,
+            interns,
+            modules_info,
+            ..
+        } = self.module()?;
+
+        let pos = position.to_roc_position(self.line_info());
+        let call = find_call_at(&self.doc_info.source, pos, declarations)?;
+
+        if !subs.is_function(call.fn_var) {
+            return None;
+        }
+
+        let type_str = format_var_type(call.fn_var, &mut subs.clone(), module_id, interns);
+        let params = parameter_ranges(&type_str);
+
+        if call.active_parameter >= params.len() {
+            return None;
+        }
+
+        // Anonymous functions are shown by their type alone
+        let prefix = match call.symbol {
+            Some(symbol) => format!("{} : ", symbol.as_str(interns)),
+            None => String::new(),
+        };
+        let utf16_len = |str: &str| str.encode_utf16().count() as u32;
+        let prefix_len = utf16_len(&prefix);
+
+        let parameters = params
+            .into_iter()
+            .map(|(start, end)| ParameterInformation {
+                label: ParameterLabel::LabelOffsets([
+                    prefix_len + utf16_len(&type_str[..start]),
+                    prefix_len + utf16_len(&type_str[..end]),
+                ]),
+                documentation: None,
+            })
+            .collect();
+
+        let documentation = call.symbol.and_then(|symbol| {
+            let docs = modules_info
+                .get_docs(&symbol.module_id())?
+                .get_doc_for_symbol(&symbol)?;
+
+            Some(Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: docs,
+            }))
+        });
+
+        Some(SignatureHelp {
+            signatures: vec![SignatureInformation {
+                label: format!("{prefix}{type_str}"),
+                documentation,
+                parameters: Some(parameters),
+                active_parameter: None,
+            }],
+            active_signature: Some(0),
+            active_parameter: Some(call.active_parameter as u32),
+        })
+    }
+
+    /// Shows the inferred types of unannotated defs and lambda parameters within `range`.
+    pub fn inlay_hints(&self, range: Range, top_level_only: bool) -> Option<Vec<InlayHint>> {
+        let AnalyzedModule {
+            subs,
+            abilities,
+            declarations,
+            module_id,
+            interns,
+            ..
+        } = self.module()?;
+
+        let region = range.to_region(self.line_info());
+        let mut subs = subs.clone();
+
+        let hints = find_inferred_types(declarations, abilities, region, top_level_only)
+            .into_iter()
+            .filter_map(|(position, var)| {
+                if subs.var_contains_error(var) {
+                    return None;
+                }
+
+                let type_str = format_var_type(var, &mut subs, module_id, interns);
+
+                Some(InlayHint {
+                    position: Region::from_pos(position).to_range(self.line_info()).start,
+                    label: InlayHintLabel::String(format!(": {type_str}")),
+                    kind: Some(InlayHintKind::TYPE),
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: Some(true),
+                    padding_right: None,
+                    data: None,
+                })
+            })
+            .collect();
+
+        Some(hints)
+    }
+
+    pub fn folding_ranges(&self) -> Option<Vec<FoldingRange>> {
+        let AnalyzedModule { declarations, .. } = self.module()?;
+
+        let mut lines: Vec<_> = foldable_regions(declarations)
+            .into_iter()
+            .map(|region| region.to_range(self.line_info()))
+            .filter(|range| range.start.line < range.end.line)
+            .map(|range| (range.start.line, range.end.line))
+            .collect();
+
+        // Regions starting and ending on the same lines would fold the same text
+        lines.sort();
+        lines.dedup();
+
+        let ranges = lines
+            .into_iter()
+            .map(|(start_line, end_line)| FoldingRange {
+                start_line,
+                end_line,
+                ..Default::default()
+            })
+            .collect();
+
+        Some(ranges)
+    }
+
+    /// Finds the values and types this document's module exposes whose names fuzzily match `query`.
+    #[allow(deprecated)] // SymbolInformation::deprecated
+    pub fn workspace_symbols(&self, query: &str) -> Vec<SymbolInformation> {
+        let Some(AnalyzedModule {
+            module_id,
+            interns,
+            subs,
+            declarations,
+            modules_info,
+            ..
+        }) = self.module()
+        else {
+            return vec![];
+        };
+        let Some(exposed) = modules_info.exposed_by_module.get(module_id) else {
+            return vec![];
+        };
+        let container_name = interns.module_name(*module_id).as_str();
+
+        let values = exposed.iter().filter_map(|(symbol, _)| {
+            let name = symbol.as_str(interns);
+            if !fuzzy_matches(query, name) {
+                return None;
+            }
+
+            let found = roc_can::traverse::find_declaration(*symbol, declarations)?;
+            let kind = if subs.is_function(found.var()) {
+                SymbolKind::FUNCTION
+            } else {
+                SymbolKind::CONSTANT
+            };
+
+            Some(SymbolInformation {
+                name: name.to_string(),
+                kind,
+                tags: None,
+                deprecated: None,
+                location: self.location(found.region().to_range(self.line_info())),
+                container_name: Some(container_name.to_string()),
+            })
+        });
+
+        values
+            .chain(self.exposed_type_symbols(query, container_name))
+            .collect()
+    }
+
+    /// The type aliases, opaque types and abilities this module exposes whose names match `query`.
+    #[allow(deprecated)] // SymbolInformation::deprecated
+    fn exposed_type_symbols(&self, query: &str, container_name: &str) -> Vec<SymbolInformation> {
+        let arena = Bump::new();
+        let Ok(ast) = Ast::parse(&arena, &self.doc_info.source) else {
+            return vec![];
+        };
+        let exposed = ast.header_exposes();
+
+        ast.defs()
+            .loc_defs()
+            .filter_map(|def| {
+                let def = def.ok()?;
+                let name = match def.value {
+                    TypeDef::Alias { header, .. }
+                    | TypeDef::Opaque { header, .. }
+                    | TypeDef::Ability { header, .. } => header.name.value,
+                };
+
+                if !fuzzy_matches(query, name) || !exposed.iter().any(|n| n.value == name) {
+                    return None;
+                }
+
+                Some(SymbolInformation {
+                    name: name.to_string(),
+                    kind: type_def_kind(&def.value),
+                    tags: None,
+                    deprecated: None,
+                    location: self.location(def.region.to_range(self.line_info())),
+                    container_name: Some(container_name.to_string()),
+                })
+            })
+            .collect()
+    }
+
     pub fn definition(&self, symbol: Symbol) -> Option<GotoDefinitionResponse> {
         let AnalyzedModule { declarations, .. } = self.module()?;
 
@@ -235,10 +504,235 @@ impl AnalyzedDocument {
         self.module()?.module_id_to_url.get(&module_id).cloned()
     }
 
+    pub(crate) fn qualify_symbol(&self, symbol: Symbol) -> Option<QualifiedSymbol> {
+        let AnalyzedModule { interns, .. } = self.module()?;
+
+        let module_name = interns.module_ids.get_name(symbol.module_id())?.clone();
+        let ident = interns
+            .all_ident_ids
+            .get(&symbol.module_id())?
+            .get_name(symbol.ident_id())?
+            .to_string();
+
+        Some(QualifiedSymbol { module_name, ident })
+    }
+
+    /// Finds the [Symbol] that `qualified` refers to within this document's analysis.
+    pub(crate) fn resolve_symbol(&self, qualified: &QualifiedSymbol) -> Option<Symbol> {
+        let AnalyzedModule { interns, .. } = self.module()?;
+
+        let module_id = interns.module_ids.get_id(&qualified.module_name)?;
+        let ident_id = interns
+            .all_ident_ids
+            .get(&module_id)?
+            .get_id(&qualified.ident)?;
+
+        Some(Symbol::new(module_id, ident_id))
+    }
+
+    /// Finds the symbol in this document that corresponds to `symbol` from the document at `url`.
+    pub(crate) fn matching_symbol(
+        &self,
+        url: &Url,
+        symbol: Symbol,
+        qualified: &QualifiedSymbol,
+    ) -> Option<Symbol> {
+        // Symbols local to a document can't be resolved by name, because separate defs may
+        // share the same name, so within the same document we use the symbol directly.
+        if self.url() == url {
+            Some(symbol)
+        } else {
+            self.resolve_symbol(qualified)
+        }
+    }
+
+    /// Finds every reference to `symbol` within this document.
+    pub fn references(&self, symbol: Symbol, include_declaration: bool) -> Vec<Location> {
+        let Some(AnalyzedModule {
+            declarations,
+            abilities,
+            ..
+        }) = self.module()
+        else {
+            return vec![];
+        };
+
+        roc_can::traverse::find_references(symbol, declarations, abilities)
+            .into_iter()
+            .filter(|found| include_declaration || found.kind == ReferenceKind::Lookup)
+            .map(|found| self.location(found.region.to_range(self.line_info())))
+            .collect()
+    }
+
+    /// Finds the type, alias, opaque type or ability named at `position`.
+    pub fn type_symbol_at(&self, position: Position) -> Option<Symbol> {
+        let position = position.to_roc_position(self.line_info());
+        let AnalyzedModule { declarations, .. } = self.module()?;
+
+        declarations
+            .type_references
+            .iter()
+            .find(|(loc_symbol, _)| loc_symbol.region.contains_pos(position))
+            .map(|(loc_symbol, _)| loc_symbol.value)
+    }
+
+    /// Finds the record field named at `position`, e.g. `foo` in `rec.foo` or `.foo`.
+    pub fn field_at(&self, position: Position) -> Option<Lowercase> {
+        let position = position.to_roc_position(self.line_info());
+
+        self.record_fields()
+            .find(|(_, found)| found.region.contains_pos(position))
+            .map(|(field, _)| field)
+    }
+
+    /// Finds every place this document names the record field `field`.
+    pub fn field_references(&self, field: &Lowercase, include_declaration: bool) -> Vec<Location> {
+        self.record_fields()
+            .filter(|(name, found)| {
+                name == field && (include_declaration || found.kind == ReferenceKind::Lookup)
+            })
+            .map(|(_, found)| self.location(found.region.to_range(self.line_info())))
+            .collect()
+    }
+
+    /// Every record field named in this document. Desugaring can create field accesses which
+    /// aren't in the source, so only the ones where the source has the field's name are kept.
+    fn record_fields(&self) -> impl Iterator<Item = (Lowercase, FoundReference)> + '_ {
+        let found = match self.module() {
+            Some(AnalyzedModule { declarations, .. }) => find_record_fields(declarations),
+            None => vec![],
+        };
+
+        found.into_iter().filter(|(field, found)| {
+            let region = found.region;
+            let text = self
+                .doc_info
+                .source
+                .get(region.start().offset as usize..region.end().offset as usize);
+
+            text == Some(field.as_str())
+        })
+    }
+
+    /// Figures out what a rename at `position` would rename, and the range of the name there.
+    pub(crate) fn rename_target(&self, position: Position) -> Option<(RenameTarget, Range)> {
+        let module = self.module()?;
+        let source = &self.doc_info.source;
+        let roc_position = position.to_roc_position(self.line_info());
+
+        let name_region = rename::ident_at(source, roc_position.offset as usize)?;
+        let name = &source[name_region.start().offset as usize..name_region.end().offset as usize];
+
+        let symbol = self.type_symbol_at(position).or_else(|| {
+            self.symbol_at(position)
+                .or_else(|| rename::ability_member_at(module, roc_position))
+        });
+
+        let target = match symbol {
+            Some(symbol) => {
+                let qualified = self.qualify_symbol(symbol)?;
+
+                // The symbol found may not be the name under the cursor, e.g. for `record.field`
+                if symbol.module_id().is_builtin() || qualified.ident() != name {
+                    return None;
+                }
+
+                RenameTarget::Symbol { symbol, qualified }
+            }
+            None if name.starts_with(|c: char| c.is_ascii_uppercase()) => {
+                let (_, owner) = rename::tag_occurrences(source, module, name)
+                    .into_iter()
+                    .find(|(region, _)| *region == name_region)?;
+                let owner = owner.filter(|owner| !owner.module_id().is_builtin())?;
+
+                RenameTarget::Tag {
+                    name: name.to_string(),
+                    owner,
+                    qualified_owner: self.qualify_symbol(owner)?,
+                }
+            }
+            None => return None,
+        };
+
+        Some((target, name_region.to_range(self.line_info())))
+    }
+
+    /// Finds the edits within this document needed to rename `target`, which was found in the
+    /// document at `url`.
+    ///
+    /// Returns an error if this document has a tag with the same name whose type can't be worked
+    /// out, since renaming it could be wrong either way.
+    pub(crate) fn rename_edits(
+        &self,
+        url: &Url,
+        target: &RenameTarget,
+        new_name: &str,
+    ) -> Result<Vec<TextEdit>, String> {
+        let Some(module) = self.module() else {
+            return Ok(vec![]);
+        };
+        let source = &self.doc_info.source;
+
+        let mut edits = match target {
+            RenameTarget::Symbol { symbol, qualified } => {
+                match self.matching_symbol(url, *symbol, qualified) {
+                    Some(symbol) => {
+                        rename::value_edits(source, module, symbol, target.name(), new_name)
+                    }
+                    None => vec![],
+                }
+            }
+            RenameTarget::Tag {
+                name,
+                owner,
+                qualified_owner,
+            } => match self.matching_symbol(url, *owner, qualified_owner) {
+                Some(owner) if rename::can_see_module(module, owner.module_id()) => {
+                    let mut edits = vec![];
+
+                    for (region, found_owner) in rename::tag_occurrences(source, module, name) {
+                        match found_owner {
+                            Some(found_owner) if found_owner == owner => {
+                                edits.push((region, new_name.to_string()));
+                            }
+                            Some(_) => {}
+                            None => {
+                                let line = region.to_range(self.line_info()).start.line + 1;
+
+                                return Err(format!(
+                                    "Can't tell which type `{name}` on line {line} of {} belongs to; \
+                                    add a type annotation there to rename it",
+                                    self.url()
+                                ));
+                            }
+                        }
+                    }
+
+                    edits
+                }
+                _ => vec![],
+            },
+        };
+
+        // The same name can be found more than once, e.g. as both a declaration and an exposed
+        // ability member, but the edits we send back must not overlap.
+        edits.sort_by_key(|(region, _)| region.start());
+        edits.dedup_by_key(|(region, _)| *region);
+
+        Ok(edits
+            .into_iter()
+            .map(|(region, new_text)| TextEdit {
+                range: region.to_range(self.line_info()),
+                new_text,
+            })
+            .collect())
+    }
+
     pub fn completion_items(
         &self,
         position: Position,
         latest_doc: &DocInfo,
+        exposed: &[ExposedValue],
     ) -> Option<Vec<CompletionItem>> {
         let symbol_prefix = latest_doc.get_prefix_at_position(position);
         debug!(
@@ -249,7 +743,8 @@ impl AnalyzedDocument {
 
         //We offset the position because we need the position to be in the correct scope in the most recently parsed version of the source. The quick and dirty method is to just remove the difference in length between the source files from the offset. This could cause issues, but is very easy
         //TODO: this is kind of a hack and should be removed once we can do some minimal parsing without full type checking
-        let mut position = position.to_roc_position(&latest_doc.line_info);
+        let cursor = position.to_roc_position(&latest_doc.line_info);
+        let mut position = cursor;
         position.offset = (position.offset as i32 - len_diff - 1) as u32;
         debug!("Completion offset: {:?}", position.offset);
 
@@ -261,6 +756,7 @@ impl AnalyzedDocument {
             exposed_imports,
             imports_by_module: imports,
             modules_info,
+            mismatches,
             ..
         } = self.module()?;
 
@@ -308,13 +804,45 @@ impl AnalyzedDocument {
                     modules_info,
                     true,
                 );
-                let tag_completions =
-                    get_tag_completion_items(&symbol_prefix, module_id, modules_info);
-                completions.extend(tag_completions);
+
+                // The latest source is used, since annotations don't need type checking
+                let arena = Bump::new();
+                let annotation_ast = Ast::parse(&arena, &latest_doc.source)
+                    .ok()
+                    .filter(|ast| in_type_annotation(ast.defs(), cursor.offset));
+
+                if let Some(ast) = annotation_ast {
+                    info!("Getting type completion...");
+                    completions.extend(type_name_completion_items(&symbol_prefix, &ast));
+                } else {
+                    let tag_completions = expected_tag_completion(
+                        position,
+                        &symbol_prefix,
+                        declarations,
+                        &mut subs.clone(),
+                        mismatches,
+                        module_id,
+                        interns,
+                    )
+                    .unwrap_or_else(|| {
+                        get_tag_completion_items(&symbol_prefix, module_id, modules_info)
+                    });
+                    completions.extend(tag_completions);
+                }
                 Some(completions)
             } else {
                 info!("Getting variable completion...");
-                let completions = get_completion_items(
+                let mut completions = expected_field_completion(
+                    position,
+                    &symbol_prefix,
+                    declarations,
+                    &mut subs.clone(),
+                    mismatches,
+                    module_id,
+                    interns,
+                )
+                .unwrap_or_default();
+                completions.extend(get_completion_items(
                     position,
                     symbol_prefix,
                     declarations,
@@ -323,7 +851,13 @@ impl AnalyzedDocument {
                     interns,
                     modules_info.get_docs(module_id),
                     exposed_imports,
-                );
+                ));
+                completions.extend(self.auto_import_completion_items(
+                    &symbol_prefix,
+                    &completions,
+                    latest_doc,
+                    exposed,
+                ));
                 Some(completions)
             }
         }
@@ -339,6 +873,273 @@ impl AnalyzedDocument {
         }
     }
 
+    /// Fixes for the non-exhaustive and redundant `when` branches within `range`.
+    pub fn when_branch_fixes(&self, range: Range) -> Vec<CodeAction> {
+        let Some(AnalyzedModule {
+            declarations,
+            interns,
+            exhaustive_problems,
+            ..
+        }) = self.module()
+        else {
+            return vec![];
+        };
+        let source = &self.doc_info.source;
+        let region = range.to_region(self.line_info());
+
+        exhaustive_problems
+            .iter()
+            .filter(|problem| {
+                let problem_region = problem.region();
+                problem_region.start() <= region.end() && region.start() <= problem_region.end()
+            })
+            .filter_map(|problem| match problem {
+                roc_exhaustive::Error::Incomplete(when_region, Context::BadCase, missing) => {
+                    let (position, new_text) = missing_branches_edit(
+                        declarations,
+                        source,
+                        interns,
+                        *when_region,
+                        missing,
+                    )?;
+                    let range = Region::from_pos(position).to_range(self.line_info());
+
+                    Some(self.quick_fix("Add missing branches", vec![TextEdit { range, new_text }]))
+                }
+                roc_exhaustive::Error::Redundant {
+                    overall_region,
+                    branch_region,
+                    ..
+                } => {
+                    let region = redundant_branch_region(
+                        declarations,
+                        source,
+                        *overall_region,
+                        *branch_region,
+                    )?;
+                    let edit = TextEdit {
+                        range: region.to_range(self.line_info()),
+                        new_text: String::new(),
+                    };
+
+                    Some(self.quick_fix("Remove redundant branch", vec![edit]))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Fixes for the names and modules within `range` which weren't found, by importing them from
+    /// one of the modules exposing them, or by qualifying them with that module.
+    pub fn auto_import_fixes(&self, range: Range, exposed: &[ExposedValue]) -> Vec<CodeAction> {
+        let Some(AnalyzedModule { unresolved, .. }) = self.module() else {
+            return vec![];
+        };
+        let source = &self.doc_info.source;
+        let arena = Bump::new();
+        let Ok(ast) = Ast::parse(&arena, source) else {
+            return vec![];
+        };
+        let Ok(path) = self.url().to_file_path() else {
+            return vec![];
+        };
+        let src_dir = find_src_dir(&path);
+        let region = range.to_region(self.line_info());
+        let overlaps =
+            |other: &Region| other.start() <= region.end() && region.start() <= other.end();
+
+        let mut actions = vec![];
+        for error in unresolved {
+            match error {
+                RuntimeError::LookupNotInScope { loc_name, .. } if overlaps(&loc_name.region) => {
+                    let name = loc_name.value.as_str();
+
+                    for (package, module_name) in
+                        modules_exposing(&ast, src_dir, exposed, Some(name), None)
+                    {
+                        if let Some(edit) =
+                            import_edit(&ast, source, package, module_name, Some(name))
+                        {
+                            let module = import_name(package, module_name);
+                            let title = format!("Import `{name}` from {module}");
+                            actions.push(self.quick_fix(&title, self.text_edits(vec![edit])));
+                        }
+
+                        let (qualified, edits) = qualify_edits(
+                            &ast,
+                            source,
+                            package,
+                            module_name,
+                            name,
+                            loc_name.region,
+                        );
+                        let title = format!("Qualify as `{qualified}`");
+                        actions.push(self.quick_fix(&title, self.text_edits(edits)));
+                    }
+                }
+                RuntimeError::ModuleNotImported {
+                    module_name,
+                    region,
+                    module_exists,
+                    ..
+                } if overlaps(region) => {
+                    let module_name = module_name.as_str();
+                    let mut packages: Vec<_> =
+                        modules_exposing(&ast, src_dir, exposed, None, Some(module_name))
+                            .into_iter()
+                            .map(|(package, _)| package)
+                            .collect();
+
+                    // We only know about modules exposing something, but the compiler knows more
+                    if packages.is_empty() && *module_exists {
+                        packages.push(None);
+                    }
+
+                    for package in packages {
+                        if let Some(edit) = import_edit(&ast, source, package, module_name, None) {
+                            let title = format!("Import {}", import_name(package, module_name));
+                            actions.push(self.quick_fix(&title, self.text_edits(vec![edit])));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        actions
+    }
+
+    /// The values this document's module exposes to the modules importing it.
+    pub(crate) fn exposed_values(&self) -> &[ExposedValue] {
+        self.exposed_values
+            .get_or_init(|| self.find_exposed_values())
+    }
+
+    fn find_exposed_values(&self) -> Vec<ExposedValue> {
+        let Some(AnalyzedModule {
+            module_id,
+            interns,
+            subs,
+            modules_info,
+            ..
+        }) = self.module()
+        else {
+            return vec![];
+        };
+
+        // Apps and platforms expose values too, but not for other modules to import
+        let arena = Bump::new();
+        if !Ast::parse(&arena, &self.doc_info.source).is_ok_and(|ast| ast.is_module()) {
+            return vec![];
+        }
+        let Some(exposed) = modules_info.exposed_by_module.get(module_id) else {
+            return vec![];
+        };
+
+        let module_name = interns.module_name(*module_id).as_str();
+        let mut subs = subs.clone();
+
+        exposed
+            .iter()
+            .map(|(symbol, var)| ExposedValue {
+                url: self.url().clone(),
+                module_name: module_name.to_string(),
+                name: symbol.as_str(interns).to_string(),
+                type_str: format_var_type(*var, &mut subs, module_id, interns),
+                is_function: subs.is_function(*var),
+            })
+            .collect()
+    }
+
+    /// Completes the values exposed by modules which aren't imported yet, along with the edit
+    /// which imports them.
+    fn auto_import_completion_items(
+        &self,
+        prefix: &str,
+        in_scope: &[CompletionItem],
+        latest_doc: &DocInfo,
+        exposed: &[ExposedValue],
+    ) -> Vec<CompletionItem> {
+        if prefix.is_empty() {
+            return vec![];
+        }
+        let Ok(path) = self.url().to_file_path() else {
+            return vec![];
+        };
+        let src_dir = find_src_dir(&path);
+
+        // The edits are made to the latest source, unless it can't be parsed while being written
+        let arena = Bump::new();
+        let (ast, doc) = match Ast::parse(&arena, &latest_doc.source) {
+            Ok(ast) => (ast, latest_doc),
+            Err(_) => match Ast::parse(&arena, &self.doc_info.source) {
+                Ok(ast) => (ast, &self.doc_info),
+                Err(_) => return vec![],
+            },
+        };
+
+        exposed
+            .iter()
+            .filter(|value| value.name.starts_with(prefix) && &value.url != self.url())
+            .filter(|value| !in_scope.iter().any(|item| item.label == value.name))
+            .filter_map(|value| {
+                let package = package_shorthand(&ast, src_dir, &value.url.to_file_path().ok()?)?;
+                let (region, new_text) = import_edit(
+                    &ast,
+                    &doc.source,
+                    package,
+                    &value.module_name,
+                    Some(&value.name),
+                )?;
+                let kind = if value.is_function {
+                    CompletionItemKind::FUNCTION
+                } else {
+                    CompletionItemKind::VARIABLE
+                };
+
+                Some(CompletionItem {
+                    label: value.name.clone(),
+                    label_details: Some(CompletionItemLabelDetails {
+                        detail: None,
+                        description: Some(format!(
+                            "auto-import from {}",
+                            import_name(package, &value.module_name)
+                        )),
+                    }),
+                    kind: Some(kind),
+                    detail: Some(value.type_str.clone()),
+                    additional_text_edits: Some(vec![TextEdit {
+                        range: region.to_range(&doc.line_info),
+                        new_text,
+                    }]),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    fn text_edits(&self, edits: Vec<(Region, String)>) -> Vec<TextEdit> {
+        edits
+            .into_iter()
+            .map(|(region, new_text)| TextEdit {
+                range: region.to_range(self.line_info()),
+                new_text,
+            })
+            .collect()
+    }
+
+    fn quick_fix(&self, title: &str, edits: Vec<TextEdit>) -> CodeAction {
+        CodeAction {
+            title: title.to_owned(),
+            edit: Some(WorkspaceEdit::new(HashMap::from([(
+                self.url().clone(),
+                edits,
+            )]))),
+            kind: Some(CodeActionKind::QUICKFIX),
+            ..Default::default()
+        }
+    }
+
     fn annnotate_top_level(&self) -> Option<CodeAction> {
         let AnalyzedModule {
             module_id,
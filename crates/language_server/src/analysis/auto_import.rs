@@ -0,0 +1,229 @@
+use std::path::{Component, Path, PathBuf};
+
+use roc_packaging::{cache, https::PackageMetadata};
+use roc_region::all::{Position, Region};
+use tower_lsp::lsp_types::Url;
+
+use super::parse_ast::{Ast, Import};
+
+/// A value exposed by a module we know about, which other modules could import.
+#[derive(Debug, Clone)]
+pub(crate) struct ExposedValue {
+    pub(crate) url: Url,
+    pub(crate) module_name: String,
+    pub(crate) name: String,
+    pub(crate) type_str: String,
+    pub(crate) is_function: bool,
+}
+
+/// Finds the shorthand of the package the module at `module_path` is imported from, by the module
+/// whose header is in `ast` and whose source is in `src_dir`. Modules of the same package have no
+/// shorthand, and modules which can't be imported at all are `None`.
+pub(super) fn package_shorthand<'a>(
+    ast: &Ast<'a>,
+    src_dir: &Path,
+    module_path: &Path,
+) -> Option<Option<&'a str>> {
+    let module_path = normalize(module_path);
+    let packages = ast
+        .packages()
+        .into_iter()
+        .filter_map(|(shorthand, location)| {
+            Some((Some(shorthand), package_dir(src_dir, location)?))
+        });
+
+    // A package may be nested in the directory of another, so the closest one wins
+    std::iter::once((None, normalize(src_dir)))
+        .chain(packages)
+        .filter(|(_, dir)| module_path.starts_with(dir))
+        .max_by_key(|(_, dir)| dir.components().count())
+        .map(|(shorthand, _)| shorthand)
+}
+
+/// Finds the modules exposing a value, optionally only those with the given `name` or
+/// `module_name`, along with the shorthand of the package each one is imported from.
+pub(super) fn modules_exposing<'a, 'b>(
+    ast: &Ast<'a>,
+    src_dir: &Path,
+    exposed: &'b [ExposedValue],
+    name: Option<&str>,
+    module_name: Option<&str>,
+) -> Vec<(Option<&'a str>, &'b str)> {
+    let mut found: Vec<_> = exposed
+        .iter()
+        .filter(|value| name.map_or(true, |name| value.name == name))
+        .filter(|value| module_name.map_or(true, |module_name| value.module_name == module_name))
+        .filter_map(|value| {
+            let package = package_shorthand(ast, src_dir, &value.url.to_file_path().ok()?)?;
+            Some((package, value.module_name.as_str()))
+        })
+        .collect();
+
+    found.sort();
+    found.dedup();
+
+    found
+}
+
+/// The directory holding the modules of the package at `location`, as written in a header.
+fn package_dir(src_dir: &Path, location: &str) -> Option<PathBuf> {
+    match PackageMetadata::try_from(location) {
+        Ok(metadata) => Some(
+            cache::roc_cache_packages_dir()
+                .join(metadata.cache_subdir)
+                .join(metadata.content_hash),
+        ),
+        Err(_) => src_dir.join(location).parent().map(normalize),
+    }
+}
+
+/// Resolves the `.` and `..` in `path` without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+
+    normalized
+}
+
+/// How a module is written in an `import` statement, e.g. `pf.Stdout`.
+pub(super) fn import_name(package: Option<&str>, module_name: &str) -> String {
+    match package {
+        Some(package) => format!("{package}.{module_name}"),
+        None => module_name.to_string(),
+    }
+}
+
+/// Finds the edit which imports `name` from a module, or only the module if `name` is `None`.
+/// An existing `import` statement of the module is extended, otherwise a new one is added after
+/// the others. Modules imported in an old-style header are left alone.
+pub(super) fn import_edit(
+    ast: &Ast,
+    source: &str,
+    package: Option<&str>,
+    module_name: &str,
+    name: Option<&str>,
+) -> Option<(Region, String)> {
+    let Some(import) = find_import(ast, package, module_name) else {
+        let position = new_import_position(ast, source);
+        // The source may end right after the header
+        let newline = if position.offset as usize == source.len() && !source.ends_with('\n') {
+            "\n"
+        } else {
+            ""
+        };
+        let exposing = name
+            .map(|name| format!(" exposing [{name}]"))
+            .unwrap_or_default();
+        let new_text = format!(
+            "{newline}import {}{exposing}\n",
+            import_name(package, module_name)
+        );
+
+        return Some((Region::from_pos(position), new_text));
+    };
+
+    let name = name?;
+    if !is_import_statement(source, &import) {
+        return None;
+    }
+
+    let edit = match import.exposed.as_deref() {
+        Some([.., last]) => (Region::from_pos(last.region.end()), format!(", {name}")),
+        // Right after the opening bracket
+        Some([]) => (
+            Region::from_pos(exposed_list_start(source, &import)?),
+            name.to_string(),
+        ),
+        None => (
+            Region::from_pos(import.region.end()),
+            format!(" exposing [{name}]"),
+        ),
+    };
+
+    Some(edit)
+}
+
+/// Finds the edits which replace the name at `region` with `name` qualified by its module, which
+/// is imported too if it wasn't already. Returns the qualified name along with the edits.
+pub(super) fn qualify_edits(
+    ast: &Ast,
+    source: &str,
+    package: Option<&str>,
+    module_name: &str,
+    name: &str,
+    region: Region,
+) -> (String, Vec<(Region, String)>) {
+    let qualifier = match find_import(ast, package, module_name) {
+        Some(import) => import.qualifier,
+        None => module_name,
+    };
+    let qualified = format!("{qualifier}.{name}");
+
+    let mut edits: Vec<_> = import_edit(ast, source, package, module_name, None)
+        .into_iter()
+        .collect();
+    edits.push((region, qualified.clone()));
+
+    (qualified, edits)
+}
+
+fn find_import<'a>(ast: &Ast<'a>, package: Option<&str>, module_name: &str) -> Option<Import<'a>> {
+    ast.imports()
+        .into_iter()
+        .find(|import| import.package == package && import.module_name == module_name)
+}
+
+/// Imports in an old-style header are turned into defs too, but their regions are in the header.
+fn is_import_statement(source: &str, import: &Import) -> bool {
+    source
+        .get(import.region.start().offset as usize..)
+        .is_some_and(|rest| rest.starts_with("import"))
+}
+
+/// Finds the position right after the `[` opening the exposed list of `import`. Module names
+/// can't contain brackets, so this is the first one outside of a comment.
+fn exposed_list_start(source: &str, import: &Import) -> Option<Position> {
+    let start = import.region.start().offset as usize;
+    let text = source.get(start..import.region.end().offset as usize)?;
+    let mut in_comment = false;
+
+    for (index, byte) in text.bytes().enumerate() {
+        match byte {
+            b'#' => in_comment = true,
+            b'\n' => in_comment = false,
+            b'[' if !in_comment => return Some(Position::new((start + index + 1) as u32)),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// New imports go on the line after the last `import` statement, or after the header if there are
+/// none.
+fn new_import_position(ast: &Ast, source: &str) -> Position {
+    let after = ast
+        .imports()
+        .iter()
+        .filter(|import| is_import_statement(source, import))
+        .map(|import| import.region.end())
+        .max()
+        .unwrap_or_else(|| ast.header_end());
+
+    // Whitespace after the header may already have been parsed along with it
+    let end = source[..after.offset as usize].trim_end().len();
+    let line_end = source[end..]
+        .find('\n')
+        .map_or(source.len(), |i| end + i + 1);
+
+    Position::new(line_end as u32)
+}
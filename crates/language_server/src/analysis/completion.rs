@@ -3,20 +3,25 @@ use std::{collections::HashMap, sync::Arc};
 use log::{debug, warn};
 
 use roc_can::{expr::Declarations, traverse::Visitor};
-use roc_collections::MutMap;
 use roc_load::docs::{DocDef, ModuleDocumentation};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
-use roc_region::all::Position;
+use roc_region::all::{Position, Region};
 use roc_types::{
     subs::{Subs, Variable},
-    types::Alias,
+    types::ErrorType,
 };
 use tower_lsp::lsp_types::{self, CompletionItem, CompletionItemKind};
 
-use self::visitor::CompletionVisitor;
+use self::{
+    expected::{expected_names, NameKind},
+    visitor::CompletionVisitor,
+};
 
 use super::{utils::format_var_type, ModulesInfo};
+pub(super) use type_names::{in_type_annotation, type_name_completion_items};
+mod expected;
 mod formatting;
+mod type_names;
 mod visitor;
 
 fn get_completions(
@@ -89,6 +94,91 @@ pub(super) fn get_tag_completion_items(
         .collect()
 }
 
+/// Completes the tags of the tag union expected at `position`, e.g. `Ok` and `Err` where a
+/// `Result` is expected. Returns `None` if no tag union is expected there.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn expected_tag_completion(
+    position: Position,
+    prefix: &str,
+    declarations: &Declarations,
+    subs: &mut Subs,
+    mismatches: &[(Region, ErrorType)],
+    module_id: &ModuleId,
+    interns: &Interns,
+) -> Option<Vec<CompletionItem>> {
+    expected_completion(
+        NameKind::Tag,
+        position,
+        prefix,
+        declarations,
+        subs,
+        mismatches,
+        module_id,
+        interns,
+    )
+}
+
+/// Completes the fields of the record expected at `position`, when constructing or destructuring
+/// a record. Fields which are already there are left out.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn expected_field_completion(
+    position: Position,
+    prefix: &str,
+    declarations: &Declarations,
+    subs: &mut Subs,
+    mismatches: &[(Region, ErrorType)],
+    module_id: &ModuleId,
+    interns: &Interns,
+) -> Option<Vec<CompletionItem>> {
+    expected_completion(
+        NameKind::Field,
+        position,
+        prefix,
+        declarations,
+        subs,
+        mismatches,
+        module_id,
+        interns,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn expected_completion(
+    kind: NameKind,
+    position: Position,
+    prefix: &str,
+    declarations: &Declarations,
+    subs: &mut Subs,
+    mismatches: &[(Region, ErrorType)],
+    module_id: &ModuleId,
+    interns: &Interns,
+) -> Option<Vec<CompletionItem>> {
+    let (found_kind, names) =
+        expected_names(position, declarations, subs, mismatches, module_id, interns)?;
+
+    if found_kind != kind {
+        return None;
+    }
+
+    let item_kind = match kind {
+        NameKind::Tag => CompletionItemKind::ENUM_MEMBER,
+        NameKind::Field => CompletionItemKind::FIELD,
+    };
+
+    let items = names
+        .into_iter()
+        .filter(|expected| expected.name.starts_with(prefix))
+        .map(|expected| CompletionItem {
+            label: expected.name,
+            detail: expected.detail,
+            kind: Some(item_kind),
+            ..Default::default()
+        })
+        .collect();
+
+    Some(items)
+}
+
 pub(super) fn get_module_completion_items(
     prefix: &String,
     interns: &Interns,
@@ -201,28 +291,6 @@ fn get_completion_docs(
         .collect()
 }
 
-/// Provides a list of completions for Type aliases within the scope.
-///TODO: Use this when we know we are within a type definition
-fn _alias_completions(
-    aliases: &MutMap<Symbol, (bool, Alias)>,
-    module_id: &ModuleId,
-    interns: &Interns,
-) -> Vec<CompletionItem> {
-    aliases
-        .iter()
-        .filter(|(symbol, (_exposed, _alias))| &symbol.module_id() == module_id)
-        .map(|(symbol, (_exposed, _alias))| {
-            let name = symbol.as_str(interns).to_string();
-            CompletionItem {
-                label: name.clone(),
-                detail: Some(name + "we don't know how to print types "),
-                kind: Some(CompletionItemKind::CLASS),
-                ..Default::default()
-            }
-        })
-        .collect()
-}
-
 fn make_completion_items(
     subs: &mut Subs,
     module_id: &ModuleId,
@@ -0,0 +1,309 @@
+use roc_can::{
+    expr::{Declarations, Expr},
+    pattern::{DestructType, Pattern},
+    traverse::{walk_expr, walk_pattern, Visitor},
+};
+use roc_module::{
+    ident::{Lowercase, TagName},
+    symbol::{Interns, ModuleId},
+};
+use roc_region::all::{Position, Region};
+use roc_types::{
+    subs::{Content, FlatType, Subs, Variable},
+    types::{AliasKind, ErrorType},
+};
+
+use crate::analysis::utils::format_var_type;
+
+/// What kind of name is being written at the cursor.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum NameKind {
+    /// A tag, in an expression or a pattern.
+    Tag,
+    /// A field of a record which is being built or destructured.
+    Field,
+}
+
+/// A name the expected type allows, along with a description of its payload or value if known.
+pub(super) struct ExpectedName {
+    pub(super) name: String,
+    pub(super) detail: Option<String>,
+}
+
+/// The innermost tag or record at the cursor.
+struct Site {
+    kind: NameKind,
+    region: Region,
+    var: Variable,
+    /// The fields of the record which are already written.
+    written: Vec<Lowercase>,
+}
+
+/// Finds the names allowed by the type expected at `position`: the tags of a tag union, e.g. `Ok`
+/// and `Err` where a `Result` is expected, or the fields of a record that aren't written yet.
+///
+/// The type is taken from `subs`, unless what's at `position` didn't type-check, in which case
+/// the type the checker expected there is used instead.
+pub(super) fn expected_names(
+    position: Position,
+    decls: &Declarations,
+    subs: &mut Subs,
+    mismatches: &[(Region, ErrorType)],
+    module_id: &ModuleId,
+    interns: &Interns,
+) -> Option<(NameKind, Vec<ExpectedName>)> {
+    let site = find_site(position, decls)?;
+    let var = resolve_alias(subs, site.var);
+    let mismatch = || {
+        mismatches
+            .iter()
+            .find(|(region, _)| *region == site.region)
+            .map(|(_, typ)| resolve_error_alias(typ))
+    };
+
+    let names = match site.kind {
+        NameKind::Tag => match closed_union_tags(subs, var) {
+            Some(tags) => tags
+                .into_iter()
+                .map(|(tag_name, payload)| {
+                    let detail = (!payload.is_empty()).then(|| {
+                        let payload: Vec<_> = payload
+                            .into_iter()
+                            .map(|var| format_var_type(var, subs, module_id, interns))
+                            .collect();
+
+                        format!("{} {}", tag_name.0.as_str(), payload.join(" "))
+                    });
+
+                    ExpectedName {
+                        name: tag_name.0.as_str().to_string(),
+                        detail,
+                    }
+                })
+                .collect(),
+            None => match mismatch()? {
+                ErrorType::TagUnion(tags, _, _) | ErrorType::RecursiveTagUnion(_, tags, _, _) => {
+                    tags.keys()
+                        .map(|tag_name| ExpectedName {
+                            name: tag_name.0.as_str().to_string(),
+                            detail: None,
+                        })
+                        .collect()
+                }
+                _ => return None,
+            },
+        },
+        NameKind::Field => {
+            let fields: Vec<_> = match record_fields(subs, var) {
+                Some(fields) => fields
+                    .into_iter()
+                    .map(|(field, var)| ExpectedName {
+                        name: field.as_str().to_string(),
+                        detail: Some(format_var_type(var, subs, module_id, interns)),
+                    })
+                    .collect(),
+                None => match mismatch()? {
+                    ErrorType::Record(fields, _) => fields
+                        .keys()
+                        .map(|field| ExpectedName {
+                            name: field.as_str().to_string(),
+                            detail: None,
+                        })
+                        .collect(),
+                    _ => return None,
+                },
+            };
+
+            fields
+                .into_iter()
+                .filter(|field| !site.written.iter().any(|w| w.as_str() == field.name))
+                .collect()
+        }
+    };
+
+    Some((site.kind, sorted(names)))
+}
+
+fn sorted(mut names: Vec<ExpectedName>) -> Vec<ExpectedName> {
+    names.sort_by(|a, b| a.name.cmp(&b.name));
+    names.dedup_by(|a, b| a.name == b.name);
+    names
+}
+
+fn find_site(position: Position, decls: &Declarations) -> Option<Site> {
+    let mut visitor = SiteFinder {
+        position,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct SiteFinder {
+        position: Position,
+        found: Option<Site>,
+    }
+
+    impl SiteFinder {
+        fn record_site(
+            &mut self,
+            kind: NameKind,
+            region: Region,
+            var: Variable,
+            written: Vec<Lowercase>,
+        ) {
+            self.found = Some(Site {
+                kind,
+                region,
+                var,
+                written,
+            });
+        }
+    }
+
+    impl Visitor for SiteFinder {
+        fn should_visit(&mut self, region: Region) -> bool {
+            region.contains_pos(self.position)
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if !self.should_visit(region) {
+                return;
+            }
+
+            // Whatever is nested deeper is closer to what's being written
+            self.found = None;
+
+            match expr {
+                Expr::Tag { .. } | Expr::ZeroArgumentTag { .. } => {
+                    self.record_site(NameKind::Tag, region, var, vec![]);
+                }
+                Expr::EmptyRecord => {
+                    self.record_site(NameKind::Field, region, var, vec![]);
+                }
+                Expr::Record { fields, .. } => {
+                    // A field without a value, like `{ name }`, is still having its name written
+                    let in_value = fields.values().any(|field| {
+                        field.region != field.loc_expr.region
+                            && field.loc_expr.region.contains_pos(self.position)
+                    });
+
+                    if !in_value {
+                        let written = fields.keys().cloned().collect();
+                        return self.record_site(NameKind::Field, region, var, written);
+                    }
+                }
+                _ => {}
+            }
+
+            walk_expr(self, expr, var);
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            if !self.should_visit(region) {
+                return;
+            }
+
+            self.found = None;
+
+            match pattern {
+                Pattern::AppliedTag { whole_var, .. } => {
+                    self.record_site(NameKind::Tag, region, *whole_var, vec![]);
+                }
+                Pattern::RecordDestructure {
+                    whole_var,
+                    destructs,
+                    ..
+                } => {
+                    let in_value = destructs.iter().any(|destruct| match &destruct.value.typ {
+                        DestructType::Required => false,
+                        DestructType::Optional(_, default) => {
+                            default.region.contains_pos(self.position)
+                        }
+                        DestructType::Guard(_, pattern) => {
+                            pattern.region.contains_pos(self.position)
+                        }
+                    });
+
+                    if !in_value {
+                        let written = destructs
+                            .iter()
+                            .map(|destruct| destruct.value.label.clone())
+                            .collect();
+                        return self.record_site(NameKind::Field, region, *whole_var, written);
+                    }
+                }
+                _ => {}
+            }
+
+            walk_pattern(self, pattern);
+        }
+    }
+}
+
+/// Follows `var` through aliases to the type they stand for. Opaque types are left alone, since
+/// what they stand for is hidden outside of their module.
+fn resolve_alias(subs: &Subs, mut var: Variable) -> Variable {
+    loop {
+        match subs.get_content_without_compacting(var) {
+            Content::Alias(_, _, real_var, AliasKind::Structural) => var = *real_var,
+            Content::RecursionVar { structure, .. } => var = *structure,
+            _ => return var,
+        }
+    }
+}
+
+fn resolve_error_alias(typ: &ErrorType) -> &ErrorType {
+    match typ {
+        ErrorType::Alias(_, _, actual, AliasKind::Structural) => resolve_error_alias(actual),
+        _ => typ,
+    }
+}
+
+/// The tags of `var`, if it's a tag union with no other tags. An open tag union only says which
+/// tags were used, not which ones are expected.
+fn closed_union_tags(subs: &Subs, var: Variable) -> Option<Vec<(TagName, Vec<Variable>)>> {
+    let mut tags = vec![];
+    let mut var = var;
+
+    loop {
+        match subs.get_content_without_compacting(var) {
+            Content::Structure(FlatType::TagUnion(union_tags, ext))
+            | Content::Structure(FlatType::RecursiveTagUnion(_, union_tags, ext)) => {
+                tags.extend(
+                    union_tags
+                        .iter_from_subs(subs)
+                        .map(|(tag_name, payload)| (tag_name.clone(), payload.to_vec())),
+                );
+                var = ext.var();
+            }
+            Content::Structure(FlatType::FunctionOrTagUnion(tag_names, _, ext)) => {
+                tags.extend(
+                    subs.get_subs_slice(*tag_names)
+                        .iter()
+                        .map(|tag_name| (tag_name.clone(), vec![])),
+                );
+                var = ext.var();
+            }
+            Content::Alias(_, _, real_var, AliasKind::Structural) => var = *real_var,
+            Content::Structure(FlatType::EmptyTagUnion) => return Some(tags),
+            _ => return None,
+        }
+    }
+}
+
+/// The fields of `var`, if it's a record.
+fn record_fields(subs: &Subs, var: Variable) -> Option<Vec<(Lowercase, Variable)>> {
+    match subs.get_content_without_compacting(var) {
+        Content::Structure(FlatType::Record(fields, ext)) => {
+            let fields = fields.unsorted_iterator(subs, *ext).ok()?;
+
+            Some(
+                fields
+                    .map(|(field, var)| (field.clone(), var.into_inner()))
+                    .collect(),
+            )
+        }
+        Content::Structure(FlatType::EmptyRecord) => Some(vec![]),
+        _ => None,
+    }
+}
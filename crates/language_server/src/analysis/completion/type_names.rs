@@ -0,0 +1,128 @@
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_parse::ast::{Defs, Expr, ExtractSpaces, TypeDef, ValueDef};
+use roc_region::all::Region;
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, SymbolKind};
+
+use crate::analysis::{parse_ast::Ast, symbols::type_def_kind};
+
+/// The builtin modules whose types are in scope in every module, see `roc_load_internal`.
+const BUILTIN_TYPE_MODULES: &[ModuleId] = &[
+    ModuleId::NUM,
+    ModuleId::BOOL,
+    ModuleId::STR,
+    ModuleId::LIST,
+    ModuleId::RESULT,
+    ModuleId::DICT,
+    ModuleId::SET,
+    ModuleId::BOX,
+    ModuleId::ENCODE,
+    ModuleId::DECODE,
+    ModuleId::HASH,
+    ModuleId::INSPECT,
+];
+
+/// Whether `offset` is within the annotation of a def, or the definition of a type alias, opaque
+/// type or ability.
+pub(super) fn in_type_annotation(defs: &Defs, offset: u32) -> bool {
+    annotation_regions(defs)
+        .iter()
+        .any(|region| region.start().offset <= offset && offset <= region.end().offset)
+}
+
+/// Completes the names of the types in scope: the builtin ones, those defined in the module and
+/// those imported from other modules.
+pub(super) fn type_name_completion_items(prefix: &str, ast: &Ast) -> Vec<CompletionItem> {
+    let item = |name: &str, kind| CompletionItem {
+        label: name.to_string(),
+        kind: Some(kind),
+        ..Default::default()
+    };
+
+    // Shown as the same kind of symbol as in the document outline
+    let defined = ast.defs().defs().filter_map(|def| {
+        let type_def = def.ok()?;
+        let kind = match type_def_kind(type_def) {
+            SymbolKind::STRUCT => CompletionItemKind::STRUCT,
+            SymbolKind::ENUM => CompletionItemKind::ENUM,
+            SymbolKind::INTERFACE => CompletionItemKind::INTERFACE,
+            _ => CompletionItemKind::CLASS,
+        };
+
+        let (TypeDef::Alias { header, .. }
+        | TypeDef::Opaque { header, .. }
+        | TypeDef::Ability { header, .. }) = type_def;
+
+        Some(item(header.name.value, kind))
+    });
+
+    let imported = ast
+        .import_exposes()
+        .into_iter()
+        .map(|(_, name)| name.value)
+        .filter(|name| name.starts_with(char::is_uppercase))
+        .map(|name| item(name, CompletionItemKind::CLASS));
+
+    let apply_types = Symbol::apply_types_in_scope();
+    let builtin = apply_types
+        .keys()
+        .map(|ident| ident.as_str())
+        .chain(BUILTIN_TYPE_MODULES.iter().flat_map(|module_id| {
+            Symbol::builtin_types_in_scope(*module_id)
+                .iter()
+                .map(|(name, _)| *name)
+        }))
+        .map(|name| item(name, CompletionItemKind::CLASS));
+
+    let mut items: Vec<_> = defined
+        .chain(imported)
+        .chain(builtin)
+        .filter(|item| item.label.starts_with(prefix))
+        .collect();
+    items.sort_by(|a, b| a.label.cmp(&b.label));
+    items.dedup_by(|a, b| a.label == b.label);
+
+    items
+}
+
+/// The regions of the annotations in `defs`, including those of defs nested in the bodies of
+/// other defs.
+fn annotation_regions(defs: &Defs) -> Vec<Region> {
+    defs.loc_defs()
+        .flat_map(|def| match def {
+            Ok(type_def) => match type_def.value {
+                TypeDef::Alias { ann, .. } => vec![ann.region],
+                TypeDef::Opaque { typ, .. } => vec![typ.region],
+                TypeDef::Ability { members, .. } => {
+                    members.iter().map(|member| member.typ.region).collect()
+                }
+            },
+            Err(value_def) => match value_def.value {
+                ValueDef::Annotation(_, ann) => vec![ann.region],
+                ValueDef::AnnotatedBody {
+                    ann_type,
+                    body_expr,
+                    ..
+                } => {
+                    let mut regions = vec![ann_type.region];
+                    regions.extend(body_annotation_regions(&body_expr.value));
+                    regions
+                }
+                ValueDef::Body(_, body) => body_annotation_regions(&body.value),
+                _ => vec![],
+            },
+        })
+        .collect()
+}
+
+fn body_annotation_regions(body: &Expr) -> Vec<Region> {
+    match body.without_spaces() {
+        Expr::Defs(defs, final_expr) => {
+            let mut regions = annotation_regions(defs);
+            regions.extend(body_annotation_regions(&final_expr.value));
+            regions
+        }
+        Expr::Closure(_, body) => body_annotation_regions(&body.value),
+        Expr::ParensAround(expr) => body_annotation_regions(expr),
+        _ => vec![],
+    }
+}
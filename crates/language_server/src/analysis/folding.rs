@@ -0,0 +1,55 @@
+use roc_can::{
+    def::Def,
+    expr::{Declarations, Expr},
+    traverse::{walk_decl, walk_def, walk_expr, DeclarationInfo, Visitor},
+};
+use roc_region::all::Region;
+use roc_types::subs::Variable;
+
+/// Finds the regions which can be folded: defs, `when` expressions and each of their branches,
+/// records and lists. Callers are expected to leave out the ones which fit on one line.
+pub(super) fn foldable_regions(decls: &Declarations) -> Vec<Region> {
+    let mut visitor = Foldable { found: vec![] };
+    visitor.visit_decls(decls);
+
+    visitor.found
+}
+
+struct Foldable {
+    found: Vec<Region>,
+}
+
+impl Visitor for Foldable {
+    fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+        self.found.push(decl.region());
+        walk_decl(self, decl);
+    }
+
+    fn visit_def(&mut self, def: &Def) {
+        self.found.push(def.region());
+        walk_def(self, def);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        match expr {
+            Expr::When { branches, .. } => {
+                self.found.push(region);
+
+                for branch in branches {
+                    if let Some(first) = branch.patterns.first() {
+                        self.found.push(Region::span_across(
+                            &first.pattern.region,
+                            &branch.value.region,
+                        ));
+                    }
+                }
+            }
+            Expr::Record { .. } | Expr::RecordUpdate { .. } | Expr::List { .. } => {
+                self.found.push(region);
+            }
+            _ => {}
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
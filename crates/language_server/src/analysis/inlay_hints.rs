@@ -0,0 +1,155 @@
+use roc_can::{
+    abilities::AbilitiesStore,
+    def::{Def, DefKind},
+    expr::{AnnotatedMark, ClosureData, Declarations, Expr},
+    pattern::Pattern,
+    traverse::{walk_decl, walk_def, walk_expr, DeclarationInfo, Visitor},
+};
+use roc_problem::can::RuntimeError;
+use roc_region::all::{Loc, Position, Region};
+use roc_types::subs::Variable;
+
+/// Finds the places where an inferred type can be shown: after the names of unannotated defs, and
+/// after the parameters of lambdas whose type isn't annotated.
+///
+/// With `top_level_only`, only unannotated top-level defs are included.
+pub(super) fn find_inferred_types(
+    decls: &Declarations,
+    abilities: &AbilitiesStore,
+    region: Region,
+    top_level_only: bool,
+) -> Vec<(Position, Variable)> {
+    let mut visitor = InferredTypes {
+        region,
+        abilities,
+        top_level_only,
+        found: vec![],
+    };
+    visitor.visit_decls(decls);
+
+    visitor
+        .found
+        .retain(|(position, _)| region.contains_pos(*position));
+    visitor.found.sort_by_key(|(position, _)| *position);
+
+    visitor.found
+}
+
+struct InferredTypes<'a> {
+    region: Region,
+    abilities: &'a AbilitiesStore,
+    top_level_only: bool,
+    found: Vec<(Position, Variable)>,
+}
+
+impl InferredTypes<'_> {
+    fn push(&mut self, region: Region, var: Variable) {
+        self.found.push((region.end(), var));
+    }
+
+    fn push_arguments(&mut self, arguments: &[(Variable, AnnotatedMark, Loc<Pattern>)]) {
+        for (var, _, loc_pattern) in arguments {
+            if let Pattern::Identifier(_) = loc_pattern.value {
+                self.push(loc_pattern.region, *var);
+            }
+        }
+    }
+
+    /// Visits a lambda whose type is annotated, so its parameters need no hints.
+    fn visit_annotated_closure(&mut self, closure: &ClosureData) {
+        let body = &closure.loc_body;
+        self.visit_expr(&body.value, body.region, closure.return_type);
+    }
+}
+
+impl Visitor for InferredTypes<'_> {
+    fn should_visit(&mut self, region: Region) -> bool {
+        // Only visit nodes overlapping the requested region
+        region.start() <= self.region.end() && self.region.start() <= region.end()
+    }
+
+    fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+        if !self.should_visit(decl.region()) {
+            return;
+        }
+
+        match &decl {
+            DeclarationInfo::Value {
+                loc_symbol,
+                loc_expr,
+                expr_var,
+                annotation: None,
+                ..
+            } if !matches!(
+                loc_expr.value,
+                Expr::ImportParams(..) | Expr::RuntimeError(RuntimeError::ExposedButNotDefined(..))
+            ) && !self.abilities.is_specialization_name(loc_symbol.value) =>
+            {
+                self.push(loc_symbol.region, *expr_var)
+            }
+            DeclarationInfo::Function {
+                loc_symbol,
+                expr_var,
+                annotation: None,
+                ..
+            } if !self.abilities.is_specialization_name(loc_symbol.value) => {
+                self.push(loc_symbol.region, *expr_var)
+            }
+            DeclarationInfo::Destructure {
+                loc_pattern,
+                expr_var,
+                annotation: None,
+                ..
+            } => self.push(loc_pattern.region, *expr_var),
+            _ => {}
+        }
+
+        if self.top_level_only {
+            return;
+        }
+
+        match decl {
+            DeclarationInfo::Function {
+                loc_body,
+                function,
+                annotation,
+                ..
+            } => {
+                if annotation.is_none() {
+                    self.push_arguments(&function.value.arguments);
+                }
+
+                self.visit_expr(&loc_body.value, loc_body.region, function.value.return_type);
+            }
+            _ => walk_decl(self, decl),
+        }
+    }
+
+    fn visit_def(&mut self, def: &Def) {
+        if !self.should_visit(def.region()) {
+            return;
+        }
+
+        if def.annotation.is_some() {
+            if let Expr::Closure(closure) = &def.loc_expr.value {
+                return self.visit_annotated_closure(closure);
+            }
+        } else if matches!(def.kind, DefKind::Let) {
+            self.push(def.loc_pattern.region, def.expr_var);
+        }
+
+        walk_def(self, def);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        if !self.should_visit(region) {
+            return;
+        }
+
+        if let Expr::Closure(closure) = expr {
+            self.push_arguments(&closure.arguments);
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
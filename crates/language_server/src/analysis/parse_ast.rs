@@ -1,11 +1,11 @@
 use bumpalo::Bump;
 use roc_fmt::{Buf, MigrationFlags};
 use roc_parse::{
-    ast::{Defs, Header, SpacesBefore},
-    header::parse_module_defs,
+    ast::{Collection, Defs, Header, Spaced, SpacesBefore, ValueDef},
+    header::{parse_module_defs, ExposedName, PackageEntry},
     parser::SyntaxError,
 };
-use roc_region::all::Loc;
+use roc_region::all::{Loc, Position, Region};
 
 use self::format::FormattedAst;
 
@@ -16,9 +16,20 @@ mod format;
 pub struct Ast<'a> {
     arena: &'a Bump,
     module: SpacesBefore<'a, Header<'a>>,
+    header_end: Position,
     defs: Defs<'a>,
 }
 
+/// An `import` statement, or an import in an old-style module header.
+pub struct Import<'a> {
+    pub package: Option<&'a str>,
+    pub module_name: &'a str,
+    /// The name the module's values are qualified with, which is its alias if it has one.
+    pub qualifier: &'a str,
+    pub region: Region,
+    pub exposed: Option<Vec<Loc<&'a str>>>,
+}
+
 impl<'a> Ast<'a> {
     pub fn parse(arena: &'a Bump, src: &'a str) -> Result<Ast<'a>, SyntaxError<'a>> {
         use roc_parse::{header::parse_header, state::State};
@@ -26,6 +37,7 @@ impl<'a> Ast<'a> {
         let (module, state) = parse_header(arena, State::new(src.as_bytes()))
             .map_err(|e| SyntaxError::Header(e.problem))?;
 
+        let header_end = state.pos();
         let (header, defs) = module.item.upgrade_header_imports(arena);
 
         let defs = parse_module_defs(arena, state, defs)?;
@@ -35,6 +47,7 @@ impl<'a> Ast<'a> {
                 before: module.before,
                 item: header,
             },
+            header_end,
             defs,
             arena,
         })
@@ -52,10 +65,114 @@ impl<'a> Ast<'a> {
         FormattedAst::new(buf)
     }
 
+    pub fn defs(&self) -> &Defs<'a> {
+        &self.defs
+    }
+
+    /// Where the module header ends and the defs begin.
+    pub fn header_end(&self) -> Position {
+        self.header_end
+    }
+
+    /// Whether this is a module whose values other modules can import, as opposed to an app,
+    /// package, platform or hosted module.
+    pub fn is_module(&self) -> bool {
+        matches!(self.module.item, Header::Module(_))
+    }
+
+    /// The shorthands and locations of the packages listed in the header.
+    pub fn packages(&self) -> Vec<(&'a str, &'a str)> {
+        let entries = match &self.module.item {
+            Header::App(header) => &header.packages.value,
+            Header::Package(header) => &header.packages.value,
+            Header::Platform(header) => &header.packages.item,
+            Header::Module(_) | Header::Hosted(_) => return vec![],
+        };
+
+        entries
+            .iter()
+            .map(|entry| {
+                let PackageEntry {
+                    shorthand,
+                    package_name,
+                    ..
+                } = entry.value.item();
+                (*shorthand, package_name.value.to_str())
+            })
+            .collect()
+    }
+
+    /// The modules imported by this module, including those imported in its header.
+    pub fn imports(&self) -> Vec<Import<'a>> {
+        self.defs
+            .loc_defs()
+            .filter_map(|def| match def {
+                Err(Loc {
+                    region,
+                    value: ValueDef::ModuleImport(import),
+                }) => {
+                    let module_name = import.name.value.name.as_str();
+                    let qualifier = match &import.alias {
+                        Some(alias) => alias.item.value.as_str(),
+                        None => module_name,
+                    };
+
+                    Some(Import {
+                        package: import.name.value.package,
+                        module_name,
+                        qualifier,
+                        region,
+                        exposed: import
+                            .exposed
+                            .as_ref()
+                            .map(|exposed| exposed_names(&exposed.item)),
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn semantic_tokens(&self) -> impl IntoIterator<Item = Loc<Token>> + '_ {
         let header_tokens = self.module.item.iter_tokens(self.arena);
         let body_tokens = self.defs.iter_tokens(self.arena);
 
         header_tokens.into_iter().chain(body_tokens)
     }
+
+    /// The names listed in the module header's `exposes` (or `provides`) list.
+    pub fn header_exposes(&self) -> Vec<Loc<&'a str>> {
+        match &self.module.item {
+            Header::Module(header) => exposed_names(&header.exposes),
+            Header::Hosted(header) => exposed_names(&header.exposes),
+            Header::App(header) => exposed_names(&header.provides),
+            Header::Platform(header) => exposed_names(&header.provides.item),
+            Header::Package(_) => vec![],
+        }
+    }
+
+    /// The names listed in `import Module exposing [...]` statements, along with the name of the
+    /// module they are imported from.
+    pub fn import_exposes(&self) -> Vec<(&'a str, Loc<&'a str>)> {
+        self.imports()
+            .into_iter()
+            .flat_map(|import| {
+                let module_name = import.module_name;
+                import
+                    .exposed
+                    .into_iter()
+                    .flatten()
+                    .map(move |name| (module_name, name))
+            })
+            .collect()
+    }
+}
+
+fn exposed_names<'a>(
+    names: &Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>,
+) -> Vec<Loc<&'a str>> {
+    names
+        .iter()
+        .map(|name| Loc::at(name.region, (*name.value.item()).into()))
+        .collect()
 }
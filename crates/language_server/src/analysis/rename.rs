@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+
+use bumpalo::Bump;
+use roc_can::traverse::{find_declaration, find_references, find_tags, ReferenceKind};
+use roc_module::{
+    ident::TagName,
+    symbol::{ModuleId, Symbol},
+};
+use roc_parse::{ast::TypeDef, keyword};
+use roc_region::all::{Position, Region};
+use roc_types::subs::{Content, FlatType, Subs, Variable};
+
+use super::{
+    analysed_doc::QualifiedSymbol, parse_ast::Ast, tokens::Token, utils::region_str, AnalyzedModule,
+};
+
+/// What a rename at some position refers to.
+#[derive(Debug, Clone)]
+pub(crate) enum RenameTarget {
+    /// A value, type, alias, opaque type or ability, which canonicalization has resolved to a
+    /// symbol. `symbol` is only meaningful in the document the rename was requested from.
+    Symbol {
+        symbol: Symbol,
+        qualified: QualifiedSymbol,
+    },
+    /// A tag of the union declared by the type `owner`. Tags are structural, so which union a
+    /// tag belongs to is worked out from its type.
+    Tag {
+        name: String,
+        owner: Symbol,
+        qualified_owner: QualifiedSymbol,
+    },
+}
+
+impl RenameTarget {
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            RenameTarget::Symbol { qualified, .. } => qualified.ident(),
+            RenameTarget::Tag { name, .. } => name,
+        }
+    }
+}
+
+/// Finds the byte range of the identifier at `offset`, leaving out any module qualifier or the
+/// `@` of an opaque type.
+pub(super) fn ident_at(source: &str, offset: usize) -> Option<Region> {
+    let bytes = source.as_bytes();
+    let is_ident_byte = |b: &u8| b.is_ascii_alphanumeric() || *b == b'_';
+
+    let offset = offset.min(bytes.len());
+    let start = offset
+        - bytes[..offset]
+            .iter()
+            .rev()
+            .take_while(|b| is_ident_byte(b))
+            .count();
+    let mut end = offset
+        + bytes[offset..]
+            .iter()
+            .take_while(|b| is_ident_byte(b))
+            .count();
+
+    if bytes.get(end) == Some(&b'!') {
+        end += 1;
+    }
+
+    if start == end {
+        return None;
+    }
+
+    Some(byte_region(start, end))
+}
+
+/// Checks that `new_name` could be used in place of the name `target` refers to. Keywords are
+/// rejected, since using one as a name would stop the module from parsing.
+pub(crate) fn is_valid_new_name(target: &RenameTarget, new_name: &str) -> bool {
+    let is_uppercase = |name: &str| name.starts_with(|c: char| c.is_ascii_uppercase());
+    let is_lowercase = |name: &str| name.starts_with(|c: char| c.is_ascii_lowercase());
+
+    let first_is_valid = if is_uppercase(target.name()) {
+        is_uppercase(new_name)
+    } else {
+        is_lowercase(new_name)
+    };
+
+    let rest = new_name.strip_suffix('!').unwrap_or(new_name);
+    first_is_valid
+        && rest.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && keyword::is_allowed_identifier(new_name)
+}
+
+/// Finds the ability member whose declaration is at `position`.
+/// Ability members are not part of the declarations, so these have to be looked up separately.
+pub(super) fn ability_member_at(module: &AnalyzedModule, position: Position) -> Option<Symbol> {
+    module
+        .abilities
+        .root_ability_members()
+        .iter()
+        .find(|(member, data)| {
+            member.module_id() == module.module_id && data.region.contains_pos(position)
+        })
+        .map(|(member, _)| *member)
+}
+
+/// Whether the module `full_name` could be referred to as `name`, e.g. `pf.Stdout` as `Stdout`.
+fn module_name_matches(full_name: &str, name: &str) -> bool {
+    full_name == name || full_name.ends_with(&format!(".{name}"))
+}
+
+/// Finds every edit needed within one document to rename `symbol` from `old_name`.
+pub(super) fn value_edits(
+    source: &str,
+    module: &AnalyzedModule,
+    symbol: Symbol,
+    old_name: &str,
+    new_name: &str,
+) -> Vec<(Region, String)> {
+    let mut edits = vec![];
+
+    for found in find_references(symbol, &module.declarations, &module.abilities) {
+        match found.kind {
+            ReferenceKind::Declaration | ReferenceKind::Lookup => {
+                if let Some(region) = trailing_name_region(source, found.region, old_name) {
+                    edits.push((region, new_name.to_string()));
+                }
+            }
+            ReferenceKind::RecordField => {
+                // `{ old }` has to become `{ old: new }` to keep destructuring the same field.
+                if region_str(source, found.region) == Some(old_name) {
+                    edits.push((found.region, format!("{old_name}: {new_name}")));
+                }
+            }
+        }
+    }
+
+    let annotation_region = find_declaration(symbol, &module.declarations)
+        .and_then(|decl| decl.annotation().map(|annotation| annotation.region));
+    if let Some(region) = annotation_region.and_then(|r| annotation_name(source, r, old_name)) {
+        edits.push((region, new_name.to_string()));
+    }
+
+    let is_home_module = symbol.module_id() == module.module_id;
+
+    if is_home_module {
+        if let Some(member) = module.abilities.member_def(symbol) {
+            if let Some(region) = trailing_name_region(source, member.region, old_name) {
+                edits.push((region, new_name.to_string()));
+            }
+        }
+    }
+
+    let arena = Bump::new();
+    if let Ok(ast) = Ast::parse(&arena, source) {
+        let exposed = if is_home_module {
+            ast.header_exposes()
+        } else {
+            let home_name = module.interns.module_name(symbol.module_id()).as_str();
+            ast.import_exposes()
+                .into_iter()
+                .filter(|(module_name, _)| module_name_matches(home_name, module_name))
+                .map(|(_, name)| name)
+                .collect()
+        };
+
+        edits.extend(
+            exposed
+                .into_iter()
+                .filter(|name| name.value == old_name)
+                .filter_map(|name| trailing_name_region(source, name.region, old_name))
+                .map(|region| (region, new_name.to_string())),
+        );
+    }
+
+    edits
+}
+
+/// Finds every tag named `name` in one document, along with the type whose union it is part of,
+/// if that can be worked out.
+///
+/// Canonicalization doesn't keep the tags written in type annotations, so we go through the syntax
+/// tokens and match them up with the tags found in expressions and patterns. Tags in the body of
+/// a type definition belong to that type.
+pub(super) fn tag_occurrences(
+    source: &str,
+    module: &AnalyzedModule,
+    name: &str,
+) -> Vec<(Region, Option<Symbol>)> {
+    let arena = Bump::new();
+    let Ok(ast) = Ast::parse(&arena, source) else {
+        return vec![];
+    };
+
+    let found = find_tags(&module.declarations);
+    let aliases = alias_real_vars(&module.subs);
+
+    let type_def_bodies: Vec<(Region, Symbol)> = ast
+        .defs()
+        .loc_defs()
+        .filter_map(|def| {
+            let (name, body) = match def.ok()?.value {
+                TypeDef::Alias { header, ann } => (header.name.region, ann.region),
+                TypeDef::Opaque { header, typ, .. } => (header.name.region, typ.region),
+                TypeDef::Ability { .. } => return None,
+            };
+
+            module
+                .declarations
+                .type_references
+                .iter()
+                .find(|(loc_symbol, kind)| {
+                    *kind == ReferenceKind::Declaration && loc_symbol.region == name
+                })
+                .map(|(loc_symbol, _)| (body, loc_symbol.value))
+        })
+        .collect();
+
+    ast.semantic_tokens()
+        .into_iter()
+        .filter(|token| matches!(token.value, Token::Tag))
+        .filter_map(|token| trailing_name_region(source, token.region, name))
+        .map(|region| {
+            let owner = match found.iter().find(|tag| tag.region == region) {
+                Some(tag) => tag_owner(&module.subs, &aliases, tag.var, name).or(tag.opaque),
+                None => type_def_bodies
+                    .iter()
+                    .find(|(body, _)| body.contains(&region))
+                    .map(|(_, owner)| *owner),
+            };
+
+            (region, owner)
+        })
+        .collect()
+}
+
+/// Maps the variable behind every alias and opaque type in `subs` to the type's symbol.
+///
+/// Unifying a tag union with an alias unifies it with the alias's real type, so this is how we
+/// get back from the union to the alias.
+fn alias_real_vars(subs: &Subs) -> HashMap<Variable, Symbol> {
+    (0..subs.len() as u32)
+        // SAFETY: every index below `subs.len()` is a variable in `subs`
+        .map(|index| unsafe { Variable::from_index(index) })
+        .filter_map(|var| match subs.get_content_without_compacting(var) {
+            Content::Alias(symbol, _, real_var, _) => {
+                Some((subs.get_root_key_without_compacting(*real_var), *symbol))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finds the alias or opaque type which declares the tag `name` in the union `var`, e.g. `Result`
+/// for `Ok` in a `Result U64 Str`.
+fn tag_owner(
+    subs: &Subs,
+    aliases: &HashMap<Variable, Symbol>,
+    var: Variable,
+    name: &str,
+) -> Option<Symbol> {
+    let has_name = |tag: &TagName| tag.0.as_str() == name;
+
+    let mut var = var;
+
+    loop {
+        let owner = aliases
+            .get(&subs.get_root_key_without_compacting(var))
+            .copied();
+
+        match subs.get_content_without_compacting(var) {
+            Content::Alias(_, _, real_var, _) => var = *real_var,
+            Content::Structure(FlatType::Func(_, _, ret_var, _)) => var = *ret_var,
+            Content::Structure(
+                FlatType::TagUnion(tags, ext) | FlatType::RecursiveTagUnion(_, tags, ext),
+            ) => {
+                if tags.iter_from_subs(subs).any(|(tag, _)| has_name(tag)) {
+                    return owner;
+                }
+
+                var = ext.var();
+            }
+            Content::Structure(FlatType::FunctionOrTagUnion(tags, _, ext)) => {
+                if subs.get_subs_slice(*tags).iter().any(has_name) {
+                    return owner;
+                }
+
+                var = ext.var();
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Whether a document with `module` could name tags of a type declared in `home`.
+pub(super) fn can_see_module(module: &AnalyzedModule, home: ModuleId) -> bool {
+    module.module_id == home || module.imports_by_module.contains_key(&home)
+}
+
+/// Returns the region of `name` at the end of `region`, e.g. `bar` in `Foo.bar` or `Bar` in `@Bar`.
+fn trailing_name_region(source: &str, region: Region, name: &str) -> Option<Region> {
+    let text = region_str(source, region)?;
+    let prefix = text.strip_suffix(name)?;
+
+    if !(prefix.is_empty() || prefix.ends_with('.') || prefix.ends_with('@')) {
+        return None;
+    }
+
+    let end = region.end().offset as usize;
+    Some(byte_region(end - name.len(), end))
+}
+
+/// Given the region of a type annotation, finds the name in front of it, i.e. `foo` in `foo : Str`.
+fn annotation_name(source: &str, annotation: Region, name: &str) -> Option<Region> {
+    let before = source.get(..annotation.start().offset as usize)?.trim_end();
+    let before = before.strip_suffix(':')?.trim_end();
+    let prefix = before.strip_suffix(name)?;
+
+    if prefix
+        .chars()
+        .last()
+        .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return None;
+    }
+
+    Some(byte_region(prefix.len(), before.len()))
+}
+
+fn byte_region(start: usize, end: usize) -> Region {
+    Region::new(Position::new(start as u32), Position::new(end as u32))
+}
@@ -0,0 +1,179 @@
+use roc_can::{
+    expr::{Declarations, Expr},
+    traverse::{walk_expr, Visitor},
+};
+use roc_module::{
+    called_via::{BinOp, CalledVia},
+    symbol::Symbol,
+};
+use roc_region::all::{Position, Region};
+use roc_types::subs::Variable;
+
+/// A function application being written at some position.
+pub(super) struct FoundCall {
+    /// The type of the function being applied.
+    pub fn_var: Variable,
+    /// The name of the function, if it is applied by name.
+    pub symbol: Option<Symbol>,
+    /// The index of the argument being written.
+    pub active_parameter: usize,
+}
+
+/// Finds the function application the argument at `position` belongs to.
+///
+/// Right after typing a space following `f a`, the argument being written isn't in the source yet,
+/// so the application that ends right before the whitespace under the cursor also counts. In that
+/// case the next argument is the active one.
+pub(super) fn find_call_at(
+    source: &str,
+    position: Position,
+    decls: &Declarations,
+) -> Option<FoundCall> {
+    let offset = (position.offset as usize).min(source.len());
+    let trimmed = Position::new(source[..offset].trim_end().len() as u32);
+
+    let mut visitor = CallFinder {
+        position,
+        trimmed,
+        containing: None,
+        ending: None,
+    };
+    visitor.visit_decls(decls);
+
+    match (visitor.containing, visitor.ending) {
+        (Some((_, containing)), None) => Some(containing),
+        (None, Some((_, ending))) => Some(ending),
+        (Some((containing_region, containing)), Some((ending_region, ending))) => {
+            if containing_region.contains(&ending_region) {
+                Some(ending)
+            } else {
+                Some(containing)
+            }
+        }
+        (None, None) => None,
+    }
+}
+
+struct CallFinder {
+    position: Position,
+    trimmed: Position,
+    /// The innermost application containing the position.
+    containing: Option<(Region, FoundCall)>,
+    /// The outermost application or function ending right before the whitespace at the position.
+    ending: Option<(Region, FoundCall)>,
+}
+
+impl CallFinder {
+    fn ends_before_whitespace(&self, region: Region) -> bool {
+        self.trimmed < self.position && region.end() == self.trimmed && self.ending.is_none()
+    }
+}
+
+impl Visitor for CallFinder {
+    fn should_visit(&mut self, region: Region) -> bool {
+        region.contains_pos(self.position) || region.contains_pos(self.trimmed)
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        if !self.should_visit(region) {
+            return;
+        }
+
+        match expr {
+            Expr::Call(fn_info, args, called_via) if is_application(called_via) => {
+                let (fn_var, loc_fn, _, _, _) = &**fn_info;
+                let symbol = function_symbol(&loc_fn.value);
+
+                if self.ends_before_whitespace(region) {
+                    let found = FoundCall {
+                        fn_var: *fn_var,
+                        symbol,
+                        active_parameter: args.len(),
+                    };
+                    self.ending = Some((region, found));
+                }
+
+                if region.contains_pos(self.position) {
+                    let active_parameter = args
+                        .iter()
+                        .position(|(_, arg)| self.position <= arg.region.end())
+                        .unwrap_or(args.len());
+
+                    let found = FoundCall {
+                        fn_var: *fn_var,
+                        symbol,
+                        active_parameter,
+                    };
+                    self.containing = Some((region, found));
+                }
+            }
+            Expr::Var(..) | Expr::ParamsVar { .. } | Expr::AbilityMember(..)
+                if self.ends_before_whitespace(region) =>
+            {
+                let found = FoundCall {
+                    fn_var: var,
+                    symbol: function_symbol(expr),
+                    active_parameter: 0,
+                };
+                self.ending = Some((region, found));
+            }
+            _ => {}
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
+
+fn is_application(called_via: &CalledVia) -> bool {
+    matches!(
+        called_via,
+        CalledVia::Space | CalledVia::BinOp(BinOp::Pizza)
+    )
+}
+
+fn function_symbol(expr: &Expr) -> Option<Symbol> {
+    match *expr {
+        Expr::Var(symbol, _)
+        | Expr::ParamsVar { symbol, .. }
+        | Expr::AbilityMember(symbol, _, _) => Some(symbol),
+        _ => None,
+    }
+}
+
+/// Finds the byte ranges of the parameters within a printed function type such as
+/// `List a, (a -> b) -> List b`.
+pub(super) fn parameter_ranges(type_str: &str) -> Vec<(usize, usize)> {
+    let bytes = type_str.as_bytes();
+    let mut ranges = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (index, byte) in bytes.iter().enumerate() {
+        match byte {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            b',' if depth == 0 => {
+                push_param(&mut ranges, type_str, start, index);
+                start = index + 1;
+            }
+            b'-' | b'=' if depth == 0 && bytes.get(index + 1) == Some(&b'>') => {
+                push_param(&mut ranges, type_str, start, index);
+                return ranges;
+            }
+            _ => {}
+        }
+    }
+
+    // Not a function type after all
+    vec![]
+}
+
+fn push_param(ranges: &mut Vec<(usize, usize)>, type_str: &str, start: usize, end: usize) {
+    let param = &type_str[start..end];
+    let leading = param.len() - param.trim_start().len();
+    let trailing = param.len() - param.trim_end().len();
+
+    if leading + trailing < param.len() {
+        ranges.push((start + leading, end - trailing));
+    }
+}
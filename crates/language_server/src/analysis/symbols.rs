@@ -0,0 +1,139 @@
+use roc_parse::ast::{Defs, Expr, ExtractSpaces, Pattern, TypeAnnotation, TypeDef, ValueDef};
+use roc_region::all::{LineInfo, Loc, Region};
+use tower_lsp::lsp_types::{DocumentSymbol, SymbolKind};
+
+use crate::convert::ToRange;
+
+use super::utils::region_str;
+
+/// The outline of a module: its top-level defs, type aliases, opaque types and abilities, with
+/// the members of each ability nested inside it.
+#[allow(deprecated)] // DocumentSymbol::deprecated
+pub(super) fn document_symbols(
+    defs: &Defs,
+    source: &str,
+    line_info: &LineInfo,
+) -> Vec<DocumentSymbol> {
+    let symbol = |name: &str, kind, detail: Option<Region>, range: Region, selection: Region| {
+        DocumentSymbol {
+            name: name.to_string(),
+            detail: detail
+                .and_then(|region| region_str(source, region))
+                .map(String::from),
+            kind,
+            tags: None,
+            deprecated: None,
+            range: range.to_range(line_info),
+            selection_range: selection.to_range(line_info),
+            children: None,
+        }
+    };
+
+    defs.loc_defs()
+        .filter_map(|def| match def {
+            Ok(Loc {
+                region,
+                value: type_def,
+            }) => match type_def {
+                TypeDef::Alias { header, ann } => Some(symbol(
+                    header.name.value,
+                    type_def_kind(&type_def),
+                    Some(ann.region),
+                    region,
+                    header.name.region,
+                )),
+                TypeDef::Opaque { header, typ, .. } => Some(symbol(
+                    header.name.value,
+                    type_def_kind(&type_def),
+                    Some(typ.region),
+                    region,
+                    header.name.region,
+                )),
+                TypeDef::Ability {
+                    header, members, ..
+                } => {
+                    let members = members
+                        .iter()
+                        .map(|member| {
+                            symbol(
+                                member.name.value.item(),
+                                SymbolKind::METHOD,
+                                Some(member.typ.region),
+                                member.region(),
+                                member.name.region,
+                            )
+                        })
+                        .collect();
+
+                    Some(DocumentSymbol {
+                        children: Some(members),
+                        ..symbol(
+                            header.name.value,
+                            type_def_kind(&type_def),
+                            None,
+                            region,
+                            header.name.region,
+                        )
+                    })
+                }
+            },
+            Err(Loc {
+                region,
+                value: value_def,
+            }) => {
+                let (pattern, body, annotation) = match value_def {
+                    ValueDef::Body(pattern, body) => (pattern, body, None),
+                    ValueDef::AnnotatedBody {
+                        ann_type,
+                        body_pattern,
+                        body_expr,
+                        ..
+                    } => (body_pattern, body_expr, Some(ann_type.region)),
+                    _ => return None,
+                };
+
+                let Pattern::Identifier { ident } = pattern.value.without_spaces() else {
+                    return None;
+                };
+
+                let kind = match body.value.without_spaces() {
+                    Expr::Closure(..) => SymbolKind::FUNCTION,
+                    _ => SymbolKind::CONSTANT,
+                };
+
+                Some(symbol(ident, kind, annotation, region, pattern.region))
+            }
+        })
+        .collect()
+}
+
+/// The kind of symbol a type definition is shown as: records and tuples are structs, tag unions
+/// enums, abilities interfaces, and any other alias or opaque type a class.
+pub(super) fn type_def_kind(type_def: &TypeDef) -> SymbolKind {
+    let ann = match type_def {
+        TypeDef::Alias { ann, .. } => &ann.value,
+        TypeDef::Opaque { typ, .. } => &typ.value,
+        TypeDef::Ability { .. } => return SymbolKind::INTERFACE,
+    };
+
+    let mut ann = ann;
+    while let TypeAnnotation::SpaceBefore(inner, _) | TypeAnnotation::SpaceAfter(inner, _) = ann {
+        ann = inner;
+    }
+
+    match ann {
+        TypeAnnotation::Record { .. } | TypeAnnotation::Tuple { .. } => SymbolKind::STRUCT,
+        TypeAnnotation::TagUnion { .. } => SymbolKind::ENUM,
+        _ => SymbolKind::CLASS,
+    }
+}
+
+/// Whether every character of `query` appears in `name`, in order and ignoring case.
+pub(super) fn fuzzy_matches(query: &str, name: &str) -> bool {
+    let mut name_chars = name.chars().flat_map(char::to_lowercase);
+
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|query_char| name_chars.any(|name_char| name_char == query_char))
+}
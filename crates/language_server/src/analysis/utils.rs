@@ -1,4 +1,5 @@
 use roc_module::symbol::{Interns, ModuleId};
+use roc_region::all::Region;
 use roc_types::subs::{Subs, Variable};
 
 pub(super) fn format_var_type(
@@ -22,3 +23,8 @@ pub(super) fn format_var_type(
 pub(super) fn is_roc_identifier_char(char: &char) -> bool {
     matches!(char,'a'..='z'|'A'..='Z'|'0'..='9'|'.')
 }
+
+/// The text of `source` within `region`.
+pub(super) fn region_str(source: &str, region: Region) -> Option<&str> {
+    source.get(region.start().offset as usize..region.end().offset as usize)
+}
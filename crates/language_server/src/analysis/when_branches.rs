@@ -0,0 +1,206 @@
+use roc_can::{
+    exhaustive::{GUARD_CTOR, NONEXHAUSIVE_CTOR},
+    expr::{Declarations, Expr},
+    traverse::{walk_expr, Visitor},
+};
+use roc_exhaustive::{CtorName, ListArity, Pattern, RenderAs};
+use roc_module::{ident::TagName, symbol::Interns};
+use roc_region::all::{Position, Region};
+use roc_types::subs::Variable;
+
+/// The body of branches inserted by the "fill in" code action.
+const TODO_BODY: &str = "crash \"todo\"";
+
+/// Where a branch of a `when` expression is.
+struct Branch {
+    /// The alternatives matched by the branch, e.g. both `A` and `B` in `A | B -> ...`.
+    patterns: Vec<Region>,
+    value: Region,
+}
+
+/// Finds the text to insert after the last branch of the `when` expression whose patterns are
+/// checked at `when_region`, so that the `missing` patterns are matched too.
+pub(super) fn missing_branches_edit(
+    decls: &Declarations,
+    source: &str,
+    interns: &Interns,
+    when_region: Region,
+    missing: &[Pattern],
+) -> Option<(Position, String)> {
+    let branches = find_when_branches(decls, when_region)?;
+    let last = branches.last()?;
+    let first_pattern = last.patterns.first()?;
+
+    // Line the new branches up with the last one
+    let start = first_pattern.start().offset as usize;
+    let line_start = source.get(..start)?.rfind('\n').map_or(0, |i| i + 1);
+    let indent = " ".repeat(start - line_start);
+
+    let new_text: String = missing
+        .iter()
+        .map(|pattern| {
+            let pattern = pattern_to_string(pattern, interns, false);
+            format!("\n{indent}{pattern} -> {TODO_BODY}")
+        })
+        .collect();
+
+    Some((last.value.end(), new_text))
+}
+
+/// Finds the lines spanned by the branch of the `when` expression at `when_region` whose pattern
+/// is at `pattern_region`. Branches matching more than one pattern are left alone, since the other
+/// patterns may not be redundant.
+pub(super) fn redundant_branch_region(
+    decls: &Declarations,
+    source: &str,
+    when_region: Region,
+    pattern_region: Region,
+) -> Option<Region> {
+    let branches = find_when_branches(decls, when_region)?;
+    let branch = branches
+        .iter()
+        .find(|branch| branch.patterns.contains(&pattern_region))?;
+
+    if branch.patterns.len() != 1 {
+        return None;
+    }
+
+    let start = branch.patterns[0].start().offset as usize;
+    let end = branch.value.end().offset as usize;
+
+    let line_start = source.get(..start)?.rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source
+        .get(end..)?
+        .find('\n')
+        .map_or(source.len(), |i| end + i + 1);
+
+    Some(Region::new(
+        Position::new(line_start as u32),
+        Position::new(line_end as u32),
+    ))
+}
+
+/// Finds the branches of the `when` expression whose patterns are checked at `when_region`.
+fn find_when_branches(decls: &Declarations, when_region: Region) -> Option<Vec<Branch>> {
+    let mut visitor = WhenFinder {
+        when_region,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct WhenFinder {
+        when_region: Region,
+        found: Option<Vec<Branch>>,
+    }
+
+    impl Visitor for WhenFinder {
+        fn should_visit(&mut self, region: Region) -> bool {
+            region.contains(&self.when_region)
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if !self.should_visit(region) {
+                return;
+            }
+
+            match expr {
+                Expr::When {
+                    loc_cond, branches, ..
+                } if branches.last().map(|last| {
+                    // The region the patterns are checked at, see `roc_constrain`
+                    Region::span_across(&loc_cond.region, &last.value.region)
+                }) == Some(self.when_region) =>
+                {
+                    let branches = branches
+                        .iter()
+                        .map(|branch| Branch {
+                            patterns: branch
+                                .patterns
+                                .iter()
+                                .map(|pattern| pattern.pattern.region)
+                                .collect(),
+                            value: branch.value.region,
+                        })
+                        .collect();
+
+                    self.found = Some(branches);
+                }
+                _ => walk_expr(self, expr, var),
+            }
+        }
+    }
+}
+
+/// Writes a pattern the exhaustiveness checker found to be missing as Roc source. Whatever can't
+/// be written out, like the remaining numbers of a numeric range, becomes `_`.
+fn pattern_to_string(pattern: &Pattern, interns: &Interns, in_type_param: bool) -> String {
+    match pattern {
+        Pattern::Anything => "_".to_string(),
+        Pattern::Literal(_) => "_".to_string(),
+        Pattern::List(arity, patterns) => {
+            let mut elems: Vec<_> = patterns
+                .iter()
+                .map(|pattern| pattern_to_string(pattern, interns, false))
+                .collect();
+
+            if let ListArity::Slice(before, _) = arity {
+                elems.insert(*before, "..".to_string());
+            }
+
+            format!("[{}]", elems.join(", "))
+        }
+        Pattern::Ctor(union, tag_id, args) => match &union.render_as {
+            // #Guard <fake-condition-tag> <unexhausted-pattern>
+            RenderAs::Guard => pattern_to_string(&args[1], interns, in_type_param),
+            RenderAs::Record(fields) => {
+                let fields: Vec<_> = fields
+                    .iter()
+                    .zip(args)
+                    .map(|(field, arg)| match arg {
+                        Pattern::Anything => field.to_string(),
+                        _ => format!("{field}: {}", pattern_to_string(arg, interns, false)),
+                    })
+                    .collect();
+
+                format!("{{ {} }}", fields.join(", "))
+            }
+            RenderAs::Tuple => {
+                let elems: Vec<_> = args
+                    .iter()
+                    .map(|arg| pattern_to_string(arg, interns, false))
+                    .collect();
+
+                format!("({})", elems.join(", "))
+            }
+            RenderAs::Tag | RenderAs::Opaque => {
+                let name = match &union.alternatives[tag_id.0 as usize].name {
+                    CtorName::Tag(TagName(name)) if name.as_str() == NONEXHAUSIVE_CTOR => {
+                        return "_".to_string();
+                    }
+                    CtorName::Tag(TagName(name)) => {
+                        debug_assert_ne!(name.as_str(), GUARD_CTOR);
+                        name.as_str().to_string()
+                    }
+                    CtorName::Opaque(symbol) => format!("@{}", symbol.as_str(interns)),
+                };
+
+                if args.is_empty() {
+                    return name;
+                }
+
+                let args: Vec<_> = args
+                    .iter()
+                    .map(|arg| pattern_to_string(arg, interns, true))
+                    .collect();
+                let applied = format!("{name} {}", args.join(" "));
+
+                if in_type_param {
+                    format!("({applied})")
+                } else {
+                    applied
+                }
+            }
+        },
+    }
+}
@@ -77,7 +77,7 @@ pub(crate) mod diag {
 
     use roc_problem::Severity;
     use roc_reporting::report::RocDocAllocator;
-    use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+    use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
 
     use super::ToRange;
 
@@ -205,13 +205,16 @@ pub(crate) mod diag {
             );
 
             let severity = report.severity.into_lsp_severity();
+            let code = report
+                .code()
+                .map(|code| NumberOrString::String(code.to_string()));
             let mut msg = String::new();
             report.render_language_server(&mut msg, fmt.alloc);
 
             Some(Diagnostic {
                 range,
                 severity: Some(severity),
-                code: None,
+                code,
                 code_description: None,
                 source: None,
                 message: msg,
@@ -242,6 +245,9 @@ pub(crate) mod diag {
             )?;
 
             let severity = report.severity.into_lsp_severity();
+            let code = report
+                .code()
+                .map(|code| NumberOrString::String(code.to_string()));
 
             let mut msg = String::new();
             report.render_language_server(&mut msg, fmt.alloc);
@@ -249,7 +255,7 @@ pub(crate) mod diag {
             Some(Diagnostic {
                 range,
                 severity: Some(severity),
-                code: None,
+                code,
                 code_description: None,
                 source: None,
                 message: msg,
@@ -10,10 +10,12 @@ use tokio::sync::{Mutex, MutexGuard};
 
 use tower_lsp::lsp_types::{
     CodeActionOrCommand, CodeActionResponse, CompletionResponse, Diagnostic,
-    GotoDefinitionResponse, Hover, Position, Range, SemanticTokensResult, TextEdit, Url,
+    DocumentSymbolResponse, FoldingRange, GotoDefinitionResponse, Hover, InlayHint, Location,
+    Position, PrepareRenameResponse, Range, SemanticTokensResult, SignatureHelp, SymbolInformation,
+    TextEdit, Url, WorkspaceEdit,
 };
 
-use crate::analysis::{AnalyzedDocument, DocInfo};
+use crate::analysis::{is_valid_new_name, AnalyzedDocument, DocInfo, ExposedValue};
 
 #[derive(Debug)]
 pub(crate) struct DocumentPair {
@@ -33,17 +35,28 @@ impl DocumentPair {
             last_good_document,
         }
     }
+
+    /// The most recent type checked version of the document.
+    fn analyzed_document(&self) -> &Arc<AnalyzedDocument> {
+        match self.latest_document.get() {
+            Some(latest) if latest.type_checked() => latest,
+            _ => &self.last_good_document,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct RegistryConfig {
     pub(crate) latest_document_timeout: Duration,
+    /// Whether inlay hints are only shown for top-level defs.
+    pub(crate) inlay_hints_top_level_only: bool,
 }
 
 impl Default for RegistryConfig {
     fn default() -> Self {
         Self {
             latest_document_timeout: Duration::from_millis(5000),
+            inlay_hints_top_level_only: false,
         }
     }
 }
@@ -164,6 +177,56 @@ impl Registry {
         .ok()
     }
 
+    /// Gets the most recent type checked version of every document we know about.
+    async fn all_analyzed_documents(&self) -> Vec<Arc<AnalyzedDocument>> {
+        self.documents
+            .lock()
+            .await
+            .values()
+            .map(|pair| pair.analyzed_document().clone())
+            .collect()
+    }
+
+    /// Gets the values exposed by every module we know about, except the one at `url`.
+    fn exposed_values<'a>(
+        documents: impl IntoIterator<Item = &'a Arc<AnalyzedDocument>>,
+        url: &Url,
+    ) -> Vec<ExposedValue> {
+        documents
+            .into_iter()
+            .filter(|document| document.url() != url)
+            .flat_map(|document| document.exposed_values().iter().cloned())
+            .collect()
+    }
+
+    /// Gets the documents which import the one at `url`, directly or not. Documents imported by
+    /// another one of these are left out, since analysing that one rechecks them too.
+    pub async fn dependents(&self, url: &Url) -> Vec<DocInfo> {
+        let documents = self.documents.lock().await;
+
+        let dependents: Vec<_> = documents
+            .values()
+            .filter_map(|pair| {
+                let document = pair.analyzed_document();
+
+                document.depends_on(url).then_some((&pair.info, document))
+            })
+            .collect();
+
+        let mut top_level: Vec<_> = dependents
+            .iter()
+            .filter(|(info, _)| {
+                !dependents
+                    .iter()
+                    .any(|(_, other)| other.depends_on(&info.url))
+            })
+            .map(|(info, _)| (*info).clone())
+            .collect();
+        top_level.sort_by(|a, b| a.url.as_str().cmp(b.url.as_str()));
+
+        top_level
+    }
+
     pub async fn diagnostics(&self, url: &Url) -> Vec<Diagnostic> {
         let Some(document) = self.latest_document_by_url(url).await else {
             return vec![];
@@ -175,6 +238,18 @@ impl Registry {
         self.latest_document_by_url(url).await?.hover(position)
     }
 
+    pub async fn signature_help(&self, url: &Url, position: Position) -> Option<SignatureHelp> {
+        self.latest_document_by_url(url)
+            .await?
+            .signature_help(position)
+    }
+
+    pub async fn inlay_hints(&self, url: &Url, range: Range) -> Option<Vec<InlayHint>> {
+        self.latest_document_by_url(url)
+            .await?
+            .inlay_hints(range, self.config.inlay_hints_top_level_only)
+    }
+
     pub async fn goto_definition(
         &self,
         url: &Url,
@@ -187,11 +262,128 @@ impl Registry {
         def_document.definition(symbol)
     }
 
+    pub async fn references(
+        &self,
+        url: &Url,
+        position: Position,
+        include_declaration: bool,
+    ) -> Option<Vec<Location>> {
+        let document = self.latest_document_by_url(url).await?;
+        let symbol = document
+            .type_symbol_at(position)
+            .or_else(|| document.symbol_at(position));
+
+        let mut locations = vec![];
+        match symbol {
+            Some(symbol) => {
+                let qualified = document.qualify_symbol(symbol)?;
+
+                for other in self.all_analyzed_documents().await {
+                    if let Some(other_symbol) = other.matching_symbol(url, symbol, &qualified) {
+                        locations.extend(other.references(other_symbol, include_declaration));
+                    }
+                }
+            }
+            None => {
+                let field = document.field_at(position)?;
+
+                for other in self.all_analyzed_documents().await {
+                    locations.extend(other.field_references(&field, include_declaration));
+                }
+            }
+        }
+
+        locations.sort_by(|a, b| {
+            (a.uri.as_str(), a.range.start, a.range.end).cmp(&(
+                b.uri.as_str(),
+                b.range.start,
+                b.range.end,
+            ))
+        });
+
+        Some(locations)
+    }
+
+    pub async fn prepare_rename(
+        &self,
+        url: &Url,
+        position: Position,
+    ) -> Option<PrepareRenameResponse> {
+        let document = self.latest_document_by_url(url).await?;
+        let (target, range) = document.rename_target(position)?;
+
+        Some(PrepareRenameResponse::RangeWithPlaceholder {
+            range,
+            placeholder: target.name().to_string(),
+        })
+    }
+
+    /// Renames whatever is at `position` in every document we know about.
+    /// Returns an error if `new_name` can't be used for the thing being renamed, or if some of the
+    /// places it is named can't be told apart from unrelated ones.
+    pub async fn rename(
+        &self,
+        url: &Url,
+        position: Position,
+        new_name: &str,
+    ) -> Result<Option<WorkspaceEdit>, String> {
+        let Some(document) = self.latest_document_by_url(url).await else {
+            return Ok(None);
+        };
+        let Some((target, _range)) = document.rename_target(position) else {
+            return Ok(None);
+        };
+
+        if !is_valid_new_name(&target, new_name) {
+            return Err(format!(
+                "`{new_name}` is not a valid new name for `{}`",
+                target.name()
+            ));
+        }
+
+        let mut changes = HashMap::new();
+
+        for other in self.all_analyzed_documents().await {
+            let edits = other.rename_edits(url, &target, new_name)?;
+
+            if !edits.is_empty() {
+                changes.insert(other.url().clone(), edits);
+            }
+        }
+
+        Ok(Some(WorkspaceEdit::new(changes)))
+    }
+
     pub async fn formatting(&self, url: &Url) -> Option<Vec<TextEdit>> {
         let document = self.document_info_by_url(url).await?;
         document.format()
     }
 
+    pub async fn document_symbols(&self, url: &Url) -> Option<DocumentSymbolResponse> {
+        let document = self.document_info_by_url(url).await?;
+        document.document_symbols()
+    }
+
+    /// Searches the values and types exposed by every module we know about.
+    pub async fn workspace_symbols(&self, query: &str) -> Vec<SymbolInformation> {
+        let mut symbols: Vec<_> = self
+            .all_analyzed_documents()
+            .await
+            .iter()
+            .flat_map(|document| document.workspace_symbols(query))
+            .collect();
+
+        symbols.sort_by(|a, b| {
+            (&a.name, a.location.uri.as_str()).cmp(&(&b.name, b.location.uri.as_str()))
+        });
+
+        symbols
+    }
+
+    pub async fn folding_ranges(&self, url: &Url) -> Option<Vec<FoldingRange>> {
+        self.latest_document_by_url(url).await?.folding_ranges()
+    }
+
     pub async fn semantic_tokens(&self, url: &Url) -> Option<SemanticTokensResult> {
         let document = self.document_info_by_url(url).await?;
         document.semantic_tokens()
@@ -211,9 +403,11 @@ impl Registry {
             latest_doc_info.version
         );
 
-        let completions = pair
-            .last_good_document
-            .completion_items(position, latest_doc_info)?;
+        let documents = lock.values().map(DocumentPair::analyzed_document);
+        let exposed = Self::exposed_values(documents, url);
+        let completions =
+            pair.last_good_document
+                .completion_items(position, latest_doc_info, &exposed)?;
 
         Some(CompletionResponse::Array(completions))
     }
@@ -225,6 +419,20 @@ impl Registry {
         if let Some(edit) = document.annotate(range) {
             responses.push(CodeActionOrCommand::CodeAction(edit));
         }
+        responses.extend(
+            document
+                .when_branch_fixes(range)
+                .into_iter()
+                .map(CodeActionOrCommand::CodeAction),
+        );
+
+        let exposed = Self::exposed_values(&self.all_analyzed_documents().await, url);
+        responses.extend(
+            document
+                .auto_import_fixes(range, &exposed)
+                .into_iter()
+                .map(CodeActionOrCommand::CodeAction),
+        );
         Some(responses)
     }
 }
@@ -2,6 +2,7 @@ use analysis::HIGHLIGHT_TOKENS_LEGEND;
 
 use log::{debug, trace};
 use registry::{Registry, RegistryConfig};
+use roc_load::SharedIncrementalCache;
 use std::future::Future;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::time::Duration;
@@ -10,7 +11,7 @@ use tower_lsp::jsonrpc::{self, Result};
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
-use crate::analysis::{global_analysis, DocInfo};
+use crate::analysis::{global_analysis, AnalyzedDocument, DocInfo};
 
 mod analysis;
 mod convert;
@@ -37,6 +38,8 @@ impl Default for RocServerConfig {
 struct RocServerState {
     registry: Registry,
     config: RocServerConfig,
+    /// Solved types of every module analysed so far, so unchanged modules aren't solved again.
+    incremental_cache: SharedIncrementalCache,
 }
 
 impl std::panic::RefUnwindSafe for RocServer {}
@@ -47,12 +50,20 @@ fn read_env_num(name: &str) -> Option<u64> {
         .and_then(|a| str::parse::<u64>(&a).ok())
 }
 
+fn read_env_bool(name: &str) -> Option<bool> {
+    std::env::var(name)
+        .ok()
+        .and_then(|a| str::parse::<bool>(&a).ok())
+}
+
 impl RocServer {
     pub fn new(client: Client) -> Self {
         let registry_config = RegistryConfig {
             latest_document_timeout: Duration::from_millis(
                 read_env_num("ROCLS_LATEST_DOC_TIMEOUT_MS").unwrap_or(5000),
             ),
+            inlay_hints_top_level_only: read_env_bool("ROCLS_INLAY_HINTS_TOP_LEVEL_ONLY")
+                .unwrap_or(false),
         };
         let config = RocServerConfig {
             debounce_ms: Duration::from_millis(read_env_num("ROCLS_DEBOUNCE_MS").unwrap_or(100)),
@@ -69,6 +80,7 @@ impl RocServer {
             TextDocumentSyncOptions {
                 open_close: Some(true),
                 change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
                 ..TextDocumentSyncOptions::default()
             },
         );
@@ -104,6 +116,20 @@ impl RocServer {
             },
         };
         let code_action_provider = CodeActionProviderCapability::Simple(true);
+        let references_provider = OneOf::Left(true);
+        let rename_provider = RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        };
+        let signature_help_provider = SignatureHelpOptions {
+            trigger_characters: Some(vec![" ".to_string()]),
+            retrigger_characters: None,
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        };
         ServerCapabilities {
             text_document_sync: Some(text_document_sync),
             hover_provider: Some(hover_provider),
@@ -112,6 +138,13 @@ impl RocServer {
             semantic_tokens_provider: Some(semantic_tokens_provider),
             completion_provider: Some(completion_provider),
             code_action_provider: Some(code_action_provider),
+            references_provider: Some(references_provider),
+            rename_provider: Some(OneOf::Right(rename_provider)),
+            signature_help_provider: Some(signature_help_provider),
+            inlay_hint_provider: Some(OneOf::Left(true)),
+            document_symbol_provider: Some(OneOf::Left(true)),
+            workspace_symbol_provider: Some(OneOf::Left(true)),
+            folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
             ..ServerCapabilities::default()
         }
     }
@@ -134,11 +167,27 @@ impl RocServer {
             .publish_diagnostics(fi, diagnostics, Some(version))
             .await;
     }
+
+    /// Records a document being saved, which affects the documents importing it.
+    async fn save(&self, fi: Url) {
+        for url in self.state.recheck_dependents(&fi).await {
+            let version = self.state.registry.get_latest_version(&url).await;
+            let diagnostics = self.state.registry.diagnostics(&url).await;
+
+            self.client
+                .publish_diagnostics(url, diagnostics, version)
+                .await;
+        }
+    }
 }
 
 impl RocServerState {
     pub fn new(config: RocServerConfig, registry: Registry) -> RocServerState {
-        Self { config, registry }
+        Self {
+            config,
+            registry,
+            incremental_cache: Default::default(),
+        }
     }
 
     async fn close(&self, _fi: Url) {}
@@ -175,25 +224,7 @@ impl RocServerState {
                 return Err("Not latest version skipping analysis".to_string());
             }
 
-            let results = match tokio::time::timeout(
-                Duration::from_secs(60),
-                tokio::task::spawn_blocking(|| catch_unwind(|| global_analysis(doc_info))),
-            )
-            .await
-            {
-                Err(e) => {
-                    return Err(format!(
-                        "Document analysis thread timeout out after: {:?}",
-                        e
-                    ))
-                }
-                Ok(Err(e)) => {
-                    return Err(format!("Document analysis thread failed. reason:{:?}", e))
-                }
-                Ok(Ok(res)) => {
-                    res.map_err(|err| format!("Document analysis panicked with: {:?}", err))?
-                }
-            };
+            let results = inner_ref.analyse(doc_info).await?;
             let latest_version = inner_ref.registry.get_latest_version(fi).await;
 
             //if this version is not the latest another change must have come in and this analysis is useless
@@ -218,6 +249,55 @@ impl RocServerState {
         debug!("V{:?}:finished document change process", version);
         updating_result
     }
+
+    /// Analyses the documents which import `fi` again. Imported modules are read from disk, so
+    /// this is done once `fi` is saved. Returns the urls of the documents which were analysed.
+    pub async fn recheck_dependents(&self, fi: &Url) -> Vec<Url> {
+        let mut rechecked = vec![];
+
+        for doc_info in self.registry.dependents(fi).await {
+            let url = doc_info.url.clone();
+
+            match self.analyse(doc_info).await {
+                Ok(mut results) => {
+                    // `fi` has just been analysed on its own, with the latest version of its source.
+                    results.retain(|document| document.url() != fi);
+                    self.registry.apply_changes(results, url.clone()).await;
+                    rechecked.push(url);
+                }
+                Err(e) => debug!("Failed to recheck dependent {:?}. Reason:{:?}", url, e),
+            }
+        }
+
+        rechecked
+    }
+
+    async fn analyse(
+        &self,
+        doc_info: DocInfo,
+    ) -> std::result::Result<Vec<AnalyzedDocument>, String> {
+        let incremental_cache = self.incremental_cache.clone();
+
+        match tokio::time::timeout(
+            Duration::from_secs(60),
+            tokio::task::spawn_blocking(|| {
+                // The cache is only ever updated one module at a time, so it stays usable even if
+                // the analysis panics halfway through.
+                catch_unwind(AssertUnwindSafe(|| {
+                    global_analysis(doc_info, incremental_cache)
+                }))
+            }),
+        )
+        .await
+        {
+            Err(e) => Err(format!(
+                "Document analysis thread timeout out after: {:?}",
+                e
+            )),
+            Ok(Err(e)) => Err(format!("Document analysis thread failed. reason:{:?}", e)),
+            Ok(Ok(res)) => res.map_err(|err| format!("Document analysis panicked with: {:?}", err)),
+        }
+    }
 }
 
 #[tower_lsp::async_trait]
@@ -262,6 +342,15 @@ impl LanguageServer for RocServer {
         }
     }
 
+    async fn did_save(&self, params: DidSaveTextDocumentParams) {
+        let TextDocumentIdentifier { uri } = params.text_document;
+
+        let _res = unwind_async(self.save(uri)).await;
+        if let Err(e) = _res {
+            self.client.log_message(MessageType::ERROR, e.message).await
+        }
+    }
+
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
         let TextDocumentIdentifier { uri } = params.text_document;
         self.state.close(uri).await;
@@ -284,6 +373,35 @@ impl LanguageServer for RocServer {
         unwind_async(self.state.registry.hover(&text_document.uri, position)).await
     }
 
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let SignatureHelpParams {
+            context: _,
+            text_document_position_params:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            work_done_progress_params: _,
+        } = params;
+
+        unwind_async(
+            self.state
+                .registry
+                .signature_help(&text_document.uri, position),
+        )
+        .await
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        let InlayHintParams {
+            text_document,
+            range,
+            work_done_progress_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.inlay_hints(&text_document.uri, range)).await
+    }
+
     async fn goto_definition(
         &self,
         params: GotoDefinitionParams,
@@ -306,6 +424,65 @@ impl LanguageServer for RocServer {
         .await
     }
 
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let ReferenceParams {
+            text_document_position:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            context: ReferenceContext {
+                include_declaration,
+            },
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.references(
+            &text_document.uri,
+            position,
+            include_declaration,
+        ))
+        .await
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params;
+
+        unwind_async(
+            self.state
+                .registry
+                .prepare_rename(&text_document.uri, position),
+        )
+        .await
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let RenameParams {
+            text_document_position:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            new_name,
+            work_done_progress_params: _,
+        } = params;
+
+        unwind_async(
+            self.state
+                .registry
+                .rename(&text_document.uri, position, &new_name),
+        )
+        .await?
+        .map_err(jsonrpc::Error::invalid_params)
+    }
+
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
         let DocumentFormattingParams {
             text_document,
@@ -316,6 +493,42 @@ impl LanguageServer for RocServer {
         unwind_async(self.state.registry.formatting(&text_document.uri)).await
     }
 
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let DocumentSymbolParams {
+            text_document,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.document_symbols(&text_document.uri)).await
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let WorkspaceSymbolParams {
+            query,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(async { Some(self.state.registry.workspace_symbols(&query).await) }).await
+    }
+
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        let FoldingRangeParams {
+            text_document,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.folding_ranges(&text_document.uri)).await
+    }
+
     async fn semantic_tokens_full(
         &self,
         params: SemanticTokensParams,
@@ -386,6 +599,7 @@ async fn main() {
 
 #[cfg(test)]
 mod tests {
+    use roc_load::IncrementalStats;
     use std::sync::Once;
 
     use expect_test::expect;
@@ -432,6 +646,10 @@ mod tests {
     static INIT: Once = Once::new();
 
     async fn test_setup(doc: String) -> (RocServerState, Url) {
+        test_setup_with_registry(doc, Registry::default()).await
+    }
+
+    async fn test_setup_with_registry(doc: String, registry: Registry) -> (RocServerState, Url) {
         INIT.call_once(|| {
             env_logger::builder()
                 .is_test(true)
@@ -441,7 +659,7 @@ mod tests {
         info!("Doc is:\n{0}", doc);
         let url = Url::parse("file:/Test.roc").unwrap();
 
-        let inner = RocServerState::new(RocServerConfig::default(), Registry::default());
+        let inner = RocServerState::new(RocServerConfig::default(), registry);
         // setup the file
         inner.change(&url, doc, 0).await.unwrap();
         (inner, url)
@@ -659,146 +877,1501 @@ mod tests {
         .assert_debug_eq(&actual);
     }
 
-    async fn code_action_edits(doc: String, position: Position, name: &str) -> Vec<TextEdit> {
-        let (inner, url) = test_setup(doc.clone()).await;
-        let registry = &inner.registry;
-
-        let actions = registry
-            .code_actions(&url, Range::new(position, position))
-            .await
-            .unwrap();
+    /// Returns the labels of the completions at `position` in `doc`, without changing it first.
+    async fn completion_labels(doc: &str, position: Position) -> Option<Vec<String>> {
+        let (inner, url) = test_setup(DOC_LIT.to_string() + doc).await;
 
-        actions
-            .into_iter()
-            .find_map(|either| match either {
-                CodeActionOrCommand::CodeAction(action) if name == action.title => Some(action),
-                _ => None,
-            })
-            .expect("Code action not present")
-            .edit
-            .expect("Code action does not have an associated edit")
-            .changes
-            .expect("Edit does not have any changes")
-            .get(&url)
-            .expect("Edit does not have changes for this file")
-            .clone()
+        comp_labels(get_basic_completion_info(&inner.registry, &url, position).await)
     }
 
     #[tokio::test]
-    async fn test_annotate_single() {
-        let edit = code_action_edits(
-            DOC_LIT.to_string() + r#"main = "Hello, world!""#,
-            Position::new(3, 2),
-            "Add signature",
+    async fn test_completion_expected_tags() {
+        let actual = completion_test_labels(
+            indoc! {r"
+            Color : [Red, Green, Grey]
+
+            shape : [Gon, Square]
+            shape = Square
+
+            color : Color
+            color =
+              "},
+            "G",
+            Position::new(10, 3),
         )
         .await;
 
         expect![[r#"
-            [
-                TextEdit {
-                    range: Range {
-                        start: Position {
-                            line: 3,
-                            character: 0,
-                        },
-                        end: Position {
-                            line: 3,
-                            character: 0,
-                        },
-                    },
-                    new_text: "main : Str\n",
-                },
-            ]
+            Some(
+                [
+                    "Green",
+                    "Grey",
+                ],
+            )
         "#]]
-        .assert_debug_eq(&edit);
+        .assert_debug_eq(&actual);
     }
 
     #[tokio::test]
-    async fn test_annotate_top_level() {
-        let edit = code_action_edits(
-            DOC_LIT.to_string()
-                + indoc! {r#"
-                other = \_ ->
-                    "Something else?"
-
-                main =
-                    other {}
+    async fn test_completion_expected_tags_in_pattern() {
+        let actual = completion_labels(
+            indoc! {r#"
+            color : [Red, Green, Grey] -> Str
+            color = \c ->
+                when c is
+                    Red -> "red"
+                    Green -> "green"
+                    Grey -> "grey"
             "#},
-            Position::new(5, 0),
-            "Add top-level signatures",
+            Position::new(7, 9),
         )
         .await;
 
         expect![[r#"
-            [
-                TextEdit {
-                    range: Range {
-                        start: Position {
-                            line: 3,
-                            character: 0,
-                        },
-                        end: Position {
-                            line: 3,
-                            character: 0,
-                        },
-                    },
-                    new_text: "other : * -> Str\n",
-                },
-                TextEdit {
-                    range: Range {
-                        start: Position {
-                            line: 6,
-                            character: 0,
-                        },
-                        end: Position {
-                            line: 6,
-                            character: 0,
-                        },
-                    },
-                    new_text: "main : Str\n",
-                },
-            ]
+            Some(
+                [
+                    "Green",
+                    "Grey",
+                ],
+            )
         "#]]
-        .assert_debug_eq(&edit);
+        .assert_debug_eq(&actual);
     }
 
     #[tokio::test]
-    async fn test_annotate_inner() {
-        let edit = code_action_edits(
-            DOC_LIT.to_string()
-                + indoc! {r#"
-                main =
-                    start = 10
-                    fib start 0 1
+    async fn test_completion_expected_fields() {
+        let actual = completion_labels(
+            indoc! {r"
+            Person : { name : Str, age : U64, nickname : Str }
 
-                fib = \n, a, b ->
-                    if n == 0 then
-                        a
-                    else
-                        fib (n - 1) b (a + b)
-            "#},
-            Position::new(4, 8),
-            "Add signature",
+            person : Person
+            person = { age: 42, n }
+            "},
+            Position::new(6, 21),
         )
         .await;
 
         expect![[r#"
-            [
-                TextEdit {
-                    range: Range {
-                        start: Position {
-                            line: 4,
-                            character: 0,
-                        },
-                        end: Position {
-                            line: 4,
-                            character: 0,
-                        },
-                    },
+            Some(
+                [
+                    "name",
+                    "nickname",
+                ],
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_completion_type_names() {
+        let actual = completion_labels(
+            indoc! {r"
+            Color : [Red, Green]
+            Coord := { x : U64 }
+
+            main : Co
+            "},
+            Position::new(6, 9),
+        )
+        .await;
+
+        expect![[r#"
+            Some(
+                [
+                    "Color",
+                    "Coord",
+                ],
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    /// Returns the label of the signature and the active parameter's label.
+    async fn signature_help_labels(doc: &str, position: Position) -> Option<(String, String)> {
+        let (inner, url) = test_setup(DOC_LIT.to_string() + doc).await;
+
+        let help = inner.registry.signature_help(&url, position).await?;
+        let signature = help.signatures.into_iter().next()?;
+        let parameters = signature.parameters?;
+
+        let ParameterLabel::LabelOffsets([start, end]) =
+            parameters[help.active_parameter? as usize].label
+        else {
+            return None;
+        };
+        let active = signature.label[start as usize..end as usize].to_string();
+
+        Some((signature.label, active))
+    }
+
+    #[tokio::test]
+    async fn test_signature_help_within_arguments() {
+        let actual = signature_help_labels(
+            indoc! {r#"
+                add : U64, Str -> U64
+                add = \a, _ -> a
+
+                main = add 1 "one"
+            "#},
+            Position::new(6, 15),
+        )
+        .await;
+
+        expect![[r#"
+            Some(
+                (
+                    "add : U64, Str -> U64",
+                    "Str",
+                ),
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_signature_help_after_space() {
+        // The argument being written isn't in the source yet, so the line ends with a space
+        let doc = indoc! {r"
+            add : U64, Str -> U64
+            add = \a, _ -> a
+
+        "}
+        .to_string()
+            + "main = add ";
+
+        let actual = signature_help_labels(&doc, Position::new(6, 11)).await;
+
+        expect![[r#"
+            Some(
+                (
+                    "add : U64, Str -> U64",
+                    "U64",
+                ),
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_signature_help_after_pipe() {
+        // The piped value is the first argument, so "one" is the second
+        let actual = signature_help_labels(
+            indoc! {r#"
+                describe : U64, Str, Bool -> U64
+                describe = \a, _, _ -> a
+
+                main = 1 |> describe "one" Bool.true
+            "#},
+            Position::new(6, 23),
+        )
+        .await;
+
+        expect![[r#"
+            Some(
+                (
+                    "describe : U64, Str, Bool -> U64",
+                    "Str",
+                ),
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    /// Returns `(line, character, label)` for each inlay hint in the document.
+    async fn inlay_hint_labels(doc: &str, top_level_only: bool) -> Vec<(u32, u32, String)> {
+        let registry = Registry::new(RegistryConfig {
+            inlay_hints_top_level_only: top_level_only,
+            ..RegistryConfig::default()
+        });
+        let doc = DOC_LIT.to_string() + doc;
+        let range = Range::new(
+            Position::new(0, 0),
+            Position::new(doc.lines().count() as u32, 0),
+        );
+        let (inner, url) = test_setup_with_registry(doc, registry).await;
+
+        inner
+            .registry
+            .inlay_hints(&url, range)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|hint| {
+                let InlayHintLabel::String(label) = hint.label else {
+                    unreachable!()
+                };
+                (hint.position.line, hint.position.character, label)
+            })
+            .collect()
+    }
+
+    const INLAY_HINT_DOC: &str = indoc! {r#"
+        main =
+            greet = \name -> Str.concat "Hi " name
+            greet "you"
+
+        total : U64
+        total = 1
+
+        shout = \s -> Str.concat s "!"
+    "#};
+
+    #[tokio::test]
+    async fn test_inlay_hints() {
+        let actual = inlay_hint_labels(INLAY_HINT_DOC, false).await;
+
+        expect![[r#"
+            [
+                (
+                    3,
+                    4,
+                    ": Str",
+                ),
+                (
+                    4,
+                    9,
+                    ": Str -> Str",
+                ),
+                (
+                    4,
+                    17,
+                    ": Str",
+                ),
+                (
+                    10,
+                    5,
+                    ": Str -> Str",
+                ),
+                (
+                    10,
+                    10,
+                    ": Str",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_inlay_hints_top_level_only() {
+        let actual = inlay_hint_labels(INLAY_HINT_DOC, true).await;
+
+        expect![[r#"
+            [
+                (
+                    3,
+                    4,
+                    ": Str",
+                ),
+                (
+                    10,
+                    5,
+                    ": Str -> Str",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_document_symbols() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r"
+                Pair a : (a, a)
+
+                Age := U64
+
+                Describe implements
+                    describe : a -> Str where a implements Describe
+
+                total : U64
+                total = 1
+
+                inc = \n -> n + 1
+            "};
+        let (inner, url) = test_setup(doc).await;
+
+        let Some(DocumentSymbolResponse::Nested(symbols)) =
+            inner.registry.document_symbols(&url).await
+        else {
+            panic!("expected nested document symbols");
+        };
+
+        fn describe(symbol: &DocumentSymbol, indent: &str, lines: &mut Vec<String>) {
+            let detail = match &symbol.detail {
+                Some(detail) => format!(" : {detail}"),
+                None => String::new(),
+            };
+            lines.push(format!(
+                "{indent}{} {:?} {}-{}{detail}",
+                symbol.name, symbol.kind, symbol.range.start.line, symbol.range.end.line,
+            ));
+
+            for child in symbol.children.iter().flatten() {
+                describe(child, &format!("{indent}  "), lines);
+            }
+        }
+
+        let mut actual = vec![];
+        for symbol in symbols.iter() {
+            describe(symbol, "", &mut actual);
+        }
+
+        expect![[r#"
+            [
+                "Pair Struct 3-3 : (a, a)",
+                "Age Class 5-5 : U64",
+                "Describe Interface 7-8",
+                "  describe Method 8-8 : a -> Str where a implements Describe",
+                "total Constant 10-11 : U64",
+                "inc Function 13-13",
+            ]
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_workspace_symbols() {
+        let doc = indoc! {r"
+            interface Test
+              exposes [total, increment, Tint, Toggle]
+              imports []
+
+            total : U64
+            total = 1
+
+            increment = \n -> n + total
+
+            secret = 2
+
+            Tint : [Light, Dark]
+
+            Toggle := Bool
+
+            Tuple : (U64, U64)
+        "};
+        let (inner, _url) = test_setup(doc.to_string()).await;
+
+        let actual: Vec<_> = inner
+            .registry
+            .workspace_symbols("t")
+            .await
+            .into_iter()
+            .map(|symbol| {
+                (
+                    symbol.name,
+                    format!("{:?}", symbol.kind),
+                    symbol.container_name,
+                    symbol.location.range.start.line,
+                )
+            })
+            .collect();
+
+        expect![[r#"
+            [
+                (
+                    "Tint",
+                    "Enum",
+                    Some(
+                        "Test",
+                    ),
+                    11,
+                ),
+                (
+                    "Toggle",
+                    "Class",
+                    Some(
+                        "Test",
+                    ),
+                    13,
+                ),
+                (
+                    "increment",
+                    "Function",
+                    Some(
+                        "Test",
+                    ),
+                    7,
+                ),
+                (
+                    "total",
+                    "Constant",
+                    Some(
+                        "Test",
+                    ),
+                    5,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_folding_ranges() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r#"
+                main =
+                    when [1, 2] is
+                        [] ->
+                            0
+
+                        _ ->
+                            1
+
+                config = {
+                    name: "roc",
+                    tags: ["a", "b"],
+                }
+            "#};
+        let (inner, url) = test_setup(doc).await;
+
+        let actual: Vec<_> = inner
+            .registry
+            .folding_ranges(&url)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|range| (range.start_line, range.end_line))
+            .collect();
+
+        expect![[r#"
+            [
+                (
+                    3,
+                    9,
+                ),
+                (
+                    4,
+                    9,
+                ),
+                (
+                    5,
+                    6,
+                ),
+                (
+                    8,
+                    9,
+                ),
+                (
+                    11,
+                    14,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    /// Returns `(start line, start character, end character)` for each reference found.
+    async fn reference_ranges(
+        doc: &str,
+        position: Position,
+        include_declaration: bool,
+    ) -> Vec<(u32, u32, u32)> {
+        let (inner, url) = test_setup(DOC_LIT.to_string() + doc).await;
+
+        inner
+            .registry
+            .references(&url, position, include_declaration)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|loc| {
+                (
+                    loc.range.start.line,
+                    loc.range.start.character,
+                    loc.range.end.character,
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_references_top_level() {
+        let actual = reference_ranges(
+            indoc! {r"
+                main =
+                    x = 1
+                    y = x + other
+                    y + x
+
+                other = 2
+            "},
+            Position::new(5, 13),
+            true,
+        )
+        .await;
+
+        expect![[r#"
+            [
+                (
+                    5,
+                    12,
+                    17,
+                ),
+                (
+                    8,
+                    0,
+                    5,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_references_local_without_declaration() {
+        let actual = reference_ranges(
+            indoc! {r"
+                main =
+                    x = 1
+                    y = x + other
+                    y + x
+
+                other = 2
+            "},
+            Position::new(4, 4),
+            false,
+        )
+        .await;
+
+        expect![[r#"
+            [
+                (
+                    5,
+                    8,
+                    9,
+                ),
+                (
+                    6,
+                    8,
+                    9,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_references_record_field() {
+        let actual = reference_ranges(
+            indoc! {r"
+                main =
+                    rec = { foo: 1, bar: 2 }
+                    getFoo = .foo
+                    rec.foo + getFoo rec
+            "},
+            Position::new(6, 9),
+            true,
+        )
+        .await;
+
+        assert_eq!(actual, vec![(4, 12, 15), (5, 14, 17), (6, 8, 11)]);
+    }
+
+    #[tokio::test]
+    async fn test_references_opaque_type() {
+        let actual = reference_ranges(
+            indoc! {r"
+                Id := U64
+
+                make : U64 -> Id
+                make = \n -> @Id n
+
+                unwrap : Id -> U64
+                unwrap = \@Id n -> n
+            "},
+            Position::new(5, 15),
+            true,
+        )
+        .await;
+
+        assert_eq!(
+            actual,
+            vec![(3, 0, 2), (5, 14, 16), (6, 14, 16), (8, 9, 11), (9, 11, 13)]
+        );
+    }
+
+    /// Writes `files` to a temporary directory and analyses the first one, which loads the
+    /// modules it imports too. Returns the url of each file.
+    async fn test_setup_project(
+        files: &[(&str, &str)],
+    ) -> (RocServerState, tempfile::TempDir, Vec<Url>) {
+        let dir = tempfile::tempdir().unwrap();
+        let urls: Vec<Url> = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, source).unwrap();
+                Url::from_file_path(path).unwrap()
+            })
+            .collect();
+
+        let inner = RocServerState::new(RocServerConfig::default(), Registry::default());
+        inner
+            .change(&urls[0], files[0].1.to_string(), 0)
+            .await
+            .unwrap();
+
+        (inner, dir, urls)
+    }
+
+    const ALIASED_IMPORT_DOC: &str = indoc! {r"
+        module []
+
+        import Helpers as H
+
+        main : H.Id
+        main = H.make (H.double 2)
+        "};
+
+    const OPAQUE_HELPERS_DOC: &str = indoc! {r"
+        module [Id, double, make]
+
+        Id := U64
+
+        double = \n -> n * 2
+
+        make : U64 -> Id
+        make = \n -> @Id n
+        "};
+
+    /// Returns `(file name, start line, start character, end character)` for each reference found.
+    async fn project_reference_ranges(position: Position) -> Vec<(String, u32, u32, u32)> {
+        let (inner, _dir, urls) = test_setup_project(&[
+            ("Test.roc", ALIASED_IMPORT_DOC),
+            ("Helpers.roc", OPAQUE_HELPERS_DOC),
+        ])
+        .await;
+
+        inner
+            .registry
+            .references(&urls[0], position, true)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|loc| {
+                let file_name = loc.uri.path().rsplit('/').next().unwrap().to_string();
+                (
+                    file_name,
+                    loc.range.start.line,
+                    loc.range.start.character,
+                    loc.range.end.character,
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_references_through_import_alias() {
+        let actual = project_reference_ranges(Position::new(5, 18)).await;
+
+        assert_eq!(
+            actual,
+            vec![
+                ("Helpers.roc".to_string(), 4, 0, 6),
+                ("Test.roc".to_string(), 5, 15, 23),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_references_type_through_import_alias() {
+        let actual = project_reference_ranges(Position::new(4, 10)).await;
+
+        assert_eq!(
+            actual,
+            vec![
+                ("Helpers.roc".to_string(), 2, 0, 2),
+                ("Helpers.roc".to_string(), 6, 14, 16),
+                ("Helpers.roc".to_string(), 7, 14, 16),
+                ("Test.roc".to_string(), 4, 7, 11),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_exposed_value() {
+        let doc = indoc! {r"
+            interface Test
+              exposes [other]
+              imports []
+
+            main =
+                other + 1
+
+            other : U64
+            other = 2
+        "};
+        let (inner, url) = test_setup(doc.to_string()).await;
+
+        let mut edits = inner
+            .registry
+            .rename(&url, Position::new(5, 5), "another")
+            .await
+            .unwrap()
+            .and_then(|edit| edit.changes)
+            .and_then(|mut changes| changes.remove(&url))
+            .unwrap_or_default();
+        edits.sort_by_key(|edit| edit.range.start);
+
+        let actual: Vec<_> = edits
+            .into_iter()
+            .map(|edit| {
+                (
+                    edit.range.start.line,
+                    edit.range.start.character,
+                    edit.range.end.character,
+                    edit.new_text,
+                )
+            })
+            .collect();
+
+        expect![[r#"
+            [
+                (
+                    1,
+                    11,
+                    16,
+                    "another",
+                ),
+                (
+                    5,
+                    4,
+                    9,
+                    "another",
+                ),
+                (
+                    7,
+                    0,
+                    5,
+                    "another",
+                ),
+                (
+                    8,
+                    0,
+                    5,
+                    "another",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_rename_rejects_invalid_name() {
+        let (inner, url) = test_setup(DOC_LIT.to_string() + "main = 1\n").await;
+
+        let result = inner
+            .registry
+            .rename(&url, Position::new(3, 1), "Main")
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rename_rejects_keywords() {
+        let (inner, url) = test_setup(DOC_LIT.to_string() + "main = 1\n").await;
+
+        for keyword in ["if", "when", "is", "expect", "import", "crash!"] {
+            let result = inner
+                .registry
+                .rename(&url, Position::new(3, 1), keyword)
+                .await;
+
+            assert!(result.is_err(), "renaming to {keyword} should fail");
+        }
+    }
+
+    /// Renames whatever is at `position` in the first of `urls`, returning
+    /// `(file name, line, start character, end character)` for each edit.
+    async fn rename_ranges(
+        inner: &RocServerState,
+        urls: &[Url],
+        position: Position,
+        new_name: &str,
+    ) -> Vec<(String, u32, u32, u32)> {
+        let changes = inner
+            .registry
+            .rename(&urls[0], position, new_name)
+            .await
+            .unwrap()
+            .and_then(|edit| edit.changes)
+            .unwrap_or_default();
+
+        let mut actual: Vec<_> = changes
+            .into_iter()
+            .flat_map(|(url, edits)| {
+                let file_name = url.path().rsplit('/').next().unwrap().to_string();
+                edits.into_iter().map(move |edit| {
+                    (
+                        file_name.clone(),
+                        edit.range.start.line,
+                        edit.range.start.character,
+                        edit.range.end.character,
+                    )
+                })
+            })
+            .collect();
+        actual.sort();
+
+        actual
+    }
+
+    #[tokio::test]
+    async fn test_rename_tag_leaves_same_named_tag_of_other_union() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r#"
+                Color : [Red, Green]
+                Light : [Red, Amber]
+
+                paint : Color
+                paint = Red
+
+                stop : Light
+                stop = Red
+
+                name : Color -> Str
+                name = \color ->
+                    when color is
+                        Red -> "red"
+                        Green -> "green"
+            "#};
+        let (inner, url) = test_setup(doc).await;
+
+        let actual = rename_ranges(&inner, &[url], Position::new(7, 9), "Crimson").await;
+
+        assert_eq!(
+            actual,
+            vec![
+                ("Test.roc".to_string(), 3, 9, 12),
+                ("Test.roc".to_string(), 7, 8, 11),
+                ("Test.roc".to_string(), 15, 8, 11),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_type_leaves_same_named_type_of_other_module() {
+        let id_module = indoc! {r"
+            module [Id, make]
+
+            Id := U64
+
+            make : U64 -> Id
+            make = \n -> @Id n
+            "};
+        let doc = indoc! {r"
+            module []
+
+            import A
+            import B
+
+            a : A.Id
+            a = A.make 1
+
+            b : B.Id
+            b = B.make 2
+            "};
+        let (inner, _dir, urls) = test_setup_project(&[
+            ("Test.roc", doc),
+            ("A.roc", id_module),
+            ("B.roc", id_module),
+        ])
+        .await;
+
+        let actual = rename_ranges(&inner, &urls, Position::new(5, 7), "Key").await;
+
+        assert_eq!(
+            actual,
+            vec![
+                ("A.roc".to_string(), 0, 8, 10),
+                ("A.roc".to_string(), 2, 0, 2),
+                ("A.roc".to_string(), 4, 14, 16),
+                ("A.roc".to_string(), 5, 14, 16),
+                ("Test.roc".to_string(), 5, 6, 8),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reanalysis_reuses_types_of_unchanged_imports() {
+        let helpers = indoc! {r#"
+            module [broken]
+
+            broken : Str
+            broken = 1
+            "#};
+        let doc = indoc! {r"
+            module []
+
+            import Helpers
+
+            main = Helpers.broken
+            "};
+        let (inner, _dir, urls) =
+            test_setup_project(&[("Test.roc", doc), ("Helpers.roc", helpers)]).await;
+        let stats = || inner.incremental_cache.lock().stats();
+
+        let first = inner.registry.diagnostics(&urls[1]).await;
+        assert_eq!(
+            stats(),
+            IncrementalStats {
+                reused: 0,
+                solved: 2
+            }
+        );
+
+        // Helpers didn't change, so its types (and type errors) come from the cache.
+        inner.change(&urls[0], doc.to_string(), 1).await.unwrap();
+        let second = inner.registry.diagnostics(&urls[1]).await;
+        assert_eq!(
+            stats(),
+            IncrementalStats {
+                reused: 1,
+                solved: 3
+            }
+        );
+
+        assert_eq!(first.len(), 1);
+        assert_eq!(first, second);
+
+        // Changing Helpers means it has to be solved again.
+        let fixed = helpers.replace("broken = 1", "broken = \"fixed\"");
+        std::fs::write(urls[1].to_file_path().unwrap(), fixed).unwrap();
+        inner.change(&urls[0], doc.to_string(), 2).await.unwrap();
+
+        assert_eq!(
+            stats(),
+            IncrementalStats {
+                reused: 1,
+                solved: 5
+            }
+        );
+        assert!(inner.registry.diagnostics(&urls[1]).await.is_empty());
+    }
+
+    async fn code_action_edits(doc: String, position: Position, name: &str) -> Vec<TextEdit> {
+        let (inner, url) = test_setup(doc.clone()).await;
+        let registry = &inner.registry;
+
+        let actions = registry
+            .code_actions(&url, Range::new(position, position))
+            .await
+            .unwrap();
+
+        actions
+            .into_iter()
+            .find_map(|either| match either {
+                CodeActionOrCommand::CodeAction(action) if name == action.title => Some(action),
+                _ => None,
+            })
+            .expect("Code action not present")
+            .edit
+            .expect("Code action does not have an associated edit")
+            .changes
+            .expect("Edit does not have any changes")
+            .get(&url)
+            .expect("Edit does not have changes for this file")
+            .clone()
+    }
+
+    #[tokio::test]
+    async fn test_annotate_single() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string() + r#"main = "Hello, world!""#,
+            Position::new(3, 2),
+            "Add signature",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 3,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: 3,
+                            character: 0,
+                        },
+                    },
+                    new_text: "main : Str\n",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    #[tokio::test]
+    async fn test_annotate_top_level() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                other = \_ ->
+                    "Something else?"
+
+                main =
+                    other {}
+            "#},
+            Position::new(5, 0),
+            "Add top-level signatures",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 3,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: 3,
+                            character: 0,
+                        },
+                    },
+                    new_text: "other : * -> Str\n",
+                },
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 6,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: 6,
+                            character: 0,
+                        },
+                    },
+                    new_text: "main : Str\n",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    #[tokio::test]
+    async fn test_annotate_inner() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                main =
+                    start = 10
+                    fib start 0 1
+
+                fib = \n, a, b ->
+                    if n == 0 then
+                        a
+                    else
+                        fib (n - 1) b (a + b)
+            "#},
+            Position::new(4, 8),
+            "Add signature",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 4,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: 4,
+                            character: 0,
+                        },
+                    },
                     new_text: "    start : Num *\n",
                 },
             ]
         "#]]
         .assert_debug_eq(&edit);
     }
+
+    #[tokio::test]
+    async fn test_fill_in_when_branches() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                color : [Red, Green, Blue]
+                color = Red
+
+                main =
+                    when color is
+                        Red -> "red"
+                        Green -> "green"
+            "#},
+            Position::new(7, 9),
+            "Add missing branches",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 9,
+                            character: 24,
+                        },
+                        end: Position {
+                            line: 9,
+                            character: 24,
+                        },
+                    },
+                    new_text: "\n        Blue -> crash \"todo\"",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    #[tokio::test]
+    async fn test_fill_in_when_branches_with_payloads() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                shape : [Circle F64, Rect F64 F64, Empty]
+                shape = Empty
+
+                main =
+                    when shape is
+                        Empty -> 0
+            "#},
+            Position::new(7, 9),
+            "Add missing branches",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 9,
+                            character: 18,
+                        },
+                        end: Position {
+                            line: 9,
+                            character: 18,
+                        },
+                    },
+                    new_text: "\n        Circle _ -> crash \"todo\"\n        Rect _ _ -> crash \"todo\"",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    #[tokio::test]
+    async fn test_fill_in_when_branches_of_numbers() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                main =
+                    when 1 is
+                        1 -> "one"
+            "#},
+            Position::new(4, 9),
+            "Add missing branches",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 5,
+                            character: 18,
+                        },
+                        end: Position {
+                            line: 5,
+                            character: 18,
+                        },
+                    },
+                    new_text: "\n        _ -> crash \"todo\"",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    #[tokio::test]
+    async fn test_fill_in_when_branches_of_bools() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                main =
+                    when Bool.true is
+                        b if b -> "yes"
+            "#},
+            Position::new(4, 9),
+            "Add missing branches",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 5,
+                            character: 23,
+                        },
+                        end: Position {
+                            line: 5,
+                            character: 23,
+                        },
+                    },
+                    new_text: "\n        _ -> crash \"todo\"",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    #[tokio::test]
+    async fn test_remove_redundant_branch() {
+        let edit = code_action_edits(
+            DOC_LIT.to_string()
+                + indoc! {r#"
+                main =
+                    when "a" is
+                        "a" -> 1
+                        _ -> 2
+                        _ -> 3
+            "#},
+            Position::new(7, 8),
+            "Remove redundant branch",
+        )
+        .await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 7,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: 8,
+                            character: 0,
+                        },
+                    },
+                    new_text: "",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edit);
+    }
+
+    const HELPERS_DOC: &str = indoc! {r"
+        interface Helpers
+          exposes [double]
+          imports []
+
+        double = \n -> n * 2
+        "};
+
+    /// Sets up the document along with a `Helpers` module it doesn't import.
+    async fn test_setup_with_helpers(doc: String) -> (RocServerState, Url) {
+        let (inner, url) = test_setup(doc).await;
+
+        let helpers_url = Url::parse("file:/Helpers.roc").unwrap();
+        inner
+            .change(&helpers_url, HELPERS_DOC.to_string(), 0)
+            .await
+            .unwrap();
+
+        (inner, url)
+    }
+
+    async fn auto_import_edits(position: Position, name: &str) -> Vec<TextEdit> {
+        let doc = DOC_LIT.to_string() + "main = double 2\n";
+        let (inner, url) = test_setup_with_helpers(doc).await;
+
+        let actions = inner
+            .registry
+            .code_actions(&url, Range::new(position, position))
+            .await
+            .unwrap();
+
+        actions
+            .into_iter()
+            .find_map(|either| match either {
+                CodeActionOrCommand::CodeAction(action) if name == action.title => Some(action),
+                _ => None,
+            })
+            .expect("Code action not present")
+            .edit
+            .and_then(|edit| edit.changes?.remove(&url))
+            .expect("Code action does not have changes for this file")
+    }
+
+    #[tokio::test]
+    async fn test_auto_import() {
+        let edits = auto_import_edits(Position::new(3, 9), "Import `double` from Helpers").await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 3,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: 3,
+                            character: 0,
+                        },
+                    },
+                    new_text: "import Helpers exposing [double]\n",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edits);
+    }
+
+    #[tokio::test]
+    async fn test_auto_import_into_empty_exposing_list() {
+        let doc = indoc! {r"
+            module [main]
+
+            import Helpers exposing [ # nothing yet
+            ]
+
+            main = double 2
+            "};
+        let helpers = indoc! {r"
+            module [double]
+
+            double = \n -> n * 2
+            "};
+        let (inner, _dir, urls) =
+            test_setup_project(&[("Test.roc", doc), ("Helpers.roc", helpers)]).await;
+
+        let position = Position::new(5, 8);
+        let edits = inner
+            .registry
+            .code_actions(&urls[0], Range::new(position, position))
+            .await
+            .unwrap()
+            .into_iter()
+            .find_map(|either| match either {
+                CodeActionOrCommand::CodeAction(action)
+                    if action.title == "Import `double` from Helpers" =>
+                {
+                    action.edit?.changes?.remove(&urls[0])
+                }
+                _ => None,
+            })
+            .expect("Code action not present");
+
+        assert_eq!(
+            edits,
+            vec![TextEdit {
+                range: Range::new(Position::new(2, 25), Position::new(2, 25)),
+                new_text: "double".to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auto_import_qualify() {
+        let edits = auto_import_edits(Position::new(3, 9), "Qualify as `Helpers.double`").await;
+
+        expect![[r#"
+            [
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 3,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: 3,
+                            character: 0,
+                        },
+                    },
+                    new_text: "import Helpers\n",
+                },
+                TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 3,
+                            character: 7,
+                        },
+                        end: Position {
+                            line: 3,
+                            character: 13,
+                        },
+                    },
+                    new_text: "Helpers.double",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&edits);
+    }
+
+    #[tokio::test]
+    async fn test_completion_auto_import() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r"
+            main = \param ->
+              "};
+        let (inner, url) = test_setup_with_helpers(doc.clone()).await;
+        inner.change(&url, doc + "dou", 1).await.unwrap();
+
+        let completion = inner
+            .registry
+            .completion_items(&url, Position::new(4, 5))
+            .await
+            .and_then(|resp| match resp {
+                CompletionResponse::Array(list) => list.into_iter().find(|c| c.label == "double"),
+                CompletionResponse::List(list) => {
+                    list.items.into_iter().find(|c| c.label == "double")
+                }
+            })
+            .expect("Auto-import completion not present");
+        let actual = (
+            completion
+                .label_details
+                .and_then(|details| details.description),
+            completion.additional_text_edits,
+        );
+
+        expect![[r#"
+            (
+                Some(
+                    "auto-import from Helpers",
+                ),
+                Some(
+                    [
+                        TextEdit {
+                            range: Range {
+                                start: Position {
+                                    line: 3,
+                                    character: 0,
+                                },
+                                end: Position {
+                                    line: 3,
+                                    character: 0,
+                                },
+                            },
+                            new_text: "import Helpers exposing [double]\n",
+                        },
+                    ],
+                ),
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
 }
@@ -0,0 +1,144 @@
+//! Benchmarks are top-level expects like `expect Inspect.bench("sort", List.sort_asc(list))`.
+//! `roc bench` times how long computing their value takes, by running them many times in a row.
+use std::time::{Duration, Instant};
+
+use crate::child::Child;
+use crate::run::{call_expect, expect_function, ExpectMemory, ExpectOutcome, ToplevelExpect};
+
+/// How benchmarks are timed.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    /// How long a benchmark runs before it's timed, so that caches and the allocator are warm.
+    pub warmup: Duration,
+    /// How many times a benchmark is timed.
+    pub samples: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            warmup: Duration::from_millis(500),
+            samples: 50,
+        }
+    }
+}
+
+/// How long a sample takes at least. A benchmark which runs faster than this is run as many times
+/// as fit in it, so that the time of a single run isn't lost in the resolution of the clock.
+const SAMPLE_TIME: Duration = Duration::from_millis(10);
+
+/// The times a benchmark was measured to take.
+#[derive(Debug, PartialEq)]
+pub struct BenchSamples {
+    /// How many times the benchmark ran in every sample.
+    pub iterations: u64,
+    /// How long a single run took in every sample, on average.
+    pub times: Vec<Duration>,
+}
+
+impl BenchSamples {
+    /// Encodes the samples as text, so that a child process can send them to its parent.
+    pub(crate) fn encode(&self) -> String {
+        let mut fields = vec![self.iterations.to_string()];
+        fields.extend(self.times.iter().map(|time| time.as_nanos().to_string()));
+
+        fields.join(" ")
+    }
+
+    pub(crate) fn decode(text: &str) -> Option<Self> {
+        let mut fields = text.split(' ');
+
+        let iterations = fields.next()?.parse().ok()?;
+        let times = fields
+            .map(|nanos| nanos.parse().ok().map(Duration::from_nanos))
+            .collect::<Option<_>>()?;
+
+        Some(Self { iterations, times })
+    }
+}
+
+/// Times a benchmark in a child process, after warming it up, so that one which crashes doesn't
+/// take `roc bench` down with it. Returns how it crashed if it did. `memory` must be shared with
+/// child processes.
+pub fn measure_bench(
+    lib: &libloading::Library,
+    memory: &mut ExpectMemory,
+    expect: ToplevelExpect<'_>,
+    options: BenchOptions,
+) -> std::io::Result<Result<BenchSamples, String>> {
+    memory.set_shared_buffer(lib);
+
+    let mut child = Child::spawn(&[], |_| match measure(lib, memory, expect, options) {
+        Ok(samples) => ExpectOutcome::Measured(samples),
+        Err(message) => ExpectOutcome::Panicked(message),
+    })?;
+
+    child.start(0)?;
+
+    let measured = match child.finish()?.0 {
+        ExpectOutcome::Measured(samples) => Ok(samples),
+        ExpectOutcome::Panicked(message) | ExpectOutcome::Crashed(message) => Err(message),
+        _ => Err("The process running the benchmark ended without timing it.".to_string()),
+    };
+
+    Ok(measured)
+}
+
+/// Times a benchmark in this process, after warming it up. Returns the message of the crash if it
+/// crashed.
+fn measure(
+    lib: &libloading::Library,
+    memory: &mut ExpectMemory,
+    expect: ToplevelExpect<'_>,
+    options: BenchOptions,
+) -> Result<BenchSamples, String> {
+    let function = expect_function(lib, &expect);
+
+    let mut run = |iterations: u64| {
+        memory.reset();
+
+        let start = Instant::now();
+        for _ in 0..iterations {
+            call_expect(&function)?;
+        }
+
+        Ok::<_, String>(start.elapsed())
+    };
+
+    // it runs at least once, which also tells roughly how long it takes
+    let mut warmup_runs = 1;
+    let mut warmup_time = run(1)?;
+    while warmup_time < options.warmup {
+        warmup_time += run(1)?;
+        warmup_runs += 1;
+    }
+
+    let time_per_run = warmup_time / warmup_runs;
+    let iterations = match time_per_run.as_nanos() {
+        0 => SAMPLE_TIME.as_nanos() as u64,
+        nanos => (SAMPLE_TIME.as_nanos() / nanos).max(1) as u64,
+    };
+
+    let mut times = Vec::with_capacity(options.samples);
+    for _ in 0..options.samples {
+        let elapsed = run(iterations)?;
+        times.push(elapsed.div_f64(iterations as f64));
+    }
+
+    Ok(BenchSamples { iterations, times })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_survive_encoding() {
+        let samples = BenchSamples {
+            iterations: 42,
+            times: vec![Duration::from_nanos(1_500), Duration::from_nanos(2_250)],
+        };
+
+        assert_eq!(BenchSamples::decode(&samples.encode()), Some(samples));
+    }
+}
@@ -0,0 +1,330 @@
+//! Running expects in child processes, so that one which crashes or never finishes doesn't take
+//! the others down with it.
+//!
+//! A child process is forked once and then runs one expect after another, so forking is only paid
+//! for again after an expect crashed or timed out. Forking is only safe while this process has a
+//! single thread: a lock held by another thread, like one of the allocator's, would stay locked
+//! forever in the child. Loading has joined its threads by the time the expects run, and nothing
+//! here starts one, so children are only ever forked by the one thread left.
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, RawFd};
+use std::time::{Duration, Instant};
+
+use roc_error_macros::internal_error;
+
+use crate::bench::BenchSamples;
+use crate::property::Falsified;
+use crate::run::ExpectOutcome;
+
+/// The exit code of a child process which panicked, rather than the expect it ran.
+const PANICKED_EXIT_CODE: i32 = 101;
+
+/// The kinds of outcomes a child process writes to the pipe it shares with its parent, followed
+/// by how long the expect ran and a message: that of the crash, how the property was falsified,
+/// which of its inputs can't be generated, or the times the benchmark took.
+const FINISHED: u8 = 0;
+const PANICKED: u8 = 1;
+const FALSIFIED: u8 = 2;
+const UNSUPPORTED: u8 = 3;
+const MEASURED: u8 = 4;
+
+/// The length of an outcome before its message: its kind, the nanoseconds the expect ran, and
+/// the length of the message.
+const OUTCOME_HEADER_LEN: usize = 1 + 8 + 8;
+
+/// A child process running expects, one at a time. Failures are written to the memory the dylib's
+/// shared buffer was set to when the child was spawned, so it must be shared with child processes.
+pub(crate) struct Child {
+    pid: libc::pid_t,
+    /// Where the indices of the expects to run are written.
+    requests: File,
+    /// Where the child writes how each expect ended. It doesn't block, so that a child still
+    /// writing doesn't block the parent.
+    outcomes: File,
+    /// What was read from `outcomes` of the outcome being written.
+    outcome: Vec<u8>,
+    /// When the expect being run was asked for.
+    started: Option<Instant>,
+    exited: bool,
+}
+
+impl Child {
+    /// Forks a child process which calls `run` with the index of every expect it's asked to run.
+    ///
+    /// The child closes `inherited`, the descriptors of the pipes to other children, so that the
+    /// parent still notices when those exit.
+    pub(crate) fn spawn(
+        inherited: &[RawFd],
+        mut run: impl FnMut(usize) -> ExpectOutcome,
+    ) -> std::io::Result<Self> {
+        let (request_rx, request_tx) = pipe()?;
+        let (outcome_rx, outcome_tx) = pipe()?;
+
+        // Anything still buffered would be written by the child process too
+        std::io::stdout().flush()?;
+
+        match unsafe { libc::fork() } {
+            -1 => Err(std::io::Error::last_os_error()),
+            0 => {
+                // we are the child
+                for fd in inherited {
+                    unsafe { libc::close(*fd) };
+                }
+                drop(request_tx);
+                drop(outcome_rx);
+
+                let mut requests = request_rx;
+                let mut outcomes = outcome_tx;
+
+                let serve = std::panic::AssertUnwindSafe(|| {
+                    let mut index = [0; 8];
+
+                    // The parent closes the pipe, or is gone, once there's nothing left to run
+                    while requests.read_exact(&mut index).is_ok() {
+                        let started = Instant::now();
+                        let outcome = run(u64::from_le_bytes(index) as usize);
+
+                        if outcomes
+                            .write_all(&encode_outcome(outcome, started.elapsed()))
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                });
+
+                let exit_code = match std::panic::catch_unwind(serve) {
+                    Ok(()) => 0,
+                    Err(_) => PANICKED_EXIT_CODE,
+                };
+
+                // Skip the exit handlers, which belong to the parent
+                unsafe { libc::_exit(exit_code) }
+            }
+            pid => {
+                drop(request_rx);
+                drop(outcome_tx);
+
+                if unsafe { libc::fcntl(outcome_rx.as_raw_fd(), libc::F_SETFL, libc::O_NONBLOCK) }
+                    == -1
+                {
+                    return Err(std::io::Error::last_os_error());
+                }
+
+                Ok(Self {
+                    pid,
+                    requests: request_tx,
+                    outcomes: outcome_rx,
+                    outcome: Vec::new(),
+                    started: None,
+                    exited: false,
+                })
+            }
+        }
+    }
+
+    /// The descriptors of the pipes to this child, which other children must not keep open.
+    pub(crate) fn fds(&self) -> [RawFd; 2] {
+        [self.requests.as_raw_fd(), self.outcomes.as_raw_fd()]
+    }
+
+    /// The descriptor which becomes readable when the expect being run ended.
+    pub(crate) fn outcome_fd(&self) -> RawFd {
+        self.outcomes.as_raw_fd()
+    }
+
+    /// Whether the process is gone, so a new one must be spawned to run more expects.
+    pub(crate) fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Asks the child to run the expect at `index`.
+    pub(crate) fn start(&mut self, index: usize) -> std::io::Result<()> {
+        self.started = Some(Instant::now());
+        self.outcome.clear();
+
+        self.requests.write_all(&(index as u64).to_le_bytes())
+    }
+
+    /// How long the expect being run has been running for.
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.started
+            .map_or(Duration::ZERO, |started| started.elapsed())
+    }
+
+    /// Reads what the child wrote since it was last asked, without waiting for it. Returns how
+    /// the expect ended and how long it ran, once it ended.
+    pub(crate) fn try_finish(&mut self) -> std::io::Result<Option<(ExpectOutcome, Duration)>> {
+        let mut buf = [0; 4096];
+
+        loop {
+            match self.outcomes.read(&mut buf) {
+                // The child exited in the middle of the expect, so the time to now is how long
+                // it ran
+                Ok(0) => {
+                    let duration = self.elapsed();
+                    let status = self.wait()?;
+
+                    return Ok(Some((describe_exit(status), duration)));
+                }
+                Ok(read) => self.outcome.extend_from_slice(&buf[..read]),
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(decode_outcome(&self.outcome))
+    }
+
+    /// Waits for the expect being run to end. Returns how it ended and how long it ran.
+    pub(crate) fn finish(&mut self) -> std::io::Result<(ExpectOutcome, Duration)> {
+        loop {
+            if let Some(finished) = self.try_finish()? {
+                return Ok(finished);
+            }
+
+            let mut fd = libc::pollfd {
+                fd: self.outcome_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+
+            if unsafe { libc::poll(&mut fd, 1, -1) } == -1 {
+                let err = std::io::Error::last_os_error();
+                if err.kind() != ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    /// Stops the child, because its expect ran for longer than `timeout`.
+    pub(crate) fn kill(mut self, timeout: Duration) -> std::io::Result<ExpectOutcome> {
+        unsafe { libc::kill(self.pid, libc::SIGKILL) };
+        self.wait()?;
+
+        Ok(ExpectOutcome::TimedOut(timeout))
+    }
+
+    fn wait(&mut self) -> std::io::Result<i32> {
+        let mut status = 0;
+
+        loop {
+            if unsafe { libc::waitpid(self.pid, &mut status, 0) } != -1 {
+                self.exited = true;
+
+                return Ok(status);
+            }
+
+            let err = std::io::Error::last_os_error();
+            if err.kind() != ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+}
+
+impl Drop for Child {
+    /// A child is only dropped running if something went wrong, or once no expects are left, so
+    /// it doesn't matter what it's doing.
+    fn drop(&mut self) {
+        if !self.exited {
+            unsafe { libc::kill(self.pid, libc::SIGKILL) };
+            let _ = self.wait();
+        }
+    }
+}
+
+fn pipe() -> std::io::Result<(File, File)> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    let [read_fd, write_fd] = fds;
+
+    Ok(unsafe { (File::from_raw_fd(read_fd), File::from_raw_fd(write_fd)) })
+}
+
+fn encode_outcome(outcome: ExpectOutcome, duration: Duration) -> Vec<u8> {
+    let (kind, message) = match outcome {
+        ExpectOutcome::Finished => (FINISHED, String::new()),
+        ExpectOutcome::Panicked(message) => (PANICKED, message),
+        ExpectOutcome::Falsified(falsified) => (FALSIFIED, falsified.encode()),
+        ExpectOutcome::Unsupported(description) => (UNSUPPORTED, description),
+        ExpectOutcome::Measured(samples) => (MEASURED, samples.encode()),
+        ExpectOutcome::Crashed(_) | ExpectOutcome::TimedOut(_) => {
+            internal_error!("only the parent process can tell an expect crashed")
+        }
+    };
+
+    let mut bytes = Vec::with_capacity(OUTCOME_HEADER_LEN + message.len());
+    bytes.push(kind);
+    bytes.extend((duration.as_nanos() as u64).to_le_bytes());
+    bytes.extend((message.len() as u64).to_le_bytes());
+    bytes.extend(message.as_bytes());
+
+    bytes
+}
+
+/// Decodes an outcome written by [encode_outcome], once all of it was read.
+fn decode_outcome(bytes: &[u8]) -> Option<(ExpectOutcome, Duration)> {
+    let header = bytes.get(..OUTCOME_HEADER_LEN)?;
+    let nanos = u64::from_le_bytes(header[1..9].try_into().unwrap());
+    let message_len = u64::from_le_bytes(header[9..].try_into().unwrap()) as usize;
+    let message = bytes.get(OUTCOME_HEADER_LEN..OUTCOME_HEADER_LEN + message_len)?;
+    let message = String::from_utf8_lossy(message).into_owned();
+
+    let outcome = match header[0] {
+        FINISHED => ExpectOutcome::Finished,
+        PANICKED => ExpectOutcome::Panicked(message),
+        FALSIFIED => match Falsified::decode(&message) {
+            Some(falsified) => ExpectOutcome::Falsified(falsified),
+            None => ExpectOutcome::Crashed(format!(
+                "The process reported a falsified property it could not describe: {message}"
+            )),
+        },
+        UNSUPPORTED => ExpectOutcome::Unsupported(message),
+        MEASURED => match BenchSamples::decode(&message) {
+            Some(samples) => ExpectOutcome::Measured(samples),
+            None => ExpectOutcome::Crashed(format!(
+                "The process reported times it could not describe: {message}"
+            )),
+        },
+        kind => internal_error!("invalid outcome kind written by the child: {kind}"),
+    };
+
+    Some((outcome, Duration::from_nanos(nanos)))
+}
+
+/// Describes how a child exited in the middle of an expect, from its status.
+fn describe_exit(status: i32) -> ExpectOutcome {
+    if libc::WIFEXITED(status) {
+        match libc::WEXITSTATUS(status) {
+            PANICKED_EXIT_CODE => {
+                ExpectOutcome::Crashed("The process running the expect panicked.".to_string())
+            }
+            code => ExpectOutcome::Crashed(format!("The process exited with code {code}.")),
+        }
+    } else if libc::WIFSIGNALED(status) {
+        ExpectOutcome::Crashed(describe_signal(libc::WTERMSIG(status)))
+    } else {
+        ExpectOutcome::Crashed(format!("The process stopped with status {status}."))
+    }
+}
+
+fn describe_signal(signal: i32) -> String {
+    let name = match signal {
+        libc::SIGSEGV => "a segmentation fault (SIGSEGV)",
+        libc::SIGBUS => "a bus error (SIGBUS)",
+        libc::SIGILL => "an illegal instruction (SIGILL)",
+        libc::SIGFPE => "an arithmetic error (SIGFPE)",
+        libc::SIGABRT => "an abort (SIGABRT)",
+        libc::SIGKILL => "being killed (SIGKILL)",
+        _ => return format!("The process was stopped by signal {signal}."),
+    };
+
+    format!("The process was stopped by {name}.")
+}
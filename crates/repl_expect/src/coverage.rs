@@ -0,0 +1,93 @@
+//! Counting how often the probes which `roc test --coverage` inserts ran, in memory shared with
+//! the child processes that run the expects.
+use roc_collections::MutMap;
+use roc_error_macros::internal_error;
+use roc_gen_llvm::llvm::build::COVERAGE_COUNTERS_GLOBAL;
+use roc_module::symbol::ModuleId;
+use roc_mono::coverage::CoverageProbes;
+
+pub struct CoverageCounters {
+    ptr: *mut u64,
+    /// The number of counters, including the start of every module's counters.
+    length: usize,
+    /// Where the counters of each module start, and how many of them there are.
+    modules: MutMap<ModuleId, (usize, usize)>,
+}
+
+impl CoverageCounters {
+    /// Counters for `probes`, all at 0, which the child processes forked after this is created
+    /// share with this process.
+    pub fn shared_with_children(probes: &MutMap<ModuleId, CoverageProbes>) -> Self {
+        // the counters start after the index for every module id
+        let module_count = probes
+            .keys()
+            .map(|module_id| module_id.to_zero_indexed() + 1)
+            .max()
+            .unwrap_or(0);
+
+        let mut modules = MutMap::default();
+        let mut length = module_count;
+
+        for (module_id, module_probes) in probes {
+            modules.insert(*module_id, (length, module_probes.probes.len()));
+            length += module_probes.probes.len();
+        }
+
+        // mmap doesn't take 0 bytes
+        let length = length.max(1);
+
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                length * std::mem::size_of::<u64>(),
+                libc::PROT_WRITE | libc::PROT_READ,
+                libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            internal_error!("failed to mmap the coverage counters")
+        }
+
+        // anonymous memory starts out zeroed, so only the starts have to be written
+        let ptr = ptr.cast::<u64>();
+        for (module_id, (start, _)) in modules.iter() {
+            unsafe { ptr.add(module_id.to_zero_indexed()).write(*start as u64) };
+        }
+
+        Self {
+            ptr,
+            length,
+            modules,
+        }
+    }
+
+    /// Makes the probes of `lib` count into these counters. A `lib` without probes is left as is.
+    pub fn set_in(&self, lib: &libloading::Library) {
+        let counters_global: libloading::Symbol<*mut *mut u64> =
+            match unsafe { lib.get(COVERAGE_COUNTERS_GLOBAL.as_bytes()) } {
+                Ok(global) => global,
+                Err(_) => return,
+            };
+
+        unsafe { **counters_global = self.ptr };
+    }
+
+    /// How often every probe of the module ran, in the order of its [CoverageProbes].
+    pub fn hits(&self, module_id: ModuleId) -> &[u64] {
+        match self.modules.get(&module_id) {
+            Some((start, count)) => unsafe {
+                std::slice::from_raw_parts(self.ptr.add(*start), *count)
+            },
+            None => &[],
+        }
+    }
+}
+
+impl Drop for CoverageCounters {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr.cast(), self.length * std::mem::size_of::<u64>()) };
+    }
+}
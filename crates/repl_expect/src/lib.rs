@@ -15,7 +15,17 @@ use {
 #[cfg(not(windows))]
 mod app;
 #[cfg(not(windows))]
+pub mod bench;
+#[cfg(not(windows))]
+mod child;
+#[cfg(not(windows))]
+pub mod coverage;
+#[cfg(not(windows))]
+pub mod property;
+#[cfg(not(windows))]
 pub mod run;
+#[cfg(not(windows))]
+mod snapshot;
 
 #[cfg(not(windows))]
 use app::{ExpectMemory, ExpectReplApp};
@@ -168,7 +178,7 @@ mod test {
 
         let global_layout_interner = layout_interner.into_global();
         for (_, expect_funcs) in expects_by_module {
-            let (_failed, _passed) = crate::run::run_expects_with_memory(
+            let _results = crate::run::run_expects_with_memory(
                 &mut writer,
                 RenderTarget::ColorTerminal,
                 arena,
@@ -0,0 +1,987 @@
+//! Properties are top-level expects of a function, like
+//! `expect |list| List.len(List.sort_asc(list)) == List.len(list)`, which are checked with many
+//! generated inputs. Once an input falsifies one, it is shrunk to the smallest input found which
+//! still does, and that one is reported like any other failure.
+//!
+//! Inputs are generated for numbers, strings, lists, records, tuples, and tag unions whose tags
+//! have no payloads. A property with an argument of another type, like a tag union with payloads
+//! or a function, is reported as unsupported.
+use bumpalo::Bump;
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_error_macros::internal_error;
+use roc_gen_llvm::{llvm::build::PROPERTY_ARGUMENTS_GLOBAL, run_roc::RocCallResult};
+use roc_mono::layout::{Builtin, GlobalLayoutInterner, InLayout, LayoutInterner, LayoutRepr};
+use roc_std::{RocDec, ROC_REFCOUNT_CONSTANT};
+
+use crate::run::{call_expect, ExpectMemory, ExpectOutcome};
+
+/// How properties are checked.
+#[derive(Debug, Clone, Copy)]
+pub struct PropertyOptions {
+    /// How many inputs a property is checked with, unless one of them falsifies it.
+    pub runs: u32,
+    /// The same seed gives the same inputs.
+    pub seed: u64,
+}
+
+impl Default for PropertyOptions {
+    fn default() -> Self {
+        Self { runs: 100, seed: 0 }
+    }
+}
+
+/// How big the inputs of the last run get, e.g. lists of up to this many elements. The inputs of
+/// the first runs are smaller, so that simple failures are found first.
+const MAX_SIZE: u64 = 100;
+
+/// How many smaller inputs are tried at most when shrinking one.
+const MAX_SHRINK_ATTEMPTS: u32 = 10_000;
+
+/// The failures of the inputs tried while searching and shrinking are written here instead of to
+/// the memory which gets reported, which has room for the failure of a small input only.
+const SCRATCH_SIZE: usize = 1 << 20;
+
+/// Characters which are generated now and then, so that strings aren't all ASCII.
+const UNUSUAL_CHARS: [char; 6] = ['é', 'ß', 'λ', '中', '🙂', '\n'];
+
+/// How a property was falsified.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Falsified {
+    /// How many inputs were tried, the last of which falsified the property.
+    pub runs: u32,
+    /// How many times that input was made smaller.
+    pub shrinks: u32,
+    pub seed: u64,
+    /// The smallest input found, as Roc source.
+    pub inputs: Vec<String>,
+    /// The message of the crash, if the smallest input makes the property crash rather than fail.
+    pub crash: Option<String>,
+}
+
+impl Falsified {
+    /// A property is checked in a child process, which sends this to its parent as text.
+    pub(crate) fn encode(&self) -> String {
+        let crash = match &self.crash {
+            Some(message) => format!("!{message}"),
+            None => String::new(),
+        };
+
+        let mut fields = vec![
+            self.runs.to_string(),
+            self.shrinks.to_string(),
+            self.seed.to_string(),
+            crash,
+        ];
+        fields.extend(self.inputs.iter().cloned());
+
+        fields.join("\0")
+    }
+
+    pub(crate) fn decode(text: &str) -> Option<Self> {
+        let mut fields = text.split('\0');
+
+        let runs = fields.next()?.parse().ok()?;
+        let shrinks = fields.next()?.parse().ok()?;
+        let seed = fields.next()?.parse().ok()?;
+        let crash = fields.next()?.strip_prefix('!').map(String::from);
+        let inputs = fields.map(String::from).collect();
+
+        Some(Self {
+            runs,
+            shrinks,
+            seed,
+            inputs,
+            crash,
+        })
+    }
+}
+
+/// Checks a property with generated inputs, in this process. The failures of the smallest input
+/// which falsifies it are written to `memory`.
+pub(crate) fn check_property(
+    lib: &libloading::Library,
+    function: &unsafe extern "C" fn(*mut RocCallResult<()>),
+    layout_interner: &GlobalLayoutInterner<'_>,
+    arguments: &[InLayout<'_>],
+    memory: &mut ExpectMemory,
+    options: PropertyOptions,
+) -> ExpectOutcome {
+    let slots: Result<Vec<_>, _> = arguments
+        .iter()
+        .map(|layout| Slot::from_layout(layout_interner, *layout))
+        .collect();
+
+    let slots = match slots {
+        Ok(slots) => slots,
+        Err(description) => return ExpectOutcome::Unsupported(description),
+    };
+
+    let arguments_global: libloading::Symbol<*mut *const *const u8> =
+        unsafe { lib.get(PROPERTY_ARGUMENTS_GLOBAL.as_bytes()) }
+            .unwrap_or_else(|_| internal_error!("the arguments of properties were not exposed"));
+    let arguments_global = *arguments_global;
+
+    let mut run = |memory: &mut ExpectMemory, inputs: &[Value]| {
+        // the inputs only have to live until the property returns
+        let arena = Bump::new();
+        let pointers: Vec<*const u8> = slots
+            .iter()
+            .zip(inputs)
+            .map(|(slot, input)| {
+                let ptr = allocate(&arena, slot.size, slot.alignment);
+                unsafe { write_value(&arena, &slot.shape, input, ptr) };
+
+                ptr as *const u8
+            })
+            .collect();
+
+        memory.reset();
+        unsafe { *arguments_global = pointers.as_ptr() };
+
+        match call_expect(function) {
+            Err(message) => Run::Crashed(message),
+            Ok(()) if memory.count_failures() > 0 => Run::Failed,
+            Ok(()) => Run::Passed,
+        }
+    };
+
+    let mut scratch_bytes = vec![0; SCRATCH_SIZE];
+    let mut scratch = ExpectMemory::from_slice(&mut scratch_bytes);
+    scratch.set_shared_buffer(lib);
+
+    let mut falsifies = |inputs: &[Value]| !matches!(run(&mut scratch, inputs), Run::Passed);
+
+    let Some((inputs, runs)) = search(&slots, options, &mut falsifies) else {
+        return ExpectOutcome::Finished;
+    };
+    let (inputs, shrinks) = shrink(&slots, inputs, &mut falsifies);
+
+    // Run the smallest input again, this time reporting its failures
+    memory.set_shared_buffer(lib);
+    let crash = match run(memory, &inputs) {
+        Run::Crashed(message) => Some(message),
+        Run::Failed | Run::Passed => None,
+    };
+
+    ExpectOutcome::Falsified(Falsified {
+        runs,
+        shrinks,
+        seed: options.seed,
+        inputs: slots
+            .iter()
+            .zip(&inputs)
+            .map(|(slot, input)| input.to_roc(&slot.shape))
+            .collect(),
+        crash,
+    })
+}
+
+enum Run {
+    Passed,
+    Failed,
+    Crashed(String),
+}
+
+/// Generates inputs until one falsifies the property. Returns it, along with how many inputs were
+/// tried.
+fn search(
+    slots: &[Slot],
+    options: PropertyOptions,
+    falsifies: &mut impl FnMut(&[Value]) -> bool,
+) -> Option<(Vec<Value>, u32)> {
+    let mut rng = SplitMix64(options.seed);
+
+    for run in 1..=options.runs {
+        let size = (MAX_SIZE * run as u64 / options.runs as u64) as i128;
+        let inputs: Vec<_> = slots
+            .iter()
+            .map(|slot| generate(&slot.shape, &mut rng, size))
+            .collect();
+
+        if falsifies(&inputs) {
+            return Some((inputs, run));
+        }
+    }
+
+    None
+}
+
+/// Shrinks inputs which falsify the property, by trying smaller ones until none of those falsify
+/// it. Returns the smallest inputs found, along with how many times they got smaller.
+fn shrink(
+    slots: &[Slot],
+    mut inputs: Vec<Value>,
+    falsifies: &mut impl FnMut(&[Value]) -> bool,
+) -> (Vec<Value>, u32) {
+    let mut shrinks = 0;
+    let mut attempts = 0;
+
+    'smaller: loop {
+        for (index, slot) in slots.iter().enumerate() {
+            for candidate in smaller_values(&slot.shape, &inputs[index]) {
+                if attempts == MAX_SHRINK_ATTEMPTS {
+                    break 'smaller;
+                }
+                attempts += 1;
+
+                let mut smaller = inputs.clone();
+                smaller[index] = candidate;
+
+                if falsifies(&smaller) {
+                    inputs = smaller;
+                    shrinks += 1;
+
+                    continue 'smaller;
+                }
+            }
+        }
+
+        break;
+    }
+
+    (inputs, shrinks)
+}
+
+/// What a value looks like in memory, following its layout.
+#[derive(Debug)]
+enum Shape {
+    Int(IntWidth),
+    Float(FloatWidth),
+    Dec,
+    Bool,
+    Str,
+    List(Box<Slot>),
+    /// The fields of records and tuples, with their offsets. Only records have `names`, in the
+    /// same order.
+    Struct {
+        fields: Vec<(usize, Shape)>,
+        names: Option<Vec<String>>,
+    },
+    /// A tag union whose tags have no payloads, which is stored as the id of its tag. These are
+    /// the names of its tags, in the order of their ids.
+    Tags(Vec<String>),
+}
+
+/// A shape, along with how much room a value of it takes up.
+#[derive(Debug)]
+struct Slot {
+    shape: Shape,
+    size: usize,
+    alignment: usize,
+}
+
+impl Slot {
+    /// The shape of values of `layout`, or a description of the part of it which can't be
+    /// generated.
+    fn from_layout(
+        interner: &GlobalLayoutInterner<'_>,
+        layout: InLayout<'_>,
+    ) -> Result<Self, String> {
+        let semantic = interner.get(layout).semantic();
+
+        let shape = match (interner.get_repr(layout), semantic.tag_names()) {
+            // Bool is an opaque type of this union, which its layout doesn't tell apart from it
+            (LayoutRepr::Builtin(Builtin::Bool), Some(["False", "True"])) => Shape::Bool,
+            (
+                LayoutRepr::Builtin(Builtin::Bool | Builtin::Int(IntWidth::U8))
+                | LayoutRepr::Struct([]),
+                Some(tags),
+            ) => Shape::Tags(to_strings(tags)),
+            (repr, _) => match (semantic.record_fields(), interner.get(layout).newtype_of()) {
+                // A record with a single field is laid out like the field
+                (Some([name]), Some(field)) => Shape::Struct {
+                    fields: vec![(0, Self::from_layout(interner, field)?.shape)],
+                    names: Some(vec![name.to_string()]),
+                },
+                (Some(names), _) => match Self::shape_from_repr(interner, repr)? {
+                    Shape::Struct { fields, .. } => Shape::Struct {
+                        fields,
+                        names: Some(to_strings(names)),
+                    },
+                    shape => shape,
+                },
+                (None, _) => Self::shape_from_repr(interner, repr)?,
+            },
+        };
+
+        Ok(Self {
+            shape,
+            size: interner.stack_size(layout) as usize,
+            alignment: interner.alignment_bytes(layout) as usize,
+        })
+    }
+
+    fn shape_from_repr(
+        interner: &GlobalLayoutInterner<'_>,
+        repr: LayoutRepr<'_>,
+    ) -> Result<Shape, String> {
+        let shape = match repr {
+            LayoutRepr::Builtin(Builtin::Int(width)) => Shape::Int(width),
+            LayoutRepr::Builtin(Builtin::Float(width)) => Shape::Float(width),
+            LayoutRepr::Builtin(Builtin::Decimal) => Shape::Dec,
+            LayoutRepr::Builtin(Builtin::Bool) => Shape::Bool,
+            LayoutRepr::Builtin(Builtin::Str) => Shape::Str,
+            LayoutRepr::Builtin(Builtin::List(element)) => {
+                Shape::List(Box::new(Self::from_layout(interner, element)?))
+            }
+            LayoutRepr::Struct(field_layouts) => {
+                let mut fields = Vec::with_capacity(field_layouts.len());
+                let mut offset = 0;
+
+                // like the fields of any struct, these are sorted by alignment already
+                for field_layout in field_layouts {
+                    fields.push((offset, Self::from_layout(interner, *field_layout)?.shape));
+                    offset += interner.stack_size(*field_layout) as usize;
+                }
+
+                Shape::Struct {
+                    fields,
+                    names: None,
+                }
+            }
+            LayoutRepr::Union(_) | LayoutRepr::RecursivePointer(_) | LayoutRepr::Ptr(_) => {
+                return Err("tag union with payloads".to_string())
+            }
+            LayoutRepr::LambdaSet(_) | LayoutRepr::FunctionPointer(_) | LayoutRepr::Erased(_) => {
+                return Err("function".to_string())
+            }
+        };
+
+        Ok(shape)
+    }
+}
+
+fn to_strings(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+/// A generated input.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    /// Every integer fits in an `i128`, except for `U128`s above `i128::MAX`, which aren't
+    /// generated.
+    Int(i128),
+    Float(f64),
+    /// The raw representation, i.e. the number times 10^18.
+    Dec(i128),
+    Bool(bool),
+    Str(String),
+    List(Vec<Value>),
+    Struct(Vec<Value>),
+    /// The id of a tag.
+    Tag(usize),
+}
+
+impl Value {
+    fn to_roc(&self, shape: &Shape) -> String {
+        let mut roc = String::new();
+        self.write_roc(shape, &mut roc);
+
+        roc
+    }
+
+    fn write_roc(&self, shape: &Shape, roc: &mut String) {
+        match (shape, self) {
+            (_, Value::Int(n)) => roc.push_str(&n.to_string()),
+            (_, Value::Float(f)) => roc.push_str(&format!("{f:?}")),
+            (_, Value::Dec(raw)) => roc.push_str(&RocDec::new(*raw).to_string()),
+            (_, Value::Bool(b)) => roc.push_str(if *b { "Bool.true" } else { "Bool.false" }),
+            (_, Value::Str(s)) => {
+                roc.push('"');
+
+                for c in s.chars() {
+                    match c {
+                        '"' => roc.push_str("\\\""),
+                        '\\' => roc.push_str("\\\\"),
+                        '$' => roc.push_str("\\$"),
+                        '\n' => roc.push_str("\\n"),
+                        '\r' => roc.push_str("\\r"),
+                        '\t' => roc.push_str("\\t"),
+                        c if c.is_control() => roc.push_str(&format!("\\u({:x})", c as u32)),
+                        c => roc.push(c),
+                    }
+                }
+
+                roc.push('"');
+            }
+            (Shape::List(element), Value::List(elements)) => {
+                roc.push('[');
+                write_separated(roc, elements.iter().map(|value| (&element.shape, value)));
+                roc.push(']');
+            }
+            (
+                Shape::Struct {
+                    fields: shapes,
+                    names: Some(names),
+                },
+                Value::Struct(fields),
+            ) => {
+                // shown in the order of the source, rather than that of the layout
+                let mut named: Vec<_> = names
+                    .iter()
+                    .zip(shapes.iter().zip(fields))
+                    .map(|(name, ((_, shape), value))| (name, shape, value))
+                    .collect();
+                named.sort_by_key(|(name, _, _)| *name);
+
+                if named.is_empty() {
+                    roc.push_str("{}");
+                    return;
+                }
+
+                roc.push_str("{ ");
+                for (index, (name, shape, value)) in named.into_iter().enumerate() {
+                    if index > 0 {
+                        roc.push_str(", ");
+                    }
+
+                    roc.push_str(name);
+                    roc.push_str(": ");
+                    value.write_roc(shape, roc);
+                }
+                roc.push_str(" }");
+            }
+            (
+                Shape::Struct {
+                    fields: shapes,
+                    names: None,
+                },
+                Value::Struct(fields),
+            ) => {
+                roc.push('(');
+                write_separated(roc, shapes.iter().map(|(_, shape)| shape).zip(fields));
+                roc.push(')');
+            }
+            (Shape::Tags(tags), Value::Tag(id)) => roc.push_str(&tags[*id]),
+            _ => internal_error!("the value {self:?} does not have the shape {shape:?}"),
+        }
+    }
+}
+
+fn write_separated<'v>(roc: &mut String, values: impl Iterator<Item = (&'v Shape, &'v Value)>) {
+    for (index, (shape, value)) in values.enumerate() {
+        if index > 0 {
+            roc.push_str(", ");
+        }
+
+        value.write_roc(shape, roc);
+    }
+}
+
+/// A small generator of random numbers. It's defined here, so that a seed keeps giving the same
+/// inputs.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `low..=high`, which must not span more than `u64::MAX` numbers.
+    fn between(&mut self, low: i128, high: i128) -> i128 {
+        let span = (high - low) as u128 + 1;
+
+        low + (self.next_u64() as u128 % span) as i128
+    }
+
+    fn one_in(&mut self, n: u64) -> bool {
+        self.next_u64() % n == 0
+    }
+
+    fn pick<T: Copy>(&mut self, options: &[T]) -> T {
+        options[self.next_u64() as usize % options.len()]
+    }
+}
+
+fn int_range(width: IntWidth) -> (i128, i128) {
+    use IntWidth::*;
+
+    match width {
+        U8 => (0, u8::MAX as i128),
+        U16 => (0, u16::MAX as i128),
+        U32 => (0, u32::MAX as i128),
+        U64 => (0, u64::MAX as i128),
+        U128 => (0, i128::MAX),
+        I8 => (i8::MIN as i128, i8::MAX as i128),
+        I16 => (i16::MIN as i128, i16::MAX as i128),
+        I32 => (i32::MIN as i128, i32::MAX as i128),
+        I64 => (i64::MIN as i128, i64::MAX as i128),
+        I128 => (i128::MIN, i128::MAX),
+    }
+}
+
+/// The raw representation of 1 as a Dec, which has 18 decimal places.
+const DEC_ONE: i128 = 1_000_000_000_000_000_000;
+const DEC_HUNDREDTH: i128 = DEC_ONE / 100;
+
+/// Generates a value which is about `size` big. Now and then an edge case is picked instead, like
+/// the largest number of a width.
+fn generate(shape: &Shape, rng: &mut SplitMix64, size: i128) -> Value {
+    match shape {
+        Shape::Int(width) => {
+            let (min, max) = int_range(*width);
+
+            if rng.one_in(10) {
+                Value::Int(rng.pick(&[0, 1, min, max]))
+            } else {
+                Value::Int(rng.between(min.max(-size), max.min(size)))
+            }
+        }
+        Shape::Float(width) => {
+            let f = if rng.one_in(10) {
+                rng.pick(&[0.0, 1.0, -1.0])
+            } else {
+                // hundredths, so that numbers with fractions are tried too
+                rng.between(-100 * size, 100 * size) as f64 / 100.0
+            };
+
+            match width {
+                // the value is shown as Roc sees it
+                FloatWidth::F32 => Value::Float(f as f32 as f64),
+                FloatWidth::F64 => Value::Float(f),
+            }
+        }
+        Shape::Dec => Value::Dec(rng.between(-100 * size, 100 * size) * DEC_HUNDREDTH),
+        Shape::Bool => Value::Bool(rng.one_in(2)),
+        Shape::Str => {
+            let length = rng.between(0, size);
+            let string = (0..length)
+                .map(|_| {
+                    if rng.one_in(10) {
+                        rng.pick(&UNUSUAL_CHARS)
+                    } else {
+                        // printable ASCII
+                        rng.between(0x20, 0x7e) as u8 as char
+                    }
+                })
+                .collect();
+
+            Value::Str(string)
+        }
+        Shape::List(element) => {
+            let length = rng.between(0, size);
+            let elements = (0..length)
+                .map(|_| generate(&element.shape, rng, size))
+                .collect();
+
+            Value::List(elements)
+        }
+        Shape::Struct { fields, .. } => {
+            let fields = fields
+                .iter()
+                .map(|(_, field)| generate(field, rng, size))
+                .collect();
+
+            Value::Struct(fields)
+        }
+        Shape::Tags(tags) => Value::Tag(rng.between(0, tags.len() as i128 - 1) as usize),
+    }
+}
+
+/// Values which are smaller than `value`, the most promising ones first. Numbers get closer to
+/// zero, and strings and lists get shorter before their elements get smaller.
+fn smaller_values(shape: &Shape, value: &Value) -> Vec<Value> {
+    match (shape, value) {
+        (Shape::Int(width), Value::Int(n)) => {
+            let (_, max) = int_range(*width);
+
+            smaller_ints(*n, max).into_iter().map(Value::Int).collect()
+        }
+        (Shape::Float(_), Value::Float(f)) => {
+            let f = *f;
+            let mut smaller = vec![];
+
+            if f != 0.0 {
+                smaller.push(0.0);
+            }
+            if f.fract() != 0.0 {
+                smaller.push(f.trunc());
+            }
+            if f.abs() >= 2.0 {
+                smaller.push((f / 2.0).trunc());
+            }
+            if f < 0.0 {
+                smaller.push(-f);
+            }
+
+            smaller.into_iter().map(Value::Float).collect()
+        }
+        (Shape::Dec, Value::Dec(raw)) => {
+            let raw = *raw;
+            let mut smaller = vec![];
+
+            if raw != 0 {
+                smaller.push(0);
+            }
+            if raw % DEC_ONE != 0 {
+                smaller.push(raw - raw % DEC_ONE);
+            }
+            if raw.abs() >= 2 * DEC_ONE {
+                smaller.push(raw / 2 - (raw / 2) % DEC_ONE);
+            }
+            if raw < 0 {
+                smaller.push(-raw);
+            }
+
+            smaller.into_iter().map(Value::Dec).collect()
+        }
+        (Shape::Bool, Value::Bool(true)) => vec![Value::Bool(false)],
+        (Shape::Bool, Value::Bool(false)) => vec![],
+        (Shape::Str, Value::Str(s)) => {
+            let chars: Vec<char> = s.chars().collect();
+            let simpler_chars =
+                chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| **c != 'a')
+                    .map(|(index, _)| {
+                        let mut simpler = chars.clone();
+                        simpler[index] = 'a';
+
+                        simpler
+                    });
+
+            shorter(&chars)
+                .into_iter()
+                .chain(simpler_chars)
+                .map(|chars| Value::Str(chars.into_iter().collect()))
+                .collect()
+        }
+        (Shape::List(element), Value::List(elements)) => {
+            let smaller_elements = elements.iter().enumerate().flat_map(|(index, value)| {
+                smaller_values(&element.shape, value)
+                    .into_iter()
+                    .map(move |smaller| {
+                        let mut elements = elements.clone();
+                        elements[index] = smaller;
+
+                        elements
+                    })
+            });
+
+            shorter(elements)
+                .into_iter()
+                .chain(smaller_elements)
+                .map(Value::List)
+                .collect()
+        }
+        (Shape::Struct { fields: shapes, .. }, Value::Struct(fields)) => shapes
+            .iter()
+            .zip(fields)
+            .enumerate()
+            .flat_map(|(index, ((_, shape), value))| {
+                smaller_values(shape, value)
+                    .into_iter()
+                    .map(move |smaller| {
+                        let mut fields = fields.clone();
+                        fields[index] = smaller;
+
+                        Value::Struct(fields)
+                    })
+            })
+            .collect(),
+        // towards the first tag
+        (Shape::Tags(_), Value::Tag(id)) => (0..*id).map(Value::Tag).collect(),
+        _ => internal_error!("the value {value:?} does not have the shape {shape:?}"),
+    }
+}
+
+/// Integers which are closer to zero than `n`, like 0, half of it and one less, but none above
+/// `max`.
+fn smaller_ints(n: i128, max: i128) -> Vec<i128> {
+    if n == 0 {
+        return vec![];
+    }
+
+    let mut smaller = vec![0];
+
+    if let Some(positive) = n.checked_neg().filter(|positive| n < 0 && *positive <= max) {
+        smaller.push(positive);
+    }
+
+    // closer and closer to n, so that a bound is found quickly
+    let mut distance = n / 2;
+    while distance != 0 {
+        smaller.push(n - distance);
+        distance /= 2;
+    }
+
+    smaller.push(n - n.signum());
+    smaller.dedup();
+
+    smaller
+}
+
+/// Shorter versions of a sequence: the empty one, its halves, and every one missing an element.
+fn shorter<T: Clone>(elements: &[T]) -> Vec<Vec<T>> {
+    if elements.is_empty() {
+        return vec![];
+    }
+
+    let mut shorter = vec![vec![]];
+
+    if elements.len() >= 2 {
+        let (front, back) = elements.split_at(elements.len() / 2);
+        shorter.push(front.to_vec());
+        shorter.push(back.to_vec());
+    }
+
+    for index in 0..elements.len() {
+        let mut without = elements.to_vec();
+        without.remove(index);
+
+        shorter.push(without);
+    }
+
+    shorter
+}
+
+fn allocate(arena: &Bump, size: usize, alignment: usize) -> *mut u8 {
+    let layout = std::alloc::Layout::from_size_align(size, alignment.max(1))
+        .unwrap_or_else(|_| internal_error!("invalid alignment {alignment}"));
+
+    arena.alloc_layout(layout).as_ptr()
+}
+
+/// Allocates the elements of a list or string like Roc does, behind room for the number of
+/// elements and a refcount. The refcount is constant, so Roc never frees or changes them.
+fn allocate_elements(arena: &Bump, elements: usize, size: usize, alignment: usize) -> *mut u8 {
+    let word = std::mem::size_of::<usize>();
+    let alignment = alignment.max(word);
+    let header = (2 * word).max(alignment);
+
+    let data = unsafe { allocate(arena, header + elements * size, alignment).add(header) };
+
+    unsafe {
+        let words = data.cast::<usize>();
+        words.sub(2).write(elements);
+        words.sub(1).write(ROC_REFCOUNT_CONSTANT);
+    }
+
+    data
+}
+
+/// Writes `value` to `ptr` like Roc lays it out. What it points to is allocated in `arena`.
+unsafe fn write_value(arena: &Bump, shape: &Shape, value: &Value, ptr: *mut u8) {
+    match (shape, value) {
+        (Shape::Int(width), Value::Int(n)) => {
+            let bytes = n.to_ne_bytes();
+            let size = width.stack_size() as usize;
+
+            // the host is little endian, so the low bytes come first
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, size);
+        }
+        (Shape::Float(FloatWidth::F32), Value::Float(f)) => {
+            ptr.cast::<f32>().write_unaligned(*f as f32);
+        }
+        (Shape::Float(FloatWidth::F64), Value::Float(f)) => {
+            ptr.cast::<f64>().write_unaligned(*f);
+        }
+        (Shape::Dec, Value::Dec(raw)) => {
+            ptr.cast::<i128>().write_unaligned(*raw);
+        }
+        (Shape::Bool, Value::Bool(b)) => {
+            ptr.write(*b as u8);
+        }
+        (Shape::Str, Value::Str(s)) => {
+            let words = if s.is_empty() {
+                // the empty small string
+                [0, 0, isize::MIN as usize]
+            } else {
+                let bytes = allocate_elements(arena, s.len(), 1, 1);
+                std::ptr::copy_nonoverlapping(s.as_ptr(), bytes, s.len());
+
+                [bytes as usize, s.len(), s.len()]
+            };
+
+            ptr.cast::<[usize; 3]>().write_unaligned(words);
+        }
+        (Shape::List(element), Value::List(elements)) => {
+            let words = if elements.is_empty() {
+                [0, 0, 0]
+            } else {
+                let data =
+                    allocate_elements(arena, elements.len(), element.size, element.alignment);
+
+                for (index, value) in elements.iter().enumerate() {
+                    write_value(arena, &element.shape, value, data.add(index * element.size));
+                }
+
+                [data as usize, elements.len(), elements.len()]
+            };
+
+            ptr.cast::<[usize; 3]>().write_unaligned(words);
+        }
+        (Shape::Struct { fields: shapes, .. }, Value::Struct(fields)) => {
+            for ((offset, shape), value) in shapes.iter().zip(fields) {
+                write_value(arena, shape, value, ptr.add(*offset));
+            }
+        }
+        (Shape::Tags(tags), Value::Tag(id)) => {
+            // a union of a single tag takes up no room
+            if tags.len() > 1 {
+                ptr.write(*id as u8);
+            }
+        }
+        _ => internal_error!("the value {value:?} does not have the shape {shape:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(shape: Shape) -> Slot {
+        Slot {
+            shape,
+            size: 0,
+            alignment: 1,
+        }
+    }
+
+    fn int(n: i128) -> Value {
+        Value::Int(n)
+    }
+
+    #[test]
+    fn shrinks_int_to_bound() {
+        let slots = [slot(Shape::Int(IntWidth::I64))];
+        let mut falsifies = |inputs: &[Value]| matches!(inputs[0], Value::Int(n) if n >= 1000);
+
+        let (inputs, shrinks) = shrink(&slots, vec![int(i64::MAX as i128)], &mut falsifies);
+
+        assert_eq!(inputs, [int(1000)]);
+        assert!(shrinks > 0);
+    }
+
+    #[test]
+    fn shrinks_list_length_and_elements() {
+        let slots = [slot(Shape::List(Box::new(Slot {
+            shape: Shape::Int(IntWidth::U8),
+            size: 1,
+            alignment: 1,
+        })))];
+        let mut falsifies = |inputs: &[Value]| matches!(&inputs[0], Value::List(l) if l.len() >= 3);
+
+        let list = Value::List(vec![int(7), int(200), int(3), int(9), int(1)]);
+        let (inputs, _) = shrink(&slots, vec![list], &mut falsifies);
+
+        assert_eq!(inputs, [Value::List(vec![int(0), int(0), int(0)])]);
+    }
+
+    #[test]
+    fn shrinks_str_towards_a() {
+        let slots = [slot(Shape::Str)];
+        let mut falsifies = |inputs: &[Value]| matches!(&inputs[0], Value::Str(s) if s.len() >= 2);
+
+        let (inputs, _) = shrink(&slots, vec![Value::Str("xyzλ".into())], &mut falsifies);
+
+        assert_eq!(inputs, [Value::Str("aa".into())]);
+    }
+
+    #[test]
+    fn search_is_seeded() {
+        let slots = [slot(Shape::Int(IntWidth::I32)), slot(Shape::Str)];
+        let options = PropertyOptions { runs: 50, seed: 7 };
+
+        let mut seen = vec![];
+        let mut record = |inputs: &[Value]| {
+            seen.push(inputs.to_vec());
+            false
+        };
+        assert!(search(&slots, options, &mut record).is_none());
+
+        let mut seen_again = vec![];
+        let mut record_again = |inputs: &[Value]| {
+            seen_again.push(inputs.to_vec());
+            false
+        };
+        search(&slots, options, &mut record_again);
+
+        assert_eq!(seen.len(), 50);
+        assert_eq!(seen, seen_again);
+    }
+
+    #[test]
+    fn generated_ints_fit_their_width() {
+        let mut rng = SplitMix64(3);
+
+        for _ in 0..1000 {
+            match generate(&Shape::Int(IntWidth::U8), &mut rng, 1000) {
+                Value::Int(n) => assert!((0..=255).contains(&n)),
+                other => panic!("{other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn renders_roc() {
+        let shape = Shape::Struct {
+            fields: vec![
+                (0, Shape::List(Box::new(slot(Shape::Int(IntWidth::I64))))),
+                (24, Shape::Str),
+                (48, Shape::Dec),
+                (64, Shape::Bool),
+                (72, Shape::Float(FloatWidth::F64)),
+            ],
+            names: None,
+        };
+        let value = Value::Struct(vec![
+            Value::List(vec![int(1), int(-2)]),
+            Value::Str("a\"$\n".into()),
+            Value::Dec(3 * DEC_ONE / 2),
+            Value::Bool(true),
+            Value::Float(2.0),
+        ]);
+
+        assert_eq!(
+            value.to_roc(&shape),
+            r#"([1, -2], "a\"\$\n", 1.5, Bool.true, 2.0)"#
+        );
+    }
+
+    #[test]
+    fn renders_records_by_field_name() {
+        let color = Shape::Tags(vec!["Blue".into(), "Green".into(), "Red".into()]);
+        // laid out by alignment, and shown in the order of their names
+        let shape = Shape::Struct {
+            fields: vec![(0, Shape::Int(IntWidth::U64)), (8, color)],
+            names: Some(vec!["size".into(), "color".into()]),
+        };
+        let value = Value::Struct(vec![int(3), Value::Tag(1)]);
+
+        assert_eq!(value.to_roc(&shape), "{ color: Green, size: 3 }");
+    }
+
+    #[test]
+    fn shrinks_tags_towards_the_first() {
+        let slots = [slot(Shape::Tags(vec!["A".into(), "B".into(), "C".into()]))];
+        let mut falsifies = |inputs: &[Value]| matches!(inputs[0], Value::Tag(id) if id >= 1);
+
+        let (inputs, _) = shrink(&slots, vec![Value::Tag(2)], &mut falsifies);
+
+        assert_eq!(inputs, [Value::Tag(1)]);
+    }
+
+    #[test]
+    fn falsified_round_trips() {
+        let falsified = Falsified {
+            runs: 12,
+            shrinks: 3,
+            seed: 42,
+            inputs: vec!["[0, 0]".into(), "\"a\"".into()],
+            crash: Some("Hit a crash!\non two lines".into()),
+        };
+
+        assert_eq!(Falsified::decode(&falsified.encode()), Some(falsified));
+
+        let failed = Falsified::decode("1\u{0}0\u{0}5\u{0}\u{0}1000").unwrap();
+        assert_eq!(failed.inputs, ["1000"]);
+        assert_eq!(failed.crash, None);
+    }
+}
@@ -1,6 +1,12 @@
-use std::sync::{
-    atomic::{AtomicBool, AtomicU32},
-    Arc,
+use std::{
+    io::Write,
+    mem::MaybeUninit,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU32},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 use bumpalo::collections::Vec as BumpVec;
@@ -19,27 +25,62 @@ use roc_load::{Expectations, MonomorphizedModule};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::{
     ir::OptLevel,
-    layout::{GlobalLayoutInterner, STLayoutInterner},
+    layout::{GlobalLayoutInterner, InLayout, STLayoutInterner},
 };
 use roc_region::all::Region;
 use roc_reporting::{error::expect::Renderer, report::RenderTarget};
 use roc_target::Target;
 use roc_types::subs::Subs;
 
+use crate::bench::BenchSamples;
+use crate::child::Child;
+use crate::property::{check_property, Falsified, PropertyOptions};
+use crate::snapshot::{check_snapshot, diff_lines, snapshot_path, SnapshotCheck};
+
 pub struct ExpectMemory<'a> {
     ptr: *mut u8,
     length: usize,
+    /// Whether the memory was mapped for this, and so must be unmapped when it's dropped.
+    mapped: bool,
     _marker: std::marker::PhantomData<&'a ()>,
 }
 
 impl<'a> ExpectMemory<'a> {
     const SHM_SIZE: usize = 1024;
 
-    #[cfg(test)]
     pub(crate) fn from_slice(slice: &mut [u8]) -> Self {
         Self {
             ptr: slice.as_mut_ptr(),
             length: slice.len(),
+            mapped: false,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Memory which the child processes forked after this is created share with this process.
+    pub fn shared_with_children() -> Self {
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                Self::SHM_SIZE,
+                libc::PROT_WRITE | libc::PROT_READ,
+                libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            internal_error!("failed to mmap shared memory")
+        }
+
+        // puts in the initial header
+        let _ = ExpectSequence::new(ptr as *mut u8);
+
+        Self {
+            ptr: ptr.cast(),
+            length: Self::SHM_SIZE,
+            mapped: true,
             _marker: std::marker::PhantomData,
         }
     }
@@ -92,11 +133,12 @@ impl<'a> ExpectMemory<'a> {
         Self {
             ptr: ptr.cast(),
             length: Self::SHM_SIZE,
+            mapped: true,
             _marker: std::marker::PhantomData,
         }
     }
 
-    fn set_shared_buffer(&mut self, lib: &libloading::Library) {
+    pub(crate) fn set_shared_buffer(&mut self, lib: &libloading::Library) {
         let set_shared_buffer = run_roc_dylib!(lib, "set_shared_buffer", (*mut u8, usize), ());
         let mut result = RocCallResult::default();
         unsafe { set_shared_buffer((self.ptr, self.length), &mut result) };
@@ -111,8 +153,78 @@ impl<'a> ExpectMemory<'a> {
         let mut sequence = ExpectSequence { ptr: self.ptr };
         sequence.reset();
     }
+
+    pub(crate) fn count_failures(&self) -> usize {
+        ExpectSequence { ptr: self.ptr }.count_failures()
+    }
+}
+
+impl Drop for ExpectMemory<'_> {
+    fn drop(&mut self) {
+        if self.mapped {
+            unsafe { libc::munmap(self.ptr.cast(), self.length) };
+        }
+    }
+}
+
+/// The outcome of running one top-level expect.
+#[derive(Debug)]
+pub struct ExpectResult {
+    pub symbol: Symbol,
+    pub region: Region,
+    pub duration: Duration,
+    /// The report of the failure as it was written, if the expect failed.
+    pub failure: Option<String>,
+}
+
+impl ExpectResult {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// How `run_toplevel_expects` runs the expects.
+#[derive(Debug, Clone, Copy)]
+pub struct ExpectOptions {
+    /// How many expects may run at once.
+    pub workers: usize,
+    /// How long an expect may run before it's stopped and counted as failed. For a property, this
+    /// is how long checking it with all of its inputs may take.
+    pub timeout: Option<Duration>,
+    pub property: PropertyOptions,
+    /// Whether to store the values of snapshots which are missing or different, rather than fail.
+    pub update_snapshots: bool,
 }
 
+/// How running a single expect ended.
+pub(crate) enum ExpectOutcome {
+    /// It ran to completion, though it may have failed.
+    Finished,
+    /// It called `crash`, or hit a runtime error which Roc reports itself.
+    Panicked(String),
+    /// Its process died, e.g. from a segfault or a stack overflow.
+    Crashed(String),
+    TimedOut(Duration),
+    /// It is a property, and an input was found which falsifies it.
+    Falsified(Falsified),
+    /// It is a property with an argument of a type which can't be generated, as described.
+    Unsupported(String),
+    /// It is a benchmark, and took these times.
+    Measured(BenchSamples),
+}
+
+/// Where a child process runs expects.
+struct Worker<'a> {
+    memory: ExpectMemory<'a>,
+    /// The process running the expects, which is spawned again if one crashed or timed out.
+    child: Option<Child>,
+    /// The index of the expect being run.
+    running: Option<usize>,
+}
+
+/// Runs every expect in a child process, so that one which crashes or never finishes doesn't
+/// stop the others. Up to `options.workers` of them run at once, but their failures are written
+/// in the order of `expects_by_module`, and their results are returned in that order too.
 #[allow(clippy::too_many_arguments)]
 pub fn run_toplevel_expects<'a, W: std::io::Write>(
     writer: &mut W,
@@ -122,24 +234,240 @@ pub fn run_toplevel_expects<'a, W: std::io::Write>(
     layout_interner: &GlobalLayoutInterner<'a>,
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
-    expects: ExpectFunctions<'_>,
-) -> std::io::Result<(usize, usize)> {
-    let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
-    let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+    expects_by_module: Vec<(ModuleId, ExpectFunctions<'_>)>,
+    options: ExpectOptions,
+) -> std::io::Result<Vec<(ModuleId, Vec<ExpectResult>)>> {
+    let expects: Vec<(usize, ToplevelExpect)> = expects_by_module
+        .iter()
+        .enumerate()
+        .flat_map(|(index, (_, functions))| {
+            functions.pure.iter().map(move |expect| (index, *expect))
+        })
+        .collect();
 
-    run_expects_with_memory(
-        writer,
-        render_target,
-        arena,
-        interns,
-        layout_interner,
-        lib,
-        expectations,
-        expects,
-        &mut memory,
-    )
+    // Every worker has memory of its own for the failures of its expect
+    let worker_count = options.workers.clamp(1, expects.len().max(1));
+    let mut workers: Vec<_> = (0..worker_count)
+        .map(|_| Worker {
+            memory: ExpectMemory::shared_with_children(),
+            child: None,
+            running: None,
+        })
+        .collect();
+
+    let mut results: Vec<Option<ExpectResult>> = expects.iter().map(|_| None).collect();
+
+    // Snapshots sharing a file would overwrite each other's value, so they fail without running
+    for (index, (path, other_region)) in snapshot_clashes(expectations, &expects) {
+        let expect = expects[index].1;
+        let failure = render_snapshot_clash(
+            render_target,
+            arena,
+            interns,
+            expectations,
+            expect,
+            &path,
+            other_region,
+        )?;
+
+        results[index] = Some(ExpectResult {
+            symbol: expect.symbol,
+            region: expect.region,
+            duration: Duration::ZERO,
+            failure: Some(failure),
+        });
+    }
+
+    let mut next_to_run = 0;
+    let mut next_to_write = 0;
+
+    while next_to_write < expects.len() {
+        for worker_index in 0..workers.len() {
+            while results.get(next_to_run).is_some_and(Option::is_some) {
+                next_to_run += 1;
+            }
+
+            if workers[worker_index].running.is_some() || next_to_run == expects.len() {
+                continue;
+            }
+
+            if workers[worker_index].child.is_none() {
+                let inherited: Vec<_> = workers
+                    .iter()
+                    .filter_map(|worker| worker.child.as_ref())
+                    .flat_map(Child::fds)
+                    .collect();
+
+                writer.flush()?;
+
+                let worker = &mut workers[worker_index];
+
+                // The child writes its failures to whichever memory this was last set to
+                worker.memory.set_shared_buffer(lib);
+
+                let memory = &mut worker.memory;
+                worker.child = Some(Child::spawn(&inherited, |index| {
+                    memory.reset();
+                    run_expect(
+                        lib,
+                        layout_interner,
+                        memory,
+                        expects[index].1,
+                        options.property,
+                    )
+                })?);
+            }
+
+            let worker = &mut workers[worker_index];
+            if let Some(child) = worker.child.as_mut() {
+                child.start(next_to_run)?;
+            }
+            worker.running = Some(next_to_run);
+            next_to_run += 1;
+        }
+
+        wait_for_outcomes(&workers, options.timeout)?;
+
+        for worker in workers.iter_mut() {
+            let (Some(index), Some(child)) = (worker.running, worker.child.as_mut()) else {
+                continue;
+            };
+
+            let (outcome, duration) = match child.try_finish()? {
+                Some(finished) => finished,
+                None => match options.timeout {
+                    Some(timeout) if child.elapsed() >= timeout => {
+                        let child = worker.child.take().unwrap();
+                        let duration = child.elapsed();
+
+                        (child.kill(timeout)?, duration)
+                    }
+                    _ => continue,
+                },
+            };
+
+            if worker.child.as_ref().is_some_and(Child::has_exited) {
+                worker.child = None;
+            }
+            worker.running = None;
+
+            let expect = expects[index].1;
+            let failure = render_outcome(
+                render_target,
+                arena,
+                interns,
+                layout_interner,
+                expectations,
+                &worker.memory,
+                expect,
+                outcome,
+                options.update_snapshots,
+            )?;
+
+            results[index] = Some(ExpectResult {
+                symbol: expect.symbol,
+                region: expect.region,
+                duration,
+                failure,
+            });
+        }
+
+        // Failures are written in order, once all the expects before them have finished
+        while let Some(Some(result)) = results.get(next_to_write) {
+            if let Some(failure) = &result.failure {
+                write!(writer, "{failure}")?;
+            }
+
+            next_to_write += 1;
+        }
+    }
+
+    let mut results_by_module: Vec<_> = expects_by_module
+        .iter()
+        .map(|(module_id, _)| (*module_id, Vec::new()))
+        .collect();
+
+    for ((index, _), result) in expects.into_iter().zip(results) {
+        results_by_module[index].1.extend(result);
+    }
+
+    Ok(results_by_module)
+}
+
+/// The snapshots among `expects` whose values would be stored in the same file as another's, by
+/// index, along with that file and the region of another snapshot stored in it.
+fn snapshot_clashes(
+    expectations: &VecMap<ModuleId, Expectations>,
+    expects: &[(usize, ToplevelExpect)],
+) -> MutMap<usize, (PathBuf, Region)> {
+    let mut by_path: MutMap<PathBuf, Vec<usize>> = MutMap::default();
+
+    for (index, (_, expect)) in expects.iter().enumerate() {
+        if let Some(name) = expect.snapshot {
+            let module_path = &expectations.get(&expect.symbol.module_id()).unwrap().path;
+
+            by_path
+                .entry(snapshot_path(module_path, name))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    let mut clashes = MutMap::default();
+
+    for (path, indices) in by_path {
+        for &index in &indices {
+            if let Some(&other) = indices.iter().find(|&&other| other != index) {
+                clashes.insert(index, (path.clone(), expects[other].1.region));
+            }
+        }
+    }
+
+    clashes
+}
+
+/// Blocks until one of the running expects ended, or the first of them to time out did.
+fn wait_for_outcomes(workers: &[Worker], timeout: Option<Duration>) -> std::io::Result<()> {
+    let running = workers
+        .iter()
+        .filter(|worker| worker.running.is_some())
+        .filter_map(|worker| worker.child.as_ref());
+
+    let mut fds: Vec<_> = running
+        .clone()
+        .map(|child| libc::pollfd {
+            fd: child.outcome_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        })
+        .collect();
+
+    if fds.is_empty() {
+        return Ok(());
+    }
+
+    let poll_timeout = match timeout {
+        None => -1,
+        Some(timeout) => running
+            .map(|child| timeout.saturating_sub(child.elapsed()))
+            .min()
+            .map_or(-1, |left| left.as_millis().min(i32::MAX as u128) as i32 + 1),
+    };
+
+    if unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, poll_timeout) } == -1 {
+        let err = std::io::Error::last_os_error();
+
+        // A signal interrupting the wait is like waking up with nothing to read
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+
+    Ok(())
 }
 
+/// Runs the expects one after the other in this process, so an expect which crashes takes the
+/// process down with it.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     writer: &mut W,
@@ -151,12 +479,11 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
     memory: &mut ExpectMemory,
-) -> std::io::Result<(usize, usize)> {
-    let mut failed = 0;
-    let mut passed = 0;
-
+) -> std::io::Result<Vec<ExpectResult>> {
     memory.set_shared_buffer(lib);
 
+    let mut results = Vec::with_capacity(expects.pure.len());
+
     for expect in expects.pure {
         let result = run_expect_pure(
             writer,
@@ -170,13 +497,10 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expect,
         )?;
 
-        match result {
-            true => passed += 1,
-            false => failed += 1,
-        }
+        results.push(result);
     }
 
-    Ok((failed, passed))
+    Ok(results)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -190,51 +514,296 @@ fn run_expect_pure<'a, W: std::io::Write>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     shared_memory: &mut ExpectMemory,
     expect: ToplevelExpect<'_>,
-) -> std::io::Result<bool> {
-    use roc_gen_llvm::try_run_jit_function;
-
-    let sequence = ExpectSequence::new(shared_memory.ptr.cast());
-
-    let result: Result<(), (String, _)> = try_run_jit_function!(lib, expect.name, (), |v: ()| v);
-
-    let shared_memory_ptr: *const u8 = shared_memory.ptr.cast();
-
-    if result.is_err() || sequence.count_failures() > 0 {
-        let module_id = expect.symbol.module_id();
-        let data = expectations.get_mut(&module_id).unwrap();
-
-        let path = &data.path;
-        let filename = data.path.to_owned();
-        let source = std::fs::read_to_string(path).unwrap();
-
-        let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
-
-        if let Err((roc_panic_message, _roc_panic_tag)) = result {
-            renderer.render_panic(writer, &roc_panic_message, expect.region)?;
-        } else {
-            let mut offset = ExpectSequence::START_OFFSET;
-
-            for _ in 0..sequence.count_failures() {
-                offset = render_expect_failure(
-                    writer,
-                    &renderer,
-                    arena,
-                    Some(expect),
-                    expectations,
-                    interns,
-                    layout_interner,
-                    shared_memory_ptr,
-                    offset,
-                )?;
+) -> std::io::Result<ExpectResult> {
+    shared_memory.reset();
+
+    let start = Instant::now();
+    let outcome = run_expect(
+        lib,
+        layout_interner,
+        shared_memory,
+        expect,
+        PropertyOptions::default(),
+    );
+    let duration = start.elapsed();
+
+    let failure = render_outcome(
+        render_target,
+        arena,
+        interns,
+        layout_interner,
+        expectations,
+        shared_memory,
+        expect,
+        outcome,
+        false,
+    )?;
+
+    if let Some(failure) = &failure {
+        write!(writer, "{failure}")?;
+    }
+
+    Ok(ExpectResult {
+        symbol: expect.symbol,
+        region: expect.region,
+        duration,
+        failure,
+    })
+}
+
+/// Runs an expect in this process. A property is checked with many generated inputs.
+pub(crate) fn run_expect(
+    lib: &libloading::Library,
+    layout_interner: &GlobalLayoutInterner<'_>,
+    memory: &mut ExpectMemory,
+    expect: ToplevelExpect<'_>,
+    property_options: PropertyOptions,
+) -> ExpectOutcome {
+    let function = expect_function(lib, &expect);
+
+    if expect.arguments.is_empty() {
+        match call_expect(&function) {
+            Ok(()) => ExpectOutcome::Finished,
+            Err(message) => ExpectOutcome::Panicked(message),
+        }
+    } else {
+        check_property(
+            lib,
+            &function,
+            layout_interner,
+            expect.arguments,
+            memory,
+            property_options,
+        )
+    }
+}
+
+pub(crate) fn expect_function<'lib>(
+    lib: &'lib libloading::Library,
+    expect: &ToplevelExpect,
+) -> libloading::Symbol<'lib, unsafe extern "C" fn(*mut RocCallResult<()>)> {
+    unsafe { lib.get(expect.name.as_bytes()) }
+        .unwrap_or_else(|_| internal_error!("Unable to JIT compile `{}`", expect.name))
+}
+
+/// Calls the function of an expect, returning the message of the crash if it crashed.
+pub(crate) fn call_expect(
+    function: &unsafe extern "C" fn(*mut RocCallResult<()>),
+) -> Result<(), String> {
+    let mut result = MaybeUninit::uninit();
+
+    let result: Result<(), (String, _)> = unsafe {
+        function(result.as_mut_ptr());
+        result.assume_init().into()
+    };
+
+    result.map_err(|(message, _tag)| message)
+}
+
+/// Renders the report of an expect which failed, reading its failures from `memory`. Returns
+/// `None` if it passed.
+#[allow(clippy::too_many_arguments)]
+fn render_outcome<'a>(
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    memory: &ExpectMemory,
+    expect: ToplevelExpect<'_>,
+    outcome: ExpectOutcome,
+    update_snapshots: bool,
+) -> std::io::Result<Option<String>> {
+    let sequence = ExpectSequence { ptr: memory.ptr };
+
+    if matches!(outcome, ExpectOutcome::Finished) && sequence.count_failures() == 0 {
+        return Ok(None);
+    }
+
+    let mut buffer = Vec::new();
+    let writer = &mut buffer;
+
+    let module_id = expect.symbol.module_id();
+    let data = expectations.get_mut(&module_id).unwrap();
+
+    let path = &data.path;
+    let filename = data.path.to_owned();
+    let source = std::fs::read_to_string(path).unwrap();
+
+    let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
+
+    if let (Some(name), ExpectOutcome::Finished) = (expect.snapshot, &outcome) {
+        let value = snapshot_value(&renderer, arena, interns, layout_interner, data, &sequence);
+        let snapshot_path = snapshot_path(&data.path, name);
+
+        match check_snapshot(&snapshot_path, &value, update_snapshots)? {
+            SnapshotCheck::Matched | SnapshotCheck::Updated => return Ok(None),
+            SnapshotCheck::Missing => {
+                renderer.render_missing_snapshot(writer, &value, &snapshot_path, expect.region)?;
+            }
+            SnapshotCheck::Mismatched { stored } => {
+                let diff = diff_lines(&stored, &value);
+                renderer.render_snapshot_mismatch(writer, &diff, &snapshot_path, expect.region)?;
             }
         }
 
         writeln!(writer)?;
 
-        Ok(false)
-    } else {
-        Ok(true)
+        return Ok(Some(String::from_utf8_lossy(&buffer).into_owned()));
     }
+
+    match outcome {
+        ExpectOutcome::Panicked(message) | ExpectOutcome::Crashed(message) => {
+            renderer.render_panic(writer, &message, expect.region)?;
+        }
+        ExpectOutcome::TimedOut(timeout) => {
+            renderer.render_timeout(writer, timeout, expect.region)?;
+        }
+        ExpectOutcome::Unsupported(description) => {
+            renderer.render_unsupported_property(writer, &description, expect.region)?;
+        }
+        ExpectOutcome::Falsified(falsified) => {
+            // The inputs are shown with the failure, unless the property crashed before that
+            let inputs: &[String] = match &falsified.crash {
+                Some(message) => {
+                    renderer.render_panic(writer, message, expect.region)?;
+                    &falsified.inputs
+                }
+                None => {
+                    render_expect_failures(
+                        writer,
+                        &renderer,
+                        arena,
+                        expect,
+                        expectations,
+                        interns,
+                        layout_interner,
+                        &sequence,
+                    )?;
+                    &[]
+                }
+            };
+
+            renderer.render_falsified(
+                writer,
+                falsified.runs,
+                falsified.shrinks,
+                falsified.seed,
+                inputs,
+            )?;
+        }
+        ExpectOutcome::Measured(_) => {
+            internal_error!("only `roc bench` times benchmarks, and it doesn't report them")
+        }
+        ExpectOutcome::Finished => {
+            render_expect_failures(
+                writer,
+                &renderer,
+                arena,
+                expect,
+                expectations,
+                interns,
+                layout_interner,
+                &sequence,
+            )?;
+        }
+    }
+
+    writeln!(writer)?;
+
+    Ok(Some(String::from_utf8_lossy(&buffer).into_owned()))
+}
+
+/// Renders the report of a snapshot which shares the file at `path` with the one at
+/// `other_region`.
+fn render_snapshot_clash<'a>(
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    expectations: &VecMap<ModuleId, Expectations>,
+    expect: ToplevelExpect<'_>,
+    path: &Path,
+    other_region: Region,
+) -> std::io::Result<String> {
+    let mut buffer = Vec::new();
+
+    let module_id = expect.symbol.module_id();
+    let filename = expectations.get(&module_id).unwrap().path.to_owned();
+    let source = std::fs::read_to_string(&filename)?;
+
+    let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
+    renderer.render_snapshot_clash(&mut buffer, path, expect.region, other_region)?;
+
+    writeln!(buffer)?;
+
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+/// The value of a snapshot, which it sent as the only failure of its expect.
+fn snapshot_value<'a>(
+    renderer: &Renderer,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    data: &Expectations,
+    sequence: &ExpectSequence,
+) -> String {
+    // we always run programs as the host
+    let target = target_lexicon::Triple::host().into();
+
+    let frame = ExpectFrame::at_offset(sequence.ptr, ExpectSequence::START_OFFSET);
+    let lookups = match data.expectations.get(&frame.region) {
+        None => internal_error!("region {:?} not in list of expects", frame.region),
+        Some(lookups) => lookups,
+    };
+
+    // mono drops lookups of functions, which the REPL doesn't show either
+    if split_expect_lookups(&data.subs, lookups).is_empty() {
+        return "<function>".to_string();
+    }
+
+    let (_, expressions, _) = crate::get_values(
+        target,
+        arena,
+        &data.subs,
+        interns,
+        layout_interner,
+        sequence.ptr,
+        frame.start_offset,
+        1,
+    );
+
+    renderer.format_value(&expressions[0]).to_string()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_expect_failures<'a>(
+    writer: &mut impl std::io::Write,
+    renderer: &Renderer,
+    arena: &'a Bump,
+    expect: ToplevelExpect<'_>,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    sequence: &ExpectSequence,
+) -> std::io::Result<()> {
+    let mut offset = ExpectSequence::START_OFFSET;
+
+    for _ in 0..sequence.count_failures() {
+        offset = render_expect_failure(
+            writer,
+            renderer,
+            arena,
+            Some(expect),
+            expectations,
+            interns,
+            layout_interner,
+            sequence.ptr,
+            offset,
+        )?;
+    }
+
+    Ok(())
 }
 
 pub fn render_expects_in_memory<'a>(
@@ -444,6 +1013,12 @@ pub struct ToplevelExpect<'a> {
     pub name: &'a str,
     pub symbol: Symbol,
     pub region: Region,
+    /// The layouts of the arguments of a property. Other expects have none.
+    pub arguments: &'a [InLayout<'a>],
+    /// The name of a snapshot, whose value is compared with the one stored for this name.
+    pub snapshot: Option<&'a str>,
+    /// The name of a benchmark, which `roc bench` times.
+    pub bench: Option<&'a str>,
 }
 
 #[derive(Debug)]
@@ -525,13 +1100,21 @@ pub fn expect_mono_module_to_dylib<'a>(
     for (module_id, expects) in toplevel_expects.into_iter() {
         let expect_names = expect_names.get(&module_id).unwrap();
 
+        let snapshots = expects.snapshots;
+        let benches = expects.benches;
+
         let expects_pure =
             bumpalo::collections::Vec::from_iter_in(
                 expects.pure.into_iter().zip(expect_names.iter()).map(
-                    |((symbol, region), name)| ToplevelExpect {
+                    |((symbol, region), exposed)| ToplevelExpect {
                         symbol,
                         region,
-                        name,
+                        name: exposed.name,
+                        arguments: exposed.arguments,
+                        snapshot: snapshots
+                            .get(&symbol)
+                            .map(|name| &*env.arena.alloc_str(name)),
+                        bench: benches.get(&symbol).map(|name| &*env.arena.alloc_str(name)),
                     },
                 ),
                 env.arena,
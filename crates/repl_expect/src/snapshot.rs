@@ -0,0 +1,158 @@
+//! Snapshots are top-level expects like `expect Inspect.snapshot("page", render(page))`. Their
+//! value is rendered like the REPL renders values, and compared with the one stored next to their
+//! module.
+use std::path::{Path, PathBuf};
+
+/// How a rendered value compared with the one stored for its snapshot.
+pub(crate) enum SnapshotCheck {
+    Matched,
+    /// No value was stored for the snapshot yet.
+    Missing,
+    Mismatched {
+        stored: String,
+    },
+    /// The stored value was missing or different, and was replaced.
+    Updated,
+}
+
+/// Where the value of a snapshot is stored: in a `snapshots` directory next to its module, named
+/// after both, like `snapshots/Page__home.snap` for `Inspect.snapshot("home", ...)` in `Page.roc`.
+/// Names which differ only in characters a file name may not contain share a path.
+pub(crate) fn snapshot_path(module_path: &Path, name: &str) -> PathBuf {
+    let module_name = module_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+
+    // the name may contain anything, but a file name may not
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect();
+
+    let dir = module_path.parent().unwrap_or_else(|| Path::new(""));
+
+    dir.join("snapshots")
+        .join(format!("{module_name}__{name}.snap"))
+}
+
+/// Compares `value` with the value stored at `path`, storing `value` there instead if `update` is
+/// set and they differ.
+pub(crate) fn check_snapshot(
+    path: &Path,
+    value: &str,
+    update: bool,
+) -> std::io::Result<SnapshotCheck> {
+    let stored = match std::fs::read_to_string(path) {
+        Ok(stored) => Some(stored),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+        Err(error) => return Err(error),
+    };
+
+    // the file ends with a newline, which the value doesn't
+    let check = match stored {
+        Some(stored) if stored.strip_suffix('\n').unwrap_or(&stored) == value => {
+            return Ok(SnapshotCheck::Matched)
+        }
+        Some(stored) => SnapshotCheck::Mismatched {
+            stored: stored.strip_suffix('\n').unwrap_or(&stored).to_string(),
+        },
+        None => SnapshotCheck::Missing,
+    };
+
+    if !update {
+        return Ok(check);
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, format!("{value}\n"))?;
+
+    Ok(SnapshotCheck::Updated)
+}
+
+/// The lines of `stored` and `value`, with a `-` before those only in `stored` and a `+` before
+/// those only in `value`.
+pub(crate) fn diff_lines(stored: &str, value: &str) -> Vec<String> {
+    let old: Vec<&str> = stored.lines().collect();
+    let new: Vec<&str> = value.lines().collect();
+
+    // the length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn path_next_to_module() {
+        assert_eq!(
+            snapshot_path(Path::new("src/Page.roc"), "home page"),
+            Path::new("src/snapshots/Page__home_page.snap")
+        );
+    }
+
+    #[test]
+    fn diff_of_changed_line() {
+        assert_eq!(
+            diff_lines("a\nb\nc", "a\nB\nc\nd"),
+            ["  a", "- b", "+ B", "  c", "+ d"]
+        );
+    }
+
+    #[test]
+    fn update_stores_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshots").join("Test__value.snap");
+
+        assert!(matches!(
+            check_snapshot(&path, "[1, 2]", false).unwrap(),
+            SnapshotCheck::Missing
+        ));
+        assert!(matches!(
+            check_snapshot(&path, "[1, 2]", true).unwrap(),
+            SnapshotCheck::Updated
+        ));
+        assert!(matches!(
+            check_snapshot(&path, "[1, 2]", false).unwrap(),
+            SnapshotCheck::Matched
+        ));
+        assert!(matches!(
+            check_snapshot(&path, "[1, 3]", false).unwrap(),
+            SnapshotCheck::Mismatched { stored } if stored == "[1, 2]"
+        ));
+    }
+}
@@ -11,6 +11,7 @@ use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Loc, Region};
 use roc_types::types::{AliasKind, EarlyReturnKind};
 use std::path::PathBuf;
 
+use crate::error::codes::{self, ErrorCode};
 use crate::error::r#type::suggest;
 use crate::report::{to_file_problem_report, Annotation, Report, RocDocAllocator, RocDocBuilder};
 use ven_pretty::{text, DocAllocator};
@@ -68,6 +69,8 @@ const UNNECESSARY_EXCLAMATION: &str = "UNNECESSARY EXCLAMATION";
 const EMPTY_TUPLE_TYPE: &str = "EMPTY TUPLE TYPE";
 const UNBOUND_TYPE_VARS_IN_AS: &str = "UNBOUND TYPE VARIABLES IN AS";
 const INTERPOLATED_STRING_NOT_ALLOWED: &str = "INTERPOLATED STRING NOT ALLOWED";
+const INVALID_SNAPSHOT_NAME: &str = "INVALID SNAPSHOT NAME";
+const INVALID_BENCHMARK_NAME: &str = "INVALID BENCHMARK NAME";
 
 pub fn can_problem<'b>(
     alloc: &'b RocDocAllocator<'b>,
@@ -77,6 +80,7 @@ pub fn can_problem<'b>(
 ) -> Report<'b> {
     let doc;
     let title;
+    let code;
     let severity = problem.severity();
 
     match problem {
@@ -96,6 +100,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = UNUSED_DEF.to_string();
+            code = codes::E0204;
         }
         Problem::UnusedImport(symbol, region) => {
             doc = alloc.stack([
@@ -112,6 +117,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = UNUSED_IMPORT.to_string();
+            code = codes::E0205;
         }
         Problem::UnusedModuleImport(module_id, region) => {
             doc = alloc.stack([
@@ -128,6 +134,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = UNUSED_IMPORT.to_string();
+            code = codes::E0205;
         }
         Problem::ImportNameConflict {
             name,
@@ -188,6 +195,7 @@ pub fn can_problem<'b>(
                 },
             ]);
             title = IMPORT_NAME_CONFLICT.to_string();
+            code = codes::E0206;
         }
 
         Problem::ExplicitBuiltinImport(module_id, region) => {
@@ -203,6 +211,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = EXPLICIT_BUILTIN_IMPORT.to_string();
+            code = codes::E0207;
         }
 
         Problem::ExplicitBuiltinTypeImport(symbol, region) => {
@@ -221,6 +230,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = EXPLICIT_BUILTIN_IMPORT.to_string();
+            code = codes::E0207;
         }
 
         Problem::ImportShadowsSymbol {
@@ -248,6 +258,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = DUPLICATE_NAME.to_string();
+            code = codes::E0218;
         }
 
         Problem::DefsOnlyUsedInRecursion(1, region) => {
@@ -260,6 +271,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = "DEFINITION ONLY USED IN RECURSION".to_string();
+            code = codes::E0254;
         }
         Problem::DefsOnlyUsedInRecursion(n, region) => {
             doc = alloc.stack([
@@ -275,6 +287,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = "DEFINITIONS ONLY USED IN RECURSION".to_string();
+            code = codes::E0255;
         }
         Problem::ExposedButNotDefined(symbol) => {
             doc = alloc.stack([
@@ -290,6 +303,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = MISSING_DEFINITION.to_string();
+            code = codes::E0213;
         }
         Problem::UnusedArgument(closure_symbol, is_anonymous, argument_symbol, region) => {
             let line = "\". Adding an underscore at the start of a variable name is a way of saying that the variable is not used.";
@@ -324,6 +338,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = UNUSED_ARG.to_string();
+            code = codes::E0212;
         }
         Problem::UnusedBranchDef(symbol, region) => {
             doc = alloc.stack([
@@ -344,6 +359,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = UNUSED_DEF.to_string();
+            code = codes::E0204;
         }
         Problem::PrecedenceProblem(BothNonAssociative(region, left_bin_op, right_bin_op)) => {
             doc = alloc.stack([
@@ -372,6 +388,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = SYNTAX_PROBLEM.to_string();
+            code = codes::E0201;
         }
         Problem::UnsupportedPattern(BadPattern::Unsupported(pattern_type), region) => {
             use roc_parse::pattern::PatternType::*;
@@ -403,6 +420,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = SYNTAX_PROBLEM.to_string();
+            code = codes::E0201;
         }
         Problem::Shadowing {
             original_region,
@@ -414,6 +432,7 @@ pub fn can_problem<'b>(
 
             doc = res_doc;
             title = res_title.to_string();
+            code = codes::E0218;
         }
         Problem::CyclicAlias(symbol, region, others, alias_kind) => {
             let answer = crate::error::r#type::cyclic_alias(
@@ -422,6 +441,7 @@ pub fn can_problem<'b>(
 
             doc = answer.0;
             title = answer.1;
+            code = codes::E0314;
         }
         Problem::PhantomTypeArgument {
             typ: alias,
@@ -449,6 +469,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = UNUSED_ALIAS_PARAM.to_string();
+            code = codes::E0208;
         }
         Problem::WildcardNotAllowed {
             typ: alias,
@@ -489,6 +510,7 @@ pub fn can_problem<'b>(
             doc = alloc.stack(stack);
 
             title = WILDCARD_NOT_ALLOWED.to_string();
+            code = codes::E0210;
         }
         Problem::UnderscoreNotAllowed {
             typ: alias,
@@ -530,6 +552,7 @@ pub fn can_problem<'b>(
             doc = alloc.stack(stack);
 
             title = UNDERSCORE_NOT_ALLOWED.to_string();
+            code = codes::E0211;
         }
         Problem::UndeclaredTypeVar {
             typ: alias,
@@ -576,10 +599,12 @@ pub fn can_problem<'b>(
             doc = alloc.stack(stack);
 
             title = UNDECLARED_TYPE_VARIABLE.to_string();
+            code = codes::E0209;
         }
         Problem::BadRecursion(entries) => {
             doc = to_circular_def_doc(alloc, lines, &entries, severity);
             title = CIRCULAR_DEF.to_string();
+            code = codes::E0217;
         }
         Problem::DuplicateRecordFieldValue {
             field_name,
@@ -614,6 +639,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = DUPLICATE_FIELD_NAME.to_string();
+            code = codes::E0214;
         }
         Problem::InvalidOptionalValue {
             field_name,
@@ -676,6 +702,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = DUPLICATE_FIELD_NAME.to_string();
+            code = codes::E0214;
         }
         Problem::DuplicateTag {
             tag_name,
@@ -710,6 +737,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = DUPLICATE_TAG_NAME.to_string();
+            code = codes::E0215;
         }
         Problem::SignatureDefMismatch {
             ref annotation_pattern,
@@ -727,6 +755,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = NAMING_PROBLEM.to_string();
+            code = codes::E0202;
         }
         Problem::InvalidAliasRigid {
             alias_name: type_name,
@@ -749,6 +778,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = SYNTAX_PROBLEM.to_string();
+            code = codes::E0201;
         }
         Problem::InvalidHexadecimal(region) => {
             doc = alloc.stack([
@@ -765,6 +795,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = INVALID_UNICODE.to_string();
+            code = codes::E0216;
         }
         Problem::InvalidUnicodeCodePt(region) => {
             doc = alloc.stack([
@@ -774,6 +805,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = INVALID_UNICODE.to_string();
+            code = codes::E0216;
         }
         Problem::InvalidInterpolation(region) => {
             doc = alloc.stack([
@@ -784,12 +816,14 @@ pub fn can_problem<'b>(
             ]);
 
             title = SYNTAX_PROBLEM.to_string();
+            code = codes::E0201;
         }
         Problem::RuntimeError(runtime_error) => {
             let answer = pretty_runtime_error(alloc, lines, runtime_error);
 
             doc = answer.0;
             title = answer.1.to_string();
+            code = answer.2;
         }
         Problem::NestedDatatype {
             alias,
@@ -817,6 +851,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = NESTED_DATATYPE.to_string();
+            code = codes::E0222;
         }
 
         Problem::InvalidExtensionType { region, kind } => {
@@ -844,6 +879,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = INVALID_EXTENSION_TYPE.to_string();
+            code = codes::E0230;
         }
 
         Problem::AbilityHasTypeVariables {
@@ -862,6 +898,7 @@ pub fn can_problem<'b>(
                 ),
             ]);
             title = ABILITY_HAS_TYPE_VARIABLES.to_string();
+            code = codes::E0231;
         }
 
         Problem::ImplementsClauseIsNotAbility {
@@ -874,6 +911,7 @@ pub fn can_problem<'b>(
                 alloc.region(lines.convert_region(clause_region), severity),
             ]);
             title = IMPLEMENTS_CLAUSE_IS_NOT_AN_ABILITY.to_string();
+            code = codes::E0232;
         }
 
         Problem::IllegalImplementsClause { region } => {
@@ -892,6 +930,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
             title = ILLEGAL_IMPLEMENTS_CLAUSE.to_string();
+            code = codes::E0233;
         }
 
         Problem::DuplicateImplementsAbility { ability, region } => {
@@ -909,6 +948,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
             title = "DUPLICATE BOUND ABILITY".to_string();
+            code = codes::E0256;
         }
 
         Problem::AbilityMemberMissingImplementsClause {
@@ -943,6 +983,7 @@ pub fn can_problem<'b>(
                     .reflow("Otherwise, the function does not need to be part of the ability!")]),
             ]);
             title = ABILITY_MEMBER_MISSING_IMPLEMENTS_CLAUSE.to_string();
+            code = codes::E0234;
         }
 
         Problem::AbilityMemberMultipleBoundVars {
@@ -970,6 +1011,7 @@ pub fn can_problem<'b>(
                 ])
             ]);
             title = ABILITY_MEMBER_BINDS_MULTIPLE_VARIABLES.to_string();
+            code = codes::E0235;
         }
 
         Problem::AbilityNotOnToplevel { region } => {
@@ -981,6 +1023,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("Abilities can only be defined on the top-level of a Roc module."),
             ]);
             title = ABILITY_NOT_ON_TOPLEVEL.to_string();
+            code = codes::E0236;
         }
 
         Problem::AbilityUsedAsType(suggested_var_name, ability, region) => {
@@ -1008,6 +1051,7 @@ pub fn can_problem<'b>(
                 ])),
             ]);
             title = ABILITY_USED_AS_TYPE.to_string();
+            code = codes::E0238;
         }
         Problem::NestedSpecialization(member, region) => {
             doc = alloc.stack([
@@ -1020,6 +1064,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("Specializations can only be defined on the top-level of a module."),
             ]);
             title = SPECIALIZATION_NOT_ON_TOPLEVEL.to_string();
+            code = codes::E0237;
         }
         Problem::IllegalDerivedAbility(region) => {
             doc = alloc.stack([
@@ -1031,6 +1076,7 @@ pub fn can_problem<'b>(
                     .append(list_builtin_abilities(alloc)),
             ]);
             title = ILLEGAL_DERIVE.to_string();
+            code = codes::E0239;
         }
         Problem::NotAnAbility(region) => {
             doc = alloc.stack([
@@ -1039,6 +1085,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("Only abilities can be implemented."),
             ]);
             title = NOT_AN_ABILITY.to_string();
+            code = codes::E0242;
         }
         Problem::NotAnAbilityMember {
             ability,
@@ -1053,6 +1100,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("Only implementations for members an ability has can be specified in this location.")
             ]);
             title = NOT_AN_ABILITY_MEMBER.to_string();
+            code = codes::E0241;
         }
         Problem::ImplementationNotFound { member, region } => {
             let member_str = member.as_str(alloc.interns);
@@ -1064,6 +1112,7 @@ pub fn can_problem<'b>(
                 alloc.tip().append(alloc.concat([alloc.reflow("consider adding a value of name "), alloc.symbol_unqualified(member), alloc.reflow(" in this scope, or using another variable that implements this ability member, like "), alloc.type_str(&format!("{{ {member_str}: my{member_str} }}"))]))
             ]);
             title = IMPLEMENTATION_NOT_FOUND.to_string();
+            code = codes::E0240;
         }
         Problem::OptionalAbilityImpl { ability, region } => {
             let hint = if ability.is_builtin() {
@@ -1082,6 +1131,7 @@ pub fn can_problem<'b>(
                 hint,
             ]);
             title = OPTIONAL_ABILITY_IMPLEMENTATION.to_string();
+            code = codes::E0243;
         }
         Problem::QualifiedAbilityImpl { region } => {
             doc = alloc.stack([
@@ -1092,6 +1142,7 @@ pub fn can_problem<'b>(
                 ),
             ]);
             title = QUALIFIED_ABILITY_IMPLEMENTATION.to_string();
+            code = codes::E0244;
         }
         Problem::AbilityImplNotIdent { region } => {
             doc = alloc.stack([
@@ -1103,6 +1154,7 @@ pub fn can_problem<'b>(
                 alloc.tip().append(alloc.reflow("consider defining this expression as a variable."))
             ]);
             title = ABILITY_IMPLEMENTATION_NOT_IDENTIFIER.to_string();
+            code = codes::E0245;
         }
         Problem::DuplicateImpl {
             original,
@@ -1117,6 +1169,7 @@ pub fn can_problem<'b>(
                     .reflow("Only one custom implementation can be defined for an ability member."),
             ]);
             title = DUPLICATE_IMPLEMENTATION.to_string();
+            code = codes::E0246;
         }
         Problem::ImplementsNonRequired {
             region,
@@ -1141,6 +1194,7 @@ pub fn can_problem<'b>(
                 ),
             ]);
             title = UNNECESSARY_IMPLEMENTATIONS.to_string();
+            code = codes::E0247;
         }
         Problem::DoesNotImplementAbility {
             region,
@@ -1165,6 +1219,7 @@ pub fn can_problem<'b>(
                 ),
             ]);
             title = INCOMPLETE_ABILITY_IMPLEMENTATION.to_string();
+            code = codes::E0248;
         }
         Problem::NotBoundInAllPatterns {
             unbound_symbol,
@@ -1185,6 +1240,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
             title = "NAME NOT BOUND IN ALL PATTERNS".to_string();
+            code = codes::E0257;
         }
         Problem::NoIdentifiersIntroduced(region) => {
             doc = alloc.stack([
@@ -1193,6 +1249,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("If you don't need to use the value on the right-hand side of this assignment, consider removing the assignment. Since effects are not allowed at the top-level, assignments that don't introduce variables cannot affect a program's behavior"),
             ]);
             title = "UNNECESSARY DEFINITION".to_string();
+            code = codes::E0258;
         }
         Problem::OverloadedSpecialization {
             ability_member,
@@ -1212,6 +1269,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("Ability specializations can only provide implementations for one opaque type, since all opaque types are different!"),
             ]);
             title = "OVERLOADED SPECIALIZATION".to_string();
+            code = codes::E0259;
         }
         Problem::UnnecessaryOutputWildcard { region } => {
             doc = alloc.stack([
@@ -1231,6 +1289,7 @@ pub fn can_problem<'b>(
                 alloc.reflow("You can safely remove this to make the code more concise without changing what it means."),
             ]);
             title = "UNNECESSARY WILDCARD".to_string();
+            code = codes::E0260;
         }
         Problem::MultipleListRestPattern { region } => {
             doc = alloc.stack([
@@ -1243,6 +1302,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
             title = "MULTIPLE LIST REST PATTERNS".to_string();
+            code = codes::E0261;
         }
         Problem::BadTypeArguments {
             symbol,
@@ -1277,10 +1337,10 @@ pub fn can_problem<'b>(
                 alloc.reflow("Are there missing parentheses?"),
             ]);
 
-            title = if type_got > alias_needs {
-                "TOO MANY TYPE ARGUMENTS".to_string()
+            (title, code) = if type_got > alias_needs {
+                ("TOO MANY TYPE ARGUMENTS".to_string(), codes::E0262)
             } else {
-                "TOO FEW TYPE ARGUMENTS".to_string()
+                ("TOO FEW TYPE ARGUMENTS".to_string(), codes::E0263)
             };
         }
         Problem::UnappliedCrash { region } => {
@@ -1295,6 +1355,7 @@ pub fn can_problem<'b>(
                 ])
             ]);
             title = "UNAPPLIED CRASH".to_string();
+            code = codes::E0264;
         }
         Problem::OverAppliedCrash { region } => {
             doc = alloc.stack([
@@ -1310,6 +1371,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
             title = "OVERAPPLIED CRASH".to_string();
+            code = codes::E0265;
         }
         Problem::UnappliedDbg { region } => {
             doc = alloc.stack([
@@ -1323,6 +1385,7 @@ pub fn can_problem<'b>(
                 ])
             ]);
             title = "UNAPPLIED DBG".to_string();
+            code = codes::E0266;
         }
         Problem::OverAppliedDbg { region } => {
             doc = alloc.stack([
@@ -1338,6 +1401,7 @@ pub fn can_problem<'b>(
                 ]),
             ]);
             title = "OVERAPPLIED DBG".to_string();
+            code = codes::E0267;
         }
         Problem::UnderAppliedTry { region } => {
             doc = alloc.stack([
@@ -1353,11 +1417,19 @@ pub fn can_problem<'b>(
                 ]),
             ]);
             title = "UNDERAPPLIED TRY".to_string();
+            code = codes::E0268;
         }
-        Problem::FileProblem { filename, error } => {
-            let report = to_file_problem_report(alloc, filename, error);
-            doc = report.doc;
-            title = report.title;
+        Problem::FileProblem {
+            filename: problem_filename,
+            error,
+        } => {
+            let report = to_file_problem_report(alloc, problem_filename, error);
+
+            return Report {
+                filename,
+                severity,
+                ..report
+            };
         }
 
         Problem::ReturnOutsideOfFunction {
@@ -1365,9 +1437,15 @@ pub fn can_problem<'b>(
             return_kind,
         } => {
             let return_keyword;
-            (title, return_keyword) = match return_kind {
-                EarlyReturnKind::Return => ("RETURN OUTSIDE OF FUNCTION".to_string(), "return"),
-                EarlyReturnKind::Try => ("TRY OUTSIDE OF FUNCTION".to_string(), "try"),
+            (title, code, return_keyword) = match return_kind {
+                EarlyReturnKind::Return => (
+                    "RETURN OUTSIDE OF FUNCTION".to_string(),
+                    codes::E0269,
+                    "return",
+                ),
+                EarlyReturnKind::Try => {
+                    ("TRY OUTSIDE OF FUNCTION".to_string(), codes::E0270, "try")
+                }
             };
 
             doc = alloc.stack([
@@ -1399,6 +1477,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = "UNREACHABLE CODE".to_string();
+            code = codes::E0271;
         }
 
         Problem::ReturnAtEndOfFunction { region } => {
@@ -1419,6 +1498,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = "UNNECESSARY RETURN".to_string();
+            code = codes::E0272;
         }
 
         Problem::UnsuffixedEffectfulRecordField(region) => {
@@ -1435,6 +1515,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = MISSING_EXCLAMATION.to_string();
+            code = codes::E0249;
         }
 
         Problem::SuffixedPureRecordField(region) => {
@@ -1455,6 +1536,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = UNNECESSARY_EXCLAMATION.to_string();
+            code = codes::E0250;
         }
 
         Problem::EmptyTupleType(region) => {
@@ -1465,6 +1547,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = EMPTY_TUPLE_TYPE.to_string();
+            code = codes::E0251;
         }
         Problem::UnboundTypeVarsInAs(region) => {
             // NOTE for the enterprising contributor:
@@ -1478,6 +1561,7 @@ pub fn can_problem<'b>(
             ]);
 
             title = UNBOUND_TYPE_VARS_IN_AS.to_string();
+            code = codes::E0252;
         }
         Problem::InterpolatedStringNotAllowed(region) => {
             doc = alloc.stack([
@@ -1487,11 +1571,41 @@ pub fn can_problem<'b>(
             ]);
 
             title = INTERPOLATED_STRING_NOT_ALLOWED.to_string();
+            code = codes::E0253;
+        }
+        Problem::InvalidSnapshotName(region) => {
+            doc = alloc.stack([
+                alloc.reflow("The name of this snapshot is not a plain string:"),
+                alloc.region(lines.convert_region(region), severity),
+                alloc.concat([
+                    alloc.reflow("The value of a snapshot is stored in a file named after it, so "),
+                    alloc.reflow("its name has to be known before it runs. Use a plain string "),
+                    alloc.reflow(r#"like "page" instead."#),
+                ]),
+            ]);
+
+            title = INVALID_SNAPSHOT_NAME.to_string();
+            code = codes::E0280;
+        }
+        Problem::InvalidBenchName(region) => {
+            doc = alloc.stack([
+                alloc.reflow("The name of this benchmark is not a plain string:"),
+                alloc.region(lines.convert_region(region), severity),
+                alloc.concat([
+                    alloc.reflow("Benchmarks are told apart by their names when they're "),
+                    alloc.reflow("compared with a baseline, so their names have to be known "),
+                    alloc.reflow(r#"before they run. Use a plain string like "sort" instead."#),
+                ]),
+            ]);
+
+            title = INVALID_BENCHMARK_NAME.to_string();
+            code = codes::E0281;
         }
     };
 
     Report {
         title,
+        code: Some(code),
         filename,
         doc,
         severity,
@@ -1525,6 +1639,7 @@ fn to_invalid_optional_value_report<'b>(
 
     Report {
         title: "BAD OPTIONAL VALUE".to_string(),
+        code: Some(codes::E0273),
         filename,
         doc,
         severity: Severity::RuntimeError,
@@ -1570,6 +1685,7 @@ fn to_invalid_ignored_value_report<'b>(
 
     Report {
         title: "BAD IGNORED VALUE".to_string(),
+        code: Some(codes::E0274),
         filename,
         doc,
         severity: Severity::RuntimeError,
@@ -2090,9 +2206,10 @@ fn pretty_runtime_error<'b>(
     alloc: &'b RocDocAllocator<'b>,
     lines: &LineInfo,
     runtime_error: RuntimeError,
-) -> (RocDocBuilder<'b>, &'static str) {
+) -> (RocDocBuilder<'b>, &'static str, ErrorCode) {
     let doc;
     let title;
+    let code;
 
     let severity = Severity::RuntimeError;
 
@@ -2114,6 +2231,7 @@ fn pretty_runtime_error<'b>(
             kind,
         } => {
             (title, doc) = report_shadowing(alloc, lines, original_region, shadow, kind, severity);
+            code = codes::E0218;
         }
 
         RuntimeError::LookupNotInScope {
@@ -2131,10 +2249,12 @@ fn pretty_runtime_error<'b>(
                 severity,
             );
             title = UNRECOGNIZED_NAME;
+            code = codes::E0203;
         }
         RuntimeError::CircularDef(entries) => {
             doc = to_circular_def_doc(alloc, lines, &entries, severity);
             title = CIRCULAR_DEF;
+            code = codes::E0217;
         }
         RuntimeError::MalformedPattern(problem, region) => {
             use roc_parse::ast::Base;
@@ -2149,9 +2269,10 @@ fn pretty_runtime_error<'b>(
                 MalformedBase(Base::Decimal) => " integer ",
                 BadIdent(bad_ident) => {
                     title = NAMING_PROBLEM;
+                    code = codes::E0202;
                     doc = to_bad_ident_pattern_report(alloc, lines, bad_ident, region, severity);
 
-                    return (doc, title);
+                    return (doc, title, code);
                 }
                 Unknown | CantApplyPattern => " ",
                 QualifiedIdentifier => " qualified ",
@@ -2188,6 +2309,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::UnsupportedPattern(_) => {
             todo!("unsupported patterns are currently not parsed!")
@@ -2229,6 +2351,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = VALUE_NOT_EXPOSED;
+            code = codes::E0219;
         }
 
         RuntimeError::ModuleNotImported {
@@ -2248,6 +2371,7 @@ fn pretty_runtime_error<'b>(
             );
 
             title = MODULE_NOT_IMPORTED;
+            code = codes::E0220;
         }
         RuntimeError::ReadIngestedFileError {
             filename,
@@ -2258,6 +2382,7 @@ fn pretty_runtime_error<'b>(
 
             doc = report.doc;
             title = INGESTED_FILE_ERROR;
+            code = codes::E0221;
         }
         RuntimeError::IngestedFilePathError(region) => {
             doc = alloc.stack([
@@ -2266,6 +2391,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = INGESTED_FILE_ERROR;
+            code = codes::E0221;
         }
         RuntimeError::InvalidPrecedence(_, _) => {
             // do nothing, reported with PrecedenceProblem
@@ -2275,6 +2401,7 @@ fn pretty_runtime_error<'b>(
             doc = to_bad_ident_expr_report(alloc, lines, bad_ident, surroundings, severity);
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::MalformedTypeName(_box_str, surroundings) => {
             doc = alloc.stack([
@@ -2291,6 +2418,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::InvalidFloat(sign @ FloatErrorKind::PositiveInfinity, region, _raw_str)
         | RuntimeError::InvalidFloat(sign @ FloatErrorKind::NegativeInfinity, region, _raw_str) => {
@@ -2322,6 +2450,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::InvalidFloat(FloatErrorKind::Error, region, _raw_str) => {
             let tip = alloc
@@ -2340,6 +2469,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::InvalidFloat(FloatErrorKind::IntSuffix, region, _raw_str) => {
             doc = alloc.stack([
@@ -2350,6 +2480,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = CONFLICTING_NUMBER_SUFFIX;
+            code = codes::E0223;
         }
         RuntimeError::InvalidInt(error @ IntErrorKind::InvalidDigit, base, region, _raw_str)
         | RuntimeError::InvalidInt(error @ IntErrorKind::Empty, base, region, _raw_str) => {
@@ -2411,6 +2542,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::InvalidInt(error_kind @ IntErrorKind::Underflow, _base, region, _raw_str)
         | RuntimeError::InvalidInt(error_kind @ IntErrorKind::Overflow, _base, region, _raw_str) => {
@@ -2454,6 +2586,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::InvalidInt(IntErrorKind::FloatSuffix, _base, region, _raw_str) => {
             doc = alloc.stack([
@@ -2464,6 +2597,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = CONFLICTING_NUMBER_SUFFIX;
+            code = codes::E0223;
         }
         RuntimeError::InvalidInt(
             IntErrorKind::OverflowsSuffix {
@@ -2488,6 +2622,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = NUMBER_OVERFLOWS_SUFFIX;
+            code = codes::E0224;
         }
         RuntimeError::InvalidInt(
             IntErrorKind::UnderflowsSuffix {
@@ -2512,6 +2647,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = NUMBER_UNDERFLOWS_SUFFIX;
+            code = codes::E0225;
         }
         RuntimeError::InvalidTupleIndex(region) => {
             doc = alloc.stack([
@@ -2526,6 +2662,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = NUMBER_UNDERFLOWS_SUFFIX;
+            code = codes::E0225;
         }
         RuntimeError::InvalidOptionalValue {
             field_name,
@@ -2541,6 +2678,7 @@ fn pretty_runtime_error<'b>(
             );
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::InvalidIgnoredValue {
             field_name,
@@ -2556,6 +2694,7 @@ fn pretty_runtime_error<'b>(
             );
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::InvalidRecordUpdate { region } => {
             doc = alloc.stack([
@@ -2568,6 +2707,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::InvalidHexadecimal(region) => {
             todo!(
@@ -2601,6 +2741,7 @@ fn pretty_runtime_error<'b>(
                 .append(alloc.reflow(", but it was not defined anywhere in that module."))]);
 
             title = MISSING_DEFINITION;
+            code = codes::E0213;
         }
         RuntimeError::EmptySingleQuote(region) => {
             let tip = alloc
@@ -2614,6 +2755,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::MultipleCharsInSingleQuote(region) => {
             let tip = alloc
@@ -2630,6 +2772,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = SYNTAX_PROBLEM;
+            code = codes::E0201;
         }
         RuntimeError::OpaqueNotDefined {
             usage:
@@ -2680,6 +2823,7 @@ fn pretty_runtime_error<'b>(
             doc = alloc.stack(stack);
 
             title = OPAQUE_NOT_DEFINED;
+            code = codes::E0226;
         }
         RuntimeError::OpaqueOutsideScope {
             opaque,
@@ -2701,6 +2845,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = OPAQUE_DECLARED_OUTSIDE_SCOPE;
+            code = codes::E0227;
         }
         RuntimeError::OpaqueNotApplied(loc_ident) => {
             doc = alloc.stack([
@@ -2710,6 +2855,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = OPAQUE_NOT_APPLIED;
+            code = codes::E0228;
         }
         RuntimeError::OpaqueAppliedToMultipleArgs(region) => {
             doc = alloc.stack([
@@ -2719,6 +2865,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = OPAQUE_OVER_APPLIED;
+            code = codes::E0229;
         }
         RuntimeError::DegenerateBranch(region) => {
             doc = alloc.stack([
@@ -2727,6 +2874,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = "DEGENERATE BRANCH";
+            code = codes::E0275;
         }
         RuntimeError::EmptyRecordBuilder(region) => {
             doc = alloc.stack([
@@ -2736,6 +2884,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = "EMPTY RECORD BUILDER";
+            code = codes::E0276;
         }
         RuntimeError::SingleFieldRecordBuilder(region) => {
             doc = alloc.stack([
@@ -2745,6 +2894,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = "NOT ENOUGH FIELDS IN RECORD BUILDER";
+            code = codes::E0277;
         }
         RuntimeError::OptionalFieldInRecordBuilder {
             record: record_region,
@@ -2761,6 +2911,7 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = "OPTIONAL FIELD IN RECORD BUILDER";
+            code = codes::E0278;
         }
         RuntimeError::NonFunctionHostedAnnotation(region) => {
             doc = alloc.stack([
@@ -2770,10 +2921,11 @@ fn pretty_runtime_error<'b>(
             ]);
 
             title = "NON-FUNCTION HOSTED ANNOTATION";
+            code = codes::E0279;
         }
     }
 
-    (doc, title)
+    (doc, title, code)
 }
 
 pub fn to_circular_def_doc<'b>(
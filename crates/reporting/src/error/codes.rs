@@ -0,0 +1,258 @@
+//! Every kind of report has a stable code, like `E0301` for a TYPE MISMATCH, which is printed in
+//! its header. `roc explain <code>` prints a longer explanation of the problem, with examples.
+//!
+//! Codes are grouped by the part of the compiler which reports them: E01xx for parsing, E02xx for
+//! canonicalization, E03xx for type checking, E04xx for loading files and packages, and E05xx for
+//! `roc test`. Once given out, a code never changes or gets reused, because docs link to them and
+//! CI scripts grep for them. A new kind of report gets the next free code in its group, and an
+//! explanation in `codes/<code>.md`. Every report of that kind is built with the code's constant.
+
+/// A kind of report. Every report of this kind is built with it, see [crate::report::Report].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorCode {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub explanation: &'static str,
+}
+
+macro_rules! error_codes {
+    ($($code:ident => $title:literal,)*) => {
+        $(
+            pub const $code: ErrorCode = ErrorCode {
+                code: stringify!($code),
+                title: $title,
+                explanation: include_str!(concat!("codes/", stringify!($code), ".md")),
+            };
+        )*
+
+        pub const ERROR_CODES: &[ErrorCode] = &[$($code,)*];
+    };
+}
+
+error_codes! {
+    E0101 => "PARSE PROBLEM",
+    E0102 => "NOT END OF FILE",
+    E0103 => "ARGUMENTS BEFORE EQUALS",
+    E0104 => "UNKNOWN OPERATOR",
+    E0105 => "WEIRD IDENTIFIER",
+    E0106 => "MISSING EXPRESSION",
+    E0107 => "MISSING FINAL EXPRESSION",
+    E0108 => "INDENT ENDS AFTER EXPRESSION",
+    E0109 => "TRAILING OPERATOR",
+    E0110 => "UNEXPECTED COMMA",
+    E0111 => "UNHANDLED PARSE ERROR",
+    E0112 => "RECORD PARSE PROBLEM",
+    E0113 => "MALFORMED ARGS LIST",
+    E0114 => "WEIRD ARROW",
+    E0115 => "MISSING ARROW",
+    E0116 => "UNFINISHED ARGUMENT LIST",
+    E0117 => "UNFINISHED FUNCTION",
+    E0118 => "WEIRD ESCAPE",
+    E0119 => "WEIRD CODE POINT",
+    E0120 => "ENDLESS FORMAT",
+    E0121 => "ENDLESS SCALAR",
+    E0122 => "INVALID SCALAR",
+    E0123 => "ENDLESS STRING",
+    E0124 => "EXPECTED STRING",
+    E0125 => "INSUFFICIENT INDENT IN MULTI-LINE STRING",
+    E0126 => "INVALID UNICODE CODE POINT",
+    E0127 => "UNICODE CODE POINT TOO LARGE",
+    E0128 => "EMPTY PARENTHESES",
+    E0129 => "UNFINISHED PARENTHESES",
+    E0130 => "UNFINISHED LIST",
+    E0131 => "IGNORED RECORD FIELD IN MODULE PARAMS",
+    E0132 => "RECORD UPDATE IN MODULE PARAMS",
+    E0133 => "RECORD BUILDER IN MODULE PARAMS",
+    E0134 => "LOWERCASE ALIAS",
+    E0135 => "WEIRD EXPOSING",
+    E0136 => "UNFINISHED IMPORT",
+    E0137 => "UNFINISHED IF",
+    E0138 => "IF GUARD NO CONDITION",
+    E0139 => "UNFINISHED WHEN",
+    E0140 => "UNEXPECTED ARROW",
+    E0141 => "UNFINISHED PATTERN",
+    E0142 => "UNFINISHED RECORD PATTERN",
+    E0143 => "PROBLEM IN RECORD PATTERN",
+    E0144 => "UNFINISHED LIST PATTERN",
+    E0145 => "INCORRECT REST PATTERN",
+    E0146 => "INVALID NUMBER LITERAL",
+    E0147 => "DOUBLE COMMA",
+    E0148 => "UNFINISHED TYPE",
+    E0149 => "UNFINISHED INLINE ALIAS",
+    E0150 => "BAD TYPE VARIABLE",
+    E0151 => "UNFINISHED RECORD TYPE",
+    E0152 => "PROBLEM IN RECORD TYPE",
+    E0153 => "NEED MORE INDENTATION",
+    E0154 => "UNFINISHED TAG UNION TYPE",
+    E0155 => "WEIRD TAG NAME",
+    E0156 => "DOUBLE DOT",
+    E0157 => "TRAILING DOT",
+    E0158 => "WEIRD QUALIFIED NAME",
+    E0159 => "END OF FILE",
+    E0160 => "NOT AN INLINE ALIAS",
+    E0161 => "QUALIFIED ALIAS NAME",
+    E0162 => "TYPE ARGUMENT NOT LOWERCASE",
+    E0163 => "INCOMPLETE HEADER",
+    E0164 => "MISSING HEADER",
+    E0165 => "WEIRD MODULE NAME",
+    E0166 => "WEIRD APP NAME",
+    E0167 => "INVALID PACKAGE NAME",
+    E0168 => "INVALID PLATFORM NAME",
+    E0169 => "WEIRD PROVIDES",
+    E0170 => "WEIRD MODULE PARAMS",
+    E0171 => "WEIRD EXPOSES",
+    E0172 => "WEIRD IMPORTS",
+    E0173 => "MISSING REQUIRES",
+    E0174 => "BAD REQUIRES RIGIDS",
+    E0175 => "BAD REQUIRES",
+    E0176 => "MISSING PACKAGES",
+    E0177 => "WEIRD PACKAGES LIST",
+    E0178 => "TAB CHARACTER",
+    E0179 => "ASCII CONTROL CHARACTER",
+    E0180 => "MISPLACED CARRIAGE RETURN",
+    E0181 => "UNFINISHED ABILITY",
+
+    E0201 => "SYNTAX PROBLEM",
+    E0202 => "NAMING PROBLEM",
+    E0203 => "UNRECOGNIZED NAME",
+    E0204 => "UNUSED DEFINITION",
+    E0205 => "UNUSED IMPORT",
+    E0206 => "IMPORT NAME CONFLICT",
+    E0207 => "EXPLICIT BUILTIN IMPORT",
+    E0208 => "UNUSED TYPE ALIAS PARAMETER",
+    E0209 => "UNDECLARED TYPE VARIABLE",
+    E0210 => "WILDCARD NOT ALLOWED HERE",
+    E0211 => "UNDERSCORE NOT ALLOWED HERE",
+    E0212 => "UNUSED ARGUMENT",
+    E0213 => "MISSING DEFINITION",
+    E0214 => "DUPLICATE FIELD NAME",
+    E0215 => "DUPLICATE TAG NAME",
+    E0216 => "INVALID UNICODE",
+    E0217 => "CIRCULAR DEFINITION",
+    E0218 => "DUPLICATE NAME",
+    E0219 => "NOT EXPOSED",
+    E0220 => "MODULE NOT IMPORTED",
+    E0221 => "INGESTED FILE ERROR",
+    E0222 => "NESTED DATATYPE",
+    E0223 => "CONFLICTING NUMBER SUFFIX",
+    E0224 => "NUMBER OVERFLOWS SUFFIX",
+    E0225 => "NUMBER UNDERFLOWS SUFFIX",
+    E0226 => "OPAQUE TYPE NOT DEFINED",
+    E0227 => "OPAQUE TYPE DECLARED OUTSIDE SCOPE",
+    E0228 => "OPAQUE TYPE NOT APPLIED",
+    E0229 => "OPAQUE TYPE APPLIED TO TOO MANY ARGS",
+    E0230 => "INVALID_EXTENSION_TYPE",
+    E0231 => "ABILITY HAS TYPE VARIABLES",
+    E0232 => "IMPLEMENTS CLAUSE IS NOT AN ABILITY",
+    E0233 => "ILLEGAL IMPLEMENTS CLAUSE",
+    E0234 => "ABILITY MEMBER MISSING IMPLEMENTS CLAUSE",
+    E0235 => "ABILITY MEMBER BINDS MULTIPLE VARIABLES",
+    E0236 => "ABILITY NOT ON TOP-LEVEL",
+    E0237 => "SPECIALIZATION NOT ON TOP-LEVEL",
+    E0238 => "ABILITY USED AS TYPE",
+    E0239 => "ILLEGAL DERIVE",
+    E0240 => "IMPLEMENTATION NOT FOUND",
+    E0241 => "NOT AN ABILITY MEMBER",
+    E0242 => "NOT AN ABILITY",
+    E0243 => "OPTIONAL ABILITY IMPLEMENTATION",
+    E0244 => "QUALIFIED ABILITY IMPLEMENTATION",
+    E0245 => "ABILITY IMPLEMENTATION NOT IDENTIFIER",
+    E0246 => "DUPLICATE IMPLEMENTATION",
+    E0247 => "UNNECESSARY IMPLEMENTATIONS",
+    E0248 => "INCOMPLETE ABILITY IMPLEMENTATION",
+    E0249 => "MISSING EXCLAMATION",
+    E0250 => "UNNECESSARY EXCLAMATION",
+    E0251 => "EMPTY TUPLE TYPE",
+    E0252 => "UNBOUND TYPE VARIABLES IN AS",
+    E0253 => "INTERPOLATED STRING NOT ALLOWED",
+    E0254 => "DEFINITION ONLY USED IN RECURSION",
+    E0255 => "DEFINITIONS ONLY USED IN RECURSION",
+    E0256 => "DUPLICATE BOUND ABILITY",
+    E0257 => "NAME NOT BOUND IN ALL PATTERNS",
+    E0258 => "UNNECESSARY DEFINITION",
+    E0259 => "OVERLOADED SPECIALIZATION",
+    E0260 => "UNNECESSARY WILDCARD",
+    E0261 => "MULTIPLE LIST REST PATTERNS",
+    E0262 => "TOO MANY TYPE ARGUMENTS",
+    E0263 => "TOO FEW TYPE ARGUMENTS",
+    E0264 => "UNAPPLIED CRASH",
+    E0265 => "OVERAPPLIED CRASH",
+    E0266 => "UNAPPLIED DBG",
+    E0267 => "OVERAPPLIED DBG",
+    E0268 => "UNDERAPPLIED TRY",
+    E0269 => "RETURN OUTSIDE OF FUNCTION",
+    E0270 => "TRY OUTSIDE OF FUNCTION",
+    E0271 => "UNREACHABLE CODE",
+    E0272 => "UNNECESSARY RETURN",
+    E0273 => "BAD OPTIONAL VALUE",
+    E0274 => "BAD IGNORED VALUE",
+    E0275 => "DEGENERATE BRANCH",
+    E0276 => "EMPTY RECORD BUILDER",
+    E0277 => "NOT ENOUGH FIELDS IN RECORD BUILDER",
+    E0278 => "OPTIONAL FIELD IN RECORD BUILDER",
+    E0279 => "NON-FUNCTION HOSTED ANNOTATION",
+    E0280 => "INVALID SNAPSHOT NAME",
+    E0281 => "INVALID BENCHMARK NAME",
+
+    E0301 => "TYPE MISMATCH",
+    E0302 => "ILLEGAL SPECIALIZATION",
+    E0303 => "WRONG SPECIALIZATION TYPE",
+    E0304 => "INVALID UTF-8",
+    E0305 => "INVALID TYPE FOR INGESTED FILE",
+    E0306 => "UNEXPECTED MODULE PARAMS",
+    E0307 => "MISSING MODULE PARAMS",
+    E0308 => "MODULE PARAMS MISMATCH",
+    E0309 => "EFFECT IN PURE FUNCTION",
+    E0310 => "EFFECT IN TOP-LEVEL",
+    E0311 => "LEFTOVER STATEMENT",
+    E0312 => "INVALID TRY TARGET",
+    E0313 => "TYPE VARIABLE IS NOT GENERIC",
+    E0314 => "CYCLIC ALIAS",
+    E0315 => "TOO MANY ARGS",
+    E0316 => "TOO FEW ARGS",
+    E0317 => "IGNORED RESULT",
+    E0318 => "CIRCULAR TYPE",
+    E0319 => "UNSAFE PATTERN",
+    E0320 => "REDUNDANT PATTERN",
+    E0321 => "UNMATCHABLE PATTERN",
+
+    E0401 => "UNSUPPORTED ENCODING",
+    E0402 => "MULTIPLE ENCODINGS",
+    E0403 => "INVALID CONTENT HASH",
+    E0404 => "NOTFOUND",
+    E0405 => "IO ERROR",
+    E0406 => "HTTP ERROR",
+    E0407 => "INVALID EXTENSION SUFFIX",
+    E0408 => "INVALID EXTENSION",
+    E0409 => "INVALID FRAGMENT",
+    E0410 => "MISSING PACKAGE HASH",
+    E0411 => "HTTPS MANDATORY",
+    E0412 => "MISLEADING CHARACTERS",
+    E0413 => "FILE TOO LARGE",
+    E0414 => "FILE NOT FOUND",
+    E0415 => "FILE PERMISSION DENIED",
+    E0416 => "NOT A ROC FILE",
+    E0417 => "FILE PROBLEM",
+    E0418 => "IMPORT CYCLE",
+    E0419 => "INCORRECT MODULE NAME",
+    E0420 => "UNSPECIFIED PLATFORM",
+    E0421 => "MULTIPLE PLATFORMS",
+    E0422 => "UNRECOGNIZED PACKAGE",
+    E0423 => "NO PLATFORM",
+
+    E0501 => "EXPECT FAILED",
+    E0502 => "EXPECT PANICKED",
+    E0503 => "EXPECT TIMED OUT",
+    E0504 => "UNSUPPORTED PROPERTY",
+    E0505 => "SNAPSHOT MISSING",
+    E0506 => "SNAPSHOT CHANGED",
+    E0507 => "PROPERTY FALSIFIED",
+    E0508 => "SNAPSHOT NAME CLASH",
+}
+
+/// Looks up a code like `E0301`, ignoring case.
+pub fn lookup_code(code: &str) -> Option<&'static ErrorCode> {
+    ERROR_CODES
+        .iter()
+        .find(|error_code| error_code.code.eq_ignore_ascii_case(code))
+}
@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::time::Duration;
 
 use bumpalo::Bump;
 use roc_fmt::MigrationFlags;
@@ -11,6 +12,7 @@ use roc_types::{
     types::{ErrorType, Polarity},
 };
 
+use crate::error::codes;
 use crate::report::{RenderTarget, RocDocAllocator, RocDocBuilder};
 
 pub struct Renderer<'a> {
@@ -56,6 +58,20 @@ impl<'a> Renderer<'a> {
         expr: &Expr<'_>,
         error_type: ErrorType,
     ) -> RocDocBuilder<'a> {
+        self.alloc.vcat([
+            self.alloc
+                .symbol_unqualified(symbol)
+                .append(" : ")
+                .append(self.render_expr(error_type)),
+            self.alloc
+                .symbol_unqualified(symbol)
+                .append(" = ")
+                .append(self.format_value(expr)),
+        ])
+    }
+
+    /// Formats a value the way the REPL shows it.
+    pub fn format_value(&self, expr: &Expr<'_>) -> &'a str {
         use roc_fmt::annotation::Formattable;
 
         let mut buf = roc_fmt::Buf::new_in(
@@ -67,16 +83,7 @@ impl<'a> Renderer<'a> {
         );
         expr.format(&mut buf, 0);
 
-        self.alloc.vcat([
-            self.alloc
-                .symbol_unqualified(symbol)
-                .append(" : ")
-                .append(self.render_expr(error_type)),
-            self.alloc
-                .symbol_unqualified(symbol)
-                .append(" = ")
-                .append(buf.into_bump_str()),
-        ])
+        buf.into_bump_str()
     }
 
     fn render_lookups(
@@ -169,6 +176,7 @@ impl<'a> Renderer<'a> {
 
         let report = Report {
             title: "EXPECT FAILED".into(),
+            code: Some(codes::E0501),
             doc,
             filename: self.filename.clone(),
             severity,
@@ -247,6 +255,229 @@ impl<'a> Renderer<'a> {
 
         let report = Report {
             title: "EXPECT PANICKED".into(),
+            code: Some(codes::E0502),
+            doc,
+            filename: self.filename.clone(),
+            severity,
+        };
+
+        let mut buf = String::new();
+
+        report.render(
+            self.render_target,
+            &mut buf,
+            &self.alloc,
+            &crate::report::DEFAULT_PALETTE,
+        );
+
+        write!(writer, "{buf}")
+    }
+
+    pub fn render_timeout<W>(
+        &self,
+        writer: &mut W,
+        timeout: Duration,
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::report::Report;
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+        let severity = Severity::RuntimeError;
+
+        let doc = self.alloc.stack([
+            self.alloc.concat([
+                self.alloc
+                    .reflow("This expectation was stopped because it ran for more than "),
+                self.alloc.text(format!("{} ms", timeout.as_millis())),
+                self.alloc.reflow(":"),
+            ]),
+            self.alloc.region(line_col_region, severity),
+            self.alloc.concat([
+                self.alloc
+                    .reflow("It may be stuck in a loop. If it's just slow, pass a larger "),
+                self.alloc.keyword("--timeout-ms"),
+                self.alloc.reflow(" to "),
+                self.alloc.keyword("roc test"),
+                self.alloc.reflow("."),
+            ]),
+        ]);
+
+        let report = Report {
+            title: "EXPECT TIMED OUT".into(),
+            code: Some(codes::E0503),
+            doc,
+            filename: self.filename.clone(),
+            severity,
+        };
+
+        let mut buf = String::new();
+
+        report.render(
+            self.render_target,
+            &mut buf,
+            &self.alloc,
+            &crate::report::DEFAULT_PALETTE,
+        );
+
+        write!(writer, "{buf}")
+    }
+
+    pub fn render_unsupported_property<W>(
+        &self,
+        writer: &mut W,
+        description: &str,
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::report::Report;
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+        let severity = Severity::RuntimeError;
+
+        let doc = self.alloc.stack([
+            self.alloc.concat([
+                self.alloc.keyword("roc test"),
+                self.alloc
+                    .reflow(" can't generate inputs for this property:"),
+            ]),
+            self.alloc.region(line_col_region, severity),
+            self.alloc.concat([
+                self.alloc.reflow("One of its arguments contains a "),
+                self.alloc.text(description.to_string()),
+                self.alloc.reflow(", but only numbers, "),
+                self.alloc.type_str("Bool"),
+                self.alloc.reflow(", "),
+                self.alloc.type_str("Str"),
+                self.alloc
+                    .reflow(", and lists, records and tuples of those can be generated."),
+            ]),
+        ]);
+
+        let report = Report {
+            title: "UNSUPPORTED PROPERTY".into(),
+            code: Some(codes::E0504),
+            doc,
+            filename: self.filename.clone(),
+            severity,
+        };
+
+        let mut buf = String::new();
+
+        report.render(
+            self.render_target,
+            &mut buf,
+            &self.alloc,
+            &crate::report::DEFAULT_PALETTE,
+        );
+
+        write!(writer, "{buf}")
+    }
+
+    pub fn render_missing_snapshot<W>(
+        &self,
+        writer: &mut W,
+        value: &str,
+        snapshot_path: &std::path::Path,
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::report::Report;
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+        let severity = Severity::RuntimeError;
+
+        let doc = self.alloc.stack([
+            self.alloc.reflow("This snapshot has no stored value yet:"),
+            self.alloc.region(line_col_region, severity),
+            self.alloc.reflow("Its value is:"),
+            self.alloc
+                .stack(value.lines().map(|line| self.alloc.text(line.to_string())))
+                .indent(4),
+            self.alloc.concat([
+                self.alloc.reflow("To store it in "),
+                self.alloc.file_path(snapshot_path),
+                self.alloc.reflow(", pass "),
+                self.alloc.keyword("--update-snapshots"),
+                self.alloc.reflow(" to "),
+                self.alloc.keyword("roc test"),
+                self.alloc.reflow("."),
+            ]),
+        ]);
+
+        let report = Report {
+            title: "SNAPSHOT MISSING".into(),
+            code: Some(codes::E0505),
+            doc,
+            filename: self.filename.clone(),
+            severity,
+        };
+
+        let mut buf = String::new();
+
+        report.render(
+            self.render_target,
+            &mut buf,
+            &self.alloc,
+            &crate::report::DEFAULT_PALETTE,
+        );
+
+        write!(writer, "{buf}")
+    }
+
+    /// `diff` has the lines of the stored value and the new one, with a `-` before those which
+    /// are only stored and a `+` before those which are only new.
+    pub fn render_snapshot_mismatch<W>(
+        &self,
+        writer: &mut W,
+        diff: &[String],
+        snapshot_path: &std::path::Path,
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::report::Report;
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+        let severity = Severity::RuntimeError;
+
+        let doc = self.alloc.stack([
+            self.alloc.concat([
+                self.alloc
+                    .reflow("The value of this snapshot is different from the one stored in "),
+                self.alloc.file_path(snapshot_path),
+                self.alloc.reflow(":"),
+            ]),
+            self.alloc.region(line_col_region, severity),
+            self.alloc.reflow(
+                "The lines starting with - were stored, and the ones starting with + are new:",
+            ),
+            self.alloc
+                .stack(diff.iter().map(|line| self.alloc.text(line.clone())))
+                .indent(4),
+            self.alloc.concat([
+                self.alloc.reflow("If the new value is right, pass "),
+                self.alloc.keyword("--update-snapshots"),
+                self.alloc.reflow(" to "),
+                self.alloc.keyword("roc test"),
+                self.alloc.reflow(" to store it."),
+            ]),
+        ]);
+
+        let report = Report {
+            title: "SNAPSHOT CHANGED".into(),
+            code: Some(codes::E0506),
             doc,
             filename: self.filename.clone(),
             severity,
@@ -263,4 +494,132 @@ impl<'a> Renderer<'a> {
 
         write!(writer, "{buf}")
     }
+
+    /// Reports a snapshot whose value would be stored in the same file as that of the snapshot at
+    /// `other_region`, so neither of them runs.
+    pub fn render_snapshot_clash<W>(
+        &self,
+        writer: &mut W,
+        snapshot_path: &std::path::Path,
+        expect_region: Region,
+        other_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::report::Report;
+        use ven_pretty::DocAllocator;
+
+        let severity = Severity::RuntimeError;
+
+        let doc = self.alloc.stack([
+            self.alloc.concat([
+                self.alloc
+                    .reflow("The value of this snapshot would be stored in "),
+                self.alloc.file_path(snapshot_path),
+                self.alloc.reflow(":"),
+            ]),
+            self.alloc
+                .region(self.line_info.convert_region(expect_region), severity),
+            self.alloc.reflow("But so would the value of this one:"),
+            self.alloc
+                .region(self.line_info.convert_region(other_region), severity),
+            self.alloc.reflow(
+                "Names only differing in characters other than letters, digits, -, _ and . \
+                 share a file. Give the snapshots names which differ in those.",
+            ),
+        ]);
+
+        let report = Report {
+            title: "SNAPSHOT NAME CLASH".into(),
+            code: Some(codes::E0508),
+            doc,
+            filename: self.filename.clone(),
+            severity,
+        };
+
+        let mut buf = String::new();
+
+        report.render(
+            self.render_target,
+            &mut buf,
+            &self.alloc,
+            &crate::report::DEFAULT_PALETTE,
+        );
+
+        write!(writer, "{buf}")
+    }
+
+    /// Follows the report of a property's failure on the smallest input that was found. `inputs`
+    /// are shown if that report doesn't show them already.
+    pub fn render_falsified<W>(
+        &self,
+        writer: &mut W,
+        runs: u32,
+        shrinks: u32,
+        seed: u64,
+        inputs: &[String],
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::report::Report;
+        use ven_pretty::DocAllocator;
+
+        let severity = Severity::RuntimeError;
+
+        let shrunk = match shrinks {
+            0 => self.alloc.reflow(", and it couldn't be made any smaller."),
+            1 => self.alloc.reflow(", and then made smaller once."),
+            n => self.alloc.concat([
+                self.alloc.reflow(", and then made smaller "),
+                self.alloc.text(n.to_string()),
+                self.alloc.reflow(" times."),
+            ]),
+        };
+
+        let mut stack = vec![self.alloc.concat([
+            self.alloc
+                .reflow("The property above failed on input number "),
+            self.alloc.text(runs.to_string()),
+            shrunk,
+        ])];
+
+        if !inputs.is_empty() {
+            stack.push(self.alloc.reflow("The smallest input found was:"));
+            stack.push(
+                self.alloc
+                    .stack(inputs.iter().map(|input| self.alloc.text(input.clone())))
+                    .indent(4),
+            );
+        }
+
+        stack.push(self.alloc.concat([
+            self.alloc.reflow("To try the same inputs again, pass "),
+            self.alloc.keyword("--seed"),
+            self.alloc.text(format!(" {seed}")),
+            self.alloc.reflow(" to "),
+            self.alloc.keyword("roc test"),
+            self.alloc.reflow("."),
+        ]));
+
+        let report = Report {
+            title: "PROPERTY FALSIFIED".into(),
+            code: Some(codes::E0507),
+            doc: self.alloc.stack(stack),
+            filename: self.filename.clone(),
+            severity,
+        };
+
+        let mut buf = String::new();
+
+        report.render(
+            self.render_target,
+            &mut buf,
+            &self.alloc,
+            &crate::report::DEFAULT_PALETTE,
+        );
+
+        write!(writer, "{buf}")
+    }
 }
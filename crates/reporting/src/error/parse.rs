@@ -3,6 +3,7 @@ use roc_problem::Severity;
 use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Position, Region};
 use std::path::PathBuf;
 
+use crate::error::codes;
 use crate::report::{Report, RocDocAllocator, RocDocBuilder};
 use ven_pretty::DocAllocator;
 
@@ -75,6 +76,7 @@ fn to_syntax_report<'a>(
         filename: filename.clone(),
         doc,
         title: "PARSE PROBLEM".to_string(),
+        code: Some(codes::E0101),
         severity,
     };
 
@@ -89,6 +91,7 @@ fn to_syntax_report<'a>(
                 filename,
                 doc,
                 title: "PARSE PROBLEM".to_string(),
+                code: Some(codes::E0101),
                 severity,
             }
         }
@@ -121,6 +124,7 @@ fn to_syntax_report<'a>(
                 filename,
                 doc,
                 title: "NOT END OF FILE".to_string(),
+                code: Some(codes::E0102),
                 severity,
             }
         }
@@ -134,6 +138,7 @@ fn to_syntax_report<'a>(
                 filename,
                 doc,
                 title: "PARSE PROBLEM".to_string(),
+                code: Some(codes::E0101),
                 severity,
             }
         }
@@ -144,6 +149,7 @@ fn to_syntax_report<'a>(
                 filename,
                 doc,
                 title: "PARSE PROBLEM".to_string(),
+                code: Some(codes::E0101),
                 severity,
             }
         }
@@ -236,6 +242,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "ARGUMENTS BEFORE EQUALS".to_string(),
+                code: Some(codes::E0103),
                 severity,
             }
         }
@@ -352,6 +359,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "UNKNOWN OPERATOR".to_string(),
+                code: Some(codes::E0104),
                 severity,
             }
         }
@@ -379,14 +387,16 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD IDENTIFIER".to_string(),
+                code: Some(codes::E0105),
                 severity,
             }
         }
 
         EExpr::Start(pos) | EExpr::IndentStart(pos) => {
-            let (title, expecting) = match &context {
+            let (title, code, expecting) = match &context {
                 Context::InNode { .. } | Context::InDef { .. } => (
                     "MISSING EXPRESSION",
+                    codes::E0106,
                     alloc.concat([
                         alloc.reflow("I was expecting to see an expression like "),
                         alloc.parser_suggestion("42"),
@@ -397,6 +407,7 @@ fn to_expr_report<'a>(
                 ),
                 Context::InDefFinalExpr { .. } => (
                     "MISSING FINAL EXPRESSION",
+                    codes::E0107,
                     alloc.stack([
                         alloc.concat([
                             alloc.reflow("This definition is missing a final expression."),
@@ -462,6 +473,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: title.to_string(),
+                code: Some(code),
                 severity,
             }
         }
@@ -490,6 +502,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "MISSING FINAL EXPRESSION".to_string(),
+                code: Some(codes::E0107),
                 severity,
             }
         }
@@ -521,6 +534,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "SYNTAX PROBLEM".to_string(),
+                code: Some(codes::E0201),
                 severity,
             }
         }
@@ -544,6 +558,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "ARGUMENTS BEFORE EQUALS".to_string(),
+                code: Some(codes::E0103),
                 severity,
             }
         }
@@ -602,6 +617,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "INDENT ENDS AFTER EXPRESSION".to_string(),
+                code: Some(codes::E0108),
                 severity,
             }
         }
@@ -629,6 +645,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "TRAILING OPERATOR".to_string(),
+                code: Some(codes::E0109),
                 severity,
             }
         }
@@ -646,6 +663,7 @@ fn to_expr_report<'a>(
                 filename,
                 doc,
                 title: "UNEXPECTED COMMA".to_string(),
+                code: Some(codes::E0110),
                 severity,
             }
         }
@@ -734,6 +752,7 @@ fn to_unhandled_parse_error_report<'a>(
         filename,
         doc,
         title: "UNHANDLED PARSE ERROR".to_string(),
+        code: Some(codes::E0111),
         severity,
     }
 }
@@ -760,6 +779,7 @@ fn to_record_report<'a>(
         filename,
         doc,
         title: "RECORD PARSE PROBLEM".to_string(),
+        code: Some(codes::E0112),
         severity,
     }
 }
@@ -794,6 +814,7 @@ fn to_lambda_report<'a>(
                 filename,
                 doc,
                 title: "MALFORMED ARGS LIST".to_string(),
+                code: Some(codes::E0113),
                 severity,
             }
         }
@@ -817,6 +838,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "WEIRD ARROW".to_string(),
+                    code: Some(codes::E0114),
                     severity,
                 }
             }
@@ -839,6 +861,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "MISSING ARROW".to_string(),
+                    code: Some(codes::E0115),
                     severity,
                 }
             }
@@ -864,6 +887,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "WEIRD ARROW".to_string(),
+                    code: Some(codes::E0114),
                     severity,
                 }
             }
@@ -886,6 +910,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "MISSING ARROW".to_string(),
+                    code: Some(codes::E0115),
                     severity,
                 }
             }
@@ -910,6 +935,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED ARGUMENT LIST".to_string(),
+                    code: Some(codes::E0116),
                     severity,
                 }
             }
@@ -931,6 +957,7 @@ fn to_lambda_report<'a>(
                     filename,
                     doc,
                     title: "MISSING ARROW".to_string(),
+                    code: Some(codes::E0115),
                     severity,
                 }
             }
@@ -1013,6 +1040,7 @@ fn to_unfinished_lambda_report<'a>(
         filename,
         doc,
         title: "UNFINISHED FUNCTION".to_string(),
+        code: Some(codes::E0117),
         severity,
     }
 }
@@ -1078,6 +1106,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD ESCAPE".to_string(),
+                code: Some(codes::E0118),
                 severity,
             }
         }
@@ -1104,6 +1133,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD CODE POINT".to_string(),
+                code: Some(codes::E0119),
                 severity,
             }
         }
@@ -1125,6 +1155,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "ENDLESS FORMAT".to_string(),
+                code: Some(codes::E0120),
                 severity,
             }
         }
@@ -1148,6 +1179,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "ENDLESS SCALAR".to_string(),
+                code: Some(codes::E0121),
                 severity,
             }
         }
@@ -1214,6 +1246,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "INVALID SCALAR".to_string(),
+                code: Some(codes::E0122),
                 severity,
             }
         }
@@ -1237,6 +1270,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "ENDLESS STRING".to_string(),
+                code: Some(codes::E0123),
                 severity,
             }
         }
@@ -1261,6 +1295,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "EXPECTED STRING".to_string(),
+                code: Some(codes::E0124),
                 severity,
             }
         }
@@ -1284,6 +1319,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "ENDLESS STRING".to_string(),
+                code: Some(codes::E0123),
                 severity,
             }
         }
@@ -1305,6 +1341,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "INSUFFICIENT INDENT IN MULTI-LINE STRING".to_string(),
+                code: Some(codes::E0125),
                 severity,
             }
         }
@@ -1330,6 +1367,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "INVALID UNICODE CODE POINT".to_string(),
+                code: Some(codes::E0126),
                 severity,
             }
         }
@@ -1351,6 +1389,7 @@ fn to_str_report<'a>(
                 filename,
                 doc,
                 title: "UNICODE CODE POINT TOO LARGE".to_string(),
+                code: Some(codes::E0127),
                 severity,
             }
         }
@@ -1393,6 +1432,7 @@ fn to_expr_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "EMPTY PARENTHESES".to_string(),
+                code: Some(codes::E0128),
                 severity,
             }
         }
@@ -1417,6 +1457,7 @@ fn to_expr_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PARENTHESES".to_string(),
+                code: Some(codes::E0129),
                 severity,
             }
         }
@@ -1442,6 +1483,7 @@ fn to_expr_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PARENTHESES".to_string(),
+                code: Some(codes::E0129),
                 severity,
             }
         }
@@ -1496,6 +1538,7 @@ fn to_list_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED LIST".to_string(),
+                        code: Some(codes::E0130),
                         severity,
                     }
                 }
@@ -1533,6 +1576,7 @@ fn to_list_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED LIST".to_string(),
+                        code: Some(codes::E0130),
                         severity,
                     }
                 }
@@ -1659,6 +1703,7 @@ fn to_import_report<'a>(
                 filename,
                 doc,
                 title: "IGNORED RECORD FIELD IN MODULE PARAMS".to_string(),
+                code: Some(codes::E0131),
                 severity,
             }
         }
@@ -1676,6 +1721,7 @@ fn to_import_report<'a>(
                 filename,
                 doc,
                 title: "RECORD UPDATE IN MODULE PARAMS".to_string(),
+                code: Some(codes::E0132),
                 severity,
             }
         }
@@ -1693,6 +1739,7 @@ fn to_import_report<'a>(
                 filename,
                 doc,
                 title: "RECORD BUILDER IN MODULE PARAMS".to_string(),
+                code: Some(codes::E0133),
                 severity,
             }
         }
@@ -1722,6 +1769,7 @@ fn to_import_report<'a>(
                 filename,
                 doc,
                 title: "LOWERCASE ALIAS".to_string(),
+                code: Some(codes::E0134),
                 severity,
             }
         }
@@ -1757,6 +1805,7 @@ fn to_import_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD EXPOSING".to_string(),
+                code: Some(codes::E0135),
                 severity,
             }
         }
@@ -1819,6 +1868,7 @@ fn to_unfinished_import_report<'a>(
         filename,
         doc,
         title: "UNFINISHED IMPORT".to_string(),
+        code: Some(codes::E0136),
         severity,
     }
 }
@@ -1932,6 +1982,7 @@ fn to_unfinished_if_report<'a>(
         filename,
         doc,
         title: "UNFINISHED IF".to_string(),
+        code: Some(codes::E0137),
         severity,
     }
 }
@@ -1969,6 +2020,7 @@ fn to_when_report<'a>(
                         filename,
                         doc,
                         title: "IF GUARD NO CONDITION".to_string(),
+                        code: Some(codes::E0138),
                         severity,
                     }
                 }
@@ -2001,6 +2053,7 @@ fn to_when_report<'a>(
                 filename,
                 doc,
                 title: "MISSING ARROW".to_string(),
+                code: Some(codes::E0115),
                 severity,
             }
         }
@@ -2156,6 +2209,7 @@ fn to_unfinished_when_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED WHEN".to_string(),
+                code: Some(codes::E0139),
                 severity,
             }
         }
@@ -2198,6 +2252,7 @@ fn to_unexpected_arrow_report<'a>(
         filename,
         doc,
         title: "UNEXPECTED ARROW".to_string(),
+        code: Some(codes::E0140),
         severity,
     }
 }
@@ -2275,6 +2330,7 @@ fn to_pattern_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PATTERN".to_string(),
+                code: Some(codes::E0141),
                 severity,
             }
         }
@@ -2343,6 +2399,7 @@ fn to_precord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD PATTERN".to_string(),
+                    code: Some(codes::E0142),
                     severity,
                 }
             }
@@ -2364,6 +2421,7 @@ fn to_precord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD PATTERN".to_string(),
+                    code: Some(codes::E0142),
                     severity,
                 }
             }
@@ -2389,6 +2447,7 @@ fn to_precord_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED RECORD PATTERN".to_string(),
+                        code: Some(codes::E0142),
                         severity,
                     }
                 }
@@ -2409,6 +2468,7 @@ fn to_precord_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED RECORD PATTERN".to_string(),
+                        code: Some(codes::E0142),
                         severity,
                     }
                 }
@@ -2434,6 +2494,7 @@ fn to_precord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD PATTERN".to_string(),
+                    code: Some(codes::E0142),
                     severity,
                 }
             }
@@ -2459,6 +2520,7 @@ fn to_precord_report<'a>(
                     filename,
                     doc,
                     title: "PROBLEM IN RECORD PATTERN".to_string(),
+                    code: Some(codes::E0143),
                     severity,
                 }
             }
@@ -2509,6 +2571,7 @@ fn to_plist_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED LIST PATTERN".to_string(),
+                code: Some(codes::E0144),
                 severity,
             }
         }
@@ -2531,6 +2594,7 @@ fn to_plist_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED LIST PATTERN".to_string(),
+                code: Some(codes::E0144),
                 severity,
             }
         }
@@ -2553,6 +2617,7 @@ fn to_plist_report<'a>(
                 filename,
                 doc,
                 title: "INCORRECT REST PATTERN".to_string(),
+                code: Some(codes::E0145),
                 severity,
             }
         }
@@ -2598,6 +2663,7 @@ fn to_pattern_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PARENTHESES".to_string(),
+                code: Some(codes::E0129),
                 severity,
             }
         }
@@ -2620,6 +2686,7 @@ fn to_pattern_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "EMPTY PARENTHESES".to_string(),
+                code: Some(codes::E0128),
                 severity,
             }
         }
@@ -2644,6 +2711,7 @@ fn to_pattern_in_parens_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PARENTHESES".to_string(),
+                code: Some(codes::E0129),
                 severity,
             }
         }
@@ -2673,6 +2741,7 @@ fn to_malformed_number_literal_report<'a>(
         filename,
         doc,
         title: "INVALID NUMBER LITERAL".to_string(),
+        code: Some(codes::E0146),
         severity,
     }
 }
@@ -2714,6 +2783,7 @@ fn to_type_report<'a>(
                         filename,
                         doc,
                         title: "DOUBLE COMMA".to_string(),
+                        code: Some(codes::E0147),
                         severity,
                     }
                 }
@@ -2741,6 +2811,7 @@ fn to_type_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED TYPE".to_string(),
+                code: Some(codes::E0148),
                 severity,
             }
         }
@@ -2759,6 +2830,7 @@ fn to_type_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED TYPE".to_string(),
+                code: Some(codes::E0148),
                 severity,
             }
         }
@@ -2777,6 +2849,7 @@ fn to_type_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED TYPE".to_string(),
+                code: Some(codes::E0148),
                 severity,
             }
         }
@@ -2795,6 +2868,7 @@ fn to_type_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED INLINE ALIAS".to_string(),
+                code: Some(codes::E0149),
                 severity,
             }
         }
@@ -2812,6 +2886,7 @@ fn to_type_report<'a>(
                 filename,
                 doc,
                 title: "BAD TYPE VARIABLE".to_string(),
+                code: Some(codes::E0150),
                 severity,
             }
         }
@@ -2866,6 +2941,7 @@ fn to_trecord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD TYPE".to_string(),
+                    code: Some(codes::E0151),
                     severity,
                 }
             }
@@ -2891,6 +2967,7 @@ fn to_trecord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD TYPE".to_string(),
+                    code: Some(codes::E0151),
                     severity,
                 }
             }
@@ -2916,6 +2993,7 @@ fn to_trecord_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED RECORD TYPE".to_string(),
+                        code: Some(codes::E0151),
                         severity,
                     }
                 }
@@ -2936,6 +3014,7 @@ fn to_trecord_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED RECORD TYPE".to_string(),
+                        code: Some(codes::E0151),
                         severity,
                     }
                 }
@@ -2961,6 +3040,7 @@ fn to_trecord_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED RECORD TYPE".to_string(),
+                    code: Some(codes::E0151),
                     severity,
                 }
             }
@@ -2986,6 +3066,7 @@ fn to_trecord_report<'a>(
                     filename,
                     doc,
                     title: "PROBLEM IN RECORD TYPE".to_string(),
+                    code: Some(codes::E0152),
                     severity,
                 }
             }
@@ -3019,6 +3100,7 @@ fn to_trecord_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED RECORD TYPE".to_string(),
+                code: Some(codes::E0151),
                 severity,
             }
         }
@@ -3043,6 +3125,7 @@ fn to_trecord_report<'a>(
                         filename,
                         doc,
                         title: "NEED MORE INDENTATION".to_string(),
+                        code: Some(codes::E0153),
                         severity,
                     }
                 }
@@ -3072,6 +3155,7 @@ fn to_trecord_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED RECORD TYPE".to_string(),
+                        code: Some(codes::E0151),
                         severity,
                     }
                 }
@@ -3121,6 +3205,7 @@ fn to_ttag_union_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED TAG UNION TYPE".to_string(),
+                    code: Some(codes::E0154),
                     severity,
                 }
             }
@@ -3147,6 +3232,7 @@ fn to_ttag_union_report<'a>(
                     filename,
                     doc,
                     title: "WEIRD TAG NAME".to_string(),
+                    code: Some(codes::E0155),
                     severity,
                 }
             }
@@ -3172,6 +3258,7 @@ fn to_ttag_union_report<'a>(
                     filename,
                     doc,
                     title: "UNFINISHED TAG UNION TYPE".to_string(),
+                    code: Some(codes::E0154),
                     severity,
                 }
             }
@@ -3202,6 +3289,7 @@ fn to_ttag_union_report<'a>(
                         filename,
                         doc,
                         title: "WEIRD TAG NAME".to_string(),
+                        code: Some(codes::E0155),
                         severity,
                     }
                 }
@@ -3222,6 +3310,7 @@ fn to_ttag_union_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED TAG UNION TYPE".to_string(),
+                        code: Some(codes::E0154),
                         severity,
                     }
                 }
@@ -3267,6 +3356,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED PARENTHESES".to_string(),
+                        code: Some(codes::E0129),
                         severity,
                     }
                 }
@@ -3289,6 +3379,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "WEIRD TAG NAME".to_string(),
+                        code: Some(codes::E0155),
                         severity,
                     }
                 }
@@ -3316,6 +3407,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED PARENTHESES".to_string(),
+                        code: Some(codes::E0129),
                         severity,
                     }
                 }
@@ -3339,6 +3431,7 @@ fn to_tinparens_report<'a>(
                 filename,
                 doc,
                 title: "EMPTY PARENTHESES".to_string(),
+                code: Some(codes::E0128),
                 severity,
             }
         }
@@ -3365,6 +3458,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "WEIRD TAG NAME".to_string(),
+                        code: Some(codes::E0155),
                         severity,
                     }
                 }
@@ -3385,6 +3479,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED PARENTHESES".to_string(),
+                        code: Some(codes::E0129),
                         severity,
                     }
                 }
@@ -3413,6 +3508,7 @@ fn to_tinparens_report<'a>(
                 filename,
                 doc,
                 title: "UNFINISHED PARENTHESES".to_string(),
+                code: Some(codes::E0129),
                 severity,
             }
         }
@@ -3437,6 +3533,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "NEED MORE INDENTATION".to_string(),
+                        code: Some(codes::E0153),
                         severity,
                     }
                 }
@@ -3462,6 +3559,7 @@ fn to_tinparens_report<'a>(
                         filename,
                         doc,
                         title: "UNFINISHED PARENTHESES".to_string(),
+                        code: Some(codes::E0129),
                         severity,
                     }
                 }
@@ -3496,6 +3594,7 @@ fn to_tapply_report<'a>(
                 filename,
                 doc,
                 title: "DOUBLE DOT".to_string(),
+                code: Some(codes::E0156),
                 severity,
             }
         }
@@ -3518,6 +3617,7 @@ fn to_tapply_report<'a>(
                 filename,
                 doc,
                 title: "TRAILING DOT".to_string(),
+                code: Some(codes::E0157),
                 severity,
             }
         }
@@ -3540,6 +3640,7 @@ fn to_tapply_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD QUALIFIED NAME".to_string(),
+                code: Some(codes::E0158),
                 severity,
             }
         }
@@ -3562,6 +3663,7 @@ fn to_tapply_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD QUALIFIED NAME".to_string(),
+                code: Some(codes::E0158),
                 severity,
             }
         }
@@ -3580,6 +3682,7 @@ fn to_tapply_report<'a>(
                 filename,
                 doc,
                 title: "END OF FILE".to_string(),
+                code: Some(codes::E0159),
                 severity,
             }
         }
@@ -3621,6 +3724,7 @@ fn to_talias_report<'a>(
                 filename,
                 doc,
                 title: "NOT AN INLINE ALIAS".to_string(),
+                code: Some(codes::E0160),
                 severity,
             }
         }
@@ -3637,6 +3741,7 @@ fn to_talias_report<'a>(
                 filename,
                 doc,
                 title: "QUALIFIED ALIAS NAME".to_string(),
+                code: Some(codes::E0161),
                 severity,
             }
         }
@@ -3653,6 +3758,7 @@ fn to_talias_report<'a>(
                 filename,
                 doc,
                 title: "TYPE ARGUMENT NOT LOWERCASE".to_string(),
+                code: Some(codes::E0162),
                 severity,
             }
         }
@@ -3703,6 +3809,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "INCOMPLETE HEADER".to_string(),
+                code: Some(codes::E0163),
                 severity,
             }
         }
@@ -3745,6 +3852,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "MISSING HEADER".to_string(),
+                code: Some(codes::E0164),
                 severity,
             }
         }
@@ -3769,6 +3877,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD MODULE NAME".to_string(),
+                code: Some(codes::E0165),
                 severity,
             }
         }
@@ -3796,6 +3905,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD MODULE NAME".to_string(),
+                code: Some(codes::E0165),
                 severity,
             }
         }
@@ -3820,6 +3930,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD APP NAME".to_string(),
+                code: Some(codes::E0166),
                 severity,
             }
         }
@@ -3842,6 +3953,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "INVALID PACKAGE NAME".to_string(),
+                code: Some(codes::E0167),
                 severity,
             }
         }
@@ -3865,6 +3977,7 @@ fn to_header_report<'a>(
                 filename,
                 doc,
                 title: "INVALID PLATFORM NAME".to_string(),
+                code: Some(codes::E0168),
                 severity,
             }
         }
@@ -3906,6 +4019,7 @@ fn to_provides_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD PROVIDES".to_string(),
+                code: Some(codes::E0169),
                 severity,
             }
         }
@@ -3931,6 +4045,7 @@ fn to_provides_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD PROVIDES".to_string(),
+                code: Some(codes::E0169),
                 severity,
             }
         }
@@ -3958,6 +4073,7 @@ fn to_provides_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD PROVIDES".to_string(),
+                code: Some(codes::E0169),
                 severity,
             }
         }
@@ -3979,6 +4095,7 @@ fn to_provides_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD PROVIDES".to_string(),
+                code: Some(codes::E0169),
                 severity,
             }
         }
@@ -4029,6 +4146,7 @@ fn to_params_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD MODULE PARAMS".to_string(),
+                code: Some(codes::E0170),
                 severity,
             }
         }
@@ -4073,6 +4191,7 @@ fn to_exposes_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD EXPOSES".to_string(),
+                code: Some(codes::E0171),
                 severity,
             }
         }
@@ -4096,6 +4215,7 @@ fn to_exposes_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD EXPOSES".to_string(),
+                code: Some(codes::E0171),
                 severity,
             }
         }
@@ -4133,6 +4253,7 @@ fn to_exposes_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD EXPOSES".to_string(),
+                code: Some(codes::E0171),
                 severity,
             }
         }
@@ -4183,6 +4304,7 @@ fn to_imports_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD IMPORTS".to_string(),
+                code: Some(codes::E0172),
                 severity,
             }
         }
@@ -4208,6 +4330,7 @@ fn to_imports_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD IMPORTS".to_string(),
+                code: Some(codes::E0172),
                 severity,
             }
         }
@@ -4234,6 +4357,7 @@ fn to_imports_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD MODULE NAME".to_string(),
+                code: Some(codes::E0165),
                 severity,
             }
         }
@@ -4253,6 +4377,7 @@ fn to_imports_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD IMPORTS".to_string(),
+                code: Some(codes::E0172),
                 severity,
             }
         }
@@ -4314,6 +4439,7 @@ fn to_requires_report<'a>(
                 filename,
                 doc,
                 title: "MISSING REQUIRES".to_string(),
+                code: Some(codes::E0173),
                 severity,
             }
         }
@@ -4341,6 +4467,7 @@ fn to_requires_report<'a>(
                 filename,
                 doc,
                 title: "MISSING REQUIRES".to_string(),
+                code: Some(codes::E0173),
                 severity,
             }
         }
@@ -4370,6 +4497,7 @@ fn to_requires_report<'a>(
                 filename,
                 doc,
                 title: "BAD REQUIRES RIGIDS".to_string(),
+                code: Some(codes::E0174),
                 severity,
             }
         }
@@ -4399,6 +4527,7 @@ fn to_requires_report<'a>(
                 filename,
                 doc,
                 title: "BAD REQUIRES".to_string(),
+                code: Some(codes::E0175),
                 severity,
             }
         }
@@ -4448,6 +4577,7 @@ fn to_packages_report<'a>(
                 filename,
                 doc,
                 title: "MISSING PACKAGES".to_string(),
+                code: Some(codes::E0176),
                 severity,
             }
         }
@@ -4470,6 +4600,7 @@ fn to_packages_report<'a>(
                 filename,
                 doc,
                 title: "WEIRD PACKAGES LIST".to_string(),
+                code: Some(codes::E0177),
                 severity,
             }
         }
@@ -4521,6 +4652,7 @@ fn to_space_report<'a>(
                 filename,
                 doc,
                 title: "TAB CHARACTER".to_string(),
+                code: Some(codes::E0178),
                 severity,
             }
         }
@@ -4538,6 +4670,7 @@ fn to_space_report<'a>(
                 filename,
                 doc,
                 title: "ASCII CONTROL CHARACTER".to_string(),
+                code: Some(codes::E0179),
                 severity,
             }
         }
@@ -4555,6 +4688,7 @@ fn to_space_report<'a>(
                 filename,
                 doc,
                 title: "MISPLACED CARRIAGE RETURN".to_string(),
+                code: Some(codes::E0180),
                 severity,
             }
         }
@@ -4646,6 +4780,7 @@ fn to_unfinished_ability_report<'a>(
         filename,
         doc,
         title: "UNFINISHED ABILITY".to_string(),
+        code: Some(codes::E0181),
         severity,
     }
 }
@@ -1,6 +1,7 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::error::canonicalize::{to_circular_def_doc, CIRCULAR_DEF};
+use crate::error::codes;
 use crate::report::{Annotation, Report, RocDocAllocator, RocDocBuilder};
 use itertools::EitherOrBoth;
 use itertools::Itertools;
@@ -46,16 +47,6 @@ pub fn type_problem<'b>(
 
     let severity = problem.severity();
 
-    let report =
-        move |title: String, doc: RocDocBuilder<'b>, filename: PathBuf| -> Option<Report<'b>> {
-            Some(Report {
-                title,
-                filename,
-                doc,
-                severity,
-            })
-        };
-
     match problem {
         BadExpr(region, category, found, expected) => Some(to_expr_report(
             alloc, lines, filename, severity, region, category, found, expected,
@@ -82,14 +73,26 @@ pub fn type_problem<'b>(
                     .append(alloc.symbol_unqualified(symbol))])
                 .append(alloc.reflow("."));
 
-            report(title, doc, filename)
+            Some(Report {
+                title,
+                code: Some(codes::E0203),
+                filename,
+                doc,
+                severity,
+            })
         }
         UnfulfilledAbility(incomplete) => {
             let title = "INCOMPLETE ABILITY IMPLEMENTATION".to_string();
 
             let doc = report_unfulfilled_ability(alloc, lines, incomplete, severity);
 
-            report(title, doc, filename)
+            Some(Report {
+                title,
+                code: Some(codes::E0248),
+                filename,
+                doc,
+                severity,
+            })
         }
         BadExprMissingAbility(region, _category, _found, incomplete) => {
             if region == roc_can::DERIVED_REGION {
@@ -111,6 +114,7 @@ pub fn type_problem<'b>(
 
             let report = Report {
                 title: "TYPE MISMATCH".to_string(),
+                code: Some(codes::E0301),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -133,6 +137,7 @@ pub fn type_problem<'b>(
 
             let report = Report {
                 title: "TYPE MISMATCH".to_string(),
+                code: Some(codes::E0301),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -146,6 +151,7 @@ pub fn type_problem<'b>(
 
             Some(Report {
                 title,
+                code: Some(codes::E0217),
                 filename,
                 doc,
                 severity,
@@ -176,6 +182,7 @@ pub fn type_problem<'b>(
 
             Some(Report {
                 title: "ILLEGAL SPECIALIZATION".to_string(),
+                code: Some(codes::E0302),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -205,6 +212,7 @@ pub fn type_problem<'b>(
 
             Some(Report {
                 title: "WRONG SPECIALIZATION TYPE".to_string(),
+                code: Some(codes::E0303),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -221,6 +229,7 @@ pub fn type_problem<'b>(
             ];
             Some(Report {
                 title: "INVALID UTF-8".to_string(),
+                code: Some(codes::E0304),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -244,6 +253,7 @@ pub fn type_problem<'b>(
             ];
             Some(Report {
                 title: "INVALID TYPE FOR INGESTED FILE".to_string(),
+                code: Some(codes::E0305),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -264,6 +274,7 @@ pub fn type_problem<'b>(
 
             Some(Report {
                 title: "UNEXPECTED MODULE PARAMS".to_string(),
+                code: Some(codes::E0306),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -286,6 +297,7 @@ pub fn type_problem<'b>(
             ];
             Some(Report {
                 title: "MISSING MODULE PARAMS".to_string(),
+                code: Some(codes::E0307),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -311,6 +323,7 @@ pub fn type_problem<'b>(
             ];
             Some(Report {
                 title: "MODULE PARAMS MISMATCH".to_string(),
+                code: Some(codes::E0308),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -345,6 +358,7 @@ pub fn type_problem<'b>(
             Some(Report {
                 filename,
                 title: "EFFECT IN PURE FUNCTION".to_string(),
+                code: Some(codes::E0309),
                 doc: alloc.stack(lines),
                 severity,
             })
@@ -365,6 +379,7 @@ pub fn type_problem<'b>(
             Some(Report {
                 filename,
                 title: "EFFECT IN TOP-LEVEL".to_string(),
+                code: Some(codes::E0310),
                 doc: alloc.stack(lines),
                 severity,
             })
@@ -380,6 +395,7 @@ pub fn type_problem<'b>(
             ];
             Some(Report {
                 title: "LEFTOVER STATEMENT".to_string(),
+                code: Some(codes::E0311),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -394,6 +410,7 @@ pub fn type_problem<'b>(
 
             Some(Report {
                 title: "UNNECESSARY DEFINITION".to_string(),
+                code: Some(codes::E0258),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -423,6 +440,7 @@ pub fn type_problem<'b>(
             ];
             Some(Report {
                 title: "MISSING EXCLAMATION".to_string(),
+                code: Some(codes::E0249),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -442,6 +460,7 @@ pub fn type_problem<'b>(
             ];
             Some(Report {
                 title: "MISSING EXCLAMATION".to_string(),
+                code: Some(codes::E0249),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -468,6 +487,7 @@ pub fn type_problem<'b>(
 
             Some(Report {
                 title: "UNNECESSARY EXCLAMATION".to_string(),
+                code: Some(codes::E0250),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -508,6 +528,7 @@ pub fn type_problem<'b>(
 
             Some(Report {
                 title: "INVALID TRY TARGET".to_string(),
+                code: Some(codes::E0312),
                 filename,
                 doc: alloc.stack(stack),
                 severity,
@@ -540,6 +561,7 @@ pub fn type_problem<'b>(
 
             Some(Report {
                 title: "TYPE VARIABLE IS NOT GENERIC".to_string(),
+                code: Some(codes::E0313),
                 filename,
                 doc,
                 severity,
@@ -836,6 +858,7 @@ fn report_mismatch<'b>(
 
     Report {
         title: "TYPE MISMATCH".to_string(),
+        code: Some(codes::E0301),
         filename,
         doc: alloc.stack(lines),
         severity,
@@ -880,6 +903,7 @@ fn report_bad_type<'b>(
 
     Report {
         title: "TYPE MISMATCH".to_string(),
+        code: Some(codes::E0301),
         filename,
         doc: alloc.stack(lines),
         severity,
@@ -964,6 +988,7 @@ fn to_expr_report<'b>(
             Report {
                 filename,
                 title: "TYPE MISMATCH".to_string(),
+                code: Some(codes::E0301),
                 doc: alloc.stack([
                     alloc.text("This expression is used in an unexpected way:"),
                     alloc.region(lines.convert_region(expr_region), severity),
@@ -1105,6 +1130,7 @@ fn to_expr_report<'b>(
 
             Report {
                 title: "TYPE MISMATCH".to_string(),
+                code: Some(codes::E0301),
                 filename,
                 doc: alloc.stack([
                     alloc.text("Something is off with the ").append(thing),
@@ -1503,6 +1529,7 @@ fn to_expr_report<'b>(
                     Report {
                         filename,
                         title: "TOO MANY ARGS".to_string(),
+                        code: Some(codes::E0315),
                         doc,
                         severity,
                     }
@@ -1538,6 +1565,7 @@ fn to_expr_report<'b>(
                         Report {
                             filename,
                             title: "TOO MANY ARGS".to_string(),
+                            code: Some(codes::E0315),
                             doc: alloc.stack(lines),
                             severity,
                         }
@@ -1565,6 +1593,7 @@ fn to_expr_report<'b>(
                         Report {
                             filename,
                             title: "TOO FEW ARGS".to_string(),
+                            code: Some(codes::E0316),
                             doc: alloc.stack(lines),
                             severity,
                         }
@@ -1766,6 +1795,7 @@ fn to_expr_report<'b>(
 
                 Report {
                     title: "TYPE MISMATCH".to_string(),
+                    code: Some(codes::E0301),
                     filename,
                     doc: alloc.stack(lines),
                     severity,
@@ -1804,6 +1834,7 @@ fn to_expr_report<'b>(
                 Report {
                     filename,
                     title: "TYPE MISMATCH".to_string(),
+                    code: Some(codes::E0301),
                     doc,
                     severity,
                 }
@@ -1840,6 +1871,7 @@ fn to_expr_report<'b>(
                 Report {
                     filename,
                     title: "TYPE MISMATCH".to_string(),
+                    code: Some(codes::E0301),
                     doc: alloc.stack(lines),
                     severity,
                 }
@@ -1895,6 +1927,7 @@ fn to_expr_report<'b>(
 
                 Report {
                     title: "TYPE MISMATCH".to_string(),
+                    code: Some(codes::E0301),
                     filename,
                     doc: alloc.stack([
                         problem,
@@ -1944,6 +1977,7 @@ fn to_expr_report<'b>(
                 Report {
                     filename,
                     title: "IGNORED RESULT".to_string(),
+                    code: Some(codes::E0317),
                     doc: alloc.stack(lines),
                     severity,
                 }
@@ -1974,6 +2008,7 @@ fn to_expr_report<'b>(
 
                 Report {
                     title: "TYPE MISMATCH".to_string(),
+                    code: Some(codes::E0301),
                     filename,
                     doc: alloc.stack([
                         problem,
@@ -2391,6 +2426,7 @@ fn to_pattern_report<'b>(
             Report {
                 filename,
                 title: "TYPE MISMATCH".to_string(),
+                code: Some(codes::E0301),
                 doc,
                 severity,
             }
@@ -2434,6 +2470,7 @@ fn to_pattern_report<'b>(
                 Report {
                     filename,
                     title: "TYPE MISMATCH".to_string(),
+                    code: Some(codes::E0301),
                     doc,
                     severity,
                 }
@@ -2516,6 +2553,7 @@ fn to_pattern_report<'b>(
                 Report {
                     filename,
                     title: "TYPE MISMATCH".to_string(),
+                    code: Some(codes::E0301),
                     doc,
                     severity,
                 }
@@ -2543,6 +2581,7 @@ fn to_pattern_report<'b>(
                 Report {
                     filename,
                     title: "TYPE MISMATCH".to_string(),
+                    code: Some(codes::E0301),
                     doc,
                     severity,
                 }
@@ -2627,6 +2666,7 @@ fn to_circular_report<'b>(
 ) -> Report<'b> {
     Report {
         title: "CIRCULAR TYPE".to_string(),
+        code: Some(codes::E0318),
         filename,
         doc: {
             alloc.stack([
@@ -5332,6 +5372,7 @@ fn report_record_field_typo<'b>(
     Report {
         filename,
         title: "TYPE MISMATCH".to_string(),
+        code: Some(codes::E0301),
         doc,
         severity,
     }
@@ -5369,6 +5410,7 @@ fn exhaustive_problem<'a>(
                 Report {
                     filename,
                     title: "UNSAFE PATTERN".to_string(),
+                    code: Some(codes::E0319),
                     doc,
                     severity,
                 }
@@ -5393,6 +5435,7 @@ fn exhaustive_problem<'a>(
                 Report {
                     filename,
                     title: "UNSAFE PATTERN".to_string(),
+                    code: Some(codes::E0319),
                     doc,
                     severity,
                 }
@@ -5417,6 +5460,7 @@ fn exhaustive_problem<'a>(
                 Report {
                     filename,
                     title: "UNSAFE PATTERN".to_string(),
+                    code: Some(codes::E0319),
                     doc,
                     severity,
                 }
@@ -5447,6 +5491,7 @@ fn exhaustive_problem<'a>(
             Report {
                 filename,
                 title: "REDUNDANT PATTERN".to_string(),
+                code: Some(codes::E0320),
                 doc,
                 severity,
             }
@@ -5476,6 +5521,7 @@ fn exhaustive_problem<'a>(
             Report {
                 filename,
                 title: "UNMATCHABLE PATTERN".to_string(),
+                code: Some(codes::E0321),
                 doc,
                 severity,
             }
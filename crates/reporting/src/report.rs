@@ -1,3 +1,4 @@
+use crate::error::codes::{self, ErrorCode};
 use roc_module::ident::Ident;
 use roc_module::ident::{Lowercase, ModuleName, TagName, Uppercase};
 use roc_module::symbol::{Interns, ModuleId, ModuleIds, PQModuleName, PackageQualified, Symbol};
@@ -121,6 +122,9 @@ pub enum RenderTarget {
 /// A textual report.
 pub struct Report<'b> {
     pub title: String,
+    /// The kind of report this is, which is shown next to the title and explained by
+    /// `roc explain`. Reports outside of the compiler's, like those of `roc docs`, have none.
+    pub code: Option<ErrorCode>,
     pub filename: PathBuf,
     pub doc: RocDocBuilder<'b>,
     pub severity: Severity,
@@ -167,14 +171,32 @@ impl<'b> Report<'b> {
             .expect(err_msg);
     }
 
+    /// The stable code of this kind of report, like `E0301`. See `roc explain`.
+    pub fn code(&self) -> Option<&'static str> {
+        self.code.map(|error_code| error_code.code)
+    }
+
     pub fn pretty(self, alloc: &'b RocDocAllocator<'b>) -> RocDocBuilder<'b> {
         if self.title.is_empty() {
             self.doc
         } else {
+            debug_assert!(
+                self.code
+                    .map_or(true, |error_code| error_code.title == self.title),
+                "a {} report was built with the code of {:?}",
+                self.title,
+                self.code,
+            );
+
+            let title = match self.code() {
+                Some(code) => format!("{} [{}]", self.title, code),
+                None => self.title.clone(),
+            };
+
             let header = if self.filename == PathBuf::from("replfile.roc") {
-                crate::report::pretty_header(&self.title)
+                crate::report::pretty_header(&title)
             } else {
-                crate::report::pretty_header_with_path(&self.title, &self.filename)
+                crate::report::pretty_header_with_path(&title, &self.filename)
             };
 
             alloc.stack([alloc.text(header).annotate(Annotation::Header), self.doc])
@@ -1266,6 +1288,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "UNSUPPORTED ENCODING".to_string(),
+                code: Some(codes::E0401),
                 severity: Severity::Fatal,
             }
         }
@@ -1301,6 +1324,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "MULTIPLE ENCODINGS".to_string(),
+                code: Some(codes::E0402),
                 severity: Severity::Fatal,
             }
         }
@@ -1336,6 +1360,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "INVALID CONTENT HASH".to_string(),
+                code: Some(codes::E0403),
                 severity: Severity::Fatal,
             }
         }
@@ -1353,6 +1378,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "NOTFOUND".to_string(),
+                code: Some(codes::E0404),
                 severity: Severity::Fatal,
             }
         }
@@ -1380,6 +1406,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "IO ERROR".to_string(),
+                code: Some(codes::E0405),
                 severity: Severity::Fatal,
             }
         }
@@ -1407,6 +1434,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "IO ERROR".to_string(),
+                code: Some(codes::E0405),
                 severity: Severity::Fatal,
             }
         }
@@ -1436,6 +1464,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "HTTP ERROR".to_string(),
+                code: Some(codes::E0406),
                 severity: Severity::Fatal,
             }
         }
@@ -1477,6 +1506,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "INVALID EXTENSION SUFFIX".to_string(),
+                code: Some(codes::E0407),
                 severity: Severity::Fatal,
             }
         }
@@ -1510,6 +1540,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "INVALID EXTENSION".to_string(),
+                code: Some(codes::E0408),
                 severity: Severity::Fatal,
             }
         }
@@ -1548,6 +1579,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "INVALID FRAGMENT".to_string(),
+                code: Some(codes::E0409),
                 severity: Severity::Fatal,
             }
         }
@@ -1586,6 +1618,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "MISSING PACKAGE HASH".to_string(),
+                code: Some(codes::E0410),
                 severity: Severity::Fatal,
             }
         }
@@ -1612,6 +1645,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "HTTPS MANDATORY".to_string(),
+                code: Some(codes::E0411),
                 severity: Severity::Fatal,
             }
         }
@@ -1655,6 +1689,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "MISLEADING CHARACTERS".to_string(),
+                code: Some(codes::E0412),
                 severity: Severity::Fatal,
             }
         }
@@ -1685,6 +1720,7 @@ pub fn to_https_problem_report<'b>(
                 filename,
                 doc,
                 title: "FILE TOO LARGE".to_string(),
+                code: Some(codes::E0413),
                 severity: Severity::Fatal,
             }
         }
@@ -1740,6 +1776,7 @@ pub fn to_file_problem_report<'b>(
                 filename,
                 doc,
                 title: "FILE NOT FOUND".to_string(),
+                code: Some(codes::E0414),
                 severity: Severity::Fatal,
             }
         }
@@ -1758,6 +1795,7 @@ pub fn to_file_problem_report<'b>(
                 filename,
                 doc,
                 title: "FILE PERMISSION DENIED".to_string(),
+                code: Some(codes::E0415),
                 severity: Severity::Fatal,
             }
         }
@@ -1794,6 +1832,7 @@ pub fn to_file_problem_report<'b>(
                 filename,
                 doc,
                 title: "NOT A ROC FILE".to_string(),
+                code: Some(codes::E0416),
                 severity: Severity::Fatal,
             }
         }
@@ -1814,6 +1853,7 @@ pub fn to_file_problem_report<'b>(
                 filename,
                 doc,
                 title: "FILE PROBLEM".to_string(),
+                code: Some(codes::E0417),
                 severity: Severity::Fatal,
             }
         }
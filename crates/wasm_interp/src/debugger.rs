@@ -0,0 +1,478 @@
+//! An interactive debugger, for stepping through a program and looking at its state.
+//! It's attached to an [`Instance`] and gets control before every instruction,
+//! pausing at breakpoints or after a step to read commands.
+use std::fmt::{self, Write as _};
+use std::io::{self, BufRead, Write};
+
+use roc_wasm_module::opcodes::OpCode;
+
+use crate::instance::Instance;
+use crate::{Error, ImportDispatcher};
+
+const HELP: &str = "\
+Commands:
+  s, step                 Execute one instruction, stepping into calls
+  n, next                 Execute one instruction, stepping over calls
+  f, finish               Run until the current function returns
+  c, continue             Run until the next breakpoint
+  b, break <target>       Pause when a function is called, given its name,
+                          or at a file offset like 0x1a2b
+  d, delete <number>      Delete a breakpoint
+  i, info                 List the breakpoints
+  w, where                Show the next instruction
+  bt, backtrace           Show the call stack
+  stack                   Show the value stack of the current function
+  locals                  Show the arguments and locals of the current function
+  x, memory <addr> [len]  Show memory as hex
+  str <addr>              Decode the RocStr at an address
+  list <addr> [type]      Decode the RocList at an address. The type of the elements is
+                          one of i8 u8 i16 u16 i32 u32 i64 u64 f32 f64 str (default u8)
+  q, quit                 Stop the program
+An empty line repeats the last command.
+";
+
+/// How many list elements or memory bytes to show when it's not specified
+const DEFAULT_LIST_ELEMENTS: usize = 100;
+const DEFAULT_MEMORY_BYTES: usize = 64;
+
+#[derive(Debug)]
+enum Breakpoint {
+    Function { fn_index: usize, name: String },
+    Offset(u32),
+}
+
+/// When to pause again after a command resumes the program
+#[derive(Debug, Clone, Copy)]
+enum Resume {
+    /// Before the next instruction
+    Step,
+    /// Before the next instruction at this call depth or above
+    Next(usize),
+    /// Before the next instruction above this call depth
+    Finish(usize),
+    /// Only at breakpoints
+    Continue,
+}
+
+pub struct Debugger {
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    breakpoints: Vec<Breakpoint>,
+    resume: Resume,
+    /// Call depth at the previous instruction, to tell when a function was just called
+    previous_depth: Option<usize>,
+    last_command: String,
+    /// Once there are no more commands to read, the program runs to the end
+    input_closed: bool,
+}
+
+impl fmt::Debug for Debugger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Debugger")
+            .field("breakpoints", &self.breakpoints)
+            .field("resume", &self.resume)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Debugger {
+    /// A debugger that reads commands from `input` and writes to `output`.
+    /// It pauses before the first instruction, so that breakpoints can be set.
+    pub fn new(input: Box<dyn BufRead>, output: Box<dyn Write>) -> Self {
+        Debugger {
+            input,
+            output,
+            breakpoints: Vec::new(),
+            resume: Resume::Step,
+            previous_depth: None,
+            last_command: String::new(),
+            input_closed: false,
+        }
+    }
+
+    /// A debugger for the terminal
+    pub fn stdio() -> Self {
+        Self::new(Box::new(io::stdin().lock()), Box::new(io::stdout()))
+    }
+
+    pub(crate) fn start_call(&mut self) {
+        self.previous_depth = None;
+    }
+
+    fn print(&mut self, text: &str) {
+        // The debugger shouldn't crash the program it's debugging, so ignore errors
+        let _ = self.output.write_all(text.as_bytes());
+        let _ = self.output.flush();
+    }
+
+    fn should_pause<I: ImportDispatcher>(&mut self, inst: &Instance<'_, I>) -> bool {
+        if self.input_closed {
+            return false;
+        }
+
+        let depth = inst.call_depth();
+        let file_offset = inst.program_counter as u32 + inst.module.code.section_offset;
+        let just_called = !matches!(self.previous_depth, Some(previous) if depth <= previous);
+        self.previous_depth = Some(depth);
+
+        let at_breakpoint = self.breakpoints.iter().any(|breakpoint| match breakpoint {
+            Breakpoint::Function { fn_index, .. } => {
+                just_called && *fn_index == inst.current_frame.fn_index
+            }
+            Breakpoint::Offset(offset) => *offset == file_offset,
+        });
+
+        at_breakpoint
+            || match self.resume {
+                Resume::Step => true,
+                Resume::Next(d) => depth <= d,
+                Resume::Finish(d) => depth < d,
+                Resume::Continue => false,
+            }
+    }
+
+    /// Read and run commands until one of them resumes the program.
+    /// After a trap, the program can't resume, so those commands just end the prompt.
+    fn prompt<I: ImportDispatcher>(
+        &mut self,
+        inst: &Instance<'_, I>,
+        trapped: bool,
+    ) -> Result<(), Error> {
+        if !trapped {
+            let location = location(inst);
+            self.print(&location);
+        }
+
+        loop {
+            self.print("(debugger) ");
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) | Err(_) => {
+                    self.input_closed = true;
+                    self.print("\n");
+                    return Ok(());
+                }
+                Ok(_) => {}
+            }
+
+            let command = match line.trim() {
+                "" => self.last_command.clone(),
+                command => command.to_string(),
+            };
+            self.last_command = command.clone();
+
+            let mut words = command.split_whitespace();
+            let depth = inst.call_depth();
+            let mut response = String::new();
+            match (words.next(), words.next(), words.next()) {
+                (None, _, _) => {}
+                (Some("s" | "step"), _, _) => {
+                    self.resume = Resume::Step;
+                    return Ok(());
+                }
+                (Some("n" | "next"), _, _) => {
+                    self.resume = Resume::Next(depth);
+                    return Ok(());
+                }
+                (Some("f" | "finish"), _, _) => {
+                    self.resume = Resume::Finish(depth);
+                    return Ok(());
+                }
+                (Some("c" | "continue"), _, _) => {
+                    self.resume = Resume::Continue;
+                    return Ok(());
+                }
+                (Some("q" | "quit"), _, _) => return Err(Error::DebuggerQuit),
+                (Some("h" | "help"), _, _) => response.push_str(HELP),
+                (Some("b" | "break"), Some(target), _) => {
+                    response = self.add_breakpoint(inst, target);
+                }
+                (Some("d" | "delete"), Some(number), _) => match number.parse::<usize>() {
+                    Ok(n) if n >= 1 && n <= self.breakpoints.len() => {
+                        self.breakpoints.remove(n - 1);
+                        writeln!(response, "Deleted breakpoint {n}").unwrap();
+                    }
+                    _ => writeln!(response, "There's no breakpoint {number}").unwrap(),
+                },
+                (Some("i" | "info"), _, _) => {
+                    if self.breakpoints.is_empty() {
+                        writeln!(response, "There are no breakpoints").unwrap();
+                    }
+                    for (i, breakpoint) in self.breakpoints.iter().enumerate() {
+                        match breakpoint {
+                            Breakpoint::Function { fn_index, name } => {
+                                writeln!(response, "{}: func[{fn_index}] {name}", i + 1).unwrap()
+                            }
+                            Breakpoint::Offset(offset) => {
+                                writeln!(response, "{}: {offset:06x}", i + 1).unwrap()
+                            }
+                        }
+                    }
+                }
+                (Some("w" | "where"), _, _) => response = location(inst),
+                (Some("bt" | "backtrace"), _, _) => inst.debug_stack_trace(&mut response).unwrap(),
+                (Some("stack"), _, _) => {
+                    let frame = &inst.current_frame;
+                    let start = frame.locals_start + frame.locals_count;
+                    let values: Vec<_> = inst.value_store.iter().skip(start).collect();
+                    writeln!(response, "{values:?}").unwrap();
+                }
+                (Some("locals"), _, _) => {
+                    let frame = &inst.current_frame;
+                    let arg_count = inst.arg_count(frame.fn_index);
+                    for index in 0..frame.locals_count {
+                        let value = inst.value_store.get(frame.locals_start + index).unwrap();
+                        let kind = if index < arg_count { "arg" } else { "local" };
+                        writeln!(response, "{kind} {index}: {value:?}").unwrap();
+                    }
+                }
+                (Some("x" | "memory"), Some(addr), len) => {
+                    response = match (parse_number(addr), len.map(parse_number)) {
+                        (Some(addr), None) => hex_dump(&inst.memory, addr, DEFAULT_MEMORY_BYTES),
+                        (Some(addr), Some(Some(len))) => hex_dump(&inst.memory, addr, len),
+                        _ => format!("I couldn't read the address in `{command}`\n"),
+                    }
+                }
+                (Some("str"), Some(addr), _) => {
+                    response = match parse_number(addr) {
+                        Some(addr) => decode_roc_str(&inst.memory, addr)
+                            .map(|(string, description)| format!("{string:?} {description}\n"))
+                            .unwrap_or_else(|e| format!("{e}\n")),
+                        None => format!("I couldn't read the address `{addr}`\n"),
+                    }
+                }
+                (Some("list"), Some(addr), elem_type) => {
+                    response = match parse_number(addr) {
+                        Some(addr) => {
+                            decode_roc_list(&inst.memory, addr, elem_type.unwrap_or("u8"))
+                                .unwrap_or_else(|e| format!("{e}\n"))
+                        }
+                        None => format!("I couldn't read the address `{addr}`\n"),
+                    }
+                }
+                (
+                    Some(
+                        command
+                        @ ("b" | "break" | "d" | "delete" | "x" | "memory" | "str" | "list"),
+                    ),
+                    None,
+                    _,
+                ) => {
+                    writeln!(
+                        response,
+                        "`{command}` needs an argument. Type `help` for the list of commands."
+                    )
+                    .unwrap();
+                }
+                (Some(other), _, _) => {
+                    writeln!(
+                        response,
+                        "I don't know the command `{other}`. Type `help` for the list of commands."
+                    )
+                    .unwrap();
+                }
+            }
+
+            self.print(&response);
+        }
+    }
+
+    fn add_breakpoint<I: ImportDispatcher>(
+        &mut self,
+        inst: &Instance<'_, I>,
+        target: &str,
+    ) -> String {
+        let module = inst.module;
+        let breakpoint = if let Some(offset) = target.strip_prefix("0x") {
+            match u32::from_str_radix(offset, 16) {
+                Ok(offset) => Breakpoint::Offset(offset),
+                Err(_) => return format!("I couldn't read the file offset `{target}`\n"),
+            }
+        } else {
+            // Look the name up in the debug info first, then in the exports
+            let names = module
+                .names
+                .function_names
+                .iter()
+                .map(|(i, name)| (*i, *name));
+            let exports = module.export.exports.iter().filter_map(|ex| {
+                (ex.ty == roc_wasm_module::ExportType::Func).then_some((ex.index, ex.name))
+            });
+            match names.chain(exports).find(|(_, name)| *name == target) {
+                Some((fn_index, name)) => Breakpoint::Function {
+                    fn_index: fn_index as usize,
+                    name: name.to_string(),
+                },
+                None => return format!("I couldn't find a function named `{target}`\n"),
+            }
+        };
+
+        self.breakpoints.push(breakpoint);
+        format!("Breakpoint {}\n", self.breakpoints.len())
+    }
+}
+
+impl<'a, I: ImportDispatcher> Instance<'a, I> {
+    /// Give control to the debugger, if there is one, before executing the next instruction
+    pub(crate) fn debugger_before_instruction(&mut self) -> Result<(), Error> {
+        let Some(mut debugger) = self.debugger.take() else {
+            return Ok(());
+        };
+        let result = if debugger.should_pause(self) {
+            debugger.prompt(self, false)
+        } else {
+            Ok(())
+        };
+        self.debugger = Some(debugger);
+        result
+    }
+
+    /// Show the error and the call stack, and let the user look around before the call returns
+    pub(crate) fn debugger_on_trap(&mut self, message: &str) {
+        let Some(mut debugger) = self.debugger.take() else {
+            return;
+        };
+        if !debugger.input_closed {
+            debugger.print(&format!("The program stopped with an error:\n{message}"));
+            let _ = debugger.prompt(self, true);
+        }
+        self.debugger = Some(debugger);
+    }
+}
+
+/// The current function and the next instruction
+fn location<I: ImportDispatcher>(inst: &Instance<'_, I>) -> String {
+    let fn_index = inst.current_frame.fn_index;
+    let file_offset = inst.program_counter as u32 + inst.module.code.section_offset;
+    let op_code = OpCode::from(inst.module.code.bytes[inst.program_counter]);
+    format!(
+        "func[{fn_index}]  {}\n  {file_offset:06x}  {op_code:?}\n",
+        inst.function_name(fn_index)
+    )
+}
+
+/// Parse a decimal number, or a hex one starting with `0x`
+fn parse_number(text: &str) -> Option<usize> {
+    match text.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn read_bytes(memory: &[u8], addr: usize, len: usize) -> Result<&[u8], String> {
+    memory.get(addr..addr.saturating_add(len)).ok_or_else(|| {
+        format!(
+            "The address {addr:#x} is outside of memory, which ends at {:#x}",
+            memory.len()
+        )
+    })
+}
+
+fn read_u32(memory: &[u8], addr: usize) -> Result<u32, String> {
+    let bytes = read_bytes(memory, addr, 4)?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn hex_dump(memory: &[u8], addr: usize, len: usize) -> String {
+    let end = addr.saturating_add(len).min(memory.len());
+    if addr >= end {
+        return format!(
+            "The address {addr:#x} is outside of memory, which ends at {:#x}\n",
+            memory.len()
+        );
+    }
+
+    let mut dump = String::new();
+    for line_start in (addr..end).step_by(16) {
+        let bytes = &memory[line_start..end.min(line_start + 16)];
+        write!(dump, "{line_start:08x} ").unwrap();
+        for i in 0..16 {
+            match bytes.get(i) {
+                Some(byte) => write!(dump, " {byte:02x}").unwrap(),
+                None => dump.push_str("   "),
+            }
+        }
+        let ascii: String = bytes
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        writeln!(dump, "  |{ascii}|").unwrap();
+    }
+    dump
+}
+
+/// A Roc `Str` in wasm32 is `{ bytes: *u8, length: u32, capacity: u32 }`.
+/// Small strings are stored in those 12 bytes directly, with the length in the last byte.
+fn decode_roc_str(memory: &[u8], addr: usize) -> Result<(String, String), String> {
+    let str_bytes = read_bytes(memory, addr, 12)?;
+    let last_byte = str_bytes[11];
+    if last_byte >= 0x80 {
+        let length = (last_byte & 0x7f) as usize;
+        let string = String::from_utf8_lossy(&str_bytes[..length.min(11)]).into_owned();
+        return Ok((string, format!("(small string, length {length})")));
+    }
+
+    let ptr = read_u32(memory, addr)? as usize;
+    // The highest bit of the length is set for seamless slices
+    let length = (read_u32(memory, addr + 4)? & i32::MAX as u32) as usize;
+    let capacity = read_u32(memory, addr + 8)?;
+    let string = String::from_utf8_lossy(read_bytes(memory, ptr, length)?).into_owned();
+    Ok((
+        string,
+        format!("(bytes at {ptr:#x}, length {length}, capacity {capacity:#x})"),
+    ))
+}
+
+/// A Roc `List` in wasm32 is `{ elements: *T, length: u32, capacity: u32 }`
+fn decode_roc_list(memory: &[u8], addr: usize, elem_type: &str) -> Result<String, String> {
+    let elem_size = match elem_type {
+        "i8" | "u8" => 1,
+        "i16" | "u16" => 2,
+        "i32" | "u32" | "f32" => 4,
+        "i64" | "u64" | "f64" => 8,
+        "str" => 12,
+        _ => {
+            return Err(format!(
+                "I don't know how to decode list elements of type `{elem_type}`"
+            ))
+        }
+    };
+
+    let ptr = read_u32(memory, addr)? as usize;
+    let length = (read_u32(memory, addr + 4)? & i32::MAX as u32) as usize;
+    let capacity = read_u32(memory, addr + 8)?;
+
+    let mut elements = Vec::new();
+    for i in 0..length.min(DEFAULT_LIST_ELEMENTS) {
+        let elem_addr = ptr + i * elem_size;
+        let bytes = read_bytes(memory, elem_addr, elem_size)?;
+        let element = match elem_type {
+            "i8" => (bytes[0] as i8).to_string(),
+            "u8" => bytes[0].to_string(),
+            "i16" => i16::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            "u16" => u16::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            "i32" => i32::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            "u32" => u32::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            "f32" => f32::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            "i64" => i64::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            "u64" => u64::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            "f64" => f64::from_le_bytes(bytes.try_into().unwrap()).to_string(),
+            _ => format!("{:?}", decode_roc_str(memory, elem_addr)?.0),
+        };
+        elements.push(element);
+    }
+    if length > DEFAULT_LIST_ELEMENTS {
+        elements.push(format!("... {} more", length - DEFAULT_LIST_ELEMENTS));
+    }
+
+    Ok(format!(
+        "[{}] (elements at {ptr:#x}, length {length}, capacity {capacity:#x})\n",
+        elements.join(", ")
+    ))
+}
@@ -54,6 +54,7 @@ impl Frame {
                 ValueType::I64 => Value::I64(0),
                 ValueType::F32 => Value::F32(0.0),
                 ValueType::F64 => Value::F64(0.0),
+                ValueType::V128 => Value::V128(0),
             };
             value_store.extend(repeat(zero).take(n));
         }
@@ -1,16 +1,20 @@
+mod simd;
+
 use bumpalo::{collections::Vec, Bump};
 use std::fmt::{self, Write};
 use std::iter::{self, once, Iterator};
 
-use roc_wasm_module::opcodes::{MemoryInstruction, OpCode};
+use roc_wasm_module::opcodes::{MemoryInstruction, OpCode, SimdInstruction};
 use roc_wasm_module::parse::{Parse, SkipBytes};
 use roc_wasm_module::sections::{ImportDesc, MemorySection, SignatureParamsIter};
 use roc_wasm_module::{ExportType, WasmModule};
 use roc_wasm_module::{Value, ValueType};
 
+use crate::debugger::Debugger;
 use crate::frame::Frame;
+use crate::profiler::Profiler;
 use crate::value_store::ValueStore;
-use crate::{Error, ImportDispatcher};
+use crate::{Error, ImportDispatcher, Limits, Trap};
 
 #[derive(Debug)]
 pub enum Action {
@@ -49,7 +53,7 @@ pub struct Instance<'a, I: ImportDispatcher> {
     /// The current call frame
     pub(crate) current_frame: Frame,
     /// Previous call frames
-    previous_frames: Vec<'a, Frame>,
+    pub(crate) previous_frames: Vec<'a, Frame>,
     /// The WebAssembly stack machine's stack of values
     pub(crate) value_store: ValueStore<'a>,
     /// Values of any global variables
@@ -68,6 +72,14 @@ pub struct Instance<'a, I: ImportDispatcher> {
     import_arguments: Vec<'a, Value>,
     /// temporary storage for output using the --debug option
     debug_string: Option<String>,
+    /// Limits on the resources each call can use
+    pub limits: Limits,
+    /// Number of instructions executed by the current or last call
+    fuel_used: u64,
+    /// Interactive debugger, which gets control before every instruction
+    pub debugger: Option<Debugger>,
+    /// Counts instructions and time per function
+    pub profiler: Option<Profiler>,
 }
 
 impl<'a, I: ImportDispatcher> Instance<'a, I> {
@@ -97,6 +109,10 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             import_dispatcher,
             import_arguments: Vec::new_in(arena),
             debug_string: Some(String::new()),
+            limits: Limits::default(),
+            fuel_used: 0,
+            debugger: None,
+            profiler: None,
         }
     }
 
@@ -108,7 +124,13 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
     ) -> Result<Self, std::string::String> {
         let module =
             WasmModule::preload(arena, module_bytes, false).map_err(|e| format!("{e:?}"))?;
-        Self::for_module(arena, arena.alloc(module), import_dispatcher, is_debug_mode)
+        Self::for_module(
+            arena,
+            arena.alloc(module),
+            import_dispatcher,
+            is_debug_mode,
+            Limits::default(),
+        )
     }
 
     pub fn for_module(
@@ -116,6 +138,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         module: &'a WasmModule<'a>,
         import_dispatcher: I,
         is_debug_mode: bool,
+        limits: Limits,
     ) -> Result<Self, std::string::String> {
         let mem_bytes = module.memory.min_bytes().map_err(|e| {
             format!(
@@ -123,6 +146,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 e.offset, e.message
             )
         })?;
+        // Checked before allocating, so that a module can't make us allocate too much either
+        if let Some(max_memory_bytes) = limits.max_memory_bytes {
+            if mem_bytes > max_memory_bytes {
+                return Err(format!(
+                    "The module needs {mem_bytes} bytes of memory to start, but it's limited to {max_memory_bytes} bytes"
+                ));
+            }
+        }
         let mut memory = Vec::from_iter_in(iter::repeat(0).take(mem_bytes as usize), arena);
         module.data.load_into(&mut memory)?;
 
@@ -165,10 +196,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             import_dispatcher,
             import_arguments: Vec::new_in(arena),
             debug_string,
+            limits,
+            fuel_used: 0,
+            debugger: None,
+            profiler: None,
         })
     }
 
-    pub fn call_export<A>(&mut self, fn_name: &str, arg_values: A) -> Result<Option<Value>, String>
+    pub fn call_export<A>(&mut self, fn_name: &str, arg_values: A) -> Result<Option<Value>, Trap>
     where
         A: IntoIterator<Item = Value>,
     {
@@ -181,7 +216,8 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             if actual_type != expected_type {
                 return Err(format!(
                     "Type mismatch on argument {i} of {fn_name}. Expected {expected_type:?} but got {value:?}"
-                ));
+                )
+                .into());
             }
             self.value_store.push(value);
         }
@@ -194,7 +230,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         module: &WasmModule<'a>,
         fn_name: &str,
         arg_strings: &'a [&'a [u8]],
-    ) -> Result<Option<Value>, String> {
+    ) -> Result<Option<Value>, Trap> {
         // We have two different mechanisms for handling CLI arguments!
         // 1. Basic numbers:
         //      e.g. `roc_wasm_interp fibonacci 12`
@@ -223,6 +259,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 I64 => Value::I64(value_str.parse::<i64>().map_err(|e| e.to_string())?),
                 F32 => Value::F32(value_str.parse::<f32>().map_err(|e| e.to_string())?),
                 F64 => Value::F64(value_str.parse::<f64>().map_err(|e| e.to_string())?),
+                V128 => Value::V128(value_str.parse::<u128>().map_err(|e| e.to_string())?),
             };
             self.value_store.push(value);
         }
@@ -297,7 +334,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         fn_index: usize,
         n_args: usize,
         return_type: Option<ValueType>,
-    ) -> Result<Option<Value>, String> {
+    ) -> Result<Option<Value>, Trap> {
+        self.fuel_used = 0;
+        if let Some(debugger) = self.debugger.as_mut() {
+            debugger.start_call();
+        }
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.start_call();
+        }
         self.previous_frames.clear();
         self.blocks.clear();
         self.blocks.push(Block {
@@ -320,19 +364,45 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         });
 
         loop {
-            match self.execute_next_instruction(module) {
+            let result = self
+                .debugger_before_instruction()
+                .and_then(|()| self.consume_fuel())
+                .and_then(|()| {
+                    if let Some(profiler) = self.profiler.as_mut() {
+                        profiler.count_instruction(
+                            self.previous_frames.len(),
+                            self.current_frame.fn_index,
+                        );
+                    }
+                    self.execute_next_instruction(module)
+                });
+            match result {
                 Ok(Action::Continue) => {}
                 Ok(Action::Break) => {
                     break;
                 }
                 Err(e) => {
+                    if let Some(profiler) = self.profiler.as_mut() {
+                        profiler.end_call();
+                    }
                     let file_offset = self.program_counter + module.code.section_offset as usize;
                     let mut message = e.to_string_at(file_offset);
                     self.debug_stack_trace(&mut message).unwrap();
-                    return Err(message);
+                    if e != Error::DebuggerQuit {
+                        self.debugger_on_trap(&message);
+                    }
+                    // Leave the instance ready for the next call
+                    self.value_store.truncate(0);
+                    return Err(Trap {
+                        kind: e.kind(),
+                        message,
+                    });
                 }
             };
         }
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.end_call();
+        }
 
         let return_value = if !self.value_store.is_empty() {
             Some(self.value_store.pop())
@@ -343,6 +413,21 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         Ok(return_value)
     }
 
+    /// Number of instructions executed by the current or last call
+    pub fn fuel_used(&self) -> u64 {
+        self.fuel_used
+    }
+
+    fn consume_fuel(&mut self) -> Result<(), Error> {
+        match self.limits.fuel {
+            Some(fuel) if self.fuel_used >= fuel => Err(Error::OutOfFuel(fuel)),
+            _ => {
+                self.fuel_used += 1;
+                Ok(())
+            }
+        }
+    }
+
     fn fetch_immediate_u32(&mut self, module: &WasmModule<'a>) -> u32 {
         let x = u32::parse((), &module.code.bytes, &mut self.program_counter).unwrap();
         if let Some(debug_string) = self.debug_string.as_mut() {
@@ -546,6 +631,13 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 write!(debug_string, " {}.{}", import.module, import.name).unwrap();
             }
         } else {
+            if let Some(max_depth) = self.limits.max_call_depth {
+                // the current frame plus the ones it was called from
+                if self.previous_frames.len() + 1 >= max_depth {
+                    return Err(Error::CallDepthLimit(max_depth));
+                }
+            }
+
             let return_addr = self.program_counter;
             // set PC to start of function bytes
             let internal_fn_index = fn_index - self.import_count;
@@ -956,6 +1048,13 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 let old_bytes = self.memory.len() as u32;
                 let old_pages = old_bytes / MemorySection::PAGE_SIZE;
                 let grow_pages = self.value_store.pop_u32()?;
+                if let Some(max_bytes) = self.limits.max_memory_bytes {
+                    let requested_bytes =
+                        old_bytes as u64 + grow_pages as u64 * MemorySection::PAGE_SIZE as u64;
+                    if requested_bytes > max_bytes as u64 {
+                        return Err(Error::MemoryLimit(requested_bytes, max_bytes));
+                    }
+                }
                 let grow_bytes = grow_pages * MemorySection::PAGE_SIZE;
                 let new_bytes = old_bytes + grow_bytes;
 
@@ -1003,6 +1102,12 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                     Err(other) => unreachable!("invalid memory instruction {other:?}"),
                 };
             }
+            SIMD => {
+                let op = SimdInstruction::parse((), &module.code.bytes, &mut self.program_counter)
+                    .unwrap();
+                self.write_debug(op);
+                self.execute_simd(op, module)?;
+            }
             I32CONST => {
                 let value = i32::parse((), &module.code.bytes, &mut self.program_counter).unwrap();
                 self.write_debug(value);
@@ -1814,7 +1919,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
     ///   locals   2: I32(412), 3: F64(3.14)
     ///   stack    [I64(111), F64(3.14)]
     /// --------------
-    fn debug_stack_trace(&self, buffer: &mut String) -> fmt::Result {
+    pub(crate) fn debug_stack_trace(&self, buffer: &mut String) -> fmt::Result {
         let divider = "-------------------";
         writeln!(buffer, "{divider}")?;
 
@@ -1843,26 +1948,8 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 ..
             } = frame;
 
-            let arg_count = {
-                let signature_index = if *fn_index < self.import_count {
-                    match self.module.import.imports[*fn_index].description {
-                        ImportDesc::Func { signature_index } => signature_index,
-                        _ => unreachable!(),
-                    }
-                } else {
-                    self.module.function.signatures[fn_index - self.import_count]
-                };
-                self.module.types.look_up(signature_index).0.len()
-            };
-
-            let fn_name = self
-                .module
-                .names
-                .function_names
-                .iter()
-                .find(|(idx, _)| *idx == *fn_index as u32)
-                .map(|(_, name)| *name)
-                .unwrap_or("");
+            let arg_count = self.arg_count(*fn_index);
+            let fn_name = self.function_name(*fn_index);
 
             // Function and address match wasm-objdump formatting, for easy copy & find
             writeln!(buffer, "func[{fn_index}]  {fn_name}")?;
@@ -1898,6 +1985,34 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         Ok(())
     }
 
+    /// Number of function calls nested inside the exported function that was called
+    pub(crate) fn call_depth(&self) -> usize {
+        self.previous_frames.len()
+    }
+
+    pub(crate) fn arg_count(&self, fn_index: usize) -> usize {
+        let signature_index = if fn_index < self.import_count {
+            match self.module.import.imports[fn_index].description {
+                ImportDesc::Func { signature_index } => signature_index,
+                _ => unreachable!(),
+            }
+        } else {
+            self.module.function.signatures[fn_index - self.import_count]
+        };
+        self.module.types.look_up(signature_index).0.len()
+    }
+
+    /// The name of a function in the debug info, if it has one
+    pub(crate) fn function_name(&self, fn_index: usize) -> &'a str {
+        self.module
+            .names
+            .function_names
+            .iter()
+            .find(|(idx, _)| *idx == fn_index as u32)
+            .map(|(_, name)| *name)
+            .unwrap_or("")
+    }
+
     // Call address is more intuitive than the return address in the stack trace. Search backward for it.
     fn debug_return_addr_to_call_addr(&self, return_addr: usize) -> usize {
         // return_addr is pointing at the next instruction after the CALL/CALLINDIRECT.
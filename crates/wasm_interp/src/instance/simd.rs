@@ -0,0 +1,700 @@
+//! Instructions from the SIMD proposal, which operate on 128-bit vectors.
+//! A vector is a `u128` with lane 0 in the lowest bits, the same as its little-endian layout in memory.
+//! https://webassembly.github.io/spec/core/exec/instructions.html#vector-instructions
+
+use std::array;
+use std::ops::{Add, Mul};
+
+use roc_wasm_module::opcodes::SimdInstruction;
+use roc_wasm_module::{Value, WasmModule};
+
+use super::Instance;
+use crate::{Error, ImportDispatcher};
+
+/// A number type that a vector can be split into
+trait Lane: Copy {
+    const BYTES: usize;
+    fn read(bytes: &[u8]) -> Self;
+    fn write(self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_lane {
+    ($($t: ty),*) => {
+        $(
+            impl Lane for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+
+                fn read(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+
+                fn write(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes())
+                }
+            }
+        )*
+    };
+}
+
+impl_lane!(i8, u8, i16, u16, i32, u32, i64, u64, f32, f64);
+
+fn get_lane<T: Lane>(v: u128, index: usize) -> T {
+    T::read(&v.to_le_bytes()[index * T::BYTES..][..T::BYTES])
+}
+
+fn lanes<T: Lane, const N: usize>(v: u128) -> [T; N] {
+    array::from_fn(|i| get_lane(v, i))
+}
+
+/// Build a vector from its first `N` lanes. Any lanes after those are zero.
+fn from_lanes<T: Lane, const N: usize>(lanes: [T; N]) -> u128 {
+    let mut bytes = [0; 16];
+    for (i, lane) in lanes.into_iter().enumerate() {
+        lane.write(&mut bytes[i * T::BYTES..][..T::BYTES]);
+    }
+    u128::from_le_bytes(bytes)
+}
+
+fn map<T: Lane, const N: usize>(v: u128, f: impl Fn(T) -> T) -> u128 {
+    from_lanes(lanes::<T, N>(v).map(f))
+}
+
+fn zip<T: Lane, const N: usize>(a: u128, b: u128, f: impl Fn(T, T) -> T) -> u128 {
+    let a = lanes::<T, N>(a);
+    let b = lanes::<T, N>(b);
+    from_lanes::<T, N>(array::from_fn(|i| f(a[i], b[i])))
+}
+
+/// Set all the bits of each lane where the comparison is true, and clear them where it's false
+fn compare<T: Lane, const N: usize>(a: u128, b: u128, f: impl Fn(&T, &T) -> bool) -> u128 {
+    let a = lanes::<T, N>(a);
+    let b = lanes::<T, N>(b);
+    let mut bytes = [0; 16];
+    for i in 0..N {
+        if f(&a[i], &b[i]) {
+            bytes[i * T::BYTES..][..T::BYTES].fill(0xff);
+        }
+    }
+    u128::from_le_bytes(bytes)
+}
+
+/// Convert the first `N` lanes to another type. Any lanes of the result after those are zero.
+fn convert<T: Lane, U: Lane, const N: usize>(v: u128, f: impl Fn(T) -> U) -> u128 {
+    from_lanes::<U, N>(array::from_fn(|i| f(get_lane(v, i))))
+}
+
+/// Widen `N` lanes, starting from lane `first`
+fn extend<T: Lane, U: Lane + From<T>, const N: usize>(v: u128, first: usize) -> u128 {
+    from_lanes::<U, N>(array::from_fn(|i| U::from(get_lane(v, first + i))))
+}
+
+/// Widen `N` lanes of each vector, starting from lane `first`, and multiply them
+fn extmul<T: Lane, U: Lane + From<T> + Mul<Output = U>, const N: usize>(
+    a: u128,
+    b: u128,
+    first: usize,
+) -> u128 {
+    from_lanes::<U, N>(array::from_fn(|i| {
+        U::from(get_lane::<T>(a, first + i)) * U::from(get_lane::<T>(b, first + i))
+    }))
+}
+
+/// Widen each lane and add it to its neighbour, giving `N` lanes
+fn extadd_pairwise<T: Lane, U: Lane + From<T> + Add<Output = U>, const N: usize>(v: u128) -> u128 {
+    from_lanes::<U, N>(array::from_fn(|i| {
+        U::from(get_lane::<T>(v, 2 * i)) + U::from(get_lane::<T>(v, 2 * i + 1))
+    }))
+}
+
+/// Narrow the lanes of two vectors into the `N` lanes of one
+fn narrow<T: Lane, U: Lane, const N: usize>(a: u128, b: u128, f: impl Fn(T) -> U) -> u128 {
+    let half = N / 2;
+    from_lanes::<U, N>(array::from_fn(|i| {
+        if i < half {
+            f(get_lane(a, i))
+        } else {
+            f(get_lane(b, i - half))
+        }
+    }))
+}
+
+/// The top bit of each lane
+fn bitmask<T: Lane + PartialOrd + Default, const N: usize>(v: u128) -> i32 {
+    let lanes = lanes::<T, N>(v);
+    (0..N)
+        .filter(|&i| lanes[i] < T::default())
+        .fold(0, |mask, i| mask | (1 << i))
+}
+
+fn all_true<T: Lane + PartialEq + Default, const N: usize>(v: u128) -> bool {
+    lanes::<T, N>(v).iter().all(|lane| *lane != T::default())
+}
+
+macro_rules! float_min_max {
+    ($t: ty, $min: ident, $max: ident) => {
+        /// Like `min`, but NaN if either argument is NaN, and -0 is less than +0
+        fn $min(a: $t, b: $t) -> $t {
+            if a.is_nan() || b.is_nan() {
+                <$t>::NAN
+            } else if a == b {
+                // Either the same number, or zeros with different signs
+                <$t>::from_bits(a.to_bits() | b.to_bits())
+            } else {
+                a.min(b)
+            }
+        }
+
+        /// Like `max`, but NaN if either argument is NaN, and +0 is greater than -0
+        fn $max(a: $t, b: $t) -> $t {
+            if a.is_nan() || b.is_nan() {
+                <$t>::NAN
+            } else if a == b {
+                <$t>::from_bits(a.to_bits() & b.to_bits())
+            } else {
+                a.max(b)
+            }
+        }
+    };
+}
+
+float_min_max!(f32, f32_min, f32_max);
+float_min_max!(f64, f64_min, f64_max);
+
+impl<'a, I: ImportDispatcher> Instance<'a, I> {
+    pub(super) fn execute_simd(
+        &mut self,
+        op: SimdInstruction,
+        module: &WasmModule<'a>,
+    ) -> Result<(), Error> {
+        use SimdInstruction::*;
+
+        match op {
+            V128Load => {
+                let addr = self.simd_address(module, 16)?;
+                let value = u128::from_le_bytes(self.memory[addr..][..16].try_into().unwrap());
+                self.value_store.push(Value::V128(value));
+            }
+            V128Load8x8S => self.load_extend::<i8, i16, 8>(module)?,
+            V128Load8x8U => self.load_extend::<u8, u16, 8>(module)?,
+            V128Load16x4S => self.load_extend::<i16, i32, 4>(module)?,
+            V128Load16x4U => self.load_extend::<u16, u32, 4>(module)?,
+            V128Load32x2S => self.load_extend::<i32, i64, 2>(module)?,
+            V128Load32x2U => self.load_extend::<u32, u64, 2>(module)?,
+            V128Load8Splat => self.load_splat(module, 1)?,
+            V128Load16Splat => self.load_splat(module, 2)?,
+            V128Load32Splat => self.load_splat(module, 4)?,
+            V128Load64Splat => self.load_splat(module, 8)?,
+            V128Load32Zero => self.load_zero(module, 4)?,
+            V128Load64Zero => self.load_zero(module, 8)?,
+            V128Load8Lane => self.load_lane(module, 1)?,
+            V128Load16Lane => self.load_lane(module, 2)?,
+            V128Load32Lane => self.load_lane(module, 4)?,
+            V128Load64Lane => self.load_lane(module, 8)?,
+            V128Store => {
+                let value = self.value_store.pop_v128()?;
+                let addr = self.simd_address(module, 16)?;
+                self.memory[addr..][..16].copy_from_slice(&value.to_le_bytes());
+            }
+            V128Store8Lane => self.store_lane(module, 1)?,
+            V128Store16Lane => self.store_lane(module, 2)?,
+            V128Store32Lane => self.store_lane(module, 4)?,
+            V128Store64Lane => self.store_lane(module, 8)?,
+
+            V128Const => {
+                let bytes = self.fetch_bytes16(module);
+                let value = u128::from_le_bytes(bytes);
+                self.write_debug(value);
+                self.value_store.push(Value::V128(value));
+            }
+            I8x16Shuffle => {
+                let indices = self.fetch_bytes16(module);
+                self.write_debug(indices);
+                self.simd_binary(|a, b| {
+                    let mut both = [0; 32];
+                    both[..16].copy_from_slice(&a.to_le_bytes());
+                    both[16..].copy_from_slice(&b.to_le_bytes());
+                    u128::from_le_bytes(indices.map(|i| both[i as usize]))
+                })?;
+            }
+            I8x16Swizzle => self.simd_binary(|a, s| {
+                let a = a.to_le_bytes();
+                let s = s.to_le_bytes();
+                u128::from_le_bytes(s.map(|i| if i < 16 { a[i as usize] } else { 0 }))
+            })?,
+
+            I8x16Splat => {
+                let x = self.value_store.pop_i32()? as i8;
+                self.value_store.push(Value::V128(from_lanes([x; 16])));
+            }
+            I16x8Splat => {
+                let x = self.value_store.pop_i32()? as i16;
+                self.value_store.push(Value::V128(from_lanes([x; 8])));
+            }
+            I32x4Splat => {
+                let x = self.value_store.pop_i32()?;
+                self.value_store.push(Value::V128(from_lanes([x; 4])));
+            }
+            I64x2Splat => {
+                let x = self.value_store.pop_i64()?;
+                self.value_store.push(Value::V128(from_lanes([x; 2])));
+            }
+            F32x4Splat => {
+                let x = self.value_store.pop_f32()?;
+                self.value_store.push(Value::V128(from_lanes([x; 4])));
+            }
+            F64x2Splat => {
+                let x = self.value_store.pop_f64()?;
+                self.value_store.push(Value::V128(from_lanes([x; 2])));
+            }
+
+            I8x16ExtractLaneS => {
+                let x = self.extract_lane::<i8, 16>(module)?;
+                self.value_store.push(Value::I32(x as i32));
+            }
+            I8x16ExtractLaneU => {
+                let x = self.extract_lane::<u8, 16>(module)?;
+                self.value_store.push(Value::I32(x as i32));
+            }
+            I16x8ExtractLaneS => {
+                let x = self.extract_lane::<i16, 8>(module)?;
+                self.value_store.push(Value::I32(x as i32));
+            }
+            I16x8ExtractLaneU => {
+                let x = self.extract_lane::<u16, 8>(module)?;
+                self.value_store.push(Value::I32(x as i32));
+            }
+            I32x4ExtractLane => {
+                let x = self.extract_lane::<i32, 4>(module)?;
+                self.value_store.push(Value::I32(x));
+            }
+            I64x2ExtractLane => {
+                let x = self.extract_lane::<i64, 2>(module)?;
+                self.value_store.push(Value::I64(x));
+            }
+            F32x4ExtractLane => {
+                let x = self.extract_lane::<f32, 4>(module)?;
+                self.value_store.push(Value::F32(x));
+            }
+            F64x2ExtractLane => {
+                let x = self.extract_lane::<f64, 2>(module)?;
+                self.value_store.push(Value::F64(x));
+            }
+            I8x16ReplaceLane => {
+                let index = self.fetch_lane_index(module);
+                let x = self.value_store.pop_i32()? as i8;
+                self.replace_lane::<i8, 16>(index, x)?;
+            }
+            I16x8ReplaceLane => {
+                let index = self.fetch_lane_index(module);
+                let x = self.value_store.pop_i32()? as i16;
+                self.replace_lane::<i16, 8>(index, x)?;
+            }
+            I32x4ReplaceLane => {
+                let index = self.fetch_lane_index(module);
+                let x = self.value_store.pop_i32()?;
+                self.replace_lane::<i32, 4>(index, x)?;
+            }
+            I64x2ReplaceLane => {
+                let index = self.fetch_lane_index(module);
+                let x = self.value_store.pop_i64()?;
+                self.replace_lane::<i64, 2>(index, x)?;
+            }
+            F32x4ReplaceLane => {
+                let index = self.fetch_lane_index(module);
+                let x = self.value_store.pop_f32()?;
+                self.replace_lane::<f32, 4>(index, x)?;
+            }
+            F64x2ReplaceLane => {
+                let index = self.fetch_lane_index(module);
+                let x = self.value_store.pop_f64()?;
+                self.replace_lane::<f64, 2>(index, x)?;
+            }
+
+            I8x16Eq => self.simd_binary(|a, b| compare::<i8, 16>(a, b, PartialEq::eq))?,
+            I8x16Ne => self.simd_binary(|a, b| compare::<i8, 16>(a, b, PartialEq::ne))?,
+            I8x16LtS => self.simd_binary(|a, b| compare::<i8, 16>(a, b, PartialOrd::lt))?,
+            I8x16LtU => self.simd_binary(|a, b| compare::<u8, 16>(a, b, PartialOrd::lt))?,
+            I8x16GtS => self.simd_binary(|a, b| compare::<i8, 16>(a, b, PartialOrd::gt))?,
+            I8x16GtU => self.simd_binary(|a, b| compare::<u8, 16>(a, b, PartialOrd::gt))?,
+            I8x16LeS => self.simd_binary(|a, b| compare::<i8, 16>(a, b, PartialOrd::le))?,
+            I8x16LeU => self.simd_binary(|a, b| compare::<u8, 16>(a, b, PartialOrd::le))?,
+            I8x16GeS => self.simd_binary(|a, b| compare::<i8, 16>(a, b, PartialOrd::ge))?,
+            I8x16GeU => self.simd_binary(|a, b| compare::<u8, 16>(a, b, PartialOrd::ge))?,
+            I16x8Eq => self.simd_binary(|a, b| compare::<i16, 8>(a, b, PartialEq::eq))?,
+            I16x8Ne => self.simd_binary(|a, b| compare::<i16, 8>(a, b, PartialEq::ne))?,
+            I16x8LtS => self.simd_binary(|a, b| compare::<i16, 8>(a, b, PartialOrd::lt))?,
+            I16x8LtU => self.simd_binary(|a, b| compare::<u16, 8>(a, b, PartialOrd::lt))?,
+            I16x8GtS => self.simd_binary(|a, b| compare::<i16, 8>(a, b, PartialOrd::gt))?,
+            I16x8GtU => self.simd_binary(|a, b| compare::<u16, 8>(a, b, PartialOrd::gt))?,
+            I16x8LeS => self.simd_binary(|a, b| compare::<i16, 8>(a, b, PartialOrd::le))?,
+            I16x8LeU => self.simd_binary(|a, b| compare::<u16, 8>(a, b, PartialOrd::le))?,
+            I16x8GeS => self.simd_binary(|a, b| compare::<i16, 8>(a, b, PartialOrd::ge))?,
+            I16x8GeU => self.simd_binary(|a, b| compare::<u16, 8>(a, b, PartialOrd::ge))?,
+            I32x4Eq => self.simd_binary(|a, b| compare::<i32, 4>(a, b, PartialEq::eq))?,
+            I32x4Ne => self.simd_binary(|a, b| compare::<i32, 4>(a, b, PartialEq::ne))?,
+            I32x4LtS => self.simd_binary(|a, b| compare::<i32, 4>(a, b, PartialOrd::lt))?,
+            I32x4LtU => self.simd_binary(|a, b| compare::<u32, 4>(a, b, PartialOrd::lt))?,
+            I32x4GtS => self.simd_binary(|a, b| compare::<i32, 4>(a, b, PartialOrd::gt))?,
+            I32x4GtU => self.simd_binary(|a, b| compare::<u32, 4>(a, b, PartialOrd::gt))?,
+            I32x4LeS => self.simd_binary(|a, b| compare::<i32, 4>(a, b, PartialOrd::le))?,
+            I32x4LeU => self.simd_binary(|a, b| compare::<u32, 4>(a, b, PartialOrd::le))?,
+            I32x4GeS => self.simd_binary(|a, b| compare::<i32, 4>(a, b, PartialOrd::ge))?,
+            I32x4GeU => self.simd_binary(|a, b| compare::<u32, 4>(a, b, PartialOrd::ge))?,
+            I64x2Eq => self.simd_binary(|a, b| compare::<i64, 2>(a, b, PartialEq::eq))?,
+            I64x2Ne => self.simd_binary(|a, b| compare::<i64, 2>(a, b, PartialEq::ne))?,
+            I64x2LtS => self.simd_binary(|a, b| compare::<i64, 2>(a, b, PartialOrd::lt))?,
+            I64x2GtS => self.simd_binary(|a, b| compare::<i64, 2>(a, b, PartialOrd::gt))?,
+            I64x2LeS => self.simd_binary(|a, b| compare::<i64, 2>(a, b, PartialOrd::le))?,
+            I64x2GeS => self.simd_binary(|a, b| compare::<i64, 2>(a, b, PartialOrd::ge))?,
+            F32x4Eq => self.simd_binary(|a, b| compare::<f32, 4>(a, b, PartialEq::eq))?,
+            F32x4Ne => self.simd_binary(|a, b| compare::<f32, 4>(a, b, PartialEq::ne))?,
+            F32x4Lt => self.simd_binary(|a, b| compare::<f32, 4>(a, b, PartialOrd::lt))?,
+            F32x4Gt => self.simd_binary(|a, b| compare::<f32, 4>(a, b, PartialOrd::gt))?,
+            F32x4Le => self.simd_binary(|a, b| compare::<f32, 4>(a, b, PartialOrd::le))?,
+            F32x4Ge => self.simd_binary(|a, b| compare::<f32, 4>(a, b, PartialOrd::ge))?,
+            F64x2Eq => self.simd_binary(|a, b| compare::<f64, 2>(a, b, PartialEq::eq))?,
+            F64x2Ne => self.simd_binary(|a, b| compare::<f64, 2>(a, b, PartialEq::ne))?,
+            F64x2Lt => self.simd_binary(|a, b| compare::<f64, 2>(a, b, PartialOrd::lt))?,
+            F64x2Gt => self.simd_binary(|a, b| compare::<f64, 2>(a, b, PartialOrd::gt))?,
+            F64x2Le => self.simd_binary(|a, b| compare::<f64, 2>(a, b, PartialOrd::le))?,
+            F64x2Ge => self.simd_binary(|a, b| compare::<f64, 2>(a, b, PartialOrd::ge))?,
+
+            V128Not => self.simd_unary(|a| !a)?,
+            V128And => self.simd_binary(|a, b| a & b)?,
+            V128Andnot => self.simd_binary(|a, b| a & !b)?,
+            V128Or => self.simd_binary(|a, b| a | b)?,
+            V128Xor => self.simd_binary(|a, b| a ^ b)?,
+            V128Bitselect => {
+                let mask = self.value_store.pop_v128()?;
+                self.simd_binary(|a, b| (a & mask) | (b & !mask))?;
+            }
+            V128AnyTrue => self.simd_test(|a| a != 0)?,
+
+            I8x16Abs => self.simd_unary(|a| map::<i8, 16>(a, i8::wrapping_abs))?,
+            I8x16Neg => self.simd_unary(|a| map::<i8, 16>(a, i8::wrapping_neg))?,
+            I8x16Popcnt => self.simd_unary(|a| map::<u8, 16>(a, |x| x.count_ones() as u8))?,
+            I8x16AllTrue => self.simd_test(all_true::<i8, 16>)?,
+            I8x16Bitmask => self.simd_bitmask(bitmask::<i8, 16>)?,
+            I8x16NarrowI16x8S => self.simd_binary(|a, b| {
+                narrow::<i16, i8, 16>(a, b, |x| x.clamp(i8::MIN as i16, i8::MAX as i16) as i8)
+            })?,
+            I8x16NarrowI16x8U => self.simd_binary(|a, b| {
+                narrow::<i16, u8, 16>(a, b, |x| x.clamp(0, u8::MAX as i16) as u8)
+            })?,
+            I8x16Shl => self.simd_shift(|a, n| map::<i8, 16>(a, |x| x.wrapping_shl(n)))?,
+            I8x16ShrS => self.simd_shift(|a, n| map::<i8, 16>(a, |x| x.wrapping_shr(n)))?,
+            I8x16ShrU => self.simd_shift(|a, n| map::<u8, 16>(a, |x| x.wrapping_shr(n)))?,
+            I8x16Add => self.simd_binary(|a, b| zip::<i8, 16>(a, b, i8::wrapping_add))?,
+            I8x16AddSatS => self.simd_binary(|a, b| zip::<i8, 16>(a, b, i8::saturating_add))?,
+            I8x16AddSatU => self.simd_binary(|a, b| zip::<u8, 16>(a, b, u8::saturating_add))?,
+            I8x16Sub => self.simd_binary(|a, b| zip::<i8, 16>(a, b, i8::wrapping_sub))?,
+            I8x16SubSatS => self.simd_binary(|a, b| zip::<i8, 16>(a, b, i8::saturating_sub))?,
+            I8x16SubSatU => self.simd_binary(|a, b| zip::<u8, 16>(a, b, u8::saturating_sub))?,
+            I8x16MinS => self.simd_binary(|a, b| zip::<i8, 16>(a, b, i8::min))?,
+            I8x16MinU => self.simd_binary(|a, b| zip::<u8, 16>(a, b, u8::min))?,
+            I8x16MaxS => self.simd_binary(|a, b| zip::<i8, 16>(a, b, i8::max))?,
+            I8x16MaxU => self.simd_binary(|a, b| zip::<u8, 16>(a, b, u8::max))?,
+            I8x16AvgrU => self.simd_binary(|a, b| {
+                zip::<u8, 16>(a, b, |a, b| ((a as u16 + b as u16 + 1) / 2) as u8)
+            })?,
+
+            I16x8ExtaddPairwiseI8x16S => {
+                self.simd_unary(extadd_pairwise::<i8, i16, 8>)?;
+            }
+            I16x8ExtaddPairwiseI8x16U => {
+                self.simd_unary(extadd_pairwise::<u8, u16, 8>)?;
+            }
+            I16x8Abs => self.simd_unary(|a| map::<i16, 8>(a, i16::wrapping_abs))?,
+            I16x8Neg => self.simd_unary(|a| map::<i16, 8>(a, i16::wrapping_neg))?,
+            I16x8Q15mulrSatS => self.simd_binary(|a, b| {
+                zip::<i16, 8>(a, b, |a, b| {
+                    let product = (a as i32 * b as i32 + 0x4000) >> 15;
+                    product.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+                })
+            })?,
+            I16x8AllTrue => self.simd_test(all_true::<i16, 8>)?,
+            I16x8Bitmask => self.simd_bitmask(bitmask::<i16, 8>)?,
+            I16x8NarrowI32x4S => self.simd_binary(|a, b| {
+                narrow::<i32, i16, 8>(a, b, |x| x.clamp(i16::MIN as i32, i16::MAX as i32) as i16)
+            })?,
+            I16x8NarrowI32x4U => self.simd_binary(|a, b| {
+                narrow::<i32, u16, 8>(a, b, |x| x.clamp(0, u16::MAX as i32) as u16)
+            })?,
+            I16x8ExtendLowI8x16S => self.simd_unary(|a| extend::<i8, i16, 8>(a, 0))?,
+            I16x8ExtendHighI8x16S => self.simd_unary(|a| extend::<i8, i16, 8>(a, 8))?,
+            I16x8ExtendLowI8x16U => self.simd_unary(|a| extend::<u8, u16, 8>(a, 0))?,
+            I16x8ExtendHighI8x16U => self.simd_unary(|a| extend::<u8, u16, 8>(a, 8))?,
+            I16x8Shl => self.simd_shift(|a, n| map::<i16, 8>(a, |x| x.wrapping_shl(n)))?,
+            I16x8ShrS => self.simd_shift(|a, n| map::<i16, 8>(a, |x| x.wrapping_shr(n)))?,
+            I16x8ShrU => self.simd_shift(|a, n| map::<u16, 8>(a, |x| x.wrapping_shr(n)))?,
+            I16x8Add => self.simd_binary(|a, b| zip::<i16, 8>(a, b, i16::wrapping_add))?,
+            I16x8AddSatS => self.simd_binary(|a, b| zip::<i16, 8>(a, b, i16::saturating_add))?,
+            I16x8AddSatU => self.simd_binary(|a, b| zip::<u16, 8>(a, b, u16::saturating_add))?,
+            I16x8Sub => self.simd_binary(|a, b| zip::<i16, 8>(a, b, i16::wrapping_sub))?,
+            I16x8SubSatS => self.simd_binary(|a, b| zip::<i16, 8>(a, b, i16::saturating_sub))?,
+            I16x8SubSatU => self.simd_binary(|a, b| zip::<u16, 8>(a, b, u16::saturating_sub))?,
+            I16x8Mul => self.simd_binary(|a, b| zip::<i16, 8>(a, b, i16::wrapping_mul))?,
+            I16x8MinS => self.simd_binary(|a, b| zip::<i16, 8>(a, b, i16::min))?,
+            I16x8MinU => self.simd_binary(|a, b| zip::<u16, 8>(a, b, u16::min))?,
+            I16x8MaxS => self.simd_binary(|a, b| zip::<i16, 8>(a, b, i16::max))?,
+            I16x8MaxU => self.simd_binary(|a, b| zip::<u16, 8>(a, b, u16::max))?,
+            I16x8AvgrU => self.simd_binary(|a, b| {
+                zip::<u16, 8>(a, b, |a, b| ((a as u32 + b as u32 + 1) / 2) as u16)
+            })?,
+            I16x8ExtmulLowI8x16S => self.simd_binary(|a, b| extmul::<i8, i16, 8>(a, b, 0))?,
+            I16x8ExtmulHighI8x16S => self.simd_binary(|a, b| extmul::<i8, i16, 8>(a, b, 8))?,
+            I16x8ExtmulLowI8x16U => self.simd_binary(|a, b| extmul::<u8, u16, 8>(a, b, 0))?,
+            I16x8ExtmulHighI8x16U => self.simd_binary(|a, b| extmul::<u8, u16, 8>(a, b, 8))?,
+
+            I32x4ExtaddPairwiseI16x8S => {
+                self.simd_unary(extadd_pairwise::<i16, i32, 4>)?;
+            }
+            I32x4ExtaddPairwiseI16x8U => {
+                self.simd_unary(extadd_pairwise::<u16, u32, 4>)?;
+            }
+            I32x4Abs => self.simd_unary(|a| map::<i32, 4>(a, i32::wrapping_abs))?,
+            I32x4Neg => self.simd_unary(|a| map::<i32, 4>(a, i32::wrapping_neg))?,
+            I32x4AllTrue => self.simd_test(all_true::<i32, 4>)?,
+            I32x4Bitmask => self.simd_bitmask(bitmask::<i32, 4>)?,
+            I32x4ExtendLowI16x8S => self.simd_unary(|a| extend::<i16, i32, 4>(a, 0))?,
+            I32x4ExtendHighI16x8S => self.simd_unary(|a| extend::<i16, i32, 4>(a, 4))?,
+            I32x4ExtendLowI16x8U => self.simd_unary(|a| extend::<u16, u32, 4>(a, 0))?,
+            I32x4ExtendHighI16x8U => self.simd_unary(|a| extend::<u16, u32, 4>(a, 4))?,
+            I32x4Shl => self.simd_shift(|a, n| map::<i32, 4>(a, |x| x.wrapping_shl(n)))?,
+            I32x4ShrS => self.simd_shift(|a, n| map::<i32, 4>(a, |x| x.wrapping_shr(n)))?,
+            I32x4ShrU => self.simd_shift(|a, n| map::<u32, 4>(a, |x| x.wrapping_shr(n)))?,
+            I32x4Add => self.simd_binary(|a, b| zip::<i32, 4>(a, b, i32::wrapping_add))?,
+            I32x4Sub => self.simd_binary(|a, b| zip::<i32, 4>(a, b, i32::wrapping_sub))?,
+            I32x4Mul => self.simd_binary(|a, b| zip::<i32, 4>(a, b, i32::wrapping_mul))?,
+            I32x4MinS => self.simd_binary(|a, b| zip::<i32, 4>(a, b, i32::min))?,
+            I32x4MinU => self.simd_binary(|a, b| zip::<u32, 4>(a, b, u32::min))?,
+            I32x4MaxS => self.simd_binary(|a, b| zip::<i32, 4>(a, b, i32::max))?,
+            I32x4MaxU => self.simd_binary(|a, b| zip::<u32, 4>(a, b, u32::max))?,
+            I32x4DotI16x8S => self.simd_binary(|a, b| {
+                let a = lanes::<i16, 8>(a);
+                let b = lanes::<i16, 8>(b);
+                from_lanes::<i32, 4>(array::from_fn(|i| {
+                    let low = a[2 * i] as i32 * b[2 * i] as i32;
+                    let high = a[2 * i + 1] as i32 * b[2 * i + 1] as i32;
+                    low.wrapping_add(high)
+                }))
+            })?,
+            I32x4ExtmulLowI16x8S => self.simd_binary(|a, b| extmul::<i16, i32, 4>(a, b, 0))?,
+            I32x4ExtmulHighI16x8S => self.simd_binary(|a, b| extmul::<i16, i32, 4>(a, b, 4))?,
+            I32x4ExtmulLowI16x8U => self.simd_binary(|a, b| extmul::<u16, u32, 4>(a, b, 0))?,
+            I32x4ExtmulHighI16x8U => self.simd_binary(|a, b| extmul::<u16, u32, 4>(a, b, 4))?,
+
+            I64x2Abs => self.simd_unary(|a| map::<i64, 2>(a, i64::wrapping_abs))?,
+            I64x2Neg => self.simd_unary(|a| map::<i64, 2>(a, i64::wrapping_neg))?,
+            I64x2AllTrue => self.simd_test(all_true::<i64, 2>)?,
+            I64x2Bitmask => self.simd_bitmask(bitmask::<i64, 2>)?,
+            I64x2ExtendLowI32x4S => self.simd_unary(|a| extend::<i32, i64, 2>(a, 0))?,
+            I64x2ExtendHighI32x4S => self.simd_unary(|a| extend::<i32, i64, 2>(a, 2))?,
+            I64x2ExtendLowI32x4U => self.simd_unary(|a| extend::<u32, u64, 2>(a, 0))?,
+            I64x2ExtendHighI32x4U => self.simd_unary(|a| extend::<u32, u64, 2>(a, 2))?,
+            I64x2Shl => self.simd_shift(|a, n| map::<i64, 2>(a, |x| x.wrapping_shl(n)))?,
+            I64x2ShrS => self.simd_shift(|a, n| map::<i64, 2>(a, |x| x.wrapping_shr(n)))?,
+            I64x2ShrU => self.simd_shift(|a, n| map::<u64, 2>(a, |x| x.wrapping_shr(n)))?,
+            I64x2Add => self.simd_binary(|a, b| zip::<i64, 2>(a, b, i64::wrapping_add))?,
+            I64x2Sub => self.simd_binary(|a, b| zip::<i64, 2>(a, b, i64::wrapping_sub))?,
+            I64x2Mul => self.simd_binary(|a, b| zip::<i64, 2>(a, b, i64::wrapping_mul))?,
+            I64x2ExtmulLowI32x4S => self.simd_binary(|a, b| extmul::<i32, i64, 2>(a, b, 0))?,
+            I64x2ExtmulHighI32x4S => self.simd_binary(|a, b| extmul::<i32, i64, 2>(a, b, 2))?,
+            I64x2ExtmulLowI32x4U => self.simd_binary(|a, b| extmul::<u32, u64, 2>(a, b, 0))?,
+            I64x2ExtmulHighI32x4U => self.simd_binary(|a, b| extmul::<u32, u64, 2>(a, b, 2))?,
+
+            F32x4Ceil => self.simd_unary(|a| map::<f32, 4>(a, f32::ceil))?,
+            F32x4Floor => self.simd_unary(|a| map::<f32, 4>(a, f32::floor))?,
+            F32x4Trunc => self.simd_unary(|a| map::<f32, 4>(a, f32::trunc))?,
+            F32x4Nearest => self.simd_unary(|a| map::<f32, 4>(a, f32::round_ties_even))?,
+            F32x4Abs => self.simd_unary(|a| map::<f32, 4>(a, f32::abs))?,
+            F32x4Neg => self.simd_unary(|a| map::<f32, 4>(a, |x| -x))?,
+            F32x4Sqrt => self.simd_unary(|a| map::<f32, 4>(a, f32::sqrt))?,
+            F32x4Add => self.simd_binary(|a, b| zip::<f32, 4>(a, b, |a, b| a + b))?,
+            F32x4Sub => self.simd_binary(|a, b| zip::<f32, 4>(a, b, |a, b| a - b))?,
+            F32x4Mul => self.simd_binary(|a, b| zip::<f32, 4>(a, b, |a, b| a * b))?,
+            F32x4Div => self.simd_binary(|a, b| zip::<f32, 4>(a, b, |a, b| a / b))?,
+            F32x4Min => self.simd_binary(|a, b| zip::<f32, 4>(a, b, f32_min))?,
+            F32x4Max => self.simd_binary(|a, b| zip::<f32, 4>(a, b, f32_max))?,
+            F32x4Pmin => {
+                self.simd_binary(|a, b| zip::<f32, 4>(a, b, |a, b| if b < a { b } else { a }))?
+            }
+            F32x4Pmax => {
+                self.simd_binary(|a, b| zip::<f32, 4>(a, b, |a, b| if a < b { b } else { a }))?
+            }
+
+            F64x2Ceil => self.simd_unary(|a| map::<f64, 2>(a, f64::ceil))?,
+            F64x2Floor => self.simd_unary(|a| map::<f64, 2>(a, f64::floor))?,
+            F64x2Trunc => self.simd_unary(|a| map::<f64, 2>(a, f64::trunc))?,
+            F64x2Nearest => self.simd_unary(|a| map::<f64, 2>(a, f64::round_ties_even))?,
+            F64x2Abs => self.simd_unary(|a| map::<f64, 2>(a, f64::abs))?,
+            F64x2Neg => self.simd_unary(|a| map::<f64, 2>(a, |x| -x))?,
+            F64x2Sqrt => self.simd_unary(|a| map::<f64, 2>(a, f64::sqrt))?,
+            F64x2Add => self.simd_binary(|a, b| zip::<f64, 2>(a, b, |a, b| a + b))?,
+            F64x2Sub => self.simd_binary(|a, b| zip::<f64, 2>(a, b, |a, b| a - b))?,
+            F64x2Mul => self.simd_binary(|a, b| zip::<f64, 2>(a, b, |a, b| a * b))?,
+            F64x2Div => self.simd_binary(|a, b| zip::<f64, 2>(a, b, |a, b| a / b))?,
+            F64x2Min => self.simd_binary(|a, b| zip::<f64, 2>(a, b, f64_min))?,
+            F64x2Max => self.simd_binary(|a, b| zip::<f64, 2>(a, b, f64_max))?,
+            F64x2Pmin => {
+                self.simd_binary(|a, b| zip::<f64, 2>(a, b, |a, b| if b < a { b } else { a }))?
+            }
+            F64x2Pmax => {
+                self.simd_binary(|a, b| zip::<f64, 2>(a, b, |a, b| if a < b { b } else { a }))?
+            }
+
+            // Rust's `as` saturates float-to-int conversions and turns NaN into zero, like Wasm
+            I32x4TruncSatF32x4S => self.simd_unary(|a| convert::<f32, i32, 4>(a, |x| x as i32))?,
+            I32x4TruncSatF32x4U => self.simd_unary(|a| convert::<f32, u32, 4>(a, |x| x as u32))?,
+            F32x4ConvertI32x4S => self.simd_unary(|a| convert::<i32, f32, 4>(a, |x| x as f32))?,
+            F32x4ConvertI32x4U => self.simd_unary(|a| convert::<u32, f32, 4>(a, |x| x as f32))?,
+            I32x4TruncSatF64x2SZero => {
+                self.simd_unary(|a| convert::<f64, i32, 2>(a, |x| x as i32))?
+            }
+            I32x4TruncSatF64x2UZero => {
+                self.simd_unary(|a| convert::<f64, u32, 2>(a, |x| x as u32))?
+            }
+            F64x2ConvertLowI32x4S => self.simd_unary(|a| convert::<i32, f64, 2>(a, f64::from))?,
+            F64x2ConvertLowI32x4U => self.simd_unary(|a| convert::<u32, f64, 2>(a, f64::from))?,
+            F32x4DemoteF64x2Zero => self.simd_unary(|a| convert::<f64, f32, 2>(a, |x| x as f32))?,
+            F64x2PromoteLowF32x4 => self.simd_unary(|a| convert::<f32, f64, 2>(a, f64::from))?,
+        }
+
+        Ok(())
+    }
+
+    fn simd_unary(&mut self, f: impl FnOnce(u128) -> u128) -> Result<(), Error> {
+        let a = self.value_store.pop_v128()?;
+        self.value_store.push(Value::V128(f(a)));
+        Ok(())
+    }
+
+    fn simd_binary(&mut self, f: impl FnOnce(u128, u128) -> u128) -> Result<(), Error> {
+        let b = self.value_store.pop_v128()?;
+        let a = self.value_store.pop_v128()?;
+        self.value_store.push(Value::V128(f(a, b)));
+        Ok(())
+    }
+
+    /// Shift instructions take a vector and an i32 shift amount
+    fn simd_shift(&mut self, f: impl FnOnce(u128, u32) -> u128) -> Result<(), Error> {
+        let n = self.value_store.pop_u32()?;
+        let a = self.value_store.pop_v128()?;
+        self.value_store.push(Value::V128(f(a, n)));
+        Ok(())
+    }
+
+    fn simd_test(&mut self, f: impl FnOnce(u128) -> bool) -> Result<(), Error> {
+        let a = self.value_store.pop_v128()?;
+        self.value_store.push(Value::I32(f(a) as i32));
+        Ok(())
+    }
+
+    fn simd_bitmask(&mut self, f: impl FnOnce(u128) -> i32) -> Result<(), Error> {
+        let a = self.value_store.pop_v128()?;
+        self.value_store.push(Value::I32(f(a)));
+        Ok(())
+    }
+
+    fn fetch_lane_index(&mut self, module: &WasmModule<'a>) -> usize {
+        let index = module.code.bytes[self.program_counter] as usize;
+        self.program_counter += 1;
+        self.write_debug(index);
+        index
+    }
+
+    fn fetch_bytes16(&mut self, module: &WasmModule<'a>) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        bytes.copy_from_slice(&module.code.bytes[self.program_counter..][..16]);
+        self.program_counter += 16;
+        bytes
+    }
+
+    fn extract_lane<T: Lane, const N: usize>(
+        &mut self,
+        module: &WasmModule<'a>,
+    ) -> Result<T, Error> {
+        let index = self.fetch_lane_index(module);
+        let v = self.value_store.pop_v128()?;
+        Ok(lanes::<T, N>(v)[index])
+    }
+
+    fn replace_lane<T: Lane, const N: usize>(&mut self, index: usize, x: T) -> Result<(), Error> {
+        let v = self.value_store.pop_v128()?;
+        let mut lanes = lanes::<T, N>(v);
+        lanes[index] = x;
+        self.value_store.push(Value::V128(from_lanes(lanes)));
+        Ok(())
+    }
+
+    /// Read the memory immediates and pop the base address, like other loads and stores.
+    /// Unlike them, check the whole range of bytes, since a vector can straddle the end of memory.
+    fn simd_address(&mut self, module: &WasmModule<'a>, size: usize) -> Result<usize, Error> {
+        let _alignment = self.fetch_immediate_u32(module);
+        let offset = self.fetch_immediate_u32(module);
+        let base_addr = self.value_store.pop_u32()?;
+        let addr = base_addr as u64 + offset as u64;
+        let memory_size = self.memory.len() as u32;
+        if addr + size as u64 > memory_size as u64 {
+            let addr = u32::try_from(addr).unwrap_or(u32::MAX);
+            Err(Error::MemoryAccessOutOfBounds(addr, memory_size))
+        } else {
+            Ok(addr as usize)
+        }
+    }
+
+    /// Load `N` narrow numbers and widen each one to a lane
+    fn load_extend<T: Lane, U: Lane + From<T>, const N: usize>(
+        &mut self,
+        module: &WasmModule<'a>,
+    ) -> Result<(), Error> {
+        let addr = self.simd_address(module, N * T::BYTES)?;
+        let bytes = &self.memory[addr..][..N * T::BYTES];
+        let lanes: [U; N] =
+            array::from_fn(|i| U::from(T::read(&bytes[i * T::BYTES..][..T::BYTES])));
+        self.value_store.push(Value::V128(from_lanes(lanes)));
+        Ok(())
+    }
+
+    /// Load one number into every lane
+    fn load_splat(&mut self, module: &WasmModule<'a>, size: usize) -> Result<(), Error> {
+        let addr = self.simd_address(module, size)?;
+        let mut bytes = [0; 16];
+        for lane in bytes.chunks_mut(size) {
+            lane.copy_from_slice(&self.memory[addr..][..size]);
+        }
+        self.value_store
+            .push(Value::V128(u128::from_le_bytes(bytes)));
+        Ok(())
+    }
+
+    /// Load one number into lane 0, and set the other lanes to zero
+    fn load_zero(&mut self, module: &WasmModule<'a>, size: usize) -> Result<(), Error> {
+        let addr = self.simd_address(module, size)?;
+        let mut bytes = [0; 16];
+        bytes[..size].copy_from_slice(&self.memory[addr..][..size]);
+        self.value_store
+            .push(Value::V128(u128::from_le_bytes(bytes)));
+        Ok(())
+    }
+
+    /// Load one number into one lane of a vector, keeping the other lanes
+    fn load_lane(&mut self, module: &WasmModule<'a>, size: usize) -> Result<(), Error> {
+        let v = self.value_store.pop_v128()?;
+        let addr = self.simd_address(module, size)?;
+        let index = self.fetch_lane_index(module);
+        let mut bytes = v.to_le_bytes();
+        bytes[index * size..][..size].copy_from_slice(&self.memory[addr..][..size]);
+        self.value_store
+            .push(Value::V128(u128::from_le_bytes(bytes)));
+        Ok(())
+    }
+
+    fn store_lane(&mut self, module: &WasmModule<'a>, size: usize) -> Result<(), Error> {
+        let v = self.value_store.pop_v128()?;
+        let addr = self.simd_address(module, size)?;
+        let index = self.fetch_lane_index(module);
+        self.memory[addr..][..size].copy_from_slice(&v.to_le_bytes()[index * size..][..size]);
+        Ok(())
+    }
+}
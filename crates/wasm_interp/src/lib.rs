@@ -1,5 +1,7 @@
+mod debugger;
 mod frame;
 mod instance;
+mod profiler;
 #[cfg(test)]
 mod tests;
 
@@ -7,11 +9,14 @@ mod value_store;
 pub mod wasi;
 
 // Main external interface
+pub use debugger::Debugger;
 pub use instance::Instance;
+pub use profiler::{ProfileMetric, Profiler};
 pub use wasi::{WasiDispatcher, WasiFile};
 
 pub use roc_wasm_module::Value;
 use roc_wasm_module::ValueType;
+use std::fmt;
 
 pub trait ImportDispatcher {
     /// Dispatch a call from WebAssembly to your own code, based on module and function name.
@@ -60,6 +65,60 @@ impl<'a> ImportDispatcher for DefaultImportDispatcher<'a> {
     }
 }
 
+/// Limits on the resources a call into the WebAssembly module can use, for running untrusted code.
+/// Going over a limit stops the call with a [`Trap`]. All limits are off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// How many instructions a call can execute
+    pub fuel: Option<u64>,
+    /// How large `memory.grow` can make the memory, in bytes
+    pub max_memory_bytes: Option<u32>,
+    /// How many WebAssembly functions can be on the call stack at once
+    pub max_call_depth: Option<usize>,
+}
+
+/// What stopped a call into the WebAssembly module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapKind {
+    /// The call executed all the instructions that [`Limits::fuel`] allows
+    OutOfFuel,
+    /// `memory.grow` tried to make the memory larger than [`Limits::max_memory_bytes`]
+    MemoryLimit,
+    /// Calls were nested more deeply than [`Limits::max_call_depth`]
+    CallDepthLimit,
+    /// Anything else, like an `unreachable` instruction or a missing export
+    Other,
+}
+
+/// The error returned when a call into the WebAssembly module fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trap {
+    pub kind: TrapKind,
+    /// What went wrong, followed by a WebAssembly stack trace if the program was running
+    pub message: String,
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for Trap {
+    fn from(message: String) -> Self {
+        Trap {
+            kind: TrapKind::Other,
+            message,
+        }
+    }
+}
+
+impl From<Trap> for String {
+    fn from(trap: Trap) -> Self {
+        trap.message
+    }
+}
+
 /// Errors that can happen while interpreting the program
 /// All of these cause a WebAssembly stack trace to be dumped
 #[derive(Debug, PartialEq)]
@@ -68,9 +127,26 @@ pub(crate) enum Error {
     StackEmpty,
     MemoryAccessOutOfBounds(u32, u32),
     UnreachableOp,
+    OutOfFuel(u64),
+    MemoryLimit(u64, u32),
+    CallDepthLimit(usize),
+    DebuggerQuit,
 }
 
 impl Error {
+    pub fn kind(&self) -> TrapKind {
+        match self {
+            Error::OutOfFuel(_) => TrapKind::OutOfFuel,
+            Error::MemoryLimit(..) => TrapKind::MemoryLimit,
+            Error::CallDepthLimit(_) => TrapKind::CallDepthLimit,
+            Error::Type(..)
+            | Error::StackEmpty
+            | Error::MemoryAccessOutOfBounds(..)
+            | Error::UnreachableOp
+            | Error::DebuggerQuit => TrapKind::Other,
+        }
+    }
+
     pub fn to_string_at(&self, file_offset: usize) -> String {
         match self {
             Error::Type(expected, actual) => {
@@ -92,6 +168,24 @@ impl Error {
             Error::UnreachableOp => {
                 format!("WebAssembly `unreachable` instruction at file offset {file_offset:#x}.\n")
             }
+            Error::OutOfFuel(fuel) => {
+                format!(
+                    "ERROR: The program ran out of fuel at file offset {file_offset:#x}, after executing {fuel} instructions.\n"
+                )
+            }
+            Error::MemoryLimit(requested_bytes, max_bytes) => {
+                format!(
+                    "ERROR: A memory.grow instruction at file offset {file_offset:#x} tried to grow memory to {requested_bytes:#x} bytes, but the limit is {max_bytes:#x} bytes.\n"
+                )
+            }
+            Error::CallDepthLimit(max_depth) => {
+                format!(
+                    "ERROR: A call at file offset {file_offset:#x} would put more than {max_depth} functions on the call stack.\n"
+                )
+            }
+            Error::DebuggerQuit => {
+                format!("The debugger stopped the program at file offset {file_offset:#x}.\n")
+            }
         }
     }
 }
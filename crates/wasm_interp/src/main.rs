@@ -4,14 +4,25 @@ use clap::{Arg, Command};
 use std::fs;
 use std::io;
 use std::iter::once;
+use std::path::Path;
 use std::process;
 
-use roc_wasm_interp::{DefaultImportDispatcher, Instance};
+use roc_wasm_interp::wasi::split_dir_arg;
+use roc_wasm_interp::{
+    Debugger, DefaultImportDispatcher, Instance, Limits, ProfileMetric, Profiler,
+};
 use roc_wasm_module::WasmModule;
 
 pub const FLAG_FUNCTION: &str = "function";
 pub const FLAG_DEBUG: &str = "debug";
+pub const FLAG_DEBUGGER: &str = "debugger";
 pub const FLAG_HEX: &str = "hex";
+pub const FLAG_DIR: &str = "dir";
+pub const FLAG_FUEL: &str = "fuel";
+pub const FLAG_MAX_MEMORY: &str = "max-memory";
+pub const FLAG_MAX_CALL_DEPTH: &str = "max-call-depth";
+pub const FLAG_PROFILE: &str = "profile";
+pub const FLAG_PROFILE_BY: &str = "profile-by";
 pub const WASM_FILE: &str = "WASM_FILE";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
 
@@ -32,12 +43,56 @@ fn main() -> io::Result<()> {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_debugger = Arg::new(FLAG_DEBUGGER)
+        .long(FLAG_DEBUGGER)
+        .help("Step through the program interactively, with breakpoints.\nType `help` at the prompt for the list of commands.")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let flag_hex = Arg::new(FLAG_HEX)
         .long(FLAG_HEX)
         .help("If the called function returns a value, print it in hexadecimal format.")
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_dir = Arg::new(FLAG_DIR)
+        .long(FLAG_DIR)
+        .value_name("HOST:GUEST")
+        .help("Give the WebAssembly app access to a directory of the host, under another name\ne.g. `--dir ./test-data:/data`. Can be given more than once.")
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_fuel = Arg::new(FLAG_FUEL)
+        .long(FLAG_FUEL)
+        .help("Stop the program after it executes this many instructions")
+        .value_parser(clap::value_parser!(u64))
+        .required(false);
+
+    let flag_max_memory = Arg::new(FLAG_MAX_MEMORY)
+        .long(FLAG_MAX_MEMORY)
+        .help("Stop the program if it tries to grow its memory past this many bytes")
+        .value_parser(clap::value_parser!(u32))
+        .required(false);
+
+    let flag_max_call_depth = Arg::new(FLAG_MAX_CALL_DEPTH)
+        .long(FLAG_MAX_CALL_DEPTH)
+        .help("Stop the program if it nests more than this many function calls")
+        .value_parser(clap::value_parser!(usize))
+        .required(false);
+
+    let flag_profile = Arg::new(FLAG_PROFILE)
+        .long(FLAG_PROFILE)
+        .value_name("FILE")
+        .help("Count the instructions executed in each function, and write them to a file in\nthe folded stacks format, for flamegraph tools like `inferno-flamegraph`")
+        .required(false);
+
+    let flag_profile_by = Arg::new(FLAG_PROFILE_BY)
+        .long(FLAG_PROFILE_BY)
+        .help("What to measure in the --profile output")
+        .value_parser(["instructions", "time"])
+        .default_value("instructions")
+        .required(false);
+
     let wasm_file_to_run = Arg::new(WASM_FILE)
         .help("The .wasm file to run")
         .required(true);
@@ -50,7 +105,14 @@ fn main() -> io::Result<()> {
         .about("Run the given .wasm file")
         .arg(flag_function)
         .arg(flag_debug)
+        .arg(flag_debugger)
         .arg(flag_hex)
+        .arg(flag_dir)
+        .arg(flag_fuel)
+        .arg(flag_max_memory)
+        .arg(flag_max_call_depth)
+        .arg(flag_profile)
+        .arg(flag_profile_by)
         .arg(wasm_file_to_run)
         .arg(args_for_app);
 
@@ -59,9 +121,20 @@ fn main() -> io::Result<()> {
     let matches = app.get_matches();
     let start_fn_name = matches.get_one::<String>(FLAG_FUNCTION).unwrap();
     let is_debug_mode = matches.get_flag(FLAG_DEBUG);
+    let is_debugger_mode = matches.get_flag(FLAG_DEBUGGER);
     let is_hex_format = matches.get_flag(FLAG_HEX);
     let start_arg_strings = matches.get_many::<String>(ARGS_FOR_APP).unwrap_or_default();
     let wasm_path = matches.get_one::<String>(WASM_FILE).unwrap();
+    let profile_path = matches.get_one::<String>(FLAG_PROFILE);
+    let profile_metric = match matches.get_one::<String>(FLAG_PROFILE_BY).unwrap().as_str() {
+        "time" => ProfileMetric::Time,
+        _ => ProfileMetric::Instructions,
+    };
+    let limits = Limits {
+        fuel: matches.get_one::<u64>(FLAG_FUEL).copied(),
+        max_memory_bytes: matches.get_one::<u32>(FLAG_MAX_MEMORY).copied(),
+        max_call_depth: matches.get_one::<usize>(FLAG_MAX_CALL_DEPTH).copied(),
+    };
     // WASI expects the .wasm file to be argv[0]
     let wasi_argv_iter = once(wasm_path)
         .chain(start_arg_strings)
@@ -87,17 +160,42 @@ fn main() -> io::Result<()> {
 
     // Create an execution instance
 
-    let dispatcher = DefaultImportDispatcher::new(&wasi_argv);
-    let mut inst =
-        Instance::for_module(&arena, &module, dispatcher, is_debug_mode).unwrap_or_else(|e| {
+    let mut dispatcher = DefaultImportDispatcher::new(&wasi_argv);
+    for dir in matches.get_many::<String>(FLAG_DIR).unwrap_or_default() {
+        let (host_path, guest_path) = split_dir_arg(dir);
+        if let Err(e) = dispatcher
+            .wasi
+            .preopen_dir(Path::new(host_path), guest_path)
+        {
+            eprintln!("I couldn't give the app access to the directory {host_path}: {e}");
+            process::exit(1);
+        }
+    }
+    let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode, limits)
+        .unwrap_or_else(|e| {
             eprintln!("{e}");
             process::exit(2);
         });
+    if is_debugger_mode {
+        inst.debugger = Some(Debugger::stdio());
+    }
+    if profile_path.is_some() {
+        inst.profiler = Some(Profiler::new());
+    }
 
     // Run
 
     let result = inst.call_export_from_cli(&module, start_fn_name, &wasi_argv);
 
+    // Write the profile, even if the program failed
+
+    if let (Some(path), Some(profiler)) = (profile_path, inst.profiler.as_ref()) {
+        let file = fs::File::create(path)?;
+        profiler.write_folded(&module, profile_metric, io::BufWriter::new(file))?;
+        eprint!("{}", profiler.summary(&module));
+        eprintln!("Wrote the profile to {path}");
+    }
+
     // Print out return value, if any
 
     match result {
@@ -0,0 +1,234 @@
+//! Counts the instructions executed and the time spent in each function, for every call stack
+//! it's called from. The results can be written in the "folded stacks" format that flamegraph
+//! tools like `inferno-flamegraph` and `flamegraph.pl` read, one line per call stack:
+//!
+//! ```text
+//! _start;main;List.map;#UserApp.f 123456
+//! ```
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use roc_wasm_module::WasmModule;
+
+/// The root of the tree of call stacks, which isn't a real function
+const ROOT: usize = 0;
+
+/// How many functions to show in the summary
+const SUMMARY_LENGTH: usize = 20;
+
+/// What the numbers in the folded stacks measure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileMetric {
+    Instructions,
+    /// Time in microseconds
+    Time,
+}
+
+/// A function in one particular call stack
+#[derive(Debug)]
+struct Node {
+    fn_index: usize,
+    children: Vec<usize>,
+    /// Instructions executed in this function itself, not counting the functions it called
+    instructions: u64,
+    /// Time spent in this function itself, including in imported functions it called
+    time: Duration,
+}
+
+impl Node {
+    fn new(fn_index: usize) -> Self {
+        Node {
+            fn_index,
+            children: Vec::new(),
+            instructions: 0,
+            time: Duration::ZERO,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Profiler {
+    /// The tree of call stacks
+    nodes: Vec<Node>,
+    /// The nodes of the functions on the call stack, starting with the root
+    path: Vec<usize>,
+    /// When the function at the top of the call stack last changed
+    last_switch: Instant,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Profiler {
+            nodes: vec![Node::new(usize::MAX)],
+            path: vec![ROOT],
+            last_switch: Instant::now(),
+        }
+    }
+
+    pub(crate) fn start_call(&mut self) {
+        self.path.truncate(1);
+        self.last_switch = Instant::now();
+    }
+
+    pub(crate) fn end_call(&mut self) {
+        self.switch();
+        self.path.truncate(1);
+    }
+
+    /// Count an instruction for the function at the top of the call stack.
+    /// `depth` is the number of calls nested inside the exported function that was called.
+    pub(crate) fn count_instruction(&mut self, depth: usize, fn_index: usize) {
+        let path_len = depth + 2;
+        let mut node = *self.path.last().unwrap();
+        if self.path.len() != path_len || self.nodes[node].fn_index != fn_index {
+            self.switch();
+
+            // After a return, the caller is already on the path. At the start of a call,
+            // the exported function may be a different one from the last call.
+            if self.path.len() >= path_len {
+                self.path.truncate(path_len);
+                if self.nodes[self.path[path_len - 1]].fn_index != fn_index {
+                    self.path.pop();
+                }
+            }
+            while self.path.len() < path_len {
+                let parent = *self.path.last().unwrap();
+                let child = self.child(parent, fn_index);
+                self.path.push(child);
+            }
+            node = *self.path.last().unwrap();
+        }
+
+        self.nodes[node].instructions += 1;
+    }
+
+    /// Add the time since the last switch to the function at the top of the call stack
+    fn switch(&mut self) {
+        let now = Instant::now();
+        let node = *self.path.last().unwrap();
+        self.nodes[node].time += now - self.last_switch;
+        self.last_switch = now;
+    }
+
+    fn child(&mut self, parent: usize, fn_index: usize) -> usize {
+        let existing = self.nodes[parent]
+            .children
+            .iter()
+            .find(|&&child| self.nodes[child].fn_index == fn_index);
+        match existing {
+            Some(&child) => child,
+            None => {
+                let child = self.nodes.len();
+                self.nodes.push(Node::new(fn_index));
+                self.nodes[parent].children.push(child);
+                child
+            }
+        }
+    }
+
+    /// Write one line per call stack, with the functions separated by `;` and then the number.
+    /// Call stacks with nothing to count are left out.
+    pub fn write_folded<W: Write>(
+        &self,
+        module: &WasmModule<'_>,
+        metric: ProfileMetric,
+        mut out: W,
+    ) -> io::Result<()> {
+        let names = FunctionNames::new(module);
+        let mut stack = Vec::new();
+        for &child in self.nodes[ROOT].children.iter() {
+            self.write_folded_help(&names, metric, child, &mut stack, &mut out)?;
+        }
+        out.flush()
+    }
+
+    fn write_folded_help<W: Write>(
+        &self,
+        names: &FunctionNames,
+        metric: ProfileMetric,
+        node_index: usize,
+        stack: &mut Vec<String>,
+        out: &mut W,
+    ) -> io::Result<()> {
+        let node = &self.nodes[node_index];
+        // `;` separates the functions, so it can't be part of a name
+        stack.push(names.get(node.fn_index).replace(';', ","));
+
+        let value = match metric {
+            ProfileMetric::Instructions => node.instructions,
+            ProfileMetric::Time => node.time.as_micros() as u64,
+        };
+        if value > 0 {
+            writeln!(out, "{} {value}", stack.join(";"))?;
+        }
+        for &child in node.children.iter() {
+            self.write_folded_help(names, metric, child, stack, out)?;
+        }
+
+        stack.pop();
+        Ok(())
+    }
+
+    /// A table of the functions that executed the most instructions themselves
+    pub fn summary(&self, module: &WasmModule<'_>) -> String {
+        let names = FunctionNames::new(module);
+
+        let mut totals: HashMap<usize, (u64, Duration)> = HashMap::new();
+        for node in self.nodes.iter().skip(1) {
+            let (instructions, time) = totals.entry(node.fn_index).or_default();
+            *instructions += node.instructions;
+            *time += node.time;
+        }
+        let mut totals: Vec<_> = totals.into_iter().collect();
+        totals.sort_by(|(_, (a, _)), (_, (b, _))| b.cmp(a));
+
+        let all_instructions: u64 = totals.iter().map(|(_, (n, _))| n).sum();
+
+        let mut summary = String::new();
+        writeln!(
+            summary,
+            "{:>14} {:>7} {:>12}  function",
+            "instructions", "%", "time (ms)"
+        )
+        .unwrap();
+        for (fn_index, (instructions, time)) in totals.iter().take(SUMMARY_LENGTH) {
+            let percent = 100.0 * *instructions as f64 / all_instructions.max(1) as f64;
+            writeln!(
+                summary,
+                "{instructions:>14} {percent:>6.1}% {:>12.3}  {}",
+                time.as_secs_f64() * 1000.0,
+                names.get(*fn_index)
+            )
+            .unwrap();
+        }
+        if totals.len() > SUMMARY_LENGTH {
+            writeln!(summary, "... and {} more", totals.len() - SUMMARY_LENGTH).unwrap();
+        }
+        summary
+    }
+}
+
+/// Function names from the module's NameSection, or `func[N]` for functions without a name
+struct FunctionNames<'a>(HashMap<usize, &'a str>);
+
+impl<'a> FunctionNames<'a> {
+    fn new(module: &WasmModule<'a>) -> Self {
+        let names = module.names.function_names.iter();
+        FunctionNames(names.map(|(i, name)| (*i as usize, *name)).collect())
+    }
+
+    fn get(&self, fn_index: usize) -> String {
+        match self.0.get(&fn_index) {
+            Some(name) => name.to_string(),
+            None => format!("func[{fn_index}]"),
+        }
+    }
+}
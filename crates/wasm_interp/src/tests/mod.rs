@@ -2,17 +2,22 @@
 
 mod test_basics;
 mod test_convert;
+mod test_debugger;
 mod test_f32;
 mod test_f64;
 mod test_i32;
 mod test_i64;
+mod test_limits;
 mod test_mem;
+mod test_profiler;
+mod test_simd;
+mod test_wasi;
 
-use crate::{DefaultImportDispatcher, Instance};
+use crate::{DefaultImportDispatcher, Instance, Limits};
 use bumpalo::{collections::Vec, Bump};
 use roc_wasm_module::{
-    opcodes::OpCode, Export, ExportType, SerialBuffer, Serialize, Signature, Value, ValueType,
-    WasmModule,
+    opcodes::{OpCode, SimdInstruction},
+    Export, ExportType, SerialBuffer, Serialize, Signature, Value, ValueType, WasmModule,
 };
 
 pub fn default_state(arena: &Bump) -> Instance<DefaultImportDispatcher> {
@@ -47,6 +52,10 @@ pub fn const_value(buf: &mut Vec<'_, u8>, value: Value) {
             buf.push(OpCode::F64CONST as u8);
             buf.encode_f64(x);
         }
+        V128(x) => {
+            SimdInstruction::V128Const.serialize(buf);
+            buf.extend(x.to_le_bytes());
+        }
     }
 }
 
@@ -92,8 +101,14 @@ where
         std::fs::write(&filename, outfile_buf).unwrap();
     }
 
-    let mut inst =
-        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), true).unwrap();
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        true,
+        Limits::default(),
+    )
+    .unwrap();
 
     let return_val = inst.call_export("test", []).unwrap().unwrap();
 
@@ -5,7 +5,7 @@ use crate::tests::{
     const_value, create_exported_function_no_locals, create_exported_function_with_locals,
     default_state,
 };
-use crate::{DefaultImportDispatcher, ImportDispatcher, Instance};
+use crate::{DefaultImportDispatcher, ImportDispatcher, Instance, Limits};
 use bumpalo::{collections::Vec, Bump};
 use roc_wasm_module::sections::{Import, ImportDesc};
 use roc_wasm_module::{
@@ -105,8 +105,14 @@ fn test_loop_help(end: i32, expected: i32) {
         index: 0,
     });
 
-    let mut inst =
-        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
     let return_val = inst.call_export("test", []).unwrap().unwrap();
 
     assert_eq!(return_val, Value::I32(expected));
@@ -173,6 +179,7 @@ fn test_if_else_help(condition: i32, expected: i32) {
         &module,
         DefaultImportDispatcher::default(),
         is_debug_mode,
+        Limits::default(),
     )
     .unwrap();
     let result = inst.call_export("test", []).unwrap().unwrap();
@@ -268,6 +275,7 @@ fn test_br() {
         &module,
         DefaultImportDispatcher::default(),
         is_debug_mode,
+        Limits::default(),
     )
     .unwrap();
     let result = inst.call_export(start_fn_name, []).unwrap().unwrap();
@@ -372,6 +380,7 @@ fn test_br_if_help(condition: i32, expected: i32) {
         &module,
         DefaultImportDispatcher::default(),
         is_debug_mode,
+        Limits::default(),
     )
     .unwrap();
     let result = inst.call_export(start_fn_name, []).unwrap().unwrap();
@@ -480,6 +489,7 @@ fn test_br_table_help(condition: i32, expected: i32) {
         &module,
         DefaultImportDispatcher::default(),
         is_debug_mode,
+        Limits::default(),
     )
     .unwrap();
     let result = inst.call_export(start_fn_name, []).unwrap().unwrap();
@@ -566,7 +576,8 @@ fn test_call_import() {
         println!("Wrote to {filename}");
     }
 
-    let mut inst = Instance::for_module(&arena, &module, import_dispatcher, true).unwrap();
+    let mut inst =
+        Instance::for_module(&arena, &module, import_dispatcher, true, Limits::default()).unwrap();
 
     let return_val = inst.call_export(start_fn_name, []).unwrap().unwrap();
 
@@ -634,8 +645,14 @@ fn test_call_return_no_args() {
         println!("Wrote to {filename}");
     }
 
-    let mut inst =
-        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), true).unwrap();
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        true,
+        Limits::default(),
+    )
+    .unwrap();
 
     let return_val = inst.call_export(start_fn_name, []).unwrap().unwrap();
 
@@ -693,8 +710,14 @@ fn test_call_return_with_args() {
         buf.push(OpCode::END as u8);
     });
 
-    let mut inst =
-        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
     let result = inst.call_export("two_plus_two", []).unwrap().unwrap();
 
     assert_eq!(result, Value::I32(4));
@@ -781,6 +804,7 @@ fn test_call_indirect_help(table_index: u32, elem_index: u32) -> Value {
         &module,
         DefaultImportDispatcher::default(),
         is_debug_mode,
+        Limits::default(),
     )
     .unwrap();
     inst.call_export(start_fn_name, []).unwrap().unwrap()
@@ -812,8 +836,14 @@ fn test_select_help(first: Value, second: Value, condition: i32, expected: Value
         buf.push(OpCode::END as u8);
     });
 
-    let mut inst =
-        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
     let result = inst.call_export("test", []).unwrap().unwrap();
 
     assert_eq!(result, expected);
@@ -0,0 +1,202 @@
+#![cfg(test)]
+
+use super::create_exported_function_no_locals;
+use crate::{Debugger, DefaultImportDispatcher, Instance, Limits, TrapKind};
+use bumpalo::{collections::Vec, Bump};
+use roc_wasm_module::{
+    opcodes::OpCode, sections::MemorySection, SerialBuffer, Signature, Value, ValueType, WasmModule,
+};
+use std::cell::RefCell;
+use std::io::{self, Cursor, Write};
+use std::rc::Rc;
+
+/// Debugger output that the test can read after the debugger is done with it
+#[derive(Clone, Default)]
+struct SharedOutput(Rc<RefCell<std::vec::Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedOutput {
+    fn text(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+/// `add_one(x) = x + 1` and `test() = add_one(41)`
+fn create_module(arena: &Bump) -> WasmModule<'_> {
+    let mut module = WasmModule::new(arena);
+    module.memory = MemorySection::new(arena, MemorySection::PAGE_SIZE);
+
+    let signature = Signature {
+        param_types: bumpalo::vec![in arena; ValueType::I32],
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "add_one", signature, |buf| {
+        buf.push(OpCode::GETLOCAL as u8);
+        buf.encode_u32(0);
+        buf.push(OpCode::I32CONST as u8);
+        buf.encode_i32(1);
+        buf.push(OpCode::I32ADD as u8);
+        buf.push(OpCode::END as u8);
+    });
+    module.names.append_function(0, "add_one");
+
+    let signature = Signature {
+        param_types: Vec::new_in(arena),
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "test", signature, |buf| {
+        buf.push(OpCode::I32CONST as u8);
+        buf.encode_i32(41);
+        buf.push(OpCode::CALL as u8);
+        buf.encode_u32(0);
+        buf.push(OpCode::END as u8);
+    });
+    module.names.append_function(1, "test");
+
+    module
+}
+
+fn debug<'a>(
+    arena: &'a Bump,
+    module: &'a WasmModule<'a>,
+    commands: &str,
+) -> (Instance<'a, DefaultImportDispatcher<'a>>, SharedOutput) {
+    let output = SharedOutput::default();
+    let mut inst = Instance::for_module(
+        arena,
+        module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
+    inst.debugger = Some(Debugger::new(
+        Box::new(Cursor::new(commands.to_string())),
+        Box::new(output.clone()),
+    ));
+    (inst, output)
+}
+
+#[test]
+fn test_break_on_function() {
+    let arena = Bump::new();
+    let module = arena.alloc(create_module(&arena));
+    let (mut inst, output) = debug(&arena, module, "b add_one\nc\nlocals\nf\nstack\nc\n");
+
+    let result = inst.call_export("test", []);
+    assert_eq!(result, Ok(Some(Value::I32(42))));
+
+    let output = output.text();
+    assert!(output.contains("Breakpoint 1"), "{output}");
+    assert!(output.contains("func[0]  add_one"), "{output}");
+    assert!(output.contains("arg 0: I32(41)"), "{output}");
+    // After `finish`, the result of add_one is on the stack of `test`
+    assert!(output.contains("[I32(42)]"), "{output}");
+}
+
+#[test]
+fn test_step_and_next() {
+    let arena = Bump::new();
+    let module = arena.alloc(create_module(&arena));
+    let (mut inst, output) = debug(&arena, module, "s\ns\ns\nn\nq\n");
+
+    let result = inst.call_export("test", []);
+    assert_eq!(result.unwrap_err().kind, TrapKind::Other);
+
+    // `step` goes into add_one, and `next` stays in it.
+    // The commands aren't echoed, so each location follows a prompt.
+    let output = output.text();
+    let locations: std::vec::Vec<_> = output
+        .lines()
+        .filter_map(|line| line.split_once("func[").map(|(_, location)| location))
+        .collect();
+    assert_eq!(
+        locations,
+        [
+            "1]  test",
+            "1]  test",
+            "0]  add_one",
+            "0]  add_one",
+            "0]  add_one"
+        ],
+        "{output}"
+    );
+}
+
+#[test]
+fn test_decode_memory() {
+    let arena = Bump::new();
+    let module = arena.alloc(create_module(&arena));
+    let (mut inst, output) = debug(
+        &arena,
+        module,
+        "str 0x100\nstr 0x110\nlist 0x120 i32\nx 0x200 5\nq\n",
+    );
+
+    // A small string
+    inst.memory[0x100..][..5].copy_from_slice(b"hello");
+    inst.memory[0x100 + 11] = 0x80 | 5;
+    // A big string
+    let big = b"this string is too long to be small";
+    inst.memory[0x200..][..big.len()].copy_from_slice(big);
+    inst.memory[0x110..][..4].copy_from_slice(&0x200u32.to_le_bytes());
+    inst.memory[0x114..][..4].copy_from_slice(&(big.len() as u32).to_le_bytes());
+    inst.memory[0x118..][..4].copy_from_slice(&(big.len() as u32).to_le_bytes());
+    // A list of I32
+    for (i, n) in [1i32, 2, 3].iter().enumerate() {
+        inst.memory[0x300 + 4 * i..][..4].copy_from_slice(&n.to_le_bytes());
+    }
+    inst.memory[0x120..][..4].copy_from_slice(&0x300u32.to_le_bytes());
+    inst.memory[0x124..][..4].copy_from_slice(&3u32.to_le_bytes());
+    inst.memory[0x128..][..4].copy_from_slice(&3u32.to_le_bytes());
+
+    let _ = inst.call_export("test", []);
+
+    let output = output.text();
+    assert!(output.contains("\"hello\" (small string"), "{output}");
+    assert!(
+        output.contains("\"this string is too long to be small\" (bytes at 0x200"),
+        "{output}"
+    );
+    assert!(output.contains("[1, 2, 3] (elements at 0x300"), "{output}");
+    assert!(output.contains("00000200  74 68 69 73 20"), "{output}");
+}
+
+#[test]
+fn test_backtrace_on_trap() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: None,
+    };
+    create_exported_function_no_locals(&mut module, "test", signature, |buf| {
+        buf.push(OpCode::UNREACHABLE as u8);
+        buf.push(OpCode::END as u8);
+    });
+    module.names.append_function(0, "test");
+    let module = arena.alloc(module);
+
+    let (mut inst, output) = debug(&arena, module, "c\nbt\nc\n");
+    let result = inst.call_export("test", []);
+    assert!(result.is_err());
+
+    let output = output.text();
+    let trap = output.find("The program stopped with an error").unwrap();
+    // the backtrace is printed with the error, and again by the `bt` command
+    assert_eq!(
+        output[trap..].matches("func[0]  test").count(),
+        2,
+        "{output}"
+    );
+}
@@ -0,0 +1,192 @@
+#![cfg(test)]
+
+use super::create_exported_function_no_locals;
+use crate::{DefaultImportDispatcher, Instance, Limits, TrapKind};
+use bumpalo::{collections::Vec, Bump};
+use roc_wasm_module::{
+    opcodes::OpCode, sections::MemorySection, SerialBuffer, Signature, Value, ValueType, WasmModule,
+};
+
+fn call_with_limits(module: &WasmModule<'_>, limits: Limits) -> Result<Option<Value>, TrapKind> {
+    let arena = Bump::new();
+    let mut inst = Instance::for_module(
+        &arena,
+        module,
+        DefaultImportDispatcher::default(),
+        false,
+        limits,
+    )
+    .unwrap();
+    inst.call_export("test", []).map_err(|trap| trap.kind)
+}
+
+#[test]
+fn test_fuel() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: None,
+    };
+    create_exported_function_no_locals(&mut module, "test", signature, |buf| {
+        // loop forever
+        buf.push(OpCode::LOOP as u8);
+        buf.push(ValueType::VOID);
+        buf.push(OpCode::BR as u8);
+        buf.encode_u32(0);
+        buf.push(OpCode::END as u8);
+        buf.push(OpCode::END as u8);
+    });
+
+    let limits = Limits {
+        fuel: Some(1000),
+        ..Default::default()
+    };
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        limits,
+    )
+    .unwrap();
+    let trap = inst.call_export("test", []).unwrap_err();
+    assert_eq!(trap.kind, TrapKind::OutOfFuel);
+    assert_eq!(inst.fuel_used(), 1000);
+
+    // Every call gets the full budget
+    let trap = inst.call_export("test", []).unwrap_err();
+    assert_eq!(trap.kind, TrapKind::OutOfFuel);
+    assert_eq!(inst.fuel_used(), 1000);
+}
+
+#[test]
+fn test_fuel_enough() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "test", signature, |buf| {
+        buf.push(OpCode::I32CONST as u8);
+        buf.encode_i32(42);
+        buf.push(OpCode::END as u8);
+    });
+
+    let limits = Limits {
+        fuel: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(call_with_limits(&module, limits), Ok(Some(Value::I32(42))));
+
+    let limits = Limits {
+        fuel: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(call_with_limits(&module, limits), Err(TrapKind::OutOfFuel));
+}
+
+#[test]
+fn test_max_memory() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    module.memory = MemorySection::new(&arena, MemorySection::PAGE_SIZE);
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "test", signature, |buf| {
+        // grow by 2 pages, to 3
+        buf.push(OpCode::I32CONST as u8);
+        buf.encode_i32(2);
+        buf.push(OpCode::GROWMEMORY as u8);
+        buf.encode_u32(0);
+        buf.push(OpCode::END as u8);
+    });
+
+    let limits = Limits {
+        max_memory_bytes: Some(3 * MemorySection::PAGE_SIZE),
+        ..Default::default()
+    };
+    assert_eq!(call_with_limits(&module, limits), Ok(Some(Value::I32(1))));
+
+    let limits = Limits {
+        max_memory_bytes: Some(2 * MemorySection::PAGE_SIZE),
+        ..Default::default()
+    };
+    assert_eq!(
+        call_with_limits(&module, limits),
+        Err(TrapKind::MemoryLimit)
+    );
+}
+
+#[test]
+fn test_max_call_depth() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: None,
+    };
+    create_exported_function_no_locals(&mut module, "test", signature, |buf| {
+        // call myself forever
+        buf.push(OpCode::CALL as u8);
+        buf.encode_u32(0);
+        buf.push(OpCode::END as u8);
+    });
+
+    let limits = Limits {
+        max_call_depth: Some(100),
+        ..Default::default()
+    };
+    assert_eq!(
+        call_with_limits(&module, limits),
+        Err(TrapKind::CallDepthLimit)
+    );
+
+    // The calls would never end, so the fuel runs out first
+    let limits = Limits {
+        fuel: Some(10_000),
+        max_call_depth: Some(100_000),
+        ..Default::default()
+    };
+    assert_eq!(call_with_limits(&module, limits), Err(TrapKind::OutOfFuel));
+}
+
+#[test]
+fn test_max_memory_initial() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    module.memory = MemorySection::new(&arena, 2 * MemorySection::PAGE_SIZE);
+
+    let limits = Limits {
+        max_memory_bytes: Some(MemorySection::PAGE_SIZE),
+        ..Default::default()
+    };
+    let result = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        limits,
+    );
+    assert!(result.is_err());
+
+    let limits = Limits {
+        max_memory_bytes: Some(2 * MemorySection::PAGE_SIZE),
+        ..Default::default()
+    };
+    let result = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        limits,
+    );
+    assert!(result.is_ok());
+}
@@ -1,5 +1,5 @@
-use super::create_exported_function_no_locals;
-use crate::{DefaultImportDispatcher, Instance};
+use super::{const_value, create_exported_function_no_locals};
+use crate::{DefaultImportDispatcher, Instance, Limits};
 use bumpalo::{collections::Vec, Bump};
 use roc_wasm_module::{
     opcodes::OpCode,
@@ -160,6 +160,7 @@ fn test_load(load_op: OpCode, ty: ValueType, data: &[u8], addr: u32, offset: u32
         &module,
         DefaultImportDispatcher::default(),
         is_debug_mode,
+        Limits::default(),
     )
     .unwrap();
     inst.call_export(start_fn_name, []).unwrap().unwrap()
@@ -331,24 +332,7 @@ fn test_store<'a>(
     create_exported_function_no_locals(module, start_fn_name, signature, |buf| {
         buf.append_u8(OpCode::I32CONST as u8);
         buf.encode_u32(addr);
-        match value {
-            Value::I32(x) => {
-                buf.append_u8(OpCode::I32CONST as u8);
-                buf.encode_i32(x);
-            }
-            Value::I64(x) => {
-                buf.append_u8(OpCode::I64CONST as u8);
-                buf.encode_i64(x);
-            }
-            Value::F32(x) => {
-                buf.append_u8(OpCode::F32CONST as u8);
-                buf.encode_f32(x);
-            }
-            Value::F64(x) => {
-                buf.append_u8(OpCode::F64CONST as u8);
-                buf.encode_f64(x);
-            }
-        }
+        const_value(buf, value);
         buf.append_u8(store_op as u8);
         buf.encode_u32(0); // align
         buf.encode_u32(offset);
@@ -361,6 +345,7 @@ fn test_store<'a>(
         module,
         DefaultImportDispatcher::default(),
         is_debug_mode,
+        Limits::default(),
     )
     .unwrap();
     inst.call_export(start_fn_name, []).unwrap();
@@ -0,0 +1,160 @@
+#![cfg(test)]
+
+use super::create_exported_function_no_locals;
+use crate::{DefaultImportDispatcher, Instance, Limits, ProfileMetric, Profiler};
+use bumpalo::{collections::Vec, Bump};
+use roc_wasm_module::{opcodes::OpCode, SerialBuffer, Signature, Value, ValueType, WasmModule};
+
+/// `add_one(x) = x + 1`, `add_two(x) = add_one(add_one(x))` and `test() = add_two(add_one(1))`
+fn create_module(arena: &Bump, with_names: bool) -> WasmModule<'_> {
+    let mut module = WasmModule::new(arena);
+
+    let signature = Signature {
+        param_types: bumpalo::vec![in arena; ValueType::I32],
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "add_one", signature, |buf| {
+        buf.push(OpCode::GETLOCAL as u8);
+        buf.encode_u32(0);
+        buf.push(OpCode::I32CONST as u8);
+        buf.encode_i32(1);
+        buf.push(OpCode::I32ADD as u8);
+        buf.push(OpCode::END as u8);
+    });
+
+    let signature = Signature {
+        param_types: bumpalo::vec![in arena; ValueType::I32],
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "add_two", signature, |buf| {
+        buf.push(OpCode::GETLOCAL as u8);
+        buf.encode_u32(0);
+        buf.push(OpCode::CALL as u8);
+        buf.encode_u32(0);
+        buf.push(OpCode::CALL as u8);
+        buf.encode_u32(0);
+        buf.push(OpCode::END as u8);
+    });
+
+    let signature = Signature {
+        param_types: Vec::new_in(arena),
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "test", signature, |buf| {
+        buf.push(OpCode::I32CONST as u8);
+        buf.encode_i32(1);
+        buf.push(OpCode::CALL as u8);
+        buf.encode_u32(0);
+        buf.push(OpCode::CALL as u8);
+        buf.encode_u32(1);
+        buf.push(OpCode::END as u8);
+    });
+
+    if with_names {
+        module.names.append_function(0, "add_one");
+        module.names.append_function(1, "add_two");
+        module.names.append_function(2, "test");
+    }
+
+    module
+}
+
+fn profile(module: &WasmModule<'_>, metric: ProfileMetric) -> String {
+    let arena = Bump::new();
+    let mut inst = Instance::for_module(
+        &arena,
+        module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
+    inst.profiler = Some(Profiler::new());
+
+    let result = inst.call_export("test", []);
+    assert_eq!(result, Ok(Some(Value::I32(4))));
+
+    let mut folded = std::vec::Vec::new();
+    let profiler = inst.profiler.as_ref().unwrap();
+    profiler.write_folded(module, metric, &mut folded).unwrap();
+    String::from_utf8(folded).unwrap()
+}
+
+#[test]
+fn test_folded_instructions() {
+    let arena = Bump::new();
+    let module = create_module(&arena, true);
+
+    // The same function called from different places gets a line for each call stack,
+    // and calls from the same place are added together.
+    assert_eq!(
+        profile(&module, ProfileMetric::Instructions),
+        "test 4\n\
+         test;add_one 4\n\
+         test;add_two 4\n\
+         test;add_two;add_one 8\n"
+    );
+}
+
+#[test]
+fn test_folded_without_names() {
+    let arena = Bump::new();
+    let module = create_module(&arena, false);
+
+    assert_eq!(
+        profile(&module, ProfileMetric::Instructions),
+        "func[2] 4\n\
+         func[2];func[0] 4\n\
+         func[2];func[1] 4\n\
+         func[2];func[1];func[0] 8\n"
+    );
+}
+
+#[test]
+fn test_folded_time() {
+    let arena = Bump::new();
+    let module = create_module(&arena, true);
+
+    // Every line is a call stack we know about, with a number of microseconds
+    let folded = profile(&module, ProfileMetric::Time);
+    for line in folded.lines() {
+        let (stack, micros) = line.rsplit_once(' ').unwrap();
+        assert!(stack.starts_with("test"), "{folded}");
+        assert!(micros.parse::<u64>().unwrap() > 0, "{folded}");
+    }
+}
+
+#[test]
+fn test_profile_adds_up_calls() {
+    let arena = Bump::new();
+    let module = create_module(&arena, true);
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
+    inst.profiler = Some(Profiler::new());
+
+    inst.call_export("test", []).unwrap();
+    inst.call_export("add_one", [Value::I32(1)]).unwrap();
+
+    let mut folded = std::vec::Vec::new();
+    let profiler = inst.profiler.as_ref().unwrap();
+    profiler
+        .write_folded(&module, ProfileMetric::Instructions, &mut folded)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(folded).unwrap(),
+        "test 4\n\
+         test;add_one 4\n\
+         test;add_two 4\n\
+         test;add_two;add_one 8\n\
+         add_one 4\n"
+    );
+
+    let summary = profiler.summary(&module);
+    assert!(summary.contains("16   66.7%"), "{summary}");
+}
@@ -0,0 +1,518 @@
+#![cfg(test)]
+
+use super::{
+    const_value, create_exported_function_no_locals, create_exported_function_with_locals,
+};
+use crate::{DefaultImportDispatcher, Instance, Limits};
+use bumpalo::{collections::Vec, Bump};
+use roc_wasm_module::{
+    opcodes::{OpCode, SimdInstruction},
+    sections::MemorySection,
+    Serialize, Signature, Value, ValueType, WasmModule,
+};
+
+use SimdInstruction::*;
+
+macro_rules! vector {
+    ($name: ident, $t: ty, $n: literal) => {
+        fn $name(lanes: [$t; $n]) -> Value {
+            let mut bytes = [0; 16];
+            for (chunk, lane) in bytes.chunks_mut(16 / $n).zip(lanes) {
+                chunk.copy_from_slice(&lane.to_le_bytes());
+            }
+            Value::V128(u128::from_le_bytes(bytes))
+        }
+    };
+}
+
+vector!(i8x16, i8, 16);
+vector!(u8x16, u8, 16);
+vector!(i16x8, i16, 8);
+vector!(i32x4, i32, 4);
+vector!(i64x2, i64, 2);
+vector!(f32x4, f32, 4);
+vector!(f64x2, f64, 2);
+
+fn run_simd_op<A>(op: SimdInstruction, immediates: &[u8], args: A, ret_type: ValueType) -> Value
+where
+    A: IntoIterator<Item = Value>,
+{
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    module.memory = MemorySection::new(&arena, MemorySection::PAGE_SIZE);
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: Some(ret_type),
+    };
+    create_exported_function_no_locals(&mut module, "test", signature, |buf| {
+        for arg in args {
+            const_value(buf, arg);
+        }
+        op.serialize(buf);
+        buf.extend_from_slice(immediates);
+        buf.push(OpCode::END as u8);
+    });
+
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
+    inst.call_export("test", []).unwrap().unwrap()
+}
+
+fn test_simd_op<A>(op: SimdInstruction, immediates: &[u8], args: A, expected: Value)
+where
+    A: IntoIterator<Item = Value>,
+{
+    let result = run_simd_op(op, immediates, args, ValueType::from(expected));
+    assert_eq!(result, expected, "{op:?}");
+}
+
+#[test]
+fn test_integer_arithmetic() {
+    test_simd_op(
+        I32x4Add,
+        &[],
+        [i32x4([1, 2, 3, i32::MAX]), i32x4([10, 20, 30, 1])],
+        i32x4([11, 22, 33, i32::MIN]),
+    );
+    test_simd_op(
+        I8x16AddSatS,
+        &[],
+        [i8x16([100; 16]), i8x16([100; 16])],
+        i8x16([127; 16]),
+    );
+    test_simd_op(
+        I8x16SubSatU,
+        &[],
+        [u8x16([5; 16]), u8x16([10; 16])],
+        u8x16([0; 16]),
+    );
+    test_simd_op(
+        I16x8Mul,
+        &[],
+        [i16x8([1, 2, 3, 4, 5, 6, 7, 0x4000]), i16x8([2; 8])],
+        i16x8([2, 4, 6, 8, 10, 12, 14, i16::MIN]),
+    );
+    test_simd_op(
+        I64x2Sub,
+        &[],
+        [i64x2([0, 100]), i64x2([1, 1])],
+        i64x2([-1, 99]),
+    );
+    test_simd_op(
+        I32x4DotI16x8S,
+        &[],
+        [
+            i16x8([1, 2, 3, 4, 5, 6, 7, 8]),
+            i16x8([1, 1, 2, 2, -1, -1, 0, 1]),
+        ],
+        i32x4([3, 14, -11, 8]),
+    );
+    test_simd_op(
+        I16x8Q15mulrSatS,
+        &[],
+        [
+            i16x8([i16::MIN, 0x4000, 0, 0, 0, 0, 0, 0]),
+            i16x8([i16::MIN, 0x4000, 0, 0, 0, 0, 0, 0]),
+        ],
+        i16x8([i16::MAX, 0x2000, 0, 0, 0, 0, 0, 0]),
+    );
+}
+
+#[test]
+fn test_shifts() {
+    // The shift amount wraps around at the lane width
+    test_simd_op(
+        I32x4Shl,
+        &[],
+        [i32x4([1, 2, 3, 4]), Value::I32(33)],
+        i32x4([2, 4, 6, 8]),
+    );
+    test_simd_op(
+        I8x16ShrS,
+        &[],
+        [i8x16([-128; 16]), Value::I32(7)],
+        i8x16([-1; 16]),
+    );
+    test_simd_op(
+        I8x16ShrU,
+        &[],
+        [i8x16([-128; 16]), Value::I32(7)],
+        i8x16([1; 16]),
+    );
+}
+
+#[test]
+fn test_comparisons() {
+    test_simd_op(
+        I32x4LtS,
+        &[],
+        [i32x4([-1, 0, 1, 2]), i32x4([0; 4])],
+        i32x4([-1, 0, 0, 0]),
+    );
+    test_simd_op(
+        I32x4LtU,
+        &[],
+        [i32x4([-1, 0, 1, 2]), i32x4([1; 4])],
+        i32x4([0, -1, 0, 0]),
+    );
+    test_simd_op(
+        F64x2Eq,
+        &[],
+        [f64x2([f64::NAN, 1.5]), f64x2([f64::NAN, 1.5])],
+        i64x2([0, -1]),
+    );
+}
+
+#[test]
+fn test_lanes() {
+    test_simd_op(
+        I8x16ExtractLaneS,
+        &[15],
+        [i8x16([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -2])],
+        Value::I32(-2),
+    );
+    test_simd_op(
+        I8x16ExtractLaneU,
+        &[15],
+        [i8x16([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -2])],
+        Value::I32(254),
+    );
+    test_simd_op(
+        F32x4ReplaceLane,
+        &[2],
+        [f32x4([1.0, 2.0, 3.0, 4.0]), Value::F32(-3.0)],
+        f32x4([1.0, 2.0, -3.0, 4.0]),
+    );
+    test_simd_op(I64x2Splat, &[], [Value::I64(-5)], i64x2([-5, -5]));
+    test_simd_op(
+        I8x16Shuffle,
+        &[0, 16, 1, 17, 2, 18, 3, 19, 4, 20, 5, 21, 6, 22, 7, 23],
+        [u8x16([1; 16]), u8x16([2; 16])],
+        u8x16([1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2]),
+    );
+    test_simd_op(
+        I8x16Swizzle,
+        &[],
+        [
+            u8x16([
+                10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+            ]),
+            u8x16([15, 0, 16, 255, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]),
+        ],
+        u8x16([25, 10, 0, 0, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11, 11]),
+    );
+}
+
+#[test]
+fn test_bitwise_and_tests() {
+    let a = Value::V128(0xff00_ff00);
+    let b = Value::V128(0x0ff0_0ff0);
+    test_simd_op(V128Andnot, &[], [a, b], Value::V128(0xf000_f000));
+    test_simd_op(
+        V128Bitselect,
+        &[],
+        [Value::V128(u128::MAX), Value::V128(0), Value::V128(0xffff)],
+        Value::V128(0xffff),
+    );
+    test_simd_op(V128AnyTrue, &[], [Value::V128(1 << 127)], Value::I32(1));
+    test_simd_op(I32x4AllTrue, &[], [i32x4([1, 2, 3, 0])], Value::I32(0));
+    test_simd_op(
+        I16x8Bitmask,
+        &[],
+        [i16x8([-1, 0, -1, 0, 0, 0, 0, i16::MIN])],
+        Value::I32(0b1000_0101),
+    );
+}
+
+#[test]
+fn test_conversions() {
+    test_simd_op(
+        I8x16NarrowI16x8S,
+        &[],
+        [
+            i16x8([300, -300, 1, -1, 0, 0, 0, 0]),
+            i16x8([0, 0, 0, 0, 0, 0, 0, 127]),
+        ],
+        i8x16([127, -128, 1, -1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 127]),
+    );
+    test_simd_op(
+        I8x16NarrowI16x8U,
+        &[],
+        [i16x8([300, -300, 1, -1, 0, 0, 0, 0]), i16x8([0; 8])],
+        u8x16([255, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+    );
+    test_simd_op(
+        I32x4ExtendHighI16x8S,
+        &[],
+        [i16x8([0, 0, 0, 0, -1, 2, -3, 4])],
+        i32x4([-1, 2, -3, 4]),
+    );
+    test_simd_op(
+        I64x2ExtmulLowI32x4U,
+        &[],
+        [i32x4([-1, 2, 0, 0]), i32x4([2, 3, 0, 0])],
+        i64x2([0x1_ffff_fffe, 6]),
+    );
+    test_simd_op(
+        I16x8ExtaddPairwiseI8x16S,
+        &[],
+        [i8x16([
+            127, 127, -128, -128, 1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0,
+        ])],
+        i16x8([254, -256, 3, 7, 0, 0, 0, 0]),
+    );
+    test_simd_op(
+        I32x4TruncSatF32x4S,
+        &[],
+        [f32x4([f32::NAN, 1e10, -1e10, -2.7])],
+        i32x4([0, i32::MAX, i32::MIN, -2]),
+    );
+    test_simd_op(
+        I32x4TruncSatF64x2UZero,
+        &[],
+        [f64x2([-1.0, 3.9])],
+        i32x4([0, 3, 0, 0]),
+    );
+    test_simd_op(
+        F64x2ConvertLowI32x4U,
+        &[],
+        [i32x4([-1, 7, 100, 100])],
+        f64x2([u32::MAX as f64, 7.0]),
+    );
+    test_simd_op(
+        F32x4DemoteF64x2Zero,
+        &[],
+        [f64x2([1.5, -0.25])],
+        f32x4([1.5, -0.25, 0.0, 0.0]),
+    );
+}
+
+#[test]
+fn test_floats() {
+    test_simd_op(
+        F32x4Add,
+        &[],
+        [f32x4([1.0, 2.0, 3.0, 4.0]), f32x4([0.5; 4])],
+        f32x4([1.5, 2.5, 3.5, 4.5]),
+    );
+    test_simd_op(
+        F32x4Min,
+        &[],
+        [
+            f32x4([0.0, -0.0, 1.0, f32::NAN]),
+            f32x4([-0.0, 0.0, 2.0, 1.0]),
+        ],
+        f32x4([-0.0, -0.0, 1.0, f32::NAN]),
+    );
+    test_simd_op(
+        F64x2Max,
+        &[],
+        [f64x2([0.0, -0.0]), f64x2([-0.0, 0.0])],
+        f64x2([0.0, 0.0]),
+    );
+    // pmin is `b < a ? b : a`, so it doesn't propagate NaN from b
+    test_simd_op(
+        F32x4Pmin,
+        &[],
+        [
+            f32x4([1.0, 1.0, 1.0, 1.0]),
+            f32x4([f32::NAN, 0.0, 2.0, 1.0]),
+        ],
+        f32x4([1.0, 0.0, 1.0, 1.0]),
+    );
+    test_simd_op(F64x2Nearest, &[], [f64x2([2.5, -3.5])], f64x2([2.0, -4.0]));
+    test_simd_op(
+        F32x4Neg,
+        &[],
+        [f32x4([0.0, -1.0, 2.0, f32::INFINITY])],
+        f32x4([-0.0, 1.0, -2.0, f32::NEG_INFINITY]),
+    );
+}
+
+#[test]
+fn test_memory() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    module.memory = MemorySection::new(&arena, MemorySection::PAGE_SIZE);
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: Some(ValueType::V128),
+    };
+    create_exported_function_no_locals(&mut module, "test", signature, |buf| {
+        // store a vector at 0x100, with an offset immediate
+        const_value(buf, Value::I32(0xf0));
+        const_value(buf, i32x4([1, 2, 3, 4]));
+        V128Store.serialize(buf);
+        buf.extend_from_slice(&[4, 0x10]);
+
+        // overwrite lane 2 in memory with the lane 0 of another vector
+        const_value(buf, Value::I32(0x108));
+        const_value(buf, i32x4([-3, 0, 0, 0]));
+        V128Store32Lane.serialize(buf);
+        buf.extend_from_slice(&[2, 0, 0]);
+
+        // load lanes 2 and 3, and widen them to 64 bits
+        const_value(buf, Value::I32(0x108));
+        V128Load32x2S.serialize(buf);
+        buf.extend_from_slice(&[3, 0]);
+
+        buf.push(OpCode::END as u8);
+    });
+
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
+    let result = inst.call_export("test", []);
+    assert_eq!(result, Ok(Some(i64x2([-3, 4]))));
+    assert_eq!(
+        &inst.memory[0x100..][..16],
+        &[1, 0, 0, 0, 2, 0, 0, 0, 0xfd, 0xff, 0xff, 0xff, 4, 0, 0, 0]
+    );
+}
+
+#[test]
+fn test_loads() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    module.memory = MemorySection::new(&arena, MemorySection::PAGE_SIZE);
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: Some(ValueType::V128),
+    };
+    create_exported_function_no_locals(&mut module, "splat", signature, |buf| {
+        const_value(buf, Value::I32(0x100));
+        V128Load16Splat.serialize(buf);
+        buf.extend_from_slice(&[1, 0]);
+        buf.push(OpCode::END as u8);
+    });
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: Some(ValueType::V128),
+    };
+    create_exported_function_no_locals(&mut module, "load_lane", signature, |buf| {
+        const_value(buf, Value::I32(0x100));
+        const_value(buf, i64x2([-1, -1]));
+        V128Load8Lane.serialize(buf);
+        buf.extend_from_slice(&[0, 0, 3]);
+        buf.push(OpCode::END as u8);
+    });
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: Some(ValueType::V128),
+    };
+    create_exported_function_no_locals(&mut module, "out_of_bounds", signature, |buf| {
+        // The address is in memory, but the end of the vector isn't
+        const_value(buf, Value::I32(MemorySection::PAGE_SIZE as i32 - 8));
+        V128Load.serialize(buf);
+        buf.extend_from_slice(&[4, 0]);
+        buf.push(OpCode::END as u8);
+    });
+
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
+    inst.memory[0x100..][..2].copy_from_slice(&0x1234u16.to_le_bytes());
+
+    assert_eq!(inst.call_export("splat", []), Ok(Some(i16x8([0x1234; 8]))));
+
+    let mut expected = [-1i8; 16];
+    expected[3] = 0x34;
+    assert_eq!(inst.call_export("load_lane", []), Ok(Some(i8x16(expected))));
+
+    let trap = inst.call_export("out_of_bounds", []).unwrap_err();
+    assert!(trap.message.contains("access memory"), "{trap}");
+}
+
+#[test]
+fn test_skip_simd_instructions() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "test", signature, |buf| {
+        // the `if` branch isn't taken, so its instructions and their immediates are skipped
+        const_value(buf, Value::I32(0));
+        buf.push(OpCode::IF as u8);
+        buf.push(ValueType::I32 as u8);
+        // 0x0b is the byte for `end`, in the immediates of both of these
+        const_value(buf, Value::V128(0x0b0b_0b0b));
+        I8x16Shuffle.serialize(buf);
+        buf.extend_from_slice(&[0x0b; 16]);
+        // a two-byte instruction number
+        I32x4Add.serialize(buf);
+        I8x16ExtractLaneU.serialize(buf);
+        buf.push(0x0b);
+        buf.push(OpCode::ELSE as u8);
+        const_value(buf, Value::I32(7));
+        buf.push(OpCode::END as u8);
+        buf.push(OpCode::END as u8);
+    });
+
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
+    assert_eq!(inst.call_export("test", []), Ok(Some(Value::I32(7))));
+}
+
+#[test]
+fn test_v128_locals() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    let signature = Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_with_locals(
+        &mut module,
+        "test",
+        signature,
+        &[(1, ValueType::V128)],
+        |buf| {
+            // locals start at zero
+            buf.push(OpCode::GETLOCAL as u8);
+            buf.push(0);
+            V128AnyTrue.serialize(buf);
+            buf.push(OpCode::END as u8);
+        },
+    );
+
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        false,
+        Limits::default(),
+    )
+    .unwrap();
+    assert_eq!(inst.call_export("test", []), Ok(Some(Value::I32(0))));
+}
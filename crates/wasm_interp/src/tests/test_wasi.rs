@@ -0,0 +1,360 @@
+#![cfg(test)]
+
+use crate::wasi::Errno;
+use crate::WasiDispatcher;
+use roc_wasm_module::Value;
+use std::fs;
+use std::path::PathBuf;
+
+/// The first file descriptor after stdin, stdout and stderr
+const PREOPEN_FD: i32 = 3;
+
+const OFLAGS_CREAT: i32 = 1;
+const OFLAGS_DIRECTORY: i32 = 2;
+const OFLAGS_TRUNC: i32 = 8;
+const RIGHTS_FD_READ: i64 = 1 << 1;
+const RIGHTS_FD_WRITE: i64 = 1 << 6;
+
+// Where things go in the test's memory
+const PTR_PATH: usize = 0x100;
+const PTR_PATH2: usize = 0x200;
+const PTR_IOV: usize = 0x300;
+const PTR_OUT: usize = 0x400;
+const PTR_DATA: usize = 0x1000;
+
+/// A directory to preopen, which is deleted at the end of the test
+struct TestDir(PathBuf);
+
+impl TestDir {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("roc_wasm_interp_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TestDir(path)
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn setup(dir: &TestDir) -> (WasiDispatcher<'static>, Vec<u8>) {
+    let mut wasi = WasiDispatcher::default();
+    wasi.preopen_dir(&dir.0, "/data").unwrap();
+    (wasi, vec![0; 0x2000])
+}
+
+fn call(wasi: &mut WasiDispatcher, name: &str, arguments: &[Value], memory: &mut [u8]) -> i32 {
+    wasi.dispatch(name, arguments, memory)
+        .unwrap()
+        .expect_i32()
+        .unwrap()
+}
+
+fn write_path(memory: &mut [u8], ptr: usize, path: &str) -> [Value; 2] {
+    memory[ptr..][..path.len()].copy_from_slice(path.as_bytes());
+    [Value::I32(ptr as i32), Value::I32(path.len() as i32)]
+}
+
+fn read_u32(memory: &[u8], ptr: usize) -> u32 {
+    u32::from_le_bytes(memory[ptr..][..4].try_into().unwrap())
+}
+
+fn path_open(
+    wasi: &mut WasiDispatcher,
+    memory: &mut [u8],
+    path: &str,
+    oflags: i32,
+    rights: i64,
+) -> Result<i32, i32> {
+    let [ptr, len] = write_path(memory, PTR_PATH, path);
+    let arguments = [
+        Value::I32(PREOPEN_FD),
+        Value::I32(0),
+        ptr,
+        len,
+        Value::I32(oflags),
+        Value::I64(rights),
+        Value::I64(rights),
+        Value::I32(0),
+        Value::I32(PTR_OUT as i32),
+    ];
+    match call(wasi, "path_open", &arguments, memory) {
+        0 => Ok(read_u32(memory, PTR_OUT) as i32),
+        errno => Err(errno),
+    }
+}
+
+/// Read or write `len` bytes at PTR_DATA, returning how many bytes it did
+fn read_or_write(
+    wasi: &mut WasiDispatcher,
+    memory: &mut [u8],
+    name: &str,
+    fd: i32,
+    len: usize,
+) -> usize {
+    memory[PTR_IOV..][..4].copy_from_slice(&(PTR_DATA as u32).to_le_bytes());
+    memory[PTR_IOV + 4..][..4].copy_from_slice(&(len as u32).to_le_bytes());
+    let arguments = [
+        Value::I32(fd),
+        Value::I32(PTR_IOV as i32),
+        Value::I32(1),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(call(wasi, name, &arguments, memory), Errno::Success as i32);
+    read_u32(memory, PTR_OUT) as usize
+}
+
+fn call_with_path(wasi: &mut WasiDispatcher, memory: &mut [u8], name: &str, path: &str) -> i32 {
+    let [ptr, len] = write_path(memory, PTR_PATH, path);
+    call(wasi, name, &[Value::I32(PREOPEN_FD), ptr, len], memory)
+}
+
+#[test]
+fn test_prestat() {
+    let dir = TestDir::new("prestat");
+    let (mut wasi, mut memory) = setup(&dir);
+
+    let arguments = [Value::I32(PREOPEN_FD), Value::I32(PTR_OUT as i32)];
+    assert_eq!(
+        call(&mut wasi, "fd_prestat_get", &arguments, &mut memory),
+        0
+    );
+    assert_eq!(read_u32(&memory, PTR_OUT), 0);
+    assert_eq!(read_u32(&memory, PTR_OUT + 4), 5);
+
+    let arguments = [
+        Value::I32(PREOPEN_FD),
+        Value::I32(PTR_PATH as i32),
+        Value::I32(5),
+    ];
+    assert_eq!(
+        call(&mut wasi, "fd_prestat_dir_name", &arguments, &mut memory),
+        0
+    );
+    assert_eq!(&memory[PTR_PATH..][..5], b"/data");
+
+    // stdout isn't a preopened directory
+    let arguments = [Value::I32(1), Value::I32(PTR_OUT as i32)];
+    assert_eq!(
+        call(&mut wasi, "fd_prestat_get", &arguments, &mut memory),
+        Errno::Badf as i32
+    );
+}
+
+#[test]
+fn test_write_then_read_file() {
+    let dir = TestDir::new("write_then_read");
+    let (mut wasi, mut memory) = setup(&dir);
+
+    let fd = path_open(
+        &mut wasi,
+        &mut memory,
+        "hello.txt",
+        OFLAGS_CREAT | OFLAGS_TRUNC,
+        RIGHTS_FD_WRITE,
+    )
+    .unwrap();
+    memory[PTR_DATA..][..13].copy_from_slice(b"Hello, World!");
+    assert_eq!(
+        read_or_write(&mut wasi, &mut memory, "fd_write", fd, 13),
+        13
+    );
+    assert_eq!(
+        call(&mut wasi, "fd_close", &[Value::I32(fd)], &mut memory),
+        0
+    );
+    assert_eq!(fs::read(dir.0.join("hello.txt")).unwrap(), b"Hello, World!");
+
+    // The closed file descriptor is given out again
+    let fd2 = path_open(&mut wasi, &mut memory, "hello.txt", 0, RIGHTS_FD_READ).unwrap();
+    assert_eq!(fd2, fd);
+
+    // Skip "Hello, "
+    let arguments = [
+        Value::I32(fd),
+        Value::I64(7),
+        Value::I32(0),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(call(&mut wasi, "fd_seek", &arguments, &mut memory), 0);
+    assert_eq!(read_u32(&memory, PTR_OUT), 7);
+
+    memory[PTR_DATA..][..13].fill(0);
+    assert_eq!(read_or_write(&mut wasi, &mut memory, "fd_read", fd, 100), 6);
+    assert_eq!(&memory[PTR_DATA..][..6], b"World!");
+    assert_eq!(read_or_write(&mut wasi, &mut memory, "fd_read", fd, 100), 0);
+
+    let arguments = [Value::I32(fd), Value::I32(PTR_OUT as i32)];
+    assert_eq!(
+        call(&mut wasi, "fd_filestat_get", &arguments, &mut memory),
+        0
+    );
+    assert_eq!(memory[PTR_OUT + 16], 4); // regular file
+    assert_eq!(read_u32(&memory, PTR_OUT + 32), 13);
+
+    assert_eq!(
+        call(&mut wasi, "fd_close", &[Value::I32(fd)], &mut memory),
+        0
+    );
+    assert_eq!(
+        call(&mut wasi, "fd_close", &[Value::I32(fd)], &mut memory),
+        Errno::Badf as i32
+    );
+}
+
+#[test]
+fn test_directories() {
+    let dir = TestDir::new("directories");
+    let (mut wasi, mut memory) = setup(&dir);
+
+    assert_eq!(
+        call_with_path(&mut wasi, &mut memory, "path_create_directory", "sub"),
+        0
+    );
+    assert!(dir.0.join("sub").is_dir());
+    assert_eq!(
+        call_with_path(&mut wasi, &mut memory, "path_create_directory", "sub"),
+        Errno::Exist as i32
+    );
+
+    fs::write(dir.0.join("sub/a.txt"), "a").unwrap();
+    assert_eq!(
+        call_with_path(&mut wasi, &mut memory, "path_remove_directory", "sub"),
+        Errno::Notempty as i32
+    );
+
+    let [old_ptr, old_len] = write_path(&mut memory, PTR_PATH, "sub/a.txt");
+    let [new_ptr, new_len] = write_path(&mut memory, PTR_PATH2, "sub/b.txt");
+    let arguments = [
+        Value::I32(PREOPEN_FD),
+        old_ptr,
+        old_len,
+        Value::I32(PREOPEN_FD),
+        new_ptr,
+        new_len,
+    ];
+    assert_eq!(call(&mut wasi, "path_rename", &arguments, &mut memory), 0);
+    assert!(dir.0.join("sub/b.txt").is_file());
+
+    // List the directory
+    let fd = path_open(&mut wasi, &mut memory, "sub", OFLAGS_DIRECTORY, 0).unwrap();
+    let arguments = [
+        Value::I32(fd),
+        Value::I32(PTR_DATA as i32),
+        Value::I32(0x100),
+        Value::I64(0),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(call(&mut wasi, "fd_readdir", &arguments, &mut memory), 0);
+    let bufused = read_u32(&memory, PTR_OUT) as usize;
+    let mut names = Vec::new();
+    let mut ptr = PTR_DATA;
+    while ptr < PTR_DATA + bufused {
+        let namlen = read_u32(&memory, ptr + 16) as usize;
+        names.push(String::from_utf8(memory[ptr + 24..][..namlen].to_vec()).unwrap());
+        ptr += 24 + namlen;
+    }
+    assert_eq!(names, [".", "..", "b.txt"]);
+
+    assert_eq!(
+        call_with_path(&mut wasi, &mut memory, "path_unlink_file", "sub"),
+        Errno::Isdir as i32
+    );
+    assert_eq!(
+        call_with_path(&mut wasi, &mut memory, "path_unlink_file", "sub/b.txt"),
+        0
+    );
+    assert_eq!(
+        call_with_path(&mut wasi, &mut memory, "path_remove_directory", "sub"),
+        0
+    );
+    assert!(!dir.0.join("sub").exists());
+}
+
+#[test]
+fn test_sandbox() {
+    let dir = TestDir::new("sandbox");
+    let (mut wasi, mut memory) = setup(&dir);
+    fs::create_dir(dir.0.join("sub")).unwrap();
+
+    let notcapable = Err(Errno::Notcapable as i32);
+    assert_eq!(
+        path_open(
+            &mut wasi,
+            &mut memory,
+            "../x",
+            OFLAGS_CREAT,
+            RIGHTS_FD_WRITE
+        ),
+        notcapable
+    );
+    assert_eq!(
+        path_open(&mut wasi, &mut memory, "sub/../../x", 0, RIGHTS_FD_READ),
+        notcapable
+    );
+    assert_eq!(
+        path_open(&mut wasi, &mut memory, "/etc/passwd", 0, RIGHTS_FD_READ),
+        notcapable
+    );
+    assert!(path_open(&mut wasi, &mut memory, "sub/../sub", 0, 0).is_ok());
+
+    #[cfg(unix)]
+    {
+        let outside = TestDir::new("sandbox_outside");
+        std::os::unix::fs::symlink(&outside.0, dir.0.join("link")).unwrap();
+        assert_eq!(
+            path_open(
+                &mut wasi,
+                &mut memory,
+                "link/x",
+                OFLAGS_CREAT,
+                RIGHTS_FD_WRITE
+            ),
+            notcapable
+        );
+        assert!(!outside.0.join("x").exists());
+    }
+}
+
+#[test]
+fn test_unsupported() {
+    let dir = TestDir::new("unsupported");
+    let (mut wasi, mut memory) = setup(&dir);
+    let notsup = Errno::Notsup as i32;
+
+    // stdin is neither a tty nor a file
+    let arguments = [Value::I32(0), Value::I32(PTR_OUT as i32)];
+    assert_eq!(
+        call(&mut wasi, "fd_fdstat_get", &arguments, &mut memory),
+        notsup
+    );
+
+    let arguments = [Value::I32(PREOPEN_FD), Value::I32(0)];
+    assert_eq!(
+        call(&mut wasi, "fd_fdstat_set_flags", &arguments, &mut memory),
+        notsup
+    );
+
+    for name in ["path_link", "path_readlink", "path_symlink"] {
+        assert_eq!(call_with_path(&mut wasi, &mut memory, name, "x"), notsup);
+    }
+}
+
+#[test]
+fn test_split_dir_arg() {
+    use crate::wasi::split_dir_arg;
+
+    assert_eq!(split_dir_arg("./test-data:/data"), ("./test-data", "/data"));
+    assert_eq!(split_dir_arg("./test-data"), ("./test-data", "./test-data"));
+    assert_eq!(split_dir_arg("data:/a:b"), ("data", "/a:b"));
+
+    #[cfg(windows)]
+    {
+        assert_eq!(split_dir_arg(r"C:\data:/data"), (r"C:\data", "/data"));
+        assert_eq!(split_dir_arg(r"C:\data"), (r"C:\data", r"C:\data"));
+    }
+}
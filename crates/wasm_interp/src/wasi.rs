@@ -3,6 +3,10 @@ use roc_wasm_module::Value;
 use std::io::{self, Read, StderrLock, StdoutLock, Write};
 use std::process::exit;
 
+mod fs;
+
+pub use fs::{split_dir_arg, HostDirectory, HostFile};
+
 pub const MODULE_NAME: &str = "wasi_snapshot_preview1";
 
 pub struct WasiDispatcher<'a> {
@@ -22,12 +26,17 @@ pub enum WasiFile {
     WriteOnly(Vec<u8>),
     ReadWrite(Vec<u8>),
     HostSystemFile,
+    HostFile(HostFile),
+    HostDirectory(HostDirectory),
+    /// A file descriptor that was closed, and can be given out again
+    Closed,
 }
 
 enum WriteLock<'a> {
     StdOut(StdoutLock<'a>),
     Stderr(StderrLock<'a>),
     RegularFile(&'a mut Vec<u8>),
+    HostFile(&'a std::fs::File),
 }
 
 /// Implementation of WASI syscalls
@@ -109,10 +118,13 @@ impl<'a> WasiDispatcher<'a> {
             }
             "clock_res_get" => success_code, // this dummy implementation seems to be good enough for some functions
             "clock_time_get" => success_code,
-            "fd_advise" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_allocate" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_close" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_datasync" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_advise" => {
+                // The advice is only a hint, so it's fine to ignore it
+                success_code
+            }
+            "fd_allocate" => errno_value(self.fd_allocate(arguments)),
+            "fd_close" => errno_value(self.fd_close(arguments)),
+            "fd_datasync" => errno_value(self.fd_datasync(arguments)),
             "fd_fdstat_get" => {
                 // (i32, i32) -> i32
 
@@ -139,37 +151,26 @@ impl<'a> WasiDispatcher<'a> {
                             *b = 0;
                         }
                     }
-                    _ => todo!("WASI {}({:?})", function_name, arguments),
+                    0 => return Some(Value::I32(Errno::Notsup as i32)),
+                    _ => {
+                        return Some(Value::I32(self.host_fdstat(fd, stat_mut_ptr, memory) as i32))
+                    }
                 }
 
                 success_code
             }
-            "fd_fdstat_set_flags" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_fdstat_set_rights" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_size" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_pread" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_prestat_get" => {
-                // The preopened file descriptor to query
-                let fd = arguments[0].expect_i32().unwrap() as usize;
-                // ptr_buf: Where the metadata will be written
-                //  preopen type: 4 bytes, where 0=dir is the only one supported, it seems
-                //  preopen name length: 4 bytes
-                let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
-                memory[ptr_buf..][..8].copy_from_slice(&0u64.to_le_bytes());
-                if fd < self.files.len() {
-                    success_code
-                } else {
-                    println!("WASI warning: file descriptor {fd} does not exist");
-                    Some(Value::I32(Errno::Badf as i32))
-                }
+            "fd_fdstat_set_flags" | "fd_fdstat_set_rights" => {
+                Some(Value::I32(Errno::Notsup as i32))
             }
-            "fd_prestat_dir_name" => {
-                // We're not giving names to any of our files so just return success
-                success_code
-            }
-            "fd_pwrite" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_filestat_get" => errno_value(self.fd_filestat_get(arguments, memory)),
+            "fd_filestat_set_size" => errno_value(self.fd_filestat_set_size(arguments)),
+            "fd_filestat_set_times" => errno_value(self.fd_filestat_set_times(arguments)),
+            "fd_pread" => errno_value(self.fd_pread(arguments, memory)),
+            "fd_prestat_get" => Some(Value::I32(self.fd_prestat_get(arguments, memory) as i32)),
+            "fd_prestat_dir_name" => Some(Value::I32(
+                self.fd_prestat_dir_name(arguments, memory) as i32
+            )),
+            "fd_pwrite" => errno_value(self.fd_pwrite(arguments, memory)),
             "fd_read" => {
                 use WasiFile::*;
 
@@ -218,17 +219,24 @@ impl<'a> WasiDispatcher<'a> {
                             }
                         }
                     }
+                    Some(HostFile(_)) => {
+                        match self.host_read(fd, ptr_iovs, iovs_len as usize, memory) {
+                            Ok(n) => n_read = n,
+                            Err(errno) => return Some(Value::I32(errno as i32)),
+                        }
+                    }
+                    Some(HostDirectory(_)) => return Some(Value::I32(Errno::Isdir as i32)),
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
                 memory[ptr_nread..][..4].copy_from_slice(&(n_read as u32).to_le_bytes());
                 success_code
             }
-            "fd_readdir" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_renumber" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_seek" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_sync" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_tell" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_readdir" => errno_value(self.fd_readdir(arguments, memory)),
+            "fd_renumber" => errno_value(self.fd_renumber(arguments)),
+            "fd_seek" => errno_value(self.fd_seek(arguments, memory)),
+            "fd_sync" => errno_value(self.fd_sync(arguments)),
+            "fd_tell" => errno_value(self.fd_tell(arguments, memory)),
             "fd_write" => {
                 use WasiFile::*;
 
@@ -252,6 +260,8 @@ impl<'a> WasiDispatcher<'a> {
                     Some(WriteOnly(content) | ReadWrite(content)) => {
                         WriteLock::RegularFile(content)
                     }
+                    Some(HostFile(file)) => WriteLock::HostFile(&file.file),
+                    Some(HostDirectory(_)) => return Some(Value::I32(Errno::Isdir as i32)),
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
@@ -280,6 +290,7 @@ impl<'a> WasiDispatcher<'a> {
                         WriteLock::StdOut(stdout) => stdout.write_all(bytes),
                         WriteLock::Stderr(stderr) => stderr.write_all(bytes),
                         WriteLock::RegularFile(content) => content.write_all(bytes),
+                        WriteLock::HostFile(file) => file.write_all(bytes),
                     };
                     if write_result.is_err() {
                         break;
@@ -300,16 +311,19 @@ impl<'a> WasiDispatcher<'a> {
                     Err(_) => Some(Value::I32(Errno::Io as i32)),
                 }
             }
-            "path_create_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_link" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_open" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_readlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_remove_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_rename" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_symlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_unlink_file" => todo!("WASI {}({:?})", function_name, arguments),
+            "path_create_directory" => errno_value(self.path_create_directory(arguments, memory)),
+            "path_filestat_get" => errno_value(self.path_filestat_get(arguments, memory)),
+            "path_filestat_set_times" => {
+                errno_value(self.path_filestat_set_times(arguments, memory))
+            }
+            // Links could point outside of the preopened directories, so they aren't supported
+            "path_link" | "path_readlink" | "path_symlink" => {
+                Some(Value::I32(Errno::Notsup as i32))
+            }
+            "path_open" => errno_value(self.path_open(arguments, memory)),
+            "path_remove_directory" => errno_value(self.path_remove_directory(arguments, memory)),
+            "path_rename" => errno_value(self.path_rename(arguments, memory)),
+            "path_unlink_file" => errno_value(self.path_unlink_file(arguments, memory)),
             "poll_oneoff" => todo!("WASI {}({:?})", function_name, arguments),
             "proc_exit" => {
                 let exit_code = arguments[0].expect_i32().unwrap();
@@ -335,6 +349,11 @@ impl<'a> WasiDispatcher<'a> {
     }
 }
 
+fn errno_value(result: Result<(), Errno>) -> Option<Value> {
+    let errno = result.err().unwrap_or(Errno::Success);
+    Some(Value::I32(errno as i32))
+}
+
 fn read_u32(memory: &[u8], addr: usize) -> u32 {
     let mut bytes = [0; 4];
     bytes.copy_from_slice(&memory[addr..][..4]);
@@ -0,0 +1,810 @@
+//! WASI calls for files in directories of the host, which were preopened with `--dir`.
+//! The program can only see inside those directories. Paths which would lead outside of them,
+//! through `..`, an absolute path or a symlink, fail with `Errno::Notcapable`.
+use super::{read_u32, write_u32, Errno, WasiDispatcher, WasiFile};
+use roc_wasm_module::Value;
+use std::fs::{self, File, FileTimes, Metadata, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// https://github.com/WebAssembly/WASI/blob/main/legacy/preview1/docs.md
+const OFLAGS_CREAT: i32 = 1;
+const OFLAGS_DIRECTORY: i32 = 2;
+const OFLAGS_EXCL: i32 = 4;
+const OFLAGS_TRUNC: i32 = 8;
+
+const FDFLAGS_APPEND: i32 = 1;
+
+const RIGHTS_FD_READ: u64 = 1 << 1;
+const RIGHTS_FD_WRITE: u64 = 1 << 6;
+
+const LOOKUPFLAGS_SYMLINK_FOLLOW: i32 = 1;
+
+const FSTFLAGS_ATIM: i32 = 1;
+const FSTFLAGS_ATIM_NOW: i32 = 2;
+const FSTFLAGS_MTIM: i32 = 4;
+const FSTFLAGS_MTIM_NOW: i32 = 8;
+
+const WHENCE_SET: i32 = 0;
+const WHENCE_CUR: i32 = 1;
+const WHENCE_END: i32 = 2;
+
+const FILETYPE_UNKNOWN: u8 = 0;
+const FILETYPE_CHARACTER_DEVICE: u8 = 2;
+const FILETYPE_DIRECTORY: u8 = 3;
+const FILETYPE_REGULAR_FILE: u8 = 4;
+const FILETYPE_SYMBOLIC_LINK: u8 = 7;
+
+/// A directory of the host that the program has access to
+pub struct HostDirectory {
+    /// Where the directory is on the host, with symlinks resolved
+    pub host_path: PathBuf,
+    /// The preopened directory this one is inside of. Paths can't lead outside of it.
+    pub root: PathBuf,
+    /// The name the program knows a preopened directory by.
+    /// Directories opened by the program itself don't have one.
+    pub guest_path: Option<String>,
+}
+
+/// A file of the host that the program opened
+pub struct HostFile {
+    pub file: File,
+    /// The fdflags it was opened with
+    pub flags: i32,
+}
+
+impl From<io::Error> for Errno {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => Errno::Noent,
+            io::ErrorKind::PermissionDenied => Errno::Access,
+            io::ErrorKind::AlreadyExists => Errno::Exist,
+            io::ErrorKind::InvalidInput => Errno::Inval,
+            io::ErrorKind::Unsupported => Errno::Notsup,
+            _ => Errno::Io,
+        }
+    }
+}
+
+/// Splits an argument like `./test-data:/data` into the directory of the host and the name the
+/// program sees it under, which may contain ':' too. On Windows, the host directory may start with
+/// a drive like `C:`. Without a guest name, the program sees the directory under its host name.
+pub fn split_dir_arg(arg: &str) -> (&str, &str) {
+    let bytes = arg.as_bytes();
+    let drive_len = match bytes {
+        [letter, b':', ..] if cfg!(windows) && letter.is_ascii_alphabetic() => 2,
+        _ => 0,
+    };
+
+    match arg[drive_len..].find(':') {
+        Some(index) => {
+            let split = drive_len + index;
+            (&arg[..split], &arg[split + 1..])
+        }
+        None => (arg, arg),
+    }
+}
+
+impl<'a> WasiDispatcher<'a> {
+    /// Give the program access to a directory of the host, under the name `guest_path`
+    pub fn preopen_dir(&mut self, host_path: &Path, guest_path: &str) -> io::Result<()> {
+        let root = host_path.canonicalize()?;
+        if !root.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a directory", host_path.display()),
+            ));
+        }
+
+        self.files.push(WasiFile::HostDirectory(HostDirectory {
+            host_path: root.clone(),
+            root,
+            guest_path: Some(guest_path.to_string()),
+        }));
+
+        Ok(())
+    }
+
+    pub(super) fn fd_prestat_get(&self, arguments: &[Value], memory: &mut [u8]) -> Errno {
+        // The preopened file descriptor to query
+        let fd = arg_usize(arguments, 0);
+        // ptr_buf: Where the metadata will be written
+        //  preopen type: 4 bytes, where 0=dir is the only one supported
+        //  preopen name length: 4 bytes
+        let ptr_buf = arg_usize(arguments, 1);
+
+        match self.files.get(fd) {
+            Some(WasiFile::HostDirectory(HostDirectory {
+                guest_path: Some(name),
+                ..
+            })) => {
+                write_u32(memory, ptr_buf, 0);
+                write_u32(memory, ptr_buf + 4, name.len() as u32);
+                Errno::Success
+            }
+            // Apps look for preopened directories by trying every fd until this fails
+            _ => Errno::Badf,
+        }
+    }
+
+    pub(super) fn fd_prestat_dir_name(&self, arguments: &[Value], memory: &mut [u8]) -> Errno {
+        let fd = arg_usize(arguments, 0);
+        let ptr_path = arg_usize(arguments, 1);
+        let path_len = arg_usize(arguments, 2);
+
+        match self.files.get(fd) {
+            Some(WasiFile::HostDirectory(HostDirectory {
+                guest_path: Some(name),
+                ..
+            })) => {
+                let len = path_len.min(name.len());
+                memory[ptr_path..][..len].copy_from_slice(&name.as_bytes()[..len]);
+                Errno::Success
+            }
+            _ => Errno::Badf,
+        }
+    }
+
+    /// The fdstat of files and directories of the host.
+    /// Rights are not enforced, so we give them all.
+    pub(super) fn host_fdstat(&self, fd: usize, ptr_stat: usize, memory: &mut [u8]) -> Errno {
+        let (filetype, flags) = match self.files.get(fd) {
+            Some(WasiFile::HostFile(file)) => (FILETYPE_REGULAR_FILE, file.flags),
+            Some(WasiFile::HostDirectory(_)) => (FILETYPE_DIRECTORY, 0),
+            _ => return Errno::Badf,
+        };
+
+        memory[ptr_stat..][..24].fill(0);
+        memory[ptr_stat] = filetype;
+        memory[ptr_stat + 2..][..2].copy_from_slice(&(flags as u16).to_le_bytes());
+        write_u64(memory, ptr_stat + 8, u64::MAX);
+        write_u64(memory, ptr_stat + 16, u64::MAX);
+        Errno::Success
+    }
+
+    pub(super) fn path_open(
+        &mut self,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Result<(), Errno> {
+        let dirfd = arg_usize(arguments, 0);
+        // Symlinks are always followed, as long as they stay inside the preopened directory
+        let _lookupflags = arguments[1].expect_i32().unwrap();
+        let path = read_path(memory, arguments, 2)?;
+        let oflags = arguments[4].expect_i32().unwrap();
+        let rights_base = arguments[5].expect_i64().unwrap() as u64;
+        // arguments[6] are the rights inherited by files opened from this one. Rights aren't enforced.
+        let fdflags = arguments[7].expect_i32().unwrap();
+        // Out param: the new file descriptor
+        let ptr_fd = arg_usize(arguments, 8);
+
+        let (host_path, root) = self.resolve(dirfd, &path, true)?;
+
+        let create = oflags & OFLAGS_CREAT != 0;
+        let exclusive = oflags & OFLAGS_EXCL != 0;
+        let truncate = oflags & OFLAGS_TRUNC != 0;
+
+        let file = if host_path.is_dir() {
+            if create && exclusive {
+                return Err(Errno::Exist);
+            }
+            // wasi-libc asks for write rights even when opening directories with O_RDONLY,
+            // so only refuse to open them when the program explicitly wants to change them
+            if truncate {
+                return Err(Errno::Isdir);
+            }
+            WasiFile::HostDirectory(HostDirectory {
+                host_path,
+                root,
+                guest_path: None,
+            })
+        } else {
+            if oflags & OFLAGS_DIRECTORY != 0 {
+                return Err(if host_path.exists() {
+                    Errno::Notdir
+                } else {
+                    Errno::Noent
+                });
+            }
+
+            let append = fdflags & FDFLAGS_APPEND != 0;
+            let mut write = rights_base & RIGHTS_FD_WRITE != 0 || create || truncate;
+            let read = rights_base & RIGHTS_FD_READ != 0 || !(write || append);
+            if append {
+                write = false;
+            }
+
+            let file = OpenOptions::new()
+                .read(read)
+                .write(write)
+                .append(append)
+                .create(create && !exclusive)
+                .create_new(create && exclusive)
+                .truncate(truncate)
+                .open(&host_path)?;
+
+            WasiFile::HostFile(HostFile {
+                file,
+                flags: fdflags,
+            })
+        };
+
+        let fd = self.insert_file(file);
+        write_u32(memory, ptr_fd, fd as u32);
+        Ok(())
+    }
+
+    /// Put a new file in the lowest free file descriptor
+    fn insert_file(&mut self, file: WasiFile) -> usize {
+        match self
+            .files
+            .iter()
+            .position(|f| matches!(f, WasiFile::Closed))
+        {
+            Some(fd) => {
+                self.files[fd] = file;
+                fd
+            }
+            None => {
+                self.files.push(file);
+                self.files.len() - 1
+            }
+        }
+    }
+
+    pub(super) fn fd_close(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        match self.files.get_mut(fd) {
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(file) => {
+                *file = WasiFile::Closed;
+                Ok(())
+            }
+        }
+    }
+
+    pub(super) fn fd_renumber(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let from = arg_usize(arguments, 0);
+        let to = arg_usize(arguments, 1);
+        for fd in [from, to] {
+            if matches!(self.files.get(fd), None | Some(WasiFile::Closed)) {
+                return Err(Errno::Badf);
+            }
+        }
+        if from != to {
+            self.files[to] = std::mem::replace(&mut self.files[from], WasiFile::Closed);
+        }
+        Ok(())
+    }
+
+    pub(super) fn fd_seek(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let offset = arguments[1].expect_i64().unwrap();
+        let whence = arguments[2].expect_i32().unwrap();
+        // Out param: the new offset, from the start of the file
+        let ptr_newoffset = arg_usize(arguments, 3);
+
+        let seek_from = match whence {
+            WHENCE_SET => SeekFrom::Start(u64::try_from(offset).map_err(|_| Errno::Inval)?),
+            WHENCE_CUR => SeekFrom::Current(offset),
+            WHENCE_END => SeekFrom::End(offset),
+            _ => return Err(Errno::Inval),
+        };
+        let new_offset = self.host_file(fd)?.seek(seek_from)?;
+
+        write_u64(memory, ptr_newoffset, new_offset);
+        Ok(())
+    }
+
+    pub(super) fn fd_tell(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        // Out param: the current offset, from the start of the file
+        let ptr_offset = arg_usize(arguments, 1);
+
+        let offset = self.host_file(fd)?.stream_position()?;
+
+        write_u64(memory, ptr_offset, offset);
+        Ok(())
+    }
+
+    /// Read from a file of the host into the iovecs
+    pub(super) fn host_read(
+        &self,
+        fd: usize,
+        ptr_iovs: usize,
+        iovs_len: usize,
+        memory: &mut [u8],
+    ) -> Result<usize, Errno> {
+        let mut file = self.host_file(fd)?;
+        let mut n_read = 0;
+        for (iov_base, iov_len) in iovecs(memory, ptr_iovs, iovs_len) {
+            let n = file.read(&mut memory[iov_base..][..iov_len])?;
+            n_read += n;
+            if n < iov_len {
+                break;
+            }
+        }
+        Ok(n_read)
+    }
+
+    pub(super) fn fd_pread(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let ptr_iovs = arg_usize(arguments, 1);
+        let iovs_len = arg_usize(arguments, 2);
+        let offset = arguments[3].expect_i64().unwrap() as u64;
+        // Out param: number of bytes read
+        let ptr_nread = arg_usize(arguments, 4);
+
+        // pread doesn't move the file's offset, so put it back afterwards
+        let mut file = self.host_file(fd)?;
+        let position = file.stream_position()?;
+        file.seek(SeekFrom::Start(offset))?;
+        let result = self.host_read(fd, ptr_iovs, iovs_len, memory);
+        file.seek(SeekFrom::Start(position))?;
+
+        write_u32(memory, ptr_nread, result? as u32);
+        Ok(())
+    }
+
+    pub(super) fn fd_pwrite(
+        &mut self,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let ptr_iovs = arg_usize(arguments, 1);
+        let iovs_len = arg_usize(arguments, 2);
+        let offset = arguments[3].expect_i64().unwrap() as u64;
+        // Out param: number of bytes written
+        let ptr_nwritten = arg_usize(arguments, 4);
+
+        // pwrite doesn't move the file's offset, so put it back afterwards
+        let mut file = self.host_file(fd)?;
+        let position = file.stream_position()?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut n_written = 0;
+        let mut result = Ok(());
+        for (iov_base, iov_len) in iovecs(memory, ptr_iovs, iovs_len) {
+            result = file.write_all(&memory[iov_base..][..iov_len]);
+            if result.is_err() {
+                break;
+            }
+            n_written += iov_len;
+        }
+        file.seek(SeekFrom::Start(position))?;
+        result?;
+
+        write_u32(memory, ptr_nwritten, n_written as u32);
+        Ok(())
+    }
+
+    pub(super) fn fd_readdir(&self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let ptr_buf = arg_usize(arguments, 1);
+        let buf_len = arg_usize(arguments, 2);
+        // The index of the first entry to read. Every dirent has the cookie of the next one.
+        let cookie = arguments[3].expect_i64().unwrap() as usize;
+        // Out param: number of bytes written into the buffer.
+        // If it's the whole buffer, there may be more entries to read.
+        let ptr_bufused = arg_usize(arguments, 4);
+
+        let dir = match self.files.get(fd) {
+            Some(WasiFile::HostDirectory(dir)) => dir,
+            Some(WasiFile::Closed) | None => return Err(Errno::Badf),
+            Some(_) => return Err(Errno::Notdir),
+        };
+
+        // Sort the entries, so that cookies refer to the same ones every time we're called
+        let mut entries = vec![
+            (".".to_string(), fs::metadata(&dir.host_path)?),
+            ("..".to_string(), fs::metadata(&dir.host_path)?),
+        ];
+        let mut children = Vec::new();
+        for entry in fs::read_dir(&dir.host_path)? {
+            let entry = entry?;
+            let name = entry.file_name().into_string().map_err(|_| Errno::Ilseq)?;
+            children.push((name, entry.metadata()?));
+        }
+        children.sort_by(|(a, _), (b, _)| a.cmp(b));
+        entries.extend(children);
+
+        // struct dirent {
+        //     d_next: u64,    // The cookie of the next entry
+        //     d_ino: u64,     // The serial number of the file
+        //     d_namlen: u32,  // The length of the name, which follows the dirent
+        //     d_type: u8,     // The type of the file
+        // };
+        let mut bufused = 0;
+        for (index, (name, metadata)) in entries.iter().enumerate().skip(cookie) {
+            let mut dirent = [0; 24];
+            dirent[0..8].copy_from_slice(&(index as u64 + 1).to_le_bytes());
+            dirent[8..16].copy_from_slice(&inode(metadata).to_le_bytes());
+            dirent[16..20].copy_from_slice(&(name.len() as u32).to_le_bytes());
+            dirent[20] = filetype(metadata);
+
+            // Entries that don't fit are cut off, and the program calls us again with a bigger buffer
+            for bytes in [&dirent[..], name.as_bytes()] {
+                let len = bytes.len().min(buf_len - bufused);
+                memory[ptr_buf + bufused..][..len].copy_from_slice(&bytes[..len]);
+                bufused += len;
+            }
+            if bufused == buf_len {
+                break;
+            }
+        }
+
+        write_u32(memory, ptr_bufused, bufused as u32);
+        Ok(())
+    }
+
+    pub(super) fn fd_filestat_get(
+        &self,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let ptr_buf = arg_usize(arguments, 1);
+
+        match self.files.get(fd) {
+            Some(WasiFile::HostFile(file)) => {
+                write_filestat(memory, ptr_buf, &file.file.metadata()?)
+            }
+            Some(WasiFile::HostDirectory(dir)) => {
+                write_filestat(memory, ptr_buf, &fs::metadata(&dir.host_path)?)
+            }
+            Some(WasiFile::HostSystemFile) => {
+                memory[ptr_buf..][..64].fill(0);
+                memory[ptr_buf + 16] = FILETYPE_CHARACTER_DEVICE;
+            }
+            Some(
+                WasiFile::ReadOnly(content)
+                | WasiFile::WriteOnly(content)
+                | WasiFile::ReadWrite(content),
+            ) => {
+                memory[ptr_buf..][..64].fill(0);
+                memory[ptr_buf + 16] = FILETYPE_REGULAR_FILE;
+                write_u64(memory, ptr_buf + 24, 1);
+                write_u64(memory, ptr_buf + 32, content.len() as u64);
+            }
+            Some(WasiFile::Closed) | None => return Err(Errno::Badf),
+        }
+        Ok(())
+    }
+
+    pub(super) fn path_filestat_get(
+        &self,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let lookupflags = arguments[1].expect_i32().unwrap();
+        let path = read_path(memory, arguments, 2)?;
+        let ptr_buf = arg_usize(arguments, 4);
+
+        let follow = lookupflags & LOOKUPFLAGS_SYMLINK_FOLLOW != 0;
+        let (host_path, _) = self.resolve(fd, &path, follow)?;
+        let metadata = if follow {
+            fs::metadata(host_path)?
+        } else {
+            fs::symlink_metadata(host_path)?
+        };
+
+        write_filestat(memory, ptr_buf, &metadata);
+        Ok(())
+    }
+
+    pub(super) fn fd_filestat_set_size(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let size = arguments[1].expect_i64().unwrap() as u64;
+
+        self.host_file(fd)?.set_len(size)?;
+        Ok(())
+    }
+
+    pub(super) fn fd_allocate(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let offset = arguments[1].expect_i64().unwrap() as u64;
+        let len = arguments[2].expect_i64().unwrap() as u64;
+
+        // Files only ever grow here, like with posix_fallocate
+        let file = self.host_file(fd)?;
+        let end = offset.checked_add(len).ok_or(Errno::Fbig)?;
+        if file.metadata()?.len() < end {
+            file.set_len(end)?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn fd_filestat_set_times(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let times = file_times(&arguments[1..])?;
+
+        self.host_file(fd)?.set_times(times)?;
+        Ok(())
+    }
+
+    pub(super) fn path_filestat_set_times(
+        &mut self,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let _lookupflags = arguments[1].expect_i32().unwrap();
+        let path = read_path(memory, arguments, 2)?;
+        let times = file_times(&arguments[4..])?;
+
+        let (host_path, _) = self.resolve(fd, &path, true)?;
+        File::open(host_path)?.set_times(times)?;
+        Ok(())
+    }
+
+    pub(super) fn fd_sync(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        self.host_file(fd)?.sync_all()?;
+        Ok(())
+    }
+
+    pub(super) fn fd_datasync(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        self.host_file(fd)?.sync_data()?;
+        Ok(())
+    }
+
+    pub(super) fn path_create_directory(
+        &mut self,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let path = read_path(memory, arguments, 1)?;
+
+        let (host_path, _) = self.resolve(fd, &path, false)?;
+        fs::create_dir(host_path)?;
+        Ok(())
+    }
+
+    pub(super) fn path_remove_directory(
+        &mut self,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let path = read_path(memory, arguments, 1)?;
+
+        let (host_path, root) = self.resolve(fd, &path, false)?;
+        if host_path == root {
+            return Err(Errno::Notcapable);
+        }
+        if !fs::symlink_metadata(&host_path)?.is_dir() {
+            return Err(Errno::Notdir);
+        }
+        if fs::read_dir(&host_path)?.next().is_some() {
+            return Err(Errno::Notempty);
+        }
+        fs::remove_dir(host_path)?;
+        Ok(())
+    }
+
+    pub(super) fn path_unlink_file(
+        &mut self,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Result<(), Errno> {
+        let fd = arg_usize(arguments, 0);
+        let path = read_path(memory, arguments, 1)?;
+
+        let (host_path, _) = self.resolve(fd, &path, false)?;
+        if fs::symlink_metadata(&host_path)?.is_dir() {
+            return Err(Errno::Isdir);
+        }
+        fs::remove_file(host_path)?;
+        Ok(())
+    }
+
+    pub(super) fn path_rename(
+        &mut self,
+        arguments: &[Value],
+        memory: &mut [u8],
+    ) -> Result<(), Errno> {
+        let old_fd = arg_usize(arguments, 0);
+        let old_path = read_path(memory, arguments, 1)?;
+        let new_fd = arg_usize(arguments, 3);
+        let new_path = read_path(memory, arguments, 4)?;
+
+        let (old_host_path, old_root) = self.resolve(old_fd, &old_path, false)?;
+        let (new_host_path, _) = self.resolve(new_fd, &new_path, false)?;
+        if old_host_path == old_root {
+            return Err(Errno::Notcapable);
+        }
+        fs::rename(old_host_path, new_host_path)?;
+        Ok(())
+    }
+
+    /// Find where a path the program gave us is on the host, and make sure it's inside the
+    /// preopened directory. Returns the path on the host and the preopened directory.
+    /// If `follow` is false and the path is a symlink, it's the symlink itself we want.
+    fn resolve(&self, dirfd: usize, path: &str, follow: bool) -> Result<(PathBuf, PathBuf), Errno> {
+        let dir = match self.files.get(dirfd) {
+            Some(WasiFile::HostDirectory(dir)) => dir,
+            Some(WasiFile::Closed) | None => return Err(Errno::Badf),
+            Some(_) => return Err(Errno::Notdir),
+        };
+
+        let mut host_path = dir.host_path.clone();
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(name) => host_path.push(name),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    if host_path == dir.root {
+                        return Err(Errno::Notcapable);
+                    }
+                    host_path.pop();
+                }
+                Component::RootDir | Component::Prefix(_) => return Err(Errno::Notcapable),
+            }
+        }
+
+        // Symlinks could still lead outside, so look at where they really go
+        let resolved = match (follow, host_path.file_name()) {
+            (false, Some(name)) => {
+                let parent = host_path.parent().ok_or(Errno::Notcapable)?;
+                parent.canonicalize()?.join(name)
+            }
+            _ => match host_path.canonicalize() {
+                Ok(resolved) => resolved,
+                // It doesn't exist (yet), so it's not a symlink
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    let (parent, name) = match (host_path.parent(), host_path.file_name()) {
+                        (Some(parent), Some(name)) => (parent, name),
+                        _ => return Err(Errno::Noent),
+                    };
+                    parent.canonicalize()?.join(name)
+                }
+                Err(e) => return Err(e.into()),
+            },
+        };
+
+        if resolved.starts_with(&dir.root) {
+            Ok((resolved, dir.root.clone()))
+        } else {
+            Err(Errno::Notcapable)
+        }
+    }
+
+    fn host_file(&self, fd: usize) -> Result<&File, Errno> {
+        match self.files.get(fd) {
+            Some(WasiFile::HostFile(file)) => Ok(&file.file),
+            Some(WasiFile::HostDirectory(_)) => Err(Errno::Isdir),
+            Some(WasiFile::Closed) | None => Err(Errno::Badf),
+            // We don't keep track of positions in stdio and in-memory files
+            Some(_) => Err(Errno::Spipe),
+        }
+    }
+}
+
+fn arg_usize(arguments: &[Value], index: usize) -> usize {
+    arguments[index].expect_i32().unwrap() as u32 as usize
+}
+
+/// Read a path from a pointer and a length, starting at `arguments[index]`
+fn read_path(memory: &[u8], arguments: &[Value], index: usize) -> Result<String, Errno> {
+    let ptr = arg_usize(arguments, index);
+    let len = arg_usize(arguments, index + 1);
+    let bytes = memory.get(ptr..ptr + len).ok_or(Errno::Fault)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| Errno::Ilseq)
+}
+
+/// The (base, length) of each iovec in an array of them
+fn iovecs(memory: &[u8], ptr_iovs: usize, iovs_len: usize) -> Vec<(usize, usize)> {
+    (0..iovs_len)
+        .map(|i| {
+            let ptr_iov = ptr_iovs + 8 * i;
+            let iov_base = read_u32(memory, ptr_iov) as usize;
+            let iov_len = read_u32(memory, ptr_iov + 4) as usize;
+            (iov_base, iov_len)
+        })
+        .collect()
+}
+
+/// The times to set, from the arguments `(atim: u64, mtim: u64, fst_flags: u16)`
+fn file_times(arguments: &[Value]) -> Result<FileTimes, Errno> {
+    let atim = arguments[0].expect_i64().unwrap() as u64;
+    let mtim = arguments[1].expect_i64().unwrap() as u64;
+    let fst_flags = arguments[2].expect_i32().unwrap();
+
+    let time = |timestamp, flag, now_flag| match (fst_flags & flag != 0, fst_flags & now_flag != 0)
+    {
+        (true, true) => Err(Errno::Inval),
+        (true, false) => Ok(Some(UNIX_EPOCH + Duration::from_nanos(timestamp))),
+        (false, true) => Ok(Some(SystemTime::now())),
+        (false, false) => Ok(None),
+    };
+
+    let mut times = FileTimes::new();
+    if let Some(accessed) = time(atim, FSTFLAGS_ATIM, FSTFLAGS_ATIM_NOW)? {
+        times = times.set_accessed(accessed);
+    }
+    if let Some(modified) = time(mtim, FSTFLAGS_MTIM, FSTFLAGS_MTIM_NOW)? {
+        times = times.set_modified(modified);
+    }
+    Ok(times)
+}
+
+// struct filestat {
+//     dev: u64,       // Device ID of device containing the file
+//     ino: u64,       // File serial number
+//     filetype: u8,   // File type
+//     nlink: u64,     // Number of hard links to the file
+//     size: u64,      // For regular files, the file size in bytes
+//     atim: u64,      // Last data access timestamp
+//     mtim: u64,      // Last data modification timestamp
+//     ctim: u64,      // Last file status change timestamp
+// };
+fn write_filestat(memory: &mut [u8], ptr_buf: usize, metadata: &Metadata) {
+    memory[ptr_buf..][..64].fill(0);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        write_u64(memory, ptr_buf, metadata.dev());
+        write_u64(memory, ptr_buf + 24, metadata.nlink());
+        write_u64(
+            memory,
+            ptr_buf + 56,
+            timestamp(metadata.ctime(), metadata.ctime_nsec()),
+        );
+    }
+    #[cfg(not(unix))]
+    write_u64(memory, ptr_buf + 24, 1);
+
+    write_u64(memory, ptr_buf + 8, inode(metadata));
+    memory[ptr_buf + 16] = filetype(metadata);
+    write_u64(memory, ptr_buf + 32, metadata.len());
+    write_u64(memory, ptr_buf + 40, nanos_since_epoch(metadata.accessed()));
+    write_u64(memory, ptr_buf + 48, nanos_since_epoch(metadata.modified()));
+}
+
+#[cfg(unix)]
+fn timestamp(secs: i64, nsecs: i64) -> u64 {
+    (secs as u64)
+        .wrapping_mul(1_000_000_000)
+        .wrapping_add(nsecs as u64)
+}
+
+fn nanos_since_epoch(time: io::Result<SystemTime>) -> u64 {
+    time.ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
+
+fn inode(metadata: &Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.ino()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        0
+    }
+}
+
+fn filetype(metadata: &Metadata) -> u8 {
+    let file_type = metadata.file_type();
+    if file_type.is_dir() {
+        FILETYPE_DIRECTORY
+    } else if file_type.is_file() {
+        FILETYPE_REGULAR_FILE
+    } else if file_type.is_symlink() {
+        FILETYPE_SYMBOLIC_LINK
+    } else {
+        FILETYPE_UNKNOWN
+    }
+}
+
+fn write_u64(memory: &mut [u8], addr: usize, value: u64) {
+    memory[addr..][..8].copy_from_slice(&value.to_le_bytes());
+}
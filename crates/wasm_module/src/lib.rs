@@ -692,6 +692,7 @@ pub enum ValueType {
     I64 = 0x7e,
     F32 = 0x7d,
     F64 = 0x7c,
+    V128 = 0x7b,
 }
 
 impl ValueType {
@@ -711,6 +712,7 @@ impl From<u8> for ValueType {
             0x7e => Self::I64,
             0x7d => Self::F32,
             0x7c => Self::F64,
+            0x7b => Self::V128,
             _ => internal_error!("Invalid ValueType 0x{:02x}", x),
         }
     }
@@ -723,6 +725,7 @@ impl From<Value> for ValueType {
             Value::I64(_) => Self::I64,
             Value::F32(_) => Self::F32,
             Value::F64(_) => Self::F64,
+            Value::V128(_) => Self::V128,
         }
     }
 }
@@ -749,6 +752,8 @@ pub enum Value {
     I64(i64),
     F32(f32),
     F64(f64),
+    /// A 128-bit vector from the SIMD proposal. Lane 0 is in the lowest bits.
+    V128(u128),
 }
 
 impl Value {
@@ -776,6 +781,12 @@ impl Value {
             _ => Err((ValueType::F64, ValueType::from(*self))),
         }
     }
+    pub fn expect_v128(&self) -> Result<u128, (ValueType, ValueType)> {
+        match self {
+            Value::V128(x) => Ok(*x),
+            _ => Err((ValueType::V128, ValueType::from(*self))),
+        }
+    }
 }
 
 impl From<u32> for Value {
@@ -52,6 +52,7 @@ pub enum OpCode {
     CURRENTMEMORY = 0x3f,
     GROWMEMORY = 0x40,
     MEMORY = 0xFC,
+    SIMD = 0xFD,
     I32CONST = 0x41,
     I64CONST = 0x42,
     F32CONST = 0x43,
@@ -244,6 +245,7 @@ pub const LOOKUP_TABLE: [Option<OpCode>; 256] = {
     result[0x3f] = Some(CURRENTMEMORY);
     result[0x40] = Some(GROWMEMORY);
     result[0xfc] = Some(MEMORY);
+    result[0xfd] = Some(SIMD);
     result[0x41] = Some(I32CONST);
     result[0x42] = Some(I64CONST);
     result[0x43] = Some(F32CONST);
@@ -421,6 +423,554 @@ impl TryFrom<u8> for MemoryInstruction {
     }
 }
 
+/// Instructions from the SIMD proposal, which operate on 128-bit vectors.
+/// Encoded as the `SIMD` prefix byte followed by the instruction number in LEB-128 format.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimdInstruction {
+    V128Load = 0x00,
+    V128Load8x8S = 0x01,
+    V128Load8x8U = 0x02,
+    V128Load16x4S = 0x03,
+    V128Load16x4U = 0x04,
+    V128Load32x2S = 0x05,
+    V128Load32x2U = 0x06,
+    V128Load8Splat = 0x07,
+    V128Load16Splat = 0x08,
+    V128Load32Splat = 0x09,
+    V128Load64Splat = 0x0a,
+    V128Store = 0x0b,
+    V128Const = 0x0c,
+    I8x16Shuffle = 0x0d,
+    I8x16Swizzle = 0x0e,
+    I8x16Splat = 0x0f,
+    I16x8Splat = 0x10,
+    I32x4Splat = 0x11,
+    I64x2Splat = 0x12,
+    F32x4Splat = 0x13,
+    F64x2Splat = 0x14,
+    I8x16ExtractLaneS = 0x15,
+    I8x16ExtractLaneU = 0x16,
+    I8x16ReplaceLane = 0x17,
+    I16x8ExtractLaneS = 0x18,
+    I16x8ExtractLaneU = 0x19,
+    I16x8ReplaceLane = 0x1a,
+    I32x4ExtractLane = 0x1b,
+    I32x4ReplaceLane = 0x1c,
+    I64x2ExtractLane = 0x1d,
+    I64x2ReplaceLane = 0x1e,
+    F32x4ExtractLane = 0x1f,
+    F32x4ReplaceLane = 0x20,
+    F64x2ExtractLane = 0x21,
+    F64x2ReplaceLane = 0x22,
+    I8x16Eq = 0x23,
+    I8x16Ne = 0x24,
+    I8x16LtS = 0x25,
+    I8x16LtU = 0x26,
+    I8x16GtS = 0x27,
+    I8x16GtU = 0x28,
+    I8x16LeS = 0x29,
+    I8x16LeU = 0x2a,
+    I8x16GeS = 0x2b,
+    I8x16GeU = 0x2c,
+    I16x8Eq = 0x2d,
+    I16x8Ne = 0x2e,
+    I16x8LtS = 0x2f,
+    I16x8LtU = 0x30,
+    I16x8GtS = 0x31,
+    I16x8GtU = 0x32,
+    I16x8LeS = 0x33,
+    I16x8LeU = 0x34,
+    I16x8GeS = 0x35,
+    I16x8GeU = 0x36,
+    I32x4Eq = 0x37,
+    I32x4Ne = 0x38,
+    I32x4LtS = 0x39,
+    I32x4LtU = 0x3a,
+    I32x4GtS = 0x3b,
+    I32x4GtU = 0x3c,
+    I32x4LeS = 0x3d,
+    I32x4LeU = 0x3e,
+    I32x4GeS = 0x3f,
+    I32x4GeU = 0x40,
+    F32x4Eq = 0x41,
+    F32x4Ne = 0x42,
+    F32x4Lt = 0x43,
+    F32x4Gt = 0x44,
+    F32x4Le = 0x45,
+    F32x4Ge = 0x46,
+    F64x2Eq = 0x47,
+    F64x2Ne = 0x48,
+    F64x2Lt = 0x49,
+    F64x2Gt = 0x4a,
+    F64x2Le = 0x4b,
+    F64x2Ge = 0x4c,
+    V128Not = 0x4d,
+    V128And = 0x4e,
+    V128Andnot = 0x4f,
+    V128Or = 0x50,
+    V128Xor = 0x51,
+    V128Bitselect = 0x52,
+    V128AnyTrue = 0x53,
+    V128Load8Lane = 0x54,
+    V128Load16Lane = 0x55,
+    V128Load32Lane = 0x56,
+    V128Load64Lane = 0x57,
+    V128Store8Lane = 0x58,
+    V128Store16Lane = 0x59,
+    V128Store32Lane = 0x5a,
+    V128Store64Lane = 0x5b,
+    V128Load32Zero = 0x5c,
+    V128Load64Zero = 0x5d,
+    F32x4DemoteF64x2Zero = 0x5e,
+    F64x2PromoteLowF32x4 = 0x5f,
+    I8x16Abs = 0x60,
+    I8x16Neg = 0x61,
+    I8x16Popcnt = 0x62,
+    I8x16AllTrue = 0x63,
+    I8x16Bitmask = 0x64,
+    I8x16NarrowI16x8S = 0x65,
+    I8x16NarrowI16x8U = 0x66,
+    F32x4Ceil = 0x67,
+    F32x4Floor = 0x68,
+    F32x4Trunc = 0x69,
+    F32x4Nearest = 0x6a,
+    I8x16Shl = 0x6b,
+    I8x16ShrS = 0x6c,
+    I8x16ShrU = 0x6d,
+    I8x16Add = 0x6e,
+    I8x16AddSatS = 0x6f,
+    I8x16AddSatU = 0x70,
+    I8x16Sub = 0x71,
+    I8x16SubSatS = 0x72,
+    I8x16SubSatU = 0x73,
+    F64x2Ceil = 0x74,
+    F64x2Floor = 0x75,
+    I8x16MinS = 0x76,
+    I8x16MinU = 0x77,
+    I8x16MaxS = 0x78,
+    I8x16MaxU = 0x79,
+    F64x2Trunc = 0x7a,
+    I8x16AvgrU = 0x7b,
+    I16x8ExtaddPairwiseI8x16S = 0x7c,
+    I16x8ExtaddPairwiseI8x16U = 0x7d,
+    I32x4ExtaddPairwiseI16x8S = 0x7e,
+    I32x4ExtaddPairwiseI16x8U = 0x7f,
+    I16x8Abs = 0x80,
+    I16x8Neg = 0x81,
+    I16x8Q15mulrSatS = 0x82,
+    I16x8AllTrue = 0x83,
+    I16x8Bitmask = 0x84,
+    I16x8NarrowI32x4S = 0x85,
+    I16x8NarrowI32x4U = 0x86,
+    I16x8ExtendLowI8x16S = 0x87,
+    I16x8ExtendHighI8x16S = 0x88,
+    I16x8ExtendLowI8x16U = 0x89,
+    I16x8ExtendHighI8x16U = 0x8a,
+    I16x8Shl = 0x8b,
+    I16x8ShrS = 0x8c,
+    I16x8ShrU = 0x8d,
+    I16x8Add = 0x8e,
+    I16x8AddSatS = 0x8f,
+    I16x8AddSatU = 0x90,
+    I16x8Sub = 0x91,
+    I16x8SubSatS = 0x92,
+    I16x8SubSatU = 0x93,
+    F64x2Nearest = 0x94,
+    I16x8Mul = 0x95,
+    I16x8MinS = 0x96,
+    I16x8MinU = 0x97,
+    I16x8MaxS = 0x98,
+    I16x8MaxU = 0x99,
+    I16x8AvgrU = 0x9b,
+    I16x8ExtmulLowI8x16S = 0x9c,
+    I16x8ExtmulHighI8x16S = 0x9d,
+    I16x8ExtmulLowI8x16U = 0x9e,
+    I16x8ExtmulHighI8x16U = 0x9f,
+    I32x4Abs = 0xa0,
+    I32x4Neg = 0xa1,
+    I32x4AllTrue = 0xa3,
+    I32x4Bitmask = 0xa4,
+    I32x4ExtendLowI16x8S = 0xa7,
+    I32x4ExtendHighI16x8S = 0xa8,
+    I32x4ExtendLowI16x8U = 0xa9,
+    I32x4ExtendHighI16x8U = 0xaa,
+    I32x4Shl = 0xab,
+    I32x4ShrS = 0xac,
+    I32x4ShrU = 0xad,
+    I32x4Add = 0xae,
+    I32x4Sub = 0xb1,
+    I32x4Mul = 0xb5,
+    I32x4MinS = 0xb6,
+    I32x4MinU = 0xb7,
+    I32x4MaxS = 0xb8,
+    I32x4MaxU = 0xb9,
+    I32x4DotI16x8S = 0xba,
+    I32x4ExtmulLowI16x8S = 0xbc,
+    I32x4ExtmulHighI16x8S = 0xbd,
+    I32x4ExtmulLowI16x8U = 0xbe,
+    I32x4ExtmulHighI16x8U = 0xbf,
+    I64x2Abs = 0xc0,
+    I64x2Neg = 0xc1,
+    I64x2AllTrue = 0xc3,
+    I64x2Bitmask = 0xc4,
+    I64x2ExtendLowI32x4S = 0xc7,
+    I64x2ExtendHighI32x4S = 0xc8,
+    I64x2ExtendLowI32x4U = 0xc9,
+    I64x2ExtendHighI32x4U = 0xca,
+    I64x2Shl = 0xcb,
+    I64x2ShrS = 0xcc,
+    I64x2ShrU = 0xcd,
+    I64x2Add = 0xce,
+    I64x2Sub = 0xd1,
+    I64x2Mul = 0xd5,
+    I64x2Eq = 0xd6,
+    I64x2Ne = 0xd7,
+    I64x2LtS = 0xd8,
+    I64x2GtS = 0xd9,
+    I64x2LeS = 0xda,
+    I64x2GeS = 0xdb,
+    I64x2ExtmulLowI32x4S = 0xdc,
+    I64x2ExtmulHighI32x4S = 0xdd,
+    I64x2ExtmulLowI32x4U = 0xde,
+    I64x2ExtmulHighI32x4U = 0xdf,
+    F32x4Abs = 0xe0,
+    F32x4Neg = 0xe1,
+    F32x4Sqrt = 0xe3,
+    F32x4Add = 0xe4,
+    F32x4Sub = 0xe5,
+    F32x4Mul = 0xe6,
+    F32x4Div = 0xe7,
+    F32x4Min = 0xe8,
+    F32x4Max = 0xe9,
+    F32x4Pmin = 0xea,
+    F32x4Pmax = 0xeb,
+    F64x2Abs = 0xec,
+    F64x2Neg = 0xed,
+    F64x2Sqrt = 0xef,
+    F64x2Add = 0xf0,
+    F64x2Sub = 0xf1,
+    F64x2Mul = 0xf2,
+    F64x2Div = 0xf3,
+    F64x2Min = 0xf4,
+    F64x2Max = 0xf5,
+    F64x2Pmin = 0xf6,
+    F64x2Pmax = 0xf7,
+    I32x4TruncSatF32x4S = 0xf8,
+    I32x4TruncSatF32x4U = 0xf9,
+    F32x4ConvertI32x4S = 0xfa,
+    F32x4ConvertI32x4U = 0xfb,
+    I32x4TruncSatF64x2SZero = 0xfc,
+    I32x4TruncSatF64x2UZero = 0xfd,
+    F64x2ConvertLowI32x4S = 0xfe,
+    F64x2ConvertLowI32x4U = 0xff,
+}
+
+impl TryFrom<u32> for SimdInstruction {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        use SimdInstruction::*;
+
+        let op = match value {
+            0x00 => V128Load,
+            0x01 => V128Load8x8S,
+            0x02 => V128Load8x8U,
+            0x03 => V128Load16x4S,
+            0x04 => V128Load16x4U,
+            0x05 => V128Load32x2S,
+            0x06 => V128Load32x2U,
+            0x07 => V128Load8Splat,
+            0x08 => V128Load16Splat,
+            0x09 => V128Load32Splat,
+            0x0a => V128Load64Splat,
+            0x0b => V128Store,
+            0x0c => V128Const,
+            0x0d => I8x16Shuffle,
+            0x0e => I8x16Swizzle,
+            0x0f => I8x16Splat,
+            0x10 => I16x8Splat,
+            0x11 => I32x4Splat,
+            0x12 => I64x2Splat,
+            0x13 => F32x4Splat,
+            0x14 => F64x2Splat,
+            0x15 => I8x16ExtractLaneS,
+            0x16 => I8x16ExtractLaneU,
+            0x17 => I8x16ReplaceLane,
+            0x18 => I16x8ExtractLaneS,
+            0x19 => I16x8ExtractLaneU,
+            0x1a => I16x8ReplaceLane,
+            0x1b => I32x4ExtractLane,
+            0x1c => I32x4ReplaceLane,
+            0x1d => I64x2ExtractLane,
+            0x1e => I64x2ReplaceLane,
+            0x1f => F32x4ExtractLane,
+            0x20 => F32x4ReplaceLane,
+            0x21 => F64x2ExtractLane,
+            0x22 => F64x2ReplaceLane,
+            0x23 => I8x16Eq,
+            0x24 => I8x16Ne,
+            0x25 => I8x16LtS,
+            0x26 => I8x16LtU,
+            0x27 => I8x16GtS,
+            0x28 => I8x16GtU,
+            0x29 => I8x16LeS,
+            0x2a => I8x16LeU,
+            0x2b => I8x16GeS,
+            0x2c => I8x16GeU,
+            0x2d => I16x8Eq,
+            0x2e => I16x8Ne,
+            0x2f => I16x8LtS,
+            0x30 => I16x8LtU,
+            0x31 => I16x8GtS,
+            0x32 => I16x8GtU,
+            0x33 => I16x8LeS,
+            0x34 => I16x8LeU,
+            0x35 => I16x8GeS,
+            0x36 => I16x8GeU,
+            0x37 => I32x4Eq,
+            0x38 => I32x4Ne,
+            0x39 => I32x4LtS,
+            0x3a => I32x4LtU,
+            0x3b => I32x4GtS,
+            0x3c => I32x4GtU,
+            0x3d => I32x4LeS,
+            0x3e => I32x4LeU,
+            0x3f => I32x4GeS,
+            0x40 => I32x4GeU,
+            0x41 => F32x4Eq,
+            0x42 => F32x4Ne,
+            0x43 => F32x4Lt,
+            0x44 => F32x4Gt,
+            0x45 => F32x4Le,
+            0x46 => F32x4Ge,
+            0x47 => F64x2Eq,
+            0x48 => F64x2Ne,
+            0x49 => F64x2Lt,
+            0x4a => F64x2Gt,
+            0x4b => F64x2Le,
+            0x4c => F64x2Ge,
+            0x4d => V128Not,
+            0x4e => V128And,
+            0x4f => V128Andnot,
+            0x50 => V128Or,
+            0x51 => V128Xor,
+            0x52 => V128Bitselect,
+            0x53 => V128AnyTrue,
+            0x54 => V128Load8Lane,
+            0x55 => V128Load16Lane,
+            0x56 => V128Load32Lane,
+            0x57 => V128Load64Lane,
+            0x58 => V128Store8Lane,
+            0x59 => V128Store16Lane,
+            0x5a => V128Store32Lane,
+            0x5b => V128Store64Lane,
+            0x5c => V128Load32Zero,
+            0x5d => V128Load64Zero,
+            0x5e => F32x4DemoteF64x2Zero,
+            0x5f => F64x2PromoteLowF32x4,
+            0x60 => I8x16Abs,
+            0x61 => I8x16Neg,
+            0x62 => I8x16Popcnt,
+            0x63 => I8x16AllTrue,
+            0x64 => I8x16Bitmask,
+            0x65 => I8x16NarrowI16x8S,
+            0x66 => I8x16NarrowI16x8U,
+            0x67 => F32x4Ceil,
+            0x68 => F32x4Floor,
+            0x69 => F32x4Trunc,
+            0x6a => F32x4Nearest,
+            0x6b => I8x16Shl,
+            0x6c => I8x16ShrS,
+            0x6d => I8x16ShrU,
+            0x6e => I8x16Add,
+            0x6f => I8x16AddSatS,
+            0x70 => I8x16AddSatU,
+            0x71 => I8x16Sub,
+            0x72 => I8x16SubSatS,
+            0x73 => I8x16SubSatU,
+            0x74 => F64x2Ceil,
+            0x75 => F64x2Floor,
+            0x76 => I8x16MinS,
+            0x77 => I8x16MinU,
+            0x78 => I8x16MaxS,
+            0x79 => I8x16MaxU,
+            0x7a => F64x2Trunc,
+            0x7b => I8x16AvgrU,
+            0x7c => I16x8ExtaddPairwiseI8x16S,
+            0x7d => I16x8ExtaddPairwiseI8x16U,
+            0x7e => I32x4ExtaddPairwiseI16x8S,
+            0x7f => I32x4ExtaddPairwiseI16x8U,
+            0x80 => I16x8Abs,
+            0x81 => I16x8Neg,
+            0x82 => I16x8Q15mulrSatS,
+            0x83 => I16x8AllTrue,
+            0x84 => I16x8Bitmask,
+            0x85 => I16x8NarrowI32x4S,
+            0x86 => I16x8NarrowI32x4U,
+            0x87 => I16x8ExtendLowI8x16S,
+            0x88 => I16x8ExtendHighI8x16S,
+            0x89 => I16x8ExtendLowI8x16U,
+            0x8a => I16x8ExtendHighI8x16U,
+            0x8b => I16x8Shl,
+            0x8c => I16x8ShrS,
+            0x8d => I16x8ShrU,
+            0x8e => I16x8Add,
+            0x8f => I16x8AddSatS,
+            0x90 => I16x8AddSatU,
+            0x91 => I16x8Sub,
+            0x92 => I16x8SubSatS,
+            0x93 => I16x8SubSatU,
+            0x94 => F64x2Nearest,
+            0x95 => I16x8Mul,
+            0x96 => I16x8MinS,
+            0x97 => I16x8MinU,
+            0x98 => I16x8MaxS,
+            0x99 => I16x8MaxU,
+            0x9b => I16x8AvgrU,
+            0x9c => I16x8ExtmulLowI8x16S,
+            0x9d => I16x8ExtmulHighI8x16S,
+            0x9e => I16x8ExtmulLowI8x16U,
+            0x9f => I16x8ExtmulHighI8x16U,
+            0xa0 => I32x4Abs,
+            0xa1 => I32x4Neg,
+            0xa3 => I32x4AllTrue,
+            0xa4 => I32x4Bitmask,
+            0xa7 => I32x4ExtendLowI16x8S,
+            0xa8 => I32x4ExtendHighI16x8S,
+            0xa9 => I32x4ExtendLowI16x8U,
+            0xaa => I32x4ExtendHighI16x8U,
+            0xab => I32x4Shl,
+            0xac => I32x4ShrS,
+            0xad => I32x4ShrU,
+            0xae => I32x4Add,
+            0xb1 => I32x4Sub,
+            0xb5 => I32x4Mul,
+            0xb6 => I32x4MinS,
+            0xb7 => I32x4MinU,
+            0xb8 => I32x4MaxS,
+            0xb9 => I32x4MaxU,
+            0xba => I32x4DotI16x8S,
+            0xbc => I32x4ExtmulLowI16x8S,
+            0xbd => I32x4ExtmulHighI16x8S,
+            0xbe => I32x4ExtmulLowI16x8U,
+            0xbf => I32x4ExtmulHighI16x8U,
+            0xc0 => I64x2Abs,
+            0xc1 => I64x2Neg,
+            0xc3 => I64x2AllTrue,
+            0xc4 => I64x2Bitmask,
+            0xc7 => I64x2ExtendLowI32x4S,
+            0xc8 => I64x2ExtendHighI32x4S,
+            0xc9 => I64x2ExtendLowI32x4U,
+            0xca => I64x2ExtendHighI32x4U,
+            0xcb => I64x2Shl,
+            0xcc => I64x2ShrS,
+            0xcd => I64x2ShrU,
+            0xce => I64x2Add,
+            0xd1 => I64x2Sub,
+            0xd5 => I64x2Mul,
+            0xd6 => I64x2Eq,
+            0xd7 => I64x2Ne,
+            0xd8 => I64x2LtS,
+            0xd9 => I64x2GtS,
+            0xda => I64x2LeS,
+            0xdb => I64x2GeS,
+            0xdc => I64x2ExtmulLowI32x4S,
+            0xdd => I64x2ExtmulHighI32x4S,
+            0xde => I64x2ExtmulLowI32x4U,
+            0xdf => I64x2ExtmulHighI32x4U,
+            0xe0 => F32x4Abs,
+            0xe1 => F32x4Neg,
+            0xe3 => F32x4Sqrt,
+            0xe4 => F32x4Add,
+            0xe5 => F32x4Sub,
+            0xe6 => F32x4Mul,
+            0xe7 => F32x4Div,
+            0xe8 => F32x4Min,
+            0xe9 => F32x4Max,
+            0xea => F32x4Pmin,
+            0xeb => F32x4Pmax,
+            0xec => F64x2Abs,
+            0xed => F64x2Neg,
+            0xef => F64x2Sqrt,
+            0xf0 => F64x2Add,
+            0xf1 => F64x2Sub,
+            0xf2 => F64x2Mul,
+            0xf3 => F64x2Div,
+            0xf4 => F64x2Min,
+            0xf5 => F64x2Max,
+            0xf6 => F64x2Pmin,
+            0xf7 => F64x2Pmax,
+            0xf8 => I32x4TruncSatF32x4S,
+            0xf9 => I32x4TruncSatF32x4U,
+            0xfa => F32x4ConvertI32x4S,
+            0xfb => F32x4ConvertI32x4U,
+            0xfc => I32x4TruncSatF64x2SZero,
+            0xfd => I32x4TruncSatF64x2UZero,
+            0xfe => F64x2ConvertLowI32x4S,
+            0xff => F64x2ConvertLowI32x4U,
+            _ => return Err(value),
+        };
+        Ok(op)
+    }
+}
+
+impl SimdInstruction {
+    fn immediates(self) -> SimdImmediates {
+        use SimdImmediates::*;
+        use SimdInstruction::*;
+
+        match self {
+            V128Load | V128Load8x8S | V128Load8x8U | V128Load16x4S | V128Load16x4U
+            | V128Load32x2S | V128Load32x2U | V128Load8Splat | V128Load16Splat
+            | V128Load32Splat | V128Load64Splat | V128Store | V128Load32Zero | V128Load64Zero => {
+                MemArg
+            }
+            V128Load8Lane | V128Load16Lane | V128Load32Lane | V128Load64Lane | V128Store8Lane
+            | V128Store16Lane | V128Store32Lane | V128Store64Lane => MemArgLane,
+            I8x16ExtractLaneS | I8x16ExtractLaneU | I8x16ReplaceLane | I16x8ExtractLaneS
+            | I16x8ExtractLaneU | I16x8ReplaceLane | I32x4ExtractLane | I32x4ReplaceLane
+            | I64x2ExtractLane | I64x2ReplaceLane | F32x4ExtractLane | F32x4ReplaceLane
+            | F64x2ExtractLane | F64x2ReplaceLane => Lane,
+            V128Const | I8x16Shuffle => Bytes16,
+            // All the other instructions operate on the value stack only
+            _ => NoImmediate,
+        }
+    }
+}
+
+impl Parse<()> for SimdInstruction {
+    fn parse(_: (), bytes: &[u8], cursor: &mut usize) -> Result<Self, ParseError> {
+        let offset = *cursor;
+        let value = u32::parse((), bytes, cursor)?;
+        SimdInstruction::try_from(value).map_err(|value| ParseError {
+            message: format!("Unknown SIMD instruction 0x{value:02x}"),
+            offset,
+        })
+    }
+}
+
+impl Serialize for SimdInstruction {
+    fn serialize<T: crate::SerialBuffer>(&self, buffer: &mut T) {
+        buffer.append_u8(OpCode::SIMD as u8);
+        buffer.encode_u32(*self as u32);
+    }
+}
+
+/// The immediate operands of a SIMD instruction
+#[derive(Debug)]
+enum SimdImmediates {
+    NoImmediate,
+    /// Alignment and offset, like other load and store instructions
+    MemArg,
+    /// Alignment and offset, followed by a lane index byte
+    MemArgLane,
+    /// A lane index byte
+    Lane,
+    /// A 128-bit constant, or the 16 lane indices of `i8x16.shuffle`
+    Bytes16,
+}
+
 /// The format of the *immediate* operands of an operator
 /// Immediates appear directly in the byte stream after the opcode,
 /// rather than being popped off the value stack. These are the possible forms.
@@ -435,6 +985,7 @@ enum OpImmediates {
     Leb32x2,
     BrTable,
     Memory,
+    Simd,
 }
 
 fn immediates_for(op: OpCode) -> Result<OpImmediates, String> {
@@ -464,6 +1015,7 @@ fn immediates_for(op: OpCode) -> Result<OpImmediates, String> {
 
         CURRENTMEMORY | GROWMEMORY => Byte1,
         MEMORY => Memory,
+        SIMD => Simd,
 
         I32CONST => Leb32x1,
         I64CONST => Leb64x1,
@@ -566,6 +1118,28 @@ impl SkipBytes for OpCode {
                     Err(other) => unreachable!("invalid memory instruction {other:?}"),
                 }
             }
+            Simd => {
+                *cursor += 1;
+                let op = SimdInstruction::parse((), bytes, cursor)?;
+                match op.immediates() {
+                    SimdImmediates::NoImmediate => {}
+                    SimdImmediates::MemArg => {
+                        u32::skip_bytes(bytes, cursor)?;
+                        u32::skip_bytes(bytes, cursor)?;
+                    }
+                    SimdImmediates::MemArgLane => {
+                        u32::skip_bytes(bytes, cursor)?;
+                        u32::skip_bytes(bytes, cursor)?;
+                        *cursor += 1;
+                    }
+                    SimdImmediates::Lane => {
+                        *cursor += 1;
+                    }
+                    SimdImmediates::Bytes16 => {
+                        *cursor += 16;
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -8,7 +8,7 @@ use roc_error_macros::internal_error;
 use crate::{Value, DUMMY_FUNCTION};
 
 use super::linking::{LinkingSection, SymInfo, WasmObjectSymbol};
-use super::opcodes::OpCode;
+use super::opcodes::{OpCode, SimdInstruction};
 use super::parse::{Parse, ParseError, SkipBytes};
 use super::serialize::{SerialBuffer, Serialize, MAX_SIZE_ENCODED_U32};
 use super::ValueType;
@@ -905,6 +905,7 @@ pub enum ConstExpr {
     I64(i64),
     F32(f32),
     F64(f64),
+    V128(u128),
 }
 
 impl ConstExpr {
@@ -944,6 +945,7 @@ impl ConstExpr {
             ConstExpr::I64(x) => Value::I64(*x),
             ConstExpr::F32(x) => Value::F32(*x),
             ConstExpr::F64(x) => Value::F64(*x),
+            ConstExpr::V128(x) => Value::V128(*x),
         }
     }
 }
@@ -972,6 +974,18 @@ impl Parse<()> for ConstExpr {
                 b.copy_from_slice(&bytes[*cursor..][..8]);
                 Ok(ConstExpr::F64(f64::from_le_bytes(b)))
             }
+            OpCode::SIMD => match SimdInstruction::parse((), bytes, cursor)? {
+                SimdInstruction::V128Const => {
+                    let mut b = [0; 16];
+                    b.copy_from_slice(&bytes[*cursor..][..16]);
+                    *cursor += 16;
+                    Ok(ConstExpr::V128(u128::from_le_bytes(b)))
+                }
+                op => Err(ParseError {
+                    offset: *cursor,
+                    message: format!("Unsupported SIMD instruction {op:?} in constant expression."),
+                }),
+            },
             _ => Err(ParseError {
                 offset: *cursor,
                 message: format!("Unsupported opcode {opcode:?} in constant expression."),
@@ -1009,6 +1023,10 @@ impl Serialize for ConstExpr {
                 buffer.append_u8(OpCode::F64CONST as u8);
                 buffer.encode_f64(*x);
             }
+            ConstExpr::V128(x) => {
+                SimdInstruction::V128Const.serialize(buffer);
+                buffer.append_slice(&x.to_le_bytes());
+            }
         }
         buffer.append_u8(OpCode::END as u8);
     }
@@ -1921,4 +1939,20 @@ mod tests {
         }
         test_assert_types_preload(arena, &section);
     }
+
+    #[test]
+    fn test_const_expr_v128() {
+        let arena = &Bump::new();
+        let expr = ConstExpr::V128(0x0f0e0d0c_0b0a0908_07060504_03020100);
+        let mut bytes = Vec::new_in(arena);
+        expr.serialize(&mut bytes);
+        assert_eq!(&bytes[..2], &[OpCode::SIMD as u8, 0x0c]);
+        assert_eq!(&bytes[2..18], &(0..16).collect::<std::vec::Vec<u8>>()[..]);
+        assert_eq!(bytes[18], OpCode::END as u8);
+
+        let mut cursor = 0;
+        let parsed = ConstExpr::parse((), &bytes, &mut cursor).unwrap();
+        assert_eq!(cursor, bytes.len());
+        assert_eq!(parsed.as_value(), expr.as_value());
+    }
 }